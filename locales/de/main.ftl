@@ -0,0 +1,180 @@
+## REPL-Statusmeldungen
+repl-welcome = LLM REPL - '/help' zeigt die Befehle, !<befehl> für die Shell, /reader für den Verlauf.
+repl-workspace-config = INFO: Verwende Arbeitsbereich-Einstellungen aus { $path }.
+repl-session = Sitzung: { $session } (/sessions zum Wechseln, /save <Name> zum Benennen).
+repl-session-saved = Sitzung als '{ $session }' gespeichert.
+repl-no-history = INFO: Kein früherer Verlauf gefunden oder Laden fehlgeschlagen.
+repl-querying = Anfrage läuft...
+repl-querying-target = Anfrage an { $provider }:{ $model } (nur diese Anfrage)...
+repl-continuing = Setze die letzte Antwort von { $provider }:{ $model } fort...
+repl-missing-prompt = Nach der @provider:modell-Angabe fehlt der Prompt.
+repl-cached-offer = Diese Frage wurde vor { $minutes } Min. gestellt ({ $target }).
+repl-cached-show = Vorherige Antwort anzeigen
+repl-cached-fresh = Erneut fragen
+repl-did-you-mean = Unbekannter Befehl /{ $command }. Stattdessen /{ $suggestion } ausführen?
+repl-included-file = Eingefügt: { $file }
+repl-included-dataset = Datensatz { $dataset } eingefügt (Schema, Statistiken und Beispielzeilen)
+repl-pending-prompts = Wartende Prompts ({ $count }):
+repl-exit-ctrl-c = STRG-C empfangen, wird beendet.
+repl-exit-ctrl-d = STRG-D empfangen, wird beendet.
+repl-command-finished = Abgeschlossen
+repl-command-failed = Fehlgeschlagen: { $error }
+repl-response-ready = Antwort bereit
+repl-query-failed = Anfrage fehlgeschlagen
+
+## Fehlerpräfixe
+error-generic = Fehler: { $error }
+error-command = Fehler beim Ausführen von { $command }: { $error }
+error-shell = Shell-Fehler: { $error }
+error-llm = LLM-Fehler: { $error }
+repl-request-id = (Anfrage { $id })
+error-readline = Eingabefehler: { $error }
+warn-history-add = WARNUNG: Eintrag konnte nicht zum rustyline-Verlauf hinzugefügt werden: { $error }
+warn-history-save = WARNUNG: rustyline-Verlauf konnte nicht gespeichert werden: { $error }
+warn-session-save = WARNUNG: Sitzung '{ $session }' konnte nicht gespeichert werden: { $error }
+
+## /reader
+reader-title = --- Sitzungsverlauf ---
+reader-scroll-hint = Der Verlauf steht unten; nach oben scrollen zum Lesen.
+reader-copy-hint = Blöcke lassen sich mit der Auswahlfunktion des Terminals kopieren.
+reader-empty = Der Verlauf ist leer.
+reader-entry-end = --- Ende ---
+reader-end = --- Ende des Verlaufs ---
+reader-usage-total = Antworten oben: { $usage }
+reader-finished = Verlaufsansicht beendet. Nach oben scrollen, um den Verlauf zu sehen.
+
+## /tui
+tui-conversation = Unterhaltung
+tui-conversation-scrolled = Unterhaltung ({ $lines } Zeilen nach oben, Runter/Bild-ab zum Zurückkehren)
+tui-input = Prompt oder /befehl
+tui-keys = Enter senden | Hoch/Runter/Bild-auf/Bild-ab scrollen | Esc zurück zur Eingabezeile | /exit beenden
+tui-streaming = Antwort läuft... { $seconds } s (Esc oder Strg+C bricht ab)
+tui-busy = Warte, bis die Antwort fertig ist, oder brich sie mit Esc ab.
+tui-cancelled = Abgebrochen
+
+## /help
+help-title = LLM REPL-Befehle
+help-category-providers = Provider und Modelle
+help-category-rendering = Darstellung
+help-category-session = Sitzung
+help-category-tools = Werkzeuge
+help-category-shell = Shell
+help-column-command = Befehl
+help-column-description = Beschreibung
+help-default-title = Standardverhalten
+help-current-theme = Aktuelles Theme: { $theme }
+help-current-mode = Aktueller Markdown-Modus: { $mode }
+help-mode-append = AppendFormatted (Rohtext streamen, formatiert darunter anhängen)
+help-mode-live = LiveStreaming (Experimentell, kann flackern)
+help-mode-off = Off (Nur Rohtext)
+help-cmd-help = Diese Hilfe anzeigen.
+help-cmd-provider =
+    LLM-Provider wählen (interaktiv, wenn kein Name angegeben).
+    Verfügbar: ollama, groq, gemini (API-Schlüssel prüfen).
+help-cmd-apikey = API-Schlüssel eines Providers für diese Sitzung setzen, löschen oder testen, ohne Neustart, z. B. /apikey groq <schlüssel>.
+help-cmd-ground = Google-Suche-Grounding für Gemini umschalten, für aktuelle Antworten mit Liste der Webquellen (/ground on|off).
+help-cmd-model = Modell des aktuellen Providers wählen (interaktiv, wenn kein Name angegeben).
+help-cmd-theme =
+    Theme wählen (interaktiv, wenn kein Name angegeben).
+    Namen: default, nord, gruvbox, grayscale, deuteranopia, protanopia (farbenblind-freundlich).
+help-cmd-theme-status = Aktuelles Theme anzeigen ({ $theme }).
+help-cmd-md = Markdown-Modus: formatiert anhängen (Standard).
+help-cmd-md-streaming = Markdown-Modus: Live-Streaming (Experimentell).
+help-cmd-md-off = Markdown-Modus: aus (Rohtext).
+help-cmd-md-split = Rohes Markdown und seine Darstellung nach jeder Antwort nebeneinander zeigen, wenn das Terminal breit genug ist (/md_split on|off).
+help-cmd-md-status = Aktuellen Markdown-Modus anzeigen (Derzeit: { $mode }).
+help-cmd-md-source = Markdown-Darstellung für eine Ausgabequelle festlegen:
+    llm, commands oder shell; Modus append, live, off oder default.
+help-cmd-llmconvo = Interaktive Einrichtung eines Gesprächs zwischen zwei LLMs starten.
+help-cmd-diffmodels = Einen Prompt an zwei Modelle senden, beide Antworten als beschriftete Blöcke streamen und wortweise vergleichen.
+help-cmd-prefer =
+    Nach /diffmodels die bessere Antwort (/prefer a, b oder tie) in preferences.jsonl festhalten.
+    /prefer zeigt die Gewinnquote je Modell; /prefer export <datei> schreibt Gewählt/Abgelehnt-Paare fürs Fine-Tuning.
+help-cmd-cache = Zwischengespeicherte Modelllisten und Bereitschaftsprüfungen der Provider anzeigen (5 Minuten gültig); /cache clear ruft sie neu ab.
+help-cmd-pull = Ein Modell mit Fortschrittsbalken in Ollama herunterladen.
+help-cmd-ollama = Ein Ollama-Modell aus einer Modelfile erstellen.
+help-cmd-rm = Ein lokales Ollama-Modell löschen.
+help-cmd-show = Familie, Größe, Quantisierung, Kontextlänge und Parameter eines Ollama-Modells anzeigen.
+help-cmd-embed = Text (oder jede Zeile einer Datei) mit einem Embedding-Modell einbetten und die Dimension anzeigen; --out schreibt die Vektoren als JSON.
+help-cmd-report = Nutzungsbericht (Standard: letzte Woche) mit Anfragen, Tokens und Kosten je Provider/Modell und den aktivsten Tagen.
+help-cmd-usage = Verbrauch dieser Sitzung: Anfragen, Tokens (wo möglich vom Provider gemeldet) und Kosten je Provider/Modell.
+help-cmd-eval = Eine TOML-Suite aus Prompts mit Regex-/JSON-Prüfungen ausführen; zeigt Bestanden/Fehlgeschlagen und Latenz je Modell.
+help-cmd-data = Einen CSV/JSON-Datensatz laden, Beispielzeilen oder lokal berechnete Statistiken anzeigen
+    (z. B. /data stats sales revenue by region); in Prompts als @data:name referenzieren.
+help-cmd-db = Mit einer SQLite-, Postgres- oder MySQL-Datenbank verbinden, das Schema anzeigen oder eine Leseabfrage ausführen.
+help-cmd-sql = Eine lesende SQL-Abfrage zu einer Frage erzeugen, bestätigen, ausführen und das Ergebnis zusammenfassen.
+help-cmd-http = Eine HTTP-Anfrage senden und Status, Dauer und Antwort (JSON formatiert) anzeigen;
+    /http headers listet Header, /http header <Name> [Wert] setzt oder entfernt einen.
+help-cmd-debug = Rohe HTTP-Anfragen und -Antworten der Provider mit maskierten API-Schlüsseln in eine Debug-Datei schreiben (/debug http on [Datei], /debug http off).
+help-cmd-tee = Alle Ausgaben (Prompts, Antworten, Befehlsausgaben) in eine Text- oder Markdown-Datei spiegeln (/tee <Datei> on, /tee off).
+help-cmd-jq = Einen jq-Filter auf die letzte JSON-Ausgabe anwenden (Antwort, /http oder Shell-Ausgabe).
+help-cmd-regex = Einen regulären Ausdruck auf die letzte Antwort anwenden; zeigt Treffer mit Zeilennummern und Gruppen.
+help-cmd-suggest = Das Modell einen Shell-Befehl für ein Ziel vorschlagen lassen (mit dem bisherigen Gespräch als Kontext);
+    er läuft erst nach Bestätigung (oder Bearbeitung), die Ausgabe landet im Sitzungsverlauf.
+help-cmd-ops = Ops-Modus umschalten oder k8s [Namespace], service <Unit> oder system mit
+    freigegebenen kubectl/systemctl/journalctl-Befehlen prüfen und vom Modell analysieren lassen.
+help-cmd-k8s = Eine Kubernetes-Ressourcenart oder ein laufendes Objekt (art/name) vom Modell erklären lassen (Ops-Modus).
+help-cmd-reader = Den Ausgabeverlauf der Sitzung schreibgeschützt anzeigen.
+help-cmd-replay = Die Sitzung Zug um Zug erneut abspielen (z. B. /replay 2x). STRG-C bricht ab.
+help-cmd-recall = Frühere Prompts und Antworten unscharf suchen; die Auswahl landet im Prompt (auch Alt+R).
+help-cmd-history = Verlaufsgröße und Aufbewahrungsgrenzen anzeigen oder mit prune sofort anwenden.
+help-cmd-memory = Den mit Prompts gesendeten Gesprächsverlauf anzeigen, mit clear vergessen oder festlegen, wie viele frühere Runden gesendet werden (Zahl, off oder all).
+help-cmd-continue = Das Modell bitten, seine letzte Antwort fortzusetzen (z. B. nach einem Token-Limit); der Rest wird an diese Antwort im Verlauf angehängt.
+help-cmd-system = Den mit jedem Prompt gesendeten Systemprompt anzeigen, setzen oder entfernen.
+help-cmd-pin = Einen Verlaufseintrag (nach seiner /reader-Nummer) oder eine Datei anheften, damit er immer mit den Prompts gesendet wird.
+help-cmd-tag = Einen Verlaufseintrag mit einem Label versehen, damit er in /bookmarks erscheint; -<Label> entfernt es.
+help-cmd-bookmarks = Markierte Verlaufseinträge auflisten, optional nur die mit einem bestimmten Label.
+help-cmd-pins = Angehefteten Kontext auflisten, eine Anheftung entfernen (/pins remove <Nummer>) oder alle löschen (/pins clear).
+help-cmd-tokens = Tokens eines Textes (mit @Datei-Verweisen) zählen oder, ohne Text, die des Kontexts, der mit jedem Prompt gesendet wird.
+help-cmd-context = Anzeigen, was der nächste Prompt gemessen am Kontextfenster des Modells sendet, oder festlegen, was mit Runden geschieht, die nicht hineinpassen (drop, summarize oder off).
+help-cmd-assistant = Eine Assistenten-Definition (Name, Anweisungen, Dateien) aus einer JSON- oder YAML-Datei als Systemprompt und Kontext der Sitzung importieren.
+help-cmd-checkpoint = Die Sitzung (Provider, Modell, Verlauf, Hooks, Limits, /set-Parameter) unter einem Namen sichern oder Checkpoints auflisten.
+help-cmd-rollback = Die mit /checkpoint gesicherte Sitzung wiederherstellen.
+help-cmd-save = Die Sitzung (Unterhaltung, Verlauf und Einstellungen) auf der Festplatte speichern, unter dem angegebenen Namen falls vorhanden.
+help-cmd-load = Zu einer gespeicherten Sitzung wechseln (die aktuelle wird vorher gespeichert) oder die Sitzungen auflisten.
+help-cmd-sessions = Eine Sitzung auswählen und wechseln, per Name wechseln, eine neue beginnen (/sessions new) oder ihren Titel setzen (/sessions title <Text>).
+help-cmd-grep = Den Verlauf aller Sitzungen (und [on_exit]-Transkripte) nach einem regulären Ausdruck durchsuchen, optional einen Treffer wählen und zu seiner Sitzung wechseln.
+help-cmd-stop = Client-seitige Stoppsequenzen anzeigen, hinzufügen oder löschen (\n erlaubt).
+help-cmd-max-length = Maximale Antwortlänge in Zeichen anzeigen oder festlegen.
+help-cmd-set = Generierungsparameter anzeigen oder setzen, die mit jeder Anfrage gesendet werden (temperature, top_p, max_tokens, presence_penalty, frequency_penalty), timeout und connect_timeout des aktuellen Providers (Sekunden) sowie num_ctx, num_gpu und keep_alive für Ollama.
+help-cmd-config = Einstellungen anzeigen, die Konfigurationsdatei in $EDITOR bearbeiten (mit Diff vor dem Speichern) oder einen Wert setzen, z. B. /config set ui.theme nord. Änderungen gelten sofort.
+help-cmd-pace = Schreibmaschinen-Tempo für gestreamte Antworten anzeigen oder festlegen (Zeichen pro Sekunde).
+help-cmd-notify = Desktop-Benachrichtigungen für lange Aufgaben umschalten oder die Schwelle festlegen.
+help-cmd-hooks = Text vor/nach jedem Prompt anzeigen oder festlegen.
+help-cmd-accessibility = Screenreader-freundliche Ausgabe: Klartext, Struktur wird in Worten angesagt.
+help-cmd-icons = Anbieter- und Status-Symbole in Prompt und Verlaufsüberschriften: nerd (benötigt eine Nerd Font), emoji oder ascii.
+help-cmd-buf =
+    Eine lange Anfrage in einem benannten Puffer zusammenstellen: /buf append <name> <text>, /buf edit <name> (öffnet $EDITOR).
+    /buf send <name> sendet sie als nächste Anfrage; /buf, /buf show <name> und /buf clear <name> verwalten die Puffer.
+help-cmd-attach = Ein Bild (Datei oder data:-URL) an die nächste Anfrage an ein Vision-Modell anhängen; /attach listet sie auf, /attach clear entfernt sie.
+help-cmd-init =
+    Das Projekt durchsuchen und das Modell .llm-repl/context.md schreiben lassen; die Datei wird hier mit jeder Anfrage gesendet.
+    /init force erzeugt sie neu.
+help-cmd-watch =
+    Prompt bei jeder Dateiänderung erneut senden (Platzhalter {"{"}content{"}"}, {"{"}file{"}"}).
+    /watch listet Überwachungen; /watch stop <id|all> beendet sie.
+help-cmd-workflow =
+    Einen Workflow aus .llm-repl/workflows ausführen: Prompts, Shell- und REPL-Befehle nacheinander, mit Bedingungen auf frühere Ausgaben.
+    /workflow listet die Workflows auf.
+help-cmd-exit = REPL beenden.
+help-cmd-tui = Zur Vollbild-Chatansicht wechseln: scrollbare Unterhaltung, Eingabefeld und Statusleiste. Esc kehrt hierher zurück.
+help-shell = Shell-Befehl ausführen (z. B. !ls -l). Die Ausgabe ist Rohtext.
+help-default-query = Jede andere Eingabe geht als Anfrage an den aktuellen LLM-Provider und das Modell.
+help-default-override =
+    Nur diesen Prompt an einen anderen Provider bzw. ein anderes Modell senden
+    (z. B. @groq:llama-3.1-70b-versatile Erkläre Lifetimes).
+help-default-queue = Zeilen, die während einer Antwort getippt werden, werden gesammelt und danach der Reihe nach gesendet.
+
+## Barrierefreiheitsmodus (angesagte Struktur)
+a11y-heading = Überschrift Ebene { $level }: { $text }
+a11y-quote = Zitat: { $text }
+a11y-code-start = Codeblock:
+a11y-code-start-language = Codeblock ({ $language }):
+a11y-code-end = Ende des Codeblocks.
+a11y-table-start = Tabelle:
+a11y-table-row = Zeile: { $cells }
+a11y-table-end = Ende der Tabelle.
+a11y-separator = Trennlinie.
+a11y-link = { $text } (Link: { $url })
+a11y-progress = Fortschritt: { $message } { $current } von { $total }
+a11y-progress-open = Fortschritt: { $message } { $current }
@@ -0,0 +1,180 @@
+## REPL status messages
+repl-welcome = LLM REPL - Type '/help' for commands, !<cmd> for shell, /reader for history.
+repl-workspace-config = INFO: Using workspace settings from { $path }.
+repl-session = Session: { $session } (/sessions to switch, /save <name> to name it).
+repl-session-saved = Session saved as '{ $session }'.
+repl-no-history = INFO: No previous history found or load failed.
+repl-querying = Querying...
+repl-querying-target = Querying { $provider }:{ $model } (this query only)...
+repl-continuing = Continuing the last answer from { $provider }:{ $model }...
+repl-missing-prompt = No prompt given after the @provider:model override.
+repl-cached-offer = You asked this { $minutes } min ago ({ $target }).
+repl-cached-show = Show the previous answer
+repl-cached-fresh = Ask again
+repl-did-you-mean = Unknown command /{ $command }. Run /{ $suggestion } instead?
+repl-included-file = Included { $file }
+repl-included-dataset = Included dataset { $dataset } (schema, statistics and sample rows)
+repl-pending-prompts = Pending prompts ({ $count }):
+repl-exit-ctrl-c = CTRL-C received, exiting.
+repl-exit-ctrl-d = CTRL-D received, exiting.
+repl-command-finished = Finished
+repl-command-failed = Failed: { $error }
+repl-response-ready = Response ready
+repl-query-failed = Query failed
+
+## Error prefixes
+error-generic = Error: { $error }
+error-command = Error executing { $command }: { $error }
+error-shell = Shell Error: { $error }
+error-llm = LLM Error: { $error }
+repl-request-id = (request { $id })
+error-readline = Readline Error: { $error }
+warn-history-add = WARN: Failed to add rustyline history entry: { $error }
+warn-history-save = WARN: Failed to save rustyline history: { $error }
+warn-session-save = WARN: Failed to save session '{ $session }': { $error }
+
+## /reader
+reader-title = --- Session Reader ---
+reader-scroll-hint = Scroll through history below.
+reader-copy-hint = Use your terminal's selection feature to copy blocks.
+reader-empty = History is empty.
+reader-entry-end = --- End ---
+reader-end = --- End of History ---
+reader-usage-total = Answers above: { $usage }
+reader-finished = Reader view finished. Scroll up to see history.
+
+## /tui
+tui-conversation = Conversation
+tui-conversation-scrolled = Conversation ({ $lines } lines up, Down/PgDn to return)
+tui-input = Prompt or /command
+tui-keys = Enter send | Up/Down/PgUp/PgDn scroll | Esc back to the prompt | /exit quit
+tui-streaming = Streaming... { $seconds }s (Esc or Ctrl+C to cancel)
+tui-busy = Wait for the answer to finish, or press Esc to cancel it.
+tui-cancelled = Cancelled
+
+## /help
+help-title = LLM REPL Commands
+help-category-providers = Providers and Models
+help-category-rendering = Rendering
+help-category-session = Session
+help-category-tools = Tools
+help-category-shell = Shell
+help-column-command = Command
+help-column-description = Description
+help-default-title = Default Behavior
+help-current-theme = Current Theme: { $theme }
+help-current-mode = Current Markdown Mode: { $mode }
+help-mode-append = AppendFormatted (Stream raw, append formatted below)
+help-mode-live = LiveStreaming (Experimental, may flicker)
+help-mode-off = Off (Raw text output only)
+help-cmd-help = Show this help message.
+help-cmd-provider =
+    Select LLM provider (interactive if name omitted).
+    Available: ollama, groq, gemini (check API keys).
+help-cmd-apikey = Set, clear or test a provider's API key for this session without restarting, e.g. /apikey groq <key>.
+help-cmd-ground = Toggle Google Search grounding for Gemini, for up-to-date answers that list their web sources (/ground on|off).
+help-cmd-model = Select model for the current provider (interactive if name omitted).
+help-cmd-theme =
+    Select theme (interactive if name omitted).
+    Names: default, nord, gruvbox, grayscale, deuteranopia, protanopia (colorblind-safe).
+help-cmd-theme-status = Show the current theme ({ $theme }).
+help-cmd-md = Set Markdown Mode: Append Formatted (default).
+help-cmd-md-streaming = Set Markdown Mode: Live Streaming (Experimental).
+help-cmd-md-off = Set Markdown Mode: Off (Raw text).
+help-cmd-md-split = Show raw Markdown and its rendering side by side after each response, when the terminal is wide enough (/md_split on|off).
+help-cmd-md-status = Show current Markdown mode (Currently: { $mode }).
+help-cmd-md-source = Set Markdown rendering for one output source:
+    llm, commands, or shell; mode append, live, off, or default.
+help-cmd-llmconvo = Start an interactive setup for LLM-to-LLM conversation.
+help-cmd-diffmodels = Run one prompt against two models, streaming both answers as labeled blocks, then show a word-level diff.
+help-cmd-prefer =
+    After /diffmodels, mark the better answer (/prefer a, b or tie) in preferences.jsonl.
+    /prefer shows win rates per model; /prefer export <file> writes chosen/rejected pairs for fine-tuning.
+help-cmd-cache = Show cached provider model lists and readiness checks (kept 5 minutes); /cache clear fetches them again.
+help-cmd-pull = Download a model to Ollama with a progress bar.
+help-cmd-ollama = Create an Ollama model from a Modelfile.
+help-cmd-rm = Delete a local Ollama model.
+help-cmd-show = Show an Ollama model's family, size, quantization, context length and parameters.
+help-cmd-embed = Embed text (or each line of a file) with an embedding model and show the dimensionality; --out writes the vectors to JSON.
+help-cmd-report = Usage report (default: last week) with queries, tokens and cost per provider/model and the busiest days.
+help-cmd-usage = What this session used: queries, tokens (provider-reported where available) and cost per provider/model.
+help-cmd-eval = Run a TOML suite of prompts with regex/JSON assertions; prints pass/fail and latency per model.
+help-cmd-data = Load a CSV/JSON dataset, show sample rows or locally computed statistics
+    (e.g. /data stats sales revenue by region); reference it in prompts as @data:name.
+help-cmd-db = Connect to a SQLite, Postgres, or MySQL database, show its schema, or run a read-only query.
+help-cmd-sql = Generate a read-only SQL query for a question, confirm and run it, and summarize the rows.
+help-cmd-http = Send an HTTP request and show status, timing, and the body (JSON pretty-printed);
+    /http headers lists configured headers, /http header <name> [value] sets or removes one.
+help-cmd-debug = Log raw provider HTTP requests and responses, with API keys masked, to a debug file (/debug http on [file], /debug http off).
+help-cmd-tee = Mirror everything printed (prompts, responses, command output) to a text or Markdown file (/tee <file> on, /tee off).
+help-cmd-jq = Apply a jq filter to the most recent JSON output (a response, /http, or shell output).
+help-cmd-regex = Match a regular expression against the last response; lists matches with line numbers and groups.
+help-cmd-suggest = Ask the model for a shell command for a goal, using recent conversation as context;
+    it runs only after you confirm (or edit) it, and the output joins the session history.
+help-cmd-ops = Toggle ops mode, or diagnose k8s [namespace], service <unit>, or system with
+    allow-listed kubectl/systemctl/journalctl commands analyzed by the model.
+help-cmd-k8s = Explain a Kubernetes resource kind or a live object (kind/name) with the model (ops mode).
+help-cmd-reader = Display the session output history in a read-only view.
+help-cmd-replay = Re-render the session turn by turn (e.g., /replay 2x). Ctrl+C stops.
+help-cmd-recall = Fuzzy-find past prompts and responses; the selection is placed at the prompt (also Alt+R).
+help-cmd-history = Show history size and retention limits, or apply them now with prune.
+help-cmd-memory = Show the conversation sent with prompts, forget it with clear, or set how many earlier turns are sent (a number, off or all).
+help-cmd-continue = Ask the model to continue its last answer (e.g. one cut off by a token limit); the rest is appended to that answer in the history.
+help-cmd-system = Show, set or clear the system prompt sent with every prompt.
+help-cmd-pin = Pin a history entry (by its /reader number) or a file so it is always included with prompts.
+help-cmd-tag = Label a history entry so it shows in /bookmarks; -<label> removes a label.
+help-cmd-bookmarks = List tagged history entries, optionally only those with one label.
+help-cmd-pins = List pinned context, remove a pin (/pins remove <number>) or clear them all (/pins clear).
+help-cmd-tokens = Count the tokens of some text (with @file references) or, without text, of the context sent with every prompt.
+help-cmd-context = Show what the next prompt sends against the model's context window, or set what happens to turns that don't fit (drop, summarize or off).
+help-cmd-assistant = Import an assistant definition (name, instructions, files) from a JSON or YAML file as the session's system prompt and context.
+help-cmd-checkpoint = Save the session (provider, model, history, hooks, limits, /set parameters) under a name, or list checkpoints.
+help-cmd-rollback = Restore the session saved by /checkpoint.
+help-cmd-save = Save the session (conversation, history and settings) to disk, naming it if a name is given.
+help-cmd-load = Switch to a saved session, saving the current one first, or list the sessions.
+help-cmd-sessions = Pick a session to switch to, switch by name, start a new one (/sessions new), or set its title (/sessions title <text>).
+help-cmd-grep = Search the history of every session (and [on_exit] transcripts) for a regular expression, optionally picking a match to switch to its session.
+help-cmd-stop = Show, add, or clear client-side stop sequences (\n allowed).
+help-cmd-max-length = Show or set the maximum response length in characters.
+help-cmd-set = Show or set generation parameters sent with every query (temperature, top_p, max_tokens, presence_penalty, frequency_penalty) the current provider's timeout and connect_timeout (seconds), and Ollama's num_ctx, num_gpu and keep_alive.
+help-cmd-config = Show the settings, edit the config file in $EDITOR (with a diff before saving), or set one value, e.g. /config set ui.theme nord. Changes apply immediately.
+help-cmd-pace = Show or set typewriter pacing for streamed responses (characters per second).
+help-cmd-notify = Toggle desktop notifications for long tasks or set the threshold.
+help-cmd-hooks = Show or set text added before/after every prompt.
+help-cmd-accessibility = Screen-reader friendly output: plain text, structure announced in words.
+help-cmd-icons = Provider and status icons in the prompt and history headers: nerd (needs a Nerd Font), emoji, or ascii.
+help-cmd-buf =
+    Assemble a long prompt in a named buffer: /buf append <name> <text>, /buf edit <name> (opens $EDITOR).
+    /buf send <name> sends it as the next prompt; /buf, /buf show <name> and /buf clear <name> manage buffers.
+help-cmd-attach = Attach an image (file or data: URL) to the next prompt for a vision model; /attach lists them, /attach clear removes them.
+help-cmd-init =
+    Scan the project and have the model write .llm-repl/context.md, sent with every prompt here.
+    /init force regenerates it.
+help-cmd-watch =
+    Re-run a prompt whenever the file changes ({"{"}content{"}"}, {"{"}file{"}"} placeholders).
+    /watch lists watches; /watch stop <id|all> stops them.
+help-cmd-workflow =
+    Run a workflow from .llm-repl/workflows: prompts, shell and REPL commands in sequence, with conditions on earlier output.
+    /workflow lists the workflows.
+help-cmd-exit = Exit the REPL.
+help-cmd-tui = Switch to the full-screen chat view: scrollable conversation, input box and status bar. Esc returns here.
+help-shell = Execute a shell command (e.g., !ls -l). Output is raw text.
+help-default-query = Any other text input is sent as a query to the current LLM provider and model.
+help-default-override =
+    Send just this prompt to another provider/model
+    (e.g., @groq:llama-3.1-70b-versatile Explain lifetimes).
+help-default-queue = Lines typed while a response is streaming are queued and sent in order once it finishes.
+
+## Accessibility mode (announced structure)
+a11y-heading = Heading level { $level }: { $text }
+a11y-quote = Quote: { $text }
+a11y-code-start = Code block:
+a11y-code-start-language = Code block ({ $language }):
+a11y-code-end = End of code block.
+a11y-table-start = Table:
+a11y-table-row = Row: { $cells }
+a11y-table-end = End of table.
+a11y-separator = Separator.
+a11y-link = { $text } (link: { $url })
+a11y-progress = Progress: { $message } { $current } of { $total }
+a11y-progress-open = Progress: { $message } { $current }
@@ -0,0 +1,95 @@
+// src/admission.rs
+//! Limits how many prompts the server answers at once (`[server.queue]`), so a burst of clients
+//! doesn't open hundreds of upstream streams. Prompts beyond `max_concurrent` wait in a bounded
+//! queue; once that is full too, callers get 429 with a Retry-After header.
+use crate::config::QueueConfig;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json as AxumJson, Response},
+};
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+// Routes that call a provider. Cancelling (`/query/{id}/cancel`) must never wait behind them.
+fn is_queued(method: &Method, path: &str) -> bool {
+    method == Method::POST && matches!(path, "/query" | "/v1/chat/completions")
+}
+
+/// State for the admission middleware: free slots and how many callers wait for one.
+#[derive(Clone)]
+pub struct Admission {
+    slots: Arc<Semaphore>,
+    waiting: Arc<AtomicUsize>,
+    max_waiting: usize,
+    retry_after_secs: u64,
+}
+
+impl Admission {
+    /// Builds the limiter from `[server.queue]`, or None if `max_concurrent` isn't set.
+    pub fn from_config(config: &QueueConfig) -> Option<Self> {
+        let max_concurrent = config.max_concurrent.filter(|max| *max > 0)?;
+        Some(Self {
+            slots: Arc::new(Semaphore::new(max_concurrent)),
+            waiting: Arc::new(AtomicUsize::new(0)),
+            max_waiting: config.max_waiting,
+            retry_after_secs: config.retry_after_secs,
+        })
+    }
+
+    fn saturated(&self) -> Response {
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, AxumJson(json!({
+            "error": StatusCode::TOO_MANY_REQUESTS.canonical_reason().unwrap_or("Error"),
+            "details": format!("The server is answering as many prompts as it allows and its queue is full; retry in {}s.", self.retry_after_secs),
+        }))).into_response();
+        if let Ok(value) = HeaderValue::from_str(&self.retry_after_secs.to_string()) {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+        response
+    }
+}
+
+// Counts a caller as waiting until it gets a slot or gives up (disconnects).
+struct Waiting(Arc<AtomicUsize>);
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Middleware: runs a prompt once a slot is free, holding the slot until its response body ends,
+/// so a streamed answer keeps it for as long as it streams.
+pub async fn limit(State(admission): State<Admission>, request: Request, next: Next) -> Response {
+    if !is_queued(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+    let slot = match admission.slots.clone().try_acquire_owned() {
+        Ok(slot) => slot,
+        Err(_) => {
+            if admission.waiting.fetch_add(1, Ordering::SeqCst) >= admission.max_waiting {
+                admission.waiting.fetch_sub(1, Ordering::SeqCst);
+                warn!("Prompt refused: all slots busy and {} waiting", admission.max_waiting);
+                return admission.saturated();
+            }
+            let _waiting = Waiting(admission.waiting.clone());
+            match admission.slots.clone().acquire_owned().await {
+                Ok(slot) => slot,
+                Err(_) => return admission.saturated(), // Only if the semaphore were closed
+            }
+        }
+    };
+    let response = next.run(request).await;
+    response.map(|body| {
+        Body::from_stream(body.into_data_stream().map(move |chunk| {
+            let _held = &slot;
+            chunk
+        }))
+    })
+}
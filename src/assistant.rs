@@ -0,0 +1,119 @@
+// src/assistant.rs
+//! Assistant definitions in the shape of OpenAI's Assistants/GPTs (`name`, `instructions`,
+//! `tools`, `files`), read from JSON or YAML so assistants written elsewhere can be reused. The
+//! instructions become the session's system prompt and the files its context: text files are
+//! read fresh with every prompt, PDFs and DOCX documents are pinned as their extracted text.
+use crate::config::{PinnedContext, SessionConfig};
+use crate::documents::extract_text;
+use crate::error::{ReplError, ReplResult};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Converted once when imported, since they aren't plain text.
+const DOCUMENT_EXTENSIONS: [&str; 2] = ["pdf", "docx"];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AssistantDefinition {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub instructions: Option<String>,
+    pub model: Option<String>,
+    /// `code_interpreter`, `{ "type": "function", "function": { "name": ... } }`, ...; listed but not run.
+    pub tools: Vec<Value>,
+    /// Knowledge files, relative to the definition file.
+    pub files: Vec<PathBuf>,
+    /// Files uploaded to OpenAI, which can't be fetched; reported so they can be added as `files`.
+    pub file_ids: Vec<String>,
+}
+
+/// What importing a definition changed, for the command's report.
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub context_files: Vec<PathBuf>,
+    pub pinned: Vec<PathBuf>,
+    pub skipped: Vec<String>,
+}
+
+// A tool's name: the string itself, or its `type` (and a function's name).
+fn describe_tool(tool: &Value) -> String {
+    match tool {
+        Value::String(name) => name.clone(),
+        Value::Object(fields) => {
+            let kind = fields.get("type").and_then(Value::as_str).unwrap_or("unknown");
+            match tool.pointer("/function/name").and_then(Value::as_str) {
+                Some(function) => format!("{} {}", kind, function),
+                None => kind.to_string(),
+            }
+        }
+        other => other.to_string(),
+    }
+}
+
+impl AssistantDefinition {
+    /// Reads a definition from a `.json`, `.yaml` or `.yml` file.
+    pub fn load(path: &Path) -> ReplResult<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| ReplError::Command(format!("Could not read '{}': {}", path.display(), e)))?;
+        let yaml = path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+        let definition: Self = if yaml {
+            serde_yaml::from_str(&text).map_err(|e| ReplError::Command(format!("Invalid assistant definition '{}': {}", path.display(), e)))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| ReplError::Command(format!("Invalid assistant definition '{}': {}", path.display(), e)))?
+        };
+        if definition.instructions.as_deref().is_none_or(|text| text.trim().is_empty()) && definition.files.is_empty() {
+            return Err(ReplError::Command(format!("'{}' has neither instructions nor files to import.", path.display())));
+        }
+        Ok(definition)
+    }
+
+    /// The name to show: `name`, else the file's stem.
+    pub fn display_name(&self, path: &Path) -> String {
+        self.name.clone()
+            .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "assistant".to_string())
+    }
+
+    /// Tool names, which this REPL can't run.
+    pub fn tool_names(&self) -> Vec<String> {
+        self.tools.iter().map(describe_tool).collect()
+    }
+
+    /// Makes this assistant the session's: the instructions replace the system prompt and the
+    /// files (relative to `base_dir`) are added as context. Files that can't be used are skipped.
+    pub fn apply(&self, base_dir: &Path, session: &mut SessionConfig) -> Imported {
+        let mut imported = Imported::default();
+        if let Some(instructions) = self.instructions.as_deref().map(str::trim).filter(|text| !text.is_empty()) {
+            session.system_prompt = Some(instructions.to_string());
+        }
+        for file in &self.files {
+            let path = base_dir.join(file);
+            if !path.is_file() {
+                imported.skipped.push(format!("{} (not found)", path.display()));
+                continue;
+            }
+            let document = path.extension().and_then(|ext| ext.to_str())
+                .is_some_and(|ext| DOCUMENT_EXTENSIONS.iter().any(|known| ext.eq_ignore_ascii_case(known)));
+            if !document {
+                if !session.context_files.contains(&path) {
+                    session.context_files.push(path.clone());
+                }
+                imported.context_files.push(path);
+                continue;
+            }
+            match extract_text(&path, None) {
+                Ok(text) => {
+                    let pin = PinnedContext { label: Some(format!("file {}", path.display())), file: None, text: Some(text) };
+                    if !session.pins.contains(&pin) {
+                        session.pins.push(pin);
+                    }
+                    imported.pinned.push(path);
+                }
+                Err(e) => imported.skipped.push(format!("{} ({})", path.display(), e)),
+            }
+        }
+        imported
+    }
+}
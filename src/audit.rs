@@ -0,0 +1,275 @@
+// src/audit.rs
+//! Append-only, hash-chained audit log of REST API calls, plus API-key caller identification.
+//! Each record stores the hash of the previous one, so editing or deleting a line breaks the
+//! chain from that point on (`llm-repl --verify-audit <file>` finds it). The chain has no key, so
+//! records dropped from the end are only noticed against a copy of the last hash. Request bodies
+//! hold prompts and command arguments (keys, among others), so only their hash is recorded.
+use crate::{
+    config::{ApiKey, ApiScope, ServerConfig},
+    error::{ReplError, ReplResult},
+};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json as AxumJson, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+// Hash that the first record chains from.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+// Largest request body accepted (and recorded) while auditing.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+// Caller recorded for requests without a valid API key.
+const UNAUTHENTICATED: &str = "unauthenticated";
+const ANONYMOUS: &str = "anonymous";
+
+/// One line of the audit log. A call produces a `request` record before it runs and a
+/// `response` record with the status once it has been handled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AuditRecord {
+    seq: u64,
+    timestamp: DateTime<Utc>,
+    event: String, // "request" or "response"
+    caller: String,
+    method: String,
+    path: String,
+    /// The request body, as records written before bodies were hashed kept it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// SHA-256 of the request body, to match a call against what a client sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_sha256: Option<String>,
+    /// The command a `POST /command` call ran, without its arguments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    prev_hash: String,
+    #[serde(default)]
+    hash: String,
+}
+
+impl AuditRecord {
+    // SHA-256 over the record serialized with an empty `hash` field (which includes `prev_hash`).
+    fn compute_hash(&self) -> String {
+        let unhashed = AuditRecord { hash: String::new(), ..self.clone() };
+        let serialized = serde_json::to_string(&unhashed).unwrap_or_default();
+        format!("{:x}", Sha256::digest(serialized.as_bytes()))
+    }
+}
+
+struct AuditWriter {
+    file: File,
+    last_hash: String,
+    next_seq: u64,
+}
+
+/// An audit log file opened for appending; records are chained to whatever the file already holds.
+pub struct AuditLog {
+    path: PathBuf,
+    writer: Arc<Mutex<AuditWriter>>,
+}
+
+impl AuditLog {
+    pub fn open(path: &Path) -> ReplResult<Self> {
+        let (last_hash, next_seq) = match File::open(path) {
+            Ok(existing) => {
+                let last = BufReader::new(existing)
+                    .lines()
+                    .map_while(Result::ok)
+                    .filter(|line| !line.trim().is_empty())
+                    .last();
+                match last {
+                    Some(line) => {
+                        let record: AuditRecord = serde_json::from_str(&line).map_err(|e| ReplError::Command(format!(
+                            "Audit log '{}' ends with an unreadable record ({}); refusing to extend it.", path.display(), e
+                        )))?;
+                        (record.hash, record.seq + 1)
+                    }
+                    None => (GENESIS_HASH.to_string(), 0),
+                }
+            }
+            Err(_) => (GENESIS_HASH.to_string(), 0),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { path: path.to_path_buf(), writer: Arc::new(Mutex::new(AuditWriter { file, last_hash, next_seq })) })
+    }
+
+    // The write and sync block, so they run off the async runtime; the lock keeps records in order.
+    async fn append(&self, mut record: AuditRecord) -> ReplResult<()> {
+        let writer = Arc::clone(&self.writer);
+        tokio::task::spawn_blocking(move || {
+            let mut writer = writer.lock().map_err(|_| ReplError::Command("Audit writer is poisoned.".to_string()))?;
+            record.seq = writer.next_seq;
+            record.prev_hash = writer.last_hash.clone();
+            record.hash = record.compute_hash();
+            let line = serde_json::to_string(&record)?;
+            writeln!(writer.file, "{}", line)?;
+            writer.file.sync_data()?;
+            writer.last_hash = record.hash;
+            writer.next_seq += 1;
+            Ok(())
+        })
+        .await
+        .map_err(|e| ReplError::Command(format!("Audit write failed to run: {}", e)))?
+    }
+}
+
+/// Checks every record's hash and link to its predecessor. Returns the number of records and the
+/// last record's hash, which only matches a copy kept elsewhere if no records were cut off the end.
+pub fn verify_chain(path: &Path) -> ReplResult<(usize, String)> {
+    let file = File::open(path)?;
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut count = 0;
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() { continue; }
+        let line_number = index + 1;
+        let record: AuditRecord = serde_json::from_str(&line)
+            .map_err(|e| ReplError::Command(format!("Line {}: unreadable record: {}", line_number, e)))?;
+        if record.prev_hash != expected_prev {
+            return Err(ReplError::Command(format!("Line {}: chain broken (record {} does not follow the previous record).", line_number, record.seq)));
+        }
+        if record.compute_hash() != record.hash {
+            return Err(ReplError::Command(format!("Line {}: record {} was modified (hash mismatch).", line_number, record.seq)));
+        }
+        expected_prev = record.hash;
+        count += 1;
+    }
+    Ok((count, expected_prev))
+}
+
+// Compares without exiting early, so response timing doesn't reveal how much of a key matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// State for the API guard middleware: configured API keys and the optional audit log.
+#[derive(Clone)]
+pub struct ApiGuard {
+    api_keys: Arc<Vec<ApiKey>>,
+    audit: Option<Arc<AuditLog>>,
+}
+
+impl ApiGuard {
+    /// Builds the guard from the `[server]` config, or None if neither keys nor an audit log are set.
+    pub fn from_config(config: &ServerConfig) -> ReplResult<Option<Self>> {
+        let audit = match &config.audit_log {
+            Some(path) => Some(Arc::new(AuditLog::open(path)?)),
+            None => None,
+        };
+        if audit.is_none() && config.api_keys.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self { api_keys: Arc::new(config.api_keys.clone()), audit }))
+    }
+
+    pub fn audit_path(&self) -> Option<&Path> {
+        self.audit.as_ref().map(|log| log.path.as_path())
+    }
+
+    // The key presented as `Authorization: Bearer <key>` or `X-API-Key: <key>`, as (id, scope).
+    // Without configured keys every caller is anonymous with full access.
+    fn identify(&self, request: &Request) -> Option<(String, ApiScope)> {
+        if self.api_keys.is_empty() {
+            return Some((ANONYMOUS.to_string(), ApiScope::Admin));
+        }
+        let headers = request.headers();
+        let presented = headers.get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))?
+            .trim();
+        self.api_keys.iter()
+            .find(|k| constant_time_eq(k.key.as_bytes(), presented.as_bytes()))
+            .map(|k| (k.id.clone(), k.scope))
+    }
+
+    async fn record(&self, record: AuditRecord) -> bool {
+        let Some(log) = &self.audit else { return true };
+        match log.append(record).await {
+            Ok(()) => true,
+            Err(e) => {
+                error!("Failed to write audit record to '{}': {}", log.path.display(), e);
+                false
+            }
+        }
+    }
+}
+
+// Scope needed for a route: reads for GETs, query for prompt endpoints, admin for the rest
+// (`/command`, `/shell`, and anything added later until it is classified here).
+fn required_scope(method: &Method, path: &str) -> ApiScope {
+    match (method, path) {
+        (&Method::GET, _) => ApiScope::Read,
+        (&Method::POST, "/query" | "/v1/chat/completions") => ApiScope::Query,
+        (&Method::POST, path) if path.starts_with("/query/") && path.ends_with("/cancel") => ApiScope::Query,
+        _ => ApiScope::Admin,
+    }
+}
+
+// The command name of a `POST /command` body (`{"command": "apikey groq ..."}` gives `apikey`).
+fn command_name(path: &str, body: &[u8]) -> Option<String> {
+    if path != "/command" {
+        return None;
+    }
+    let body: serde_json::Value = serde_json::from_slice(body).ok()?;
+    body.get("command")?.as_str()?.split_whitespace().next().map(str::to_string)
+}
+
+fn guard_error(status: StatusCode, message: &str) -> Response {
+    let body = AxumJson(json!({ "error": status.canonical_reason().unwrap_or("Error"), "details": message }));
+    (status, body).into_response()
+}
+
+/// Middleware: identifies the caller by API key, checks the key's scope for the route, and audits
+/// the call. Calls are refused if their request record can't be written, so nothing runs unaudited.
+pub async fn guard(State(guard): State<ApiGuard>, request: Request, next: Next) -> Response {
+    let caller = guard.identify(&request);
+    let required = required_scope(request.method(), request.uri().path());
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return guard_error(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large."),
+    };
+    let base = AuditRecord {
+        seq: 0,
+        timestamp: Utc::now(),
+        event: "request".to_string(),
+        caller: caller.as_ref().map(|(id, _)| id.clone()).unwrap_or_else(|| UNAUTHENTICATED.to_string()),
+        command: command_name(&path, &bytes),
+        method,
+        path,
+        body: None,
+        body_sha256: (!bytes.is_empty()).then(|| format!("{:x}", Sha256::digest(&bytes))),
+        status: None,
+        prev_hash: String::new(),
+        hash: String::new(),
+    };
+    if !guard.record(base.clone()).await {
+        return guard_error(StatusCode::SERVICE_UNAVAILABLE, "Audit log unavailable; request refused.");
+    }
+
+    let response = match caller {
+        Some((_, scope)) if scope >= required => next.run(Request::from_parts(parts, Body::from(bytes))).await,
+        Some((_, scope)) => guard_error(StatusCode::FORBIDDEN, &format!("This API key's '{:?}' scope does not allow this call; it needs '{:?}'.", scope, required)),
+        None => guard_error(StatusCode::UNAUTHORIZED, "Missing or invalid API key."),
+    };
+
+    let outcome = AuditRecord { timestamp: Utc::now(), event: "response".to_string(), body_sha256: None, command: None, status: Some(response.status().as_u16()), ..base };
+    guard.record(outcome).await;
+    response
+}
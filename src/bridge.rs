@@ -0,0 +1,307 @@
+// src/bridge.rs
+//! `--bridge slack|discord`: relays messages from chat channels to the current provider and posts
+//! the answers back as replies. Messages starting with `/` run commands, but only those listed in
+//! the bridge's `commands` (and enabled for remote use), since anyone in a channel can send them.
+//! The bridge polls the platform's REST API, so it needs no public URL or gateway socket.
+use crate::{
+    config::{BridgeSettings, CommandOrigin},
+    error::{ReplError, ReplResult},
+    history, request_id,
+    state::{AppState, HistoryContentType, HistoryEntry},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const SLACK_API: &str = "https://slack.com/api";
+const DISCORD_API: &str = "https://discord.com/api/v10";
+// Discord snowflakes count milliseconds from 2015-01-01.
+const DISCORD_EPOCH_MS: i64 = 1_420_070_400_000;
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum BridgePlatform {
+    Slack,
+    Discord,
+}
+
+// One incoming channel message.
+struct ChatMessage {
+    id: String,
+    author: String,
+    text: String,
+    from_bot: bool,
+}
+
+#[async_trait]
+trait ChatClient: Send + Sync {
+    /// The bot's own user id, used to skip its replies and strip its mentions.
+    async fn bot_user(&self) -> ReplResult<String>;
+    /// A cursor for "messages from now on".
+    fn start_cursor(&self) -> String;
+    /// Messages after `cursor`, oldest first.
+    async fn fetch_since(&self, channel: &str, cursor: &str) -> ReplResult<Vec<ChatMessage>>;
+    /// Posts `text` as a reply to `message`.
+    async fn reply(&self, channel: &str, message: &ChatMessage, text: &str) -> ReplResult<()>;
+    /// Longest text one message may carry.
+    fn max_message_len(&self) -> usize;
+}
+
+struct SlackClient {
+    http: Client,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct SlackMessage {
+    ts: String,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    bot_id: Option<String>,
+    #[serde(default)]
+    subtype: Option<String>,
+}
+
+impl SlackClient {
+    // Slack answers 200 with `"ok": false` on failure.
+    async fn call(&self, request: RequestBuilder) -> ReplResult<Value> {
+        let body: Value = request.bearer_auth(&self.token).send().await?.json().await?;
+        if body["ok"].as_bool() != Some(true) {
+            return Err(ReplError::Bridge(format!("Slack: {}", body["error"].as_str().unwrap_or("request failed"))));
+        }
+        Ok(body)
+    }
+}
+
+#[async_trait]
+impl ChatClient for SlackClient {
+    async fn bot_user(&self) -> ReplResult<String> {
+        let body = self.call(self.http.post(format!("{}/auth.test", SLACK_API))).await?;
+        body["user_id"].as_str().map(str::to_string).ok_or_else(|| ReplError::Bridge("Slack: auth.test returned no user id".to_string()))
+    }
+
+    fn start_cursor(&self) -> String {
+        format!("{}.000000", Utc::now().timestamp())
+    }
+
+    async fn fetch_since(&self, channel: &str, cursor: &str) -> ReplResult<Vec<ChatMessage>> {
+        let request = self.http.get(format!("{}/conversations.history", SLACK_API))
+            .query(&[("channel", channel), ("oldest", cursor), ("limit", "100")]);
+        let body = self.call(request).await?;
+        let messages: Vec<SlackMessage> = serde_json::from_value(body["messages"].clone())?;
+        // Newest first; edits, joins and other subtypes aren't prompts
+        Ok(messages.into_iter().rev()
+            .filter(|message| message.subtype.is_none())
+            .map(|message| ChatMessage {
+                from_bot: message.bot_id.is_some(),
+                author: message.user.unwrap_or_default(),
+                id: message.ts,
+                text: message.text,
+            })
+            .collect())
+    }
+
+    async fn reply(&self, channel: &str, message: &ChatMessage, text: &str) -> ReplResult<()> {
+        let request = self.http.post(format!("{}/chat.postMessage", SLACK_API))
+            .json(&json!({ "channel": channel, "text": text, "thread_ts": message.id }));
+        self.call(request).await.map(|_| ())
+    }
+
+    fn max_message_len(&self) -> usize { 3900 }
+}
+
+struct DiscordClient {
+    http: Client,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct DiscordMessage {
+    id: String,
+    #[serde(default)]
+    content: String,
+    author: DiscordAuthor,
+}
+
+#[derive(Deserialize)]
+struct DiscordAuthor {
+    id: String,
+    #[serde(default)]
+    bot: bool,
+}
+
+impl DiscordClient {
+    async fn call(&self, request: RequestBuilder) -> ReplResult<Value> {
+        let response = request.header("Authorization", format!("Bot {}", self.token)).send().await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(ReplError::Bridge(format!("Discord: {} {}", status, body["message"].as_str().unwrap_or_default())));
+        }
+        Ok(body)
+    }
+}
+
+#[async_trait]
+impl ChatClient for DiscordClient {
+    async fn bot_user(&self) -> ReplResult<String> {
+        let body = self.call(self.http.get(format!("{}/users/@me", DISCORD_API))).await?;
+        body["id"].as_str().map(str::to_string).ok_or_else(|| ReplError::Bridge("Discord: /users/@me returned no id".to_string()))
+    }
+
+    fn start_cursor(&self) -> String {
+        ((Utc::now().timestamp_millis() - DISCORD_EPOCH_MS) << 22).to_string()
+    }
+
+    async fn fetch_since(&self, channel: &str, cursor: &str) -> ReplResult<Vec<ChatMessage>> {
+        let request = self.http.get(format!("{}/channels/{}/messages", DISCORD_API, channel))
+            .query(&[("after", cursor), ("limit", "100")]);
+        let messages: Vec<DiscordMessage> = serde_json::from_value(self.call(request).await?)?;
+        Ok(messages.into_iter().rev()
+            .map(|message| ChatMessage { id: message.id, author: message.author.id, text: message.content, from_bot: message.author.bot })
+            .collect())
+    }
+
+    async fn reply(&self, channel: &str, message: &ChatMessage, text: &str) -> ReplResult<()> {
+        let request = self.http.post(format!("{}/channels/{}/messages", DISCORD_API, channel))
+            .json(&json!({ "content": text, "message_reference": { "message_id": message.id } }));
+        self.call(request).await.map(|_| ())
+    }
+
+    fn max_message_len(&self) -> usize { 2000 }
+}
+
+// Splits a reply into message-sized parts, preferring line breaks.
+fn split_message(text: &str, max_len: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for line in text.split_inclusive('\n') {
+        for piece in line.chars().collect::<Vec<_>>().chunks(max_len) {
+            let piece: String = piece.iter().collect();
+            if current.chars().count() + piece.chars().count() > max_len {
+                parts.push(std::mem::take(&mut current));
+            }
+            current.push_str(&piece);
+        }
+    }
+    if !current.trim().is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+// The reply for a failed request. Provider and transport errors can carry URLs with keys (Gemini's
+// `?key=`) or other details not meant for a shared channel, so only refusals are spelled out; the
+// full error is in the local log under the request id.
+fn channel_error(e: &ReplError, request: &str) -> String {
+    match e {
+        ReplError::UnknownCommand(_) | ReplError::CommandDisabled(_) => format!("Error: {} (request {})", e, request),
+        _ => format!("Sorry, that request failed (request {}); the details are in the bridge's log.", request),
+    }
+}
+
+// Runs one message as a command or a prompt, recording it in the session history like the server does.
+async fn answer(state: &AppState, settings: &BridgeSettings, text: &str) -> ReplResult<String> {
+    let started = Instant::now();
+    if let Some(command_line) = text.strip_prefix('/') {
+        let (name, args) = command_line.split_once(char::is_whitespace).unwrap_or((command_line, ""));
+        let registry = state.command_registry();
+        let command = registry.get_command(name).ok_or_else(|| ReplError::UnknownCommand(name.to_string()))?;
+        if !settings.commands.contains(name) || !state.is_command_enabled(name, CommandOrigin::Remote).await {
+            return Err(ReplError::CommandDisabled(name.to_string()));
+        }
+        let output = command.execute(args.trim()).await?;
+        state.add_history_entry(
+            HistoryEntry::new(HistoryContentType::CommandResult { command: history::recorded_command(command_line) }, output.clone())
+                .with_duration(started.elapsed()),
+        ).await;
+        return Ok(output);
+    }
+    let provider_name = state.get_provider_name().await;
+    let provider = state.get_current_provider().await.ok_or_else(|| ReplError::UnknownProvider(provider_name.clone()))?;
+    let model = state.get_model().await;
+    let messages = state.get_config().await.prompt_messages(text);
+    let params = state.get_generation_params().await;
+    let response = state.get_output_limits().await.apply(&provider.chat(&model, &messages, &params).await?);
+    state.add_history_entry(
+        HistoryEntry::new(HistoryContentType::LlmResponse { model: model.clone() }, response.clone())
+            .with_model(&provider_name, &model)
+            .with_prompt(text)
+            .with_duration(started.elapsed()),
+    ).await;
+    Ok(response)
+}
+
+/// Relays the configured channels until the process is stopped.
+pub async fn run_bridge(state: AppState, platform: BridgePlatform) -> ReplResult<()> {
+    let config = state.get_config().await;
+    let (settings, token_var): (&BridgeSettings, _) = match platform {
+        BridgePlatform::Slack => (&config.bridge.slack, "SLACK_BOT_TOKEN"),
+        BridgePlatform::Discord => (&config.bridge.discord, "DISCORD_BOT_TOKEN"),
+    };
+    let section = format!("[bridge.{}]", format!("{:?}", platform).to_lowercase());
+    let token = settings.token.clone().or_else(|| std::env::var(token_var).ok())
+        .ok_or_else(|| ReplError::Bridge(format!("No bot token: set {} or token under {}", token_var, section)))?;
+    if settings.channels.is_empty() {
+        return Err(ReplError::Bridge(format!("No channels to relay: set channels under {}", section)));
+    }
+    let http = Client::builder().timeout(Duration::from_secs(30)).build()?;
+    let client: Box<dyn ChatClient> = match platform {
+        BridgePlatform::Slack => Box::new(SlackClient { http, token }),
+        BridgePlatform::Discord => Box::new(DiscordClient { http, token }),
+    };
+    let bot_user = client.bot_user().await?;
+    let mention = format!("<@{}>", bot_user);
+    let mut cursors: HashMap<String, String> = settings.channels.iter().map(|channel| (channel.clone(), client.start_cursor())).collect();
+    let interval = Duration::from_secs(settings.poll_interval_secs.max(1));
+    println!("Bridging {} channel(s) on {:?} as {}. Press Ctrl+C to stop.", cursors.len(), platform, bot_user);
+
+    loop {
+        for channel in &settings.channels {
+            let messages = match client.fetch_since(channel, &cursors[channel]).await {
+                Ok(messages) => messages,
+                Err(e) => {
+                    warn!("Bridge: reading {} failed: {}", channel, e);
+                    continue;
+                }
+            };
+            for message in messages {
+                cursors.insert(channel.clone(), message.id.clone());
+                if message.from_bot || message.author == bot_user || (settings.mention_only && !message.text.contains(&mention)) {
+                    continue;
+                }
+                if !settings.users.is_empty() && !settings.users.contains(&message.author) {
+                    continue;
+                }
+                let text = message.text.replace(&mention, "");
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                let request = request_id::next();
+                let reply = match request_id::scope(request.clone(), answer(&state, settings, text)).await {
+                    Ok(output) if output.trim().is_empty() => "(no output)".to_string(),
+                    Ok(output) => output,
+                    Err(e) => {
+                        warn!("Bridge: request {} in {} failed: {}", request, channel, e);
+                        channel_error(&e, &request)
+                    }
+                };
+                for part in split_message(&reply, client.max_message_len()) {
+                    if let Err(e) = client.reply(channel, &message, &part).await {
+                        warn!("Bridge: replying in {} failed: {}", channel, e);
+                        break;
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
@@ -0,0 +1,84 @@
+// src/cache.rs
+//! Results of slow read-only provider lookups (model lists and readiness checks), kept for a few
+//! minutes so `/model`, `/provider`, `/llmconvo` and the model listing endpoints don't ask the
+//! provider again each time. Failures aren't kept. Rebuilding the providers clears everything;
+//! `/cache clear` does so by hand.
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::providers::ModelInfo;
+
+/// How long a lookup is reused before the provider is asked again.
+pub const TTL: Duration = Duration::from_secs(300);
+
+/// What is cached for one provider.
+#[derive(Default)]
+struct Entry {
+    models: Option<(Instant, Vec<ModelInfo>)>,
+    ready: Option<Instant>,
+}
+
+/// One cached lookup, for `/cache`.
+pub struct CachedLookup {
+    pub provider: String,
+    pub what: String,
+    pub age: Duration,
+}
+
+#[derive(Default)]
+pub struct LookupCache {
+    entries: Mutex<BTreeMap<String, Entry>>,
+}
+
+fn fresh(stored: Instant) -> bool {
+    stored.elapsed() < TTL
+}
+
+impl LookupCache {
+    fn entries(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The provider's model list, if it was fetched within the TTL.
+    pub fn models(&self, provider: &str) -> Option<Vec<ModelInfo>> {
+        let entries = self.entries();
+        let (stored, models) = entries.get(provider)?.models.as_ref()?;
+        fresh(*stored).then(|| models.clone())
+    }
+
+    pub fn store_models(&self, provider: &str, models: &[ModelInfo]) {
+        self.entries().entry(provider.to_string()).or_default().models = Some((Instant::now(), models.to_vec()));
+    }
+
+    /// Whether the provider passed a readiness check within the TTL.
+    pub fn is_ready(&self, provider: &str) -> bool {
+        self.entries().get(provider).and_then(|entry| entry.ready).is_some_and(fresh)
+    }
+
+    pub fn mark_ready(&self, provider: &str) {
+        self.entries().entry(provider.to_string()).or_default().ready = Some(Instant::now());
+    }
+
+    /// Forgets everything; returns how many providers had cached lookups.
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries();
+        let count = entries.len();
+        entries.clear();
+        count
+    }
+
+    /// The lookups still within the TTL, by provider.
+    pub fn list(&self) -> Vec<CachedLookup> {
+        let mut lookups = Vec::new();
+        for (provider, entry) in self.entries().iter() {
+            if let Some((stored, models)) = entry.models.as_ref().filter(|(stored, _)| fresh(*stored)) {
+                lookups.push(CachedLookup { provider: provider.clone(), what: format!("{} models", models.len()), age: stored.elapsed() });
+            }
+            if let Some(stored) = entry.ready.filter(|stored| fresh(*stored)) {
+                lookups.push(CachedLookup { provider: provider.clone(), what: "ready".to_string(), age: stored.elapsed() });
+            }
+        }
+        lookups
+    }
+}
@@ -0,0 +1,69 @@
+// src/cancel.rs
+//! Generations running for the server, by request ID, so `POST /query/{id}/cancel` can stop one
+//! whatever the provider: the generation's future is dropped, which closes the connection to the
+//! provider, and the handler records what had arrived in history, marked as cancelled.
+use futures::{channel::oneshot, Stream, StreamExt};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref RUNNING: Mutex<HashMap<String, oneshot::Sender<()>>> = Mutex::new(HashMap::new());
+}
+
+/// A generation that can be cancelled by its request ID until this is dropped.
+pub struct Cancellable {
+    id: String,
+    signal: oneshot::Receiver<()>,
+    cancelled: bool,
+}
+
+impl Cancellable {
+    /// Registers request `id`, replacing an earlier generation with the same ID.
+    pub fn register(id: &str) -> Self {
+        let (sender, signal) = oneshot::channel();
+        RUNNING.lock().unwrap_or_else(|e| e.into_inner()).insert(id.to_string(), sender);
+        Self { id: id.to_string(), signal, cancelled: false }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Runs `future` until it finishes, or drops it and returns None if the request is cancelled.
+    pub async fn run<F: Future>(&mut self, future: F) -> Option<F::Output> {
+        if self.cancelled {
+            return None;
+        }
+        tokio::select! {
+            output = future => Some(output),
+            // Err means it was replaced, not cancelled; then only the future is awaited
+            Ok(()) = &mut self.signal => {
+                self.cancelled = true;
+                None
+            }
+        }
+    }
+
+    /// The stream's next item; None when it ends or the request is cancelled.
+    pub async fn next<S: Stream + Unpin>(&mut self, stream: &mut S) -> Option<S::Item> {
+        self.run(stream.next()).await.flatten()
+    }
+}
+
+impl Drop for Cancellable {
+    fn drop(&mut self) {
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        // Only if it wasn't replaced by a newer generation with the same ID
+        if running.get(&self.id).is_some_and(|sender| sender.is_connected_to(&self.signal)) {
+            running.remove(&self.id);
+        }
+    }
+}
+
+/// Cancels the generation for request `id`; false if none is running.
+pub fn cancel(id: &str) -> bool {
+    let sender = RUNNING.lock().unwrap_or_else(|e| e.into_inner()).remove(id);
+    sender.is_some_and(|sender| sender.send(()).is_ok())
+}
@@ -0,0 +1,51 @@
+// src/commands/accessibility.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    icons,
+    render::apply_accessibility,
+    state::{AppState, MarkdownMode},
+};
+
+#[derive(Clone)]
+pub struct AccessibilityCommand {
+    state: AppState,
+}
+
+impl AccessibilityCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for AccessibilityCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+
+        match args.trim().to_lowercase().as_str() {
+            "" => {}
+            "on" => config.ui.accessible = true,
+            "off" => config.ui.accessible = false,
+            other => {
+                return Err(ReplError::Command(format!("Invalid argument '{}'. Use /accessibility <on|off>.", other)));
+            }
+        }
+        // Live rendering redraws the screen with cursor movement, which screen readers can't follow
+        if config.ui.accessible && self.state.get_markdown_mode().await == MarkdownMode::LiveStreaming {
+            self.state.set_markdown_mode(MarkdownMode::AppendFormatted).await;
+        }
+        apply_accessibility(config.ui.accessible);
+        icons::apply(&config.ui);
+        let status = format!("Accessibility mode: {}", if config.ui.accessible { "On" } else { "Off" });
+        self.state.set_config(config).await;
+        Ok(status)
+    }
+
+    fn name(&self) -> &str { "accessibility" }
+    fn help(&self) -> &str { "Toggle screen-reader friendly output: plain sequential text with announced structure (/accessibility <on|off>)." }
+    fn usage(&self) -> &str { "[on|off]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
+}
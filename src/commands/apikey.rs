@@ -0,0 +1,97 @@
+// src/commands/apikey.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    providers::{group, BUILT_IN_PROVIDERS},
+    state::AppState,
+};
+
+const USAGE: &str = "Usage: /apikey [<provider> <key> | <provider> clear | <provider> test]";
+
+// The last few characters, enough to tell keys apart without revealing them.
+fn masked(key: &str) -> String {
+    let tail: String = key.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    format!("...{}", tail)
+}
+
+pub struct ApiKeyCommand {
+    state: AppState,
+}
+
+impl ApiKeyCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // Built-in provider types plus the instances declared under [providers], without groups.
+    async fn provider_names(&self) -> Vec<String> {
+        let config = self.state.get_config().await;
+        let mut names: Vec<String> = BUILT_IN_PROVIDERS.iter().map(|name| name.to_string()).collect();
+        names.extend(config.providers.iter()
+            .filter(|(name, settings)| !BUILT_IN_PROVIDERS.contains(&name.as_str()) && settings.kind.as_deref() != Some("group"))
+            .map(|(name, _)| name.clone()));
+        names
+    }
+
+    async fn list(&self) -> ReplResult<String> {
+        let providers = self.state.get_config().await.providers;
+        let mut listing = String::from("API keys (set with /apikey <provider> <key>; others come from the environment):");
+        for name in self.provider_names().await {
+            let key = providers.get(&name).and_then(|settings| settings.api_key.as_deref());
+            listing.push_str(&format!("\n  {:<12} {}", name, key.map_or_else(|| "-".to_string(), masked)));
+        }
+        Ok(listing)
+    }
+
+    // Replaces the provider's key (None falls back to its environment variable) and rebuilds it.
+    async fn set_key(&self, provider: &str, key: Option<&str>) -> ReplResult<String> {
+        let mut settings = self.state.get_config().await.providers.get(provider).cloned().unwrap_or_default();
+        settings.api_key = key.map(str::to_string);
+        self.state.set_provider_settings(provider, settings).await?;
+        Ok(match key {
+            Some(key) => format!("API key for {} set to {} for this session (/apikey {} test to check it).", provider, masked(key), provider),
+            None => format!("API key for {} cleared; its environment variable applies again, if set.", provider),
+        })
+    }
+
+    // Asks the provider itself, bypassing the lookup cache, so a new key is really checked.
+    async fn test(&self, provider: &str) -> ReplResult<String> {
+        let Some(instance) = self.state.get_provider_by_name(provider) else {
+            return Err(ReplError::Provider(format!("{} is not available; it may need an API key.", provider)));
+        };
+        instance.check_readiness().await
+            .map_err(|e| ReplError::Provider(format!("{} is not ready: {}", provider, e)))?;
+        let models = instance.get_models().await
+            .map_err(|e| ReplError::Provider(format!("{} is ready, but listing its models failed: {}", provider, e)))?;
+        Ok(format!("{} accepted the key ({} models available).", provider, models.len()))
+    }
+}
+
+#[async_trait]
+impl Command for ApiKeyCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let Some(provider) = parts.first().map(|name| name.to_lowercase()) else {
+            return self.list().await;
+        };
+        if provider.starts_with(group::PREFIX) {
+            return Err(ReplError::Command(format!("{} is a provider group; set keys for its members instead.", provider)));
+        }
+        if !self.provider_names().await.contains(&provider) {
+            return Err(ReplError::UnknownProvider(provider));
+        }
+        match parts[1..] {
+            ["clear"] => self.set_key(&provider, None).await,
+            ["test"] => self.test(&provider).await,
+            [key] => self.set_key(&provider, Some(key)).await,
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "apikey" }
+    fn help(&self) -> &str { "Set, clear, or test a provider's API key for this session, without restarting (/apikey groq <key>)." }
+    fn usage(&self) -> &str { "[<provider> <key>|clear|test]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
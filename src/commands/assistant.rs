@@ -0,0 +1,82 @@
+// src/commands/assistant.rs
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    assistant::AssistantDefinition,
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const USAGE: &str = "Usage: /assistant import <file.json|file.yaml>";
+
+fn list(paths: &[PathBuf]) -> String {
+    paths.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+pub struct AssistantCommand {
+    state: AppState,
+}
+
+impl AssistantCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn import(&self, file: &str) -> ReplResult<String> {
+        let path = Path::new(file);
+        let definition = AssistantDefinition::load(path)?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        let mut config = self.state.get_config().await;
+        let imported = definition.apply(base_dir, &mut config.session);
+        self.state.set_config(config).await;
+
+        let mut report = format!("Imported assistant '{}'.", definition.display_name(path));
+        if let Some(description) = definition.description.as_deref().filter(|text| !text.trim().is_empty()) {
+            report.push_str(&format!(" {}", description.trim()));
+        }
+        if definition.instructions.as_deref().is_some_and(|text| !text.trim().is_empty()) {
+            report.push_str("\n  System prompt: set from its instructions");
+        }
+        if !imported.context_files.is_empty() {
+            report.push_str(&format!("\n  Context files (read with every prompt): {}", list(&imported.context_files)));
+        }
+        if !imported.pinned.is_empty() {
+            report.push_str(&format!("\n  Pinned documents (/pins to list): {}", list(&imported.pinned)));
+        }
+        if !imported.skipped.is_empty() {
+            report.push_str(&format!("\n  Skipped: {}", imported.skipped.join(", ")));
+        }
+        let tools = definition.tool_names();
+        if !tools.is_empty() {
+            report.push_str(&format!("\n  Tools aren't available here and were left out: {}", tools.join(", ")));
+        }
+        if !definition.file_ids.is_empty() {
+            report.push_str(&format!(
+                "\n  {} uploaded file(s) (file_ids) can't be fetched; download them and list them under files.",
+                definition.file_ids.len()
+            ));
+        }
+        if let Some(model) = &definition.model {
+            report.push_str(&format!("\n  It was written for {}; switch with /model if you have it.", model));
+        }
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl Command for AssistantCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        match args.trim().split_once(char::is_whitespace) {
+            Some(("import", file)) if !file.trim().is_empty() => self.import(file.trim()).await,
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "assistant" }
+    fn help(&self) -> &str { "Import an assistant definition (name, instructions, files) from JSON or YAML as the session's system prompt and context." }
+    fn usage(&self) -> &str { "import <file>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,56 @@
+// src/commands/attach.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::ReplResult,
+    providers::ImageAttachment,
+    state::AppState,
+};
+
+pub struct AttachCommand {
+    state: AppState,
+}
+
+impl AttachCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for AttachCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        match args.trim() {
+            "" => {
+                let images = self.state.get_attachments().await;
+                if images.is_empty() {
+                    return Ok("No images attached. Use /attach <path> to send one with the next prompt.".to_string());
+                }
+                let mut output = format!("{} image(s) will be sent with the next prompt:", images.len());
+                for image in &images {
+                    output.push_str(&format!("\n  {} ({}, {} KB)", image.name, image.mime_type, image.bytes.div_ceil(1024)));
+                }
+                Ok(output)
+            }
+            "clear" => {
+                let removed = self.state.clear_attachments().await;
+                Ok(format!("Removed {} attached image(s).", removed))
+            }
+            source => {
+                let image = ImageAttachment::load(source)?;
+                let name = image.name.clone();
+                let count = self.state.attach_image(image).await;
+                Ok(format!(
+                    "Attached {}; {} image(s) will be sent with the next prompt. The model must accept images (e.g. Gemini, or llava on Ollama).",
+                    name, count
+                ))
+            }
+        }
+    }
+
+    fn name(&self) -> &str { "attach" }
+    fn help(&self) -> &str { "Attach an image (a file, or a data: URL) to send with the next prompt to a vision model; without arguments lists the attached images, /attach clear removes them." }
+    fn usage(&self) -> &str { "[<path>|clear]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,106 @@
+// src/commands/buf.rs
+use async_trait::async_trait;
+use dialoguer::Editor;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const USAGE: &str = "Use /buf [list], /buf show|edit|send|clear <name>, or /buf append <name> <text>.";
+
+/// The buffer named by `/buf send <name>`, which the REPL sends like a typed prompt.
+pub fn send_target(args: &str) -> Option<&str> {
+    let (action, name) = args.trim().split_once(char::is_whitespace)?;
+    (action == "send").then(|| name.trim()).filter(|name| !name.is_empty())
+}
+
+fn check_name(name: &str) -> ReplResult<&str> {
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(ReplError::Command(format!("Invalid buffer name '{}'. Use letters, digits, '-' and '_'.", name)));
+    }
+    Ok(name)
+}
+
+/// Named scratch buffers for assembling a long prompt over several inputs before sending it.
+pub struct BufCommand {
+    state: AppState,
+}
+
+impl BufCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn existing(&self, name: &str) -> ReplResult<String> {
+        self.state.get_buffer(name).await
+            .ok_or_else(|| ReplError::Command(format!("No buffer named '{}'. /buf lists them.", name)))
+    }
+}
+
+#[async_trait]
+impl Command for BufCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (action, rest) = args.trim().split_once(char::is_whitespace).unwrap_or((args.trim(), ""));
+        let (name, text) = rest.trim_start().split_once(char::is_whitespace).unwrap_or((rest.trim(), ""));
+        match action {
+            "" | "list" => {
+                let buffers = self.state.list_buffers().await;
+                if buffers.is_empty() {
+                    return Ok("No buffers. Start one with /buf append <name> <text> or /buf edit <name>.".to_string());
+                }
+                let lines = buffers.iter().map(|(name, text)| {
+                    let first_line = text.lines().next().unwrap_or_default();
+                    format!("* **{}** ({} lines, {} chars): {}", name, text.lines().count(), text.chars().count(), first_line)
+                });
+                Ok(format!("Buffers:\n{}", lines.collect::<Vec<_>>().join("\n")))
+            }
+            "show" => {
+                let text = self.existing(check_name(name)?).await?;
+                Ok(format!("Buffer **{}**:\n\n```\n{}\n```", name, text))
+            }
+            "append" => {
+                let name = check_name(name)?;
+                if text.is_empty() {
+                    return Err(ReplError::Command("Usage: /buf append <name> <text>".to_string()));
+                }
+                let lines = self.state.append_buffer(name, text).await;
+                Ok(format!("Appended to '{}' ({} lines).", name, lines))
+            }
+            "edit" => {
+                let name = check_name(name)?;
+                let current = self.state.get_buffer(name).await.unwrap_or_default();
+                match Editor::new().edit(&current).map_err(ReplError::from)? {
+                    Some(edited) => {
+                        let edited = edited.trim_end().to_string();
+                        let lines = edited.lines().count();
+                        self.state.set_buffer(name, edited).await;
+                        Ok(format!("Saved '{}' ({} lines).", name, lines))
+                    }
+                    None => Ok(format!("'{}' unchanged.", name)),
+                }
+            }
+            "send" => {
+                // The REPL sends buffers itself, streamed like a typed prompt; this path serves API callers
+                let buffer = self.existing(check_name(name)?).await?;
+                let prompt = self.state.get_config().await.apply_prompt(&buffer);
+                self.state.ask_current_model(&prompt).await
+            }
+            "clear" => {
+                let name = check_name(name)?;
+                if self.state.remove_buffer(name).await {
+                    Ok(format!("Cleared '{}'.", name))
+                } else {
+                    Err(ReplError::Command(format!("No buffer named '{}'.", name)))
+                }
+            }
+            other => Err(ReplError::Command(format!("Unknown action '{}'. {}", other, USAGE))),
+        }
+    }
+
+    fn name(&self) -> &str { "buf" }
+    fn help(&self) -> &str { "Assemble a prompt in a named buffer (/buf append, /buf edit), then send it with /buf send." }
+    fn usage(&self) -> &str { "[list|show|edit|append|send|clear] <name> [text]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
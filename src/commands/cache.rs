@@ -0,0 +1,48 @@
+// src/commands/cache.rs
+use async_trait::async_trait;
+
+use crate::{
+    cache::TTL,
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+pub struct CacheCommand {
+    state: AppState,
+}
+
+impl CacheCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for CacheCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        match args.trim() {
+            "" => {
+                let lookups = self.state.cached_lookups();
+                if lookups.is_empty() {
+                    return Ok("No cached provider lookups.".to_string());
+                }
+                let mut listing = format!("Cached provider lookups (kept for {}s, /cache clear to refetch):", TTL.as_secs());
+                for lookup in lookups {
+                    listing.push_str(&format!("\n  {:<20} {:<12} {}s ago", lookup.provider, lookup.what, lookup.age.as_secs()));
+                }
+                Ok(listing)
+            }
+            "clear" => {
+                let cleared = self.state.clear_lookups();
+                Ok(format!("Cleared cached lookups for {} provider(s); model lists and readiness are fetched again on next use.", cleared))
+            }
+            other => Err(ReplError::Command(format!("Invalid argument '{}'. Use /cache [clear].", other))),
+        }
+    }
+
+    fn name(&self) -> &str { "cache" }
+    fn help(&self) -> &str { "Show the cached provider model lists and readiness checks, or clear them so they are fetched again." }
+    fn usage(&self) -> &str { "[clear]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
@@ -0,0 +1,85 @@
+// src/commands/checkpoint.rs
+use async_trait::async_trait;
+use chrono::Local;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::{AppState, Checkpoint},
+};
+
+fn describe(name: &str, checkpoint: &Checkpoint) -> String {
+    format!(
+        "'{}' ({}): {}:{}, {} history entries",
+        name,
+        checkpoint.created.with_timezone(&Local).format("%H:%M:%S"),
+        checkpoint.provider,
+        checkpoint.model,
+        checkpoint.history.len()
+    )
+}
+
+// Checkpoint names are single words so they are easy to type back into /rollback.
+fn checkpoint_name(args: &str, usage: &str) -> ReplResult<String> {
+    let name = args.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(ReplError::Command(usage.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+pub struct CheckpointCommand {
+    state: AppState,
+}
+
+impl CheckpointCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for CheckpointCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        if args.trim().is_empty() {
+            let checkpoints = self.state.list_checkpoints().await;
+            if checkpoints.is_empty() {
+                return Ok("No checkpoints. Usage: /checkpoint <name>".to_string());
+            }
+            let lines = checkpoints.iter().map(|(name, c)| format!("  {}", describe(name, c))).collect::<Vec<_>>();
+            return Ok(format!("Checkpoints:\n{}", lines.join("\n")));
+        }
+        let name = checkpoint_name(args, "Usage: /checkpoint <name> (a single word)")?;
+        let checkpoint = self.state.create_checkpoint(&name).await;
+        Ok(format!("Saved checkpoint {}. Use /rollback {} to return to it.", describe(&name, &checkpoint), name))
+    }
+
+    fn name(&self) -> &str { "checkpoint" }
+    fn help(&self) -> &str { "Save the session (provider, model, history, hooks, limits, /set parameters) under a name, or list checkpoints." }
+    fn usage(&self) -> &str { "[name]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
+
+pub struct RollbackCommand {
+    state: AppState,
+}
+
+impl RollbackCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for RollbackCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let name = checkpoint_name(args, "Usage: /rollback <name>")?;
+        let checkpoint = self.state.rollback(&name).await?;
+        Ok(format!("Rolled back to checkpoint {}.", describe(&name, &checkpoint)))
+    }
+
+    fn name(&self) -> &str { "rollback" }
+    fn help(&self) -> &str { "Restore the session saved by /checkpoint <name>." }
+    fn usage(&self) -> &str { "<name>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,222 @@
+// src/commands/config.rs
+use async_trait::async_trait;
+use dialoguer::{theme::ColorfulTheme, Confirm, Editor};
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use std::{fs, io, path::{Path, PathBuf}};
+use toml_edit::{DocumentMut, TableLike};
+
+use crate::{
+    commands::{Command, CommandCategory},
+    config::AppConfig,
+    error::{ReplError, ReplResult},
+    httplog::is_secret,
+    providers::ProviderRegistry,
+    reload::reload,
+    state::AppState,
+};
+
+const USAGE: &str = "Usage: /config [show | edit | set <key> <value>]";
+const MASK: &str = "********";
+// Settings holding request headers; all their values are hidden, whatever the header's name.
+const HEADER_TABLES: [&str; 2] = ["http.headers", "http.hosts"];
+
+fn is_header(key: &str) -> bool {
+    HEADER_TABLES.iter().any(|table| key.starts_with(table))
+}
+
+// Hides secret values and drops unset ones (TOML has no null). `key` is the dotted path of `value`.
+fn masked(key: &str, value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(fields.into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(name, value)| {
+                let path = if key.is_empty() { name.clone() } else { format!("{}.{}", key, name) };
+                let value = match value {
+                    Value::String(_) if is_header(&path) => Value::String(MASK.to_string()),
+                    _ if is_secret(&name) => Value::String(MASK.to_string()),
+                    value => masked(&path, value),
+                };
+                (name, value)
+            })
+            .collect()),
+        Value::Array(items) => Value::Array(items.into_iter().filter(|item| !item.is_null()).map(|item| masked(key, item)).collect()),
+        other => other,
+    }
+}
+
+// `name = value` in `section` with the value hidden if it's a header or the name looks like a secret.
+fn masked_line(section: &str, line: &str) -> String {
+    let Some((name, _)) = line.split_once('=') else { return line.to_string() };
+    let key = if section.is_empty() { name.trim().to_string() } else { format!("{}.{}", section, name.trim()) };
+    if is_secret(name.trim()) || is_header(&key) {
+        format!("{}= \"{}\"", name, MASK)
+    } else {
+        line.to_string()
+    }
+}
+
+/// The changed lines between two versions of the config file, as `-`/`+` lines.
+fn diff(old: &str, new: &str) -> String {
+    // The `[section]` each line is in, so header values can be told apart from other settings
+    let mut section = String::new();
+    TextDiff::from_lines(old, new).iter_all_changes()
+        .filter_map(|change| {
+            let line = change.value().trim_end();
+            if let Some(name) = line.trim().strip_prefix('[').and_then(|rest| rest.split(']').next()) {
+                section = name.trim_start_matches('[').trim().to_string();
+            }
+            let sign = match change.tag() {
+                ChangeTag::Equal => return None,
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+            };
+            Some(format!("{} {}", sign, masked_line(&section, line)))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Checks new contents for the config file: they must parse, and the providers must build.
+fn validate(text: &str) -> Result<AppConfig, String> {
+    let config = AppConfig::parse(text)?;
+    ProviderRegistry::from_config(&config.providers).map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+/// Sets `key` (e.g. `ui.theme`) in the file's TOML, keeping its comments and layout. Values that
+/// aren't valid TOML (`nord`) are taken as strings.
+fn set_key(text: &str, key: &str, raw: &str) -> Result<String, String> {
+    let mut document = text.parse::<DocumentMut>().map_err(|e| format!("The config file is not valid TOML: {}", e))?;
+    let value = raw.parse::<toml_edit::Value>().unwrap_or_else(|_| raw.into());
+    let names: Vec<&str> = key.split('.').collect();
+    let (field, tables) = names.split_last().filter(|(field, _)| !field.is_empty()).ok_or_else(|| USAGE.to_string())?;
+    let mut table: &mut dyn TableLike = document.as_table_mut();
+    for (depth, name) in tables.iter().enumerate() {
+        table = table.entry(name).or_insert(toml_edit::table()).as_table_like_mut()
+            .ok_or_else(|| format!("'{}' is a value, not a section.", names[..=depth].join(".")))?;
+    }
+    table.insert(field, toml_edit::Item::Value(value));
+    Ok(document.to_string())
+}
+
+// Whether the setting made it into the parsed config; unknown names are dropped when parsing.
+fn has_setting(config: &AppConfig, key: &str) -> bool {
+    serde_json::to_value(config).ok()
+        .and_then(|value| value.pointer(&format!("/{}", key.replace('.', "/"))).cloned())
+        .is_some_and(|value| !value.is_null())
+}
+
+fn read_file(path: &Path) -> ReplResult<String> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn config_path() -> ReplResult<PathBuf> {
+    AppConfig::config_path().ok_or_else(|| ReplError::Command("No config file location: set LLM_REPL_CONFIG or HOME.".to_string()))
+}
+
+pub struct ConfigCommand {
+    state: AppState,
+}
+
+impl ConfigCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn show(&self) -> ReplResult<String> {
+        let path = config_path()?;
+        let mut listing = format!("Config file: {}{}", path.display(), if path.exists() { "" } else { " (not created yet)" });
+        if let Some(workspace) = AppConfig::workspace_path() {
+            listing.push_str(&format!("\nWorkspace overlay: {}", workspace.display()));
+        }
+        let settings = masked("", serde_json::to_value(self.state.get_config().await)?);
+        let settings = toml::to_string_pretty(&settings).map_err(|e| ReplError::Command(format!("Could not format the config: {}", e)))?;
+        listing.push_str(&format!("\n\nCurrent settings, including changes made this session (secrets hidden):\n\n{}", settings.trim_end()));
+        Ok(listing)
+    }
+
+    async fn edit(&self) -> ReplResult<String> {
+        let path = config_path()?;
+        let original = read_file(&path)?;
+        let mut text = original.clone();
+        loop {
+            let Some(edited) = Editor::new().extension(".toml").edit(&text).map_err(ReplError::from)? else {
+                return Ok("Editor closed without saving; config unchanged.".to_string());
+            };
+            text = edited;
+            if text == original {
+                return Ok("No changes.".to_string());
+            }
+            let Err(e) = validate(&text) else { break };
+            println!("{}", e);
+            let again = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("The config is invalid. Edit it again?")
+                .default(true)
+                .interact_opt();
+            if !matches!(again, Ok(Some(true))) {
+                return Ok("Edits discarded; config unchanged.".to_string());
+            }
+        }
+        println!("{}\n", diff(&original, &text));
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Save these changes to {}?", path.display()))
+            .default(true)
+            .interact_opt();
+        match confirmed {
+            Ok(Some(true)) => self.save(&path, &text).await,
+            Ok(_) => Ok("Edits discarded; config unchanged.".to_string()),
+            Err(e) => Err(ReplError::Command(format!("Config unchanged; confirmation failed: {}", e))),
+        }
+    }
+
+    async fn set(&self, key: &str, raw: &str) -> ReplResult<String> {
+        let path = config_path()?;
+        let original = read_file(&path)?;
+        let text = set_key(&original, key, raw).map_err(ReplError::Command)?;
+        let config = validate(&text).map_err(|e| ReplError::Command(format!("{} not set: {}", key, e)))?;
+        if !has_setting(&config, key) {
+            return Err(ReplError::Command(format!("Unknown setting '{}'. /config show lists the settings.", key)));
+        }
+        if text == original {
+            return Ok(format!("{} is already {}.", key, raw));
+        }
+        let applied = self.save(&path, &text).await?;
+        Ok(format!("{}\n\n{}", diff(&original, &text), applied))
+    }
+
+    // Writes the file and applies it now, rather than waiting for the file watcher.
+    async fn save(&self, path: &Path, text: &str) -> ReplResult<String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, text)?;
+        Ok(match reload(&self.state, path).await {
+            Ok(Some(message)) => format!("Saved {}. {}", path.display(), message),
+            Ok(None) => format!("Saved {}. No settings changed.", path.display()),
+            Err(e) => format!("Saved {}, but not applied: {}", path.display(), e),
+        })
+    }
+}
+
+#[async_trait]
+impl Command for ConfigCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut parts = args.trim().splitn(3, char::is_whitespace);
+        match (parts.next().unwrap_or(""), parts.next(), parts.next().map(str::trim)) {
+            ("" | "show", None, _) => self.show().await,
+            ("edit", None, _) => self.edit().await,
+            ("set", Some(key), Some(value)) if !value.is_empty() => self.set(key, value).await,
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "config" }
+    fn help(&self) -> &str { "Show the settings, edit the config file in $EDITOR, or set one value (/config set ui.theme nord); changes apply immediately." }
+    fn usage(&self) -> &str { "[show|edit|set <key> <value>]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
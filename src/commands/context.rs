@@ -0,0 +1,102 @@
+// src/commands/context.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    context::{self, ContextOverflow, SUMMARY_REQUEST},
+    error::{ReplError, ReplResult},
+    providers::{chat::Role, tokens, ChatMessage},
+    state::AppState,
+};
+
+// Longest preview of a message's text.
+const PREVIEW_CHARS: usize = 60;
+
+fn preview(text: &str) -> String {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut preview: String = flattened.chars().take(PREVIEW_CHARS).collect();
+    if flattened.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+    preview
+}
+
+fn describe_messages(messages: &[ChatMessage]) -> Vec<String> {
+    messages.iter()
+        .map(|message| {
+            let role = match message.role {
+                Role::System => "system",
+                Role::User if message.content == SUMMARY_REQUEST => "summary",
+                Role::User => "user",
+                Role::Assistant => "assistant",
+            };
+            format!("  {:<9} ~{:>6}  {}", role, tokens::estimate(&message.content), preview(&message.content))
+        })
+        .collect()
+}
+
+pub struct ContextCommand {
+    state: AppState,
+}
+
+impl ContextCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for ContextCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        let overflow = match args.trim().to_lowercase().as_str() {
+            "" => None,
+            "drop" => Some(ContextOverflow::Drop),
+            "summarize" => Some(ContextOverflow::Summarize),
+            "off" => Some(ContextOverflow::Off),
+            other => return Err(ReplError::Command(format!("Invalid argument '{}'. Use /context [drop|summarize|off].", other))),
+        };
+        if let Some(overflow) = overflow {
+            config.session.context_overflow = overflow;
+            self.state.set_config(config.clone()).await;
+        }
+
+        let provider = self.state.get_provider_name().await;
+        let model = self.state.get_model().await;
+        let messages = config.chat_messages(&self.state.get_conversation().await, "");
+        let plan = context::plan(&self.state, &provider, &model, messages).await;
+        let window = match (plan.window, plan.budget) {
+            (Some(window), Some(budget)) => format!("context window {} tokens, {} of them for the prompt", window, budget),
+            _ => "context window unknown, so nothing is left out".to_string(),
+        };
+        let mut output = format!(
+            "Next prompt to {}:{} ({}; overflow: {}).\nAbout {} tokens are sent before your prompt's own text:\n\n",
+            provider, model, window, config.session.context_overflow.describe(), plan.tokens
+        );
+        // The last message is the (empty) prompt itself
+        let sent = &plan.messages[..plan.messages.len().saturating_sub(1)];
+        if sent.is_empty() {
+            output.push_str("  nothing: no session context or earlier turns\n");
+        } else {
+            output.push_str(&describe_messages(sent).join("\n"));
+            output.push('\n');
+        }
+        if !plan.overflow.is_empty() {
+            let action = match config.session.context_overflow {
+                ContextOverflow::Summarize => "summarized into one turn",
+                _ => "left out",
+            };
+            let overflow_tokens: u64 = plan.overflow.iter().map(|message| tokens::estimate(&message.content)).sum();
+            output.push_str(&format!(
+                "\n{} earlier turns (~{} tokens) don't fit and will be {}:\n{}\n",
+                plan.overflow_turns(), overflow_tokens, action, describe_messages(&plan.overflow).join("\n")
+            ));
+        }
+        Ok(output)
+    }
+
+    fn name(&self) -> &str { "context" }
+    fn help(&self) -> &str { "Show what the next prompt sends (session context and earlier turns) against the model's context window, or set what happens to turns that don't fit (drop, summarize or off)." }
+    fn usage(&self) -> &str { "[drop|summarize|off]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
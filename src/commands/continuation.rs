@@ -0,0 +1,83 @@
+// src/commands/continuation.rs
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    providers::resume::{continuation_prompt, strip_repeat},
+    state::{AppState, HistoryContentType, HistoryEntry},
+};
+
+/// A request for the rest of the newest answer, e.g. one truncated by a provider's token limit.
+pub struct Continuation {
+    index: usize,
+    pub entry: HistoryEntry,
+    pub provider: String,
+    pub model: String,
+    /// The original prompt (with hooks and context) followed by the answer so far.
+    pub prompt: String,
+}
+
+impl Continuation {
+    /// Prepares the continuation of the newest answer, sent to the provider and model that wrote it.
+    pub async fn of_last_answer(state: &AppState) -> ReplResult<Self> {
+        let (index, entry) = state.last_answer().await
+            .ok_or_else(|| ReplError::Command("Nothing to continue: there is no answer in this session yet.".to_string()))?;
+        let prompt = entry.prompt.clone()
+            .ok_or_else(|| ReplError::Command("The last answer has no recorded prompt to continue from.".to_string()))?;
+        let provider = match &entry.provider {
+            Some(provider) => provider.clone(),
+            None => state.get_provider_name().await,
+        };
+        let model = match (&entry.model, &entry.entry_type) {
+            (Some(model), _) | (None, HistoryContentType::LlmResponse { model }) => model.clone(),
+            _ => state.get_model().await,
+        };
+        let prompt = continuation_prompt(&state.get_config().await.apply_prompt(&prompt), &entry.content);
+        Ok(Self { index, entry, provider, model, prompt })
+    }
+
+    /// Appends the continuation to the answer's history entry, or records it as a new answer if
+    /// that entry has been pruned meanwhile.
+    pub async fn record(&self, state: &AppState, text: &str, elapsed: Duration) {
+        if state.extend_history_entry(self.index, self.entry.timestamp, text, elapsed).await {
+            return;
+        }
+        let mut entry = HistoryEntry::new(HistoryContentType::LlmResponse { model: self.model.clone() }, text.to_string())
+            .with_model(&self.provider, &self.model)
+            .with_duration(elapsed);
+        entry.prompt = self.entry.prompt.clone();
+        state.add_history_entry(entry).await;
+    }
+}
+
+pub struct ContinueCommand {
+    state: AppState,
+}
+
+impl ContinueCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for ContinueCommand {
+    // The REPL streams the continuation itself; this path serves the API and other front ends.
+    async fn execute(&self, _args: &str) -> ReplResult<String> {
+        let continuation = Continuation::of_last_answer(&self.state).await?;
+        let provider = self.state.get_provider_by_name(&continuation.provider)
+            .ok_or_else(|| ReplError::UnknownProvider(continuation.provider.clone()))?;
+        let started = Instant::now();
+        let params = self.state.get_generation_params().await;
+        let response = provider.query(&continuation.model, &continuation.prompt, &params).await?;
+        let text = self.state.get_output_limits().await.apply(&strip_repeat(&continuation.entry.content, &response));
+        continuation.record(&self.state, &text, started.elapsed()).await;
+        Ok(text)
+    }
+
+    fn name(&self) -> &str { "continue" }
+    fn help(&self) -> &str { "Ask the model to continue its last answer (e.g. one cut off by a token limit); the rest is appended to that answer in the history." }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,106 @@
+// src/commands/data.rs
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    data::{Dataset, SAMPLE_ROWS},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const USAGE: &str = "Usage: /data [load <file> [name] | show <name> | stats <name> [column] [by <column>] | drop <name>]";
+
+// Dataset names are used in `@data:<name>` references, so they are restricted to word characters.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+pub struct DataCommand {
+    state: AppState,
+}
+
+impl DataCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn dataset(&self, name: &str) -> ReplResult<Arc<Dataset>> {
+        self.state.get_dataset(name).await
+            .ok_or_else(|| ReplError::Command(format!("No dataset named '{}'. See /data for loaded datasets.", name)))
+    }
+
+    async fn list(&self) -> String {
+        let datasets = self.state.list_datasets().await;
+        if datasets.is_empty() {
+            return format!("No datasets loaded. {}", USAGE);
+        }
+        let lines = datasets.iter().map(|d| format!("  {}", d.describe())).collect::<Vec<_>>();
+        format!("Datasets (reference in prompts as @data:<name>):\n{}", lines.join("\n"))
+    }
+
+    async fn load(&self, file: &str, name: Option<&str>) -> ReplResult<String> {
+        let path = Path::new(file);
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().replace(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'), "_"),
+        };
+        if !is_valid_name(&name) {
+            return Err(ReplError::Command(format!("Invalid dataset name '{}'. Use letters, digits, '_' or '-'.", name)));
+        }
+        let dataset = self.state.add_dataset(Dataset::load(&name, path)?).await;
+        let columns = dataset.columns.iter().zip(&dataset.kinds)
+            .map(|(column, kind)| format!("{} ({})", column, kind))
+            .collect::<Vec<_>>();
+        Ok(format!(
+            "Loaded {}.\nColumns: {}\nReference it in a prompt as @data:{}",
+            dataset.describe(), columns.join(", "), dataset.name
+        ))
+    }
+
+    async fn stats(&self, name: &str, args: &[&str]) -> ReplResult<String> {
+        let dataset = self.dataset(name).await?;
+        match args {
+            [] => {
+                let stats = dataset.stats(None)?;
+                let lines = stats.iter().map(|s| format!("  {}", s.describe())).collect::<Vec<_>>();
+                Ok(format!("{}\n{}", dataset.describe(), lines.join("\n")))
+            }
+            [column] => Ok(dataset.stats(Some(column))?.iter().map(|s| s.describe()).collect::<Vec<_>>().join("\n")),
+            ["by", group] => dataset.group_stats(group, None),
+            [column, "by", group] => dataset.group_stats(group, Some(column)),
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl Command for DataCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] => Ok(self.list().await),
+            ["load", file] => self.load(file, None).await,
+            ["load", file, name] => self.load(file, Some(name)).await,
+            ["show", name] => {
+                let dataset = self.dataset(name).await?;
+                Ok(format!("{}\n\n{}", dataset.describe(), dataset.sample_table(SAMPLE_ROWS)))
+            }
+            ["stats", name, rest @ ..] => self.stats(name, rest).await,
+            ["drop", name] => {
+                if self.state.remove_dataset(name).await {
+                    Ok(format!("Dropped dataset '{}'.", name))
+                } else {
+                    Err(ReplError::Command(format!("No dataset named '{}'.", name)))
+                }
+            }
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "data" }
+    fn help(&self) -> &str { "Load CSV/JSON datasets, show samples and locally computed statistics; use @data:<name> in prompts." }
+    fn usage(&self) -> &str { "[load <file> [name]|show|stats|drop]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
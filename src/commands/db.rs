@@ -0,0 +1,121 @@
+// src/commands/db.rs
+use async_trait::async_trait;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+use crate::{
+    commands::{Command, CommandCategory},
+    database::{check_read_only, extract_sql, Database},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const DB_USAGE: &str = "Usage: /db [connect <connection-string> | schema | query <sql> | disconnect]";
+
+async fn connected(state: &AppState) -> ReplResult<Database> {
+    state.get_database().await
+        .ok_or_else(|| ReplError::Command("No database connected. Use /db connect <connection-string>.".to_string()))
+}
+
+pub struct DbCommand {
+    state: AppState,
+}
+
+impl DbCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for DbCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (subcommand, rest) = args.trim().split_once(char::is_whitespace).unwrap_or((args.trim(), ""));
+        match (subcommand, rest.trim()) {
+            ("", _) => match self.state.get_database().await {
+                Some(db) => Ok(format!("Connected to {} ({}).", db.display_url, db.backend)),
+                None => Ok(format!("No database connected. {}", DB_USAGE)),
+            },
+            ("connect", url) if !url.is_empty() => {
+                let db = Database::connect(url).await?;
+                let message = format!("Connected to {} ({}). Ask questions with /sql <question>.", db.display_url, db.backend);
+                if let Some(previous) = self.state.set_database(Some(db)).await {
+                    previous.close().await;
+                }
+                Ok(message)
+            }
+            ("schema", "") => connected(&self.state).await?.schema().await,
+            ("query", sql) if !sql.is_empty() => Ok(connected(&self.state).await?.query(sql).await?.to_table()),
+            ("disconnect", "") => match self.state.set_database(None).await {
+                Some(db) => {
+                    db.close().await;
+                    Ok(format!("Disconnected from {}.", db.display_url))
+                }
+                None => Ok("No database connected.".to_string()),
+            },
+            _ => Err(ReplError::Command(DB_USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "db" }
+    fn help(&self) -> &str { "Connect to a SQLite/Postgres/MySQL database, show its schema, or run a read-only query." }
+    fn usage(&self) -> &str { "[connect <url>|schema|query <sql>|disconnect]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
+
+pub struct SqlCommand {
+    state: AppState,
+}
+
+impl SqlCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for SqlCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let question = args.trim();
+        if question.is_empty() {
+            return Err(ReplError::Command("Usage: /sql <question about the connected database>".to_string()));
+        }
+        let db = connected(&self.state).await?;
+        let schema = db.schema().await?;
+
+        // 1. Have the model write a query against the real schema
+        let answer = self.state.ask_current_model(&format!(
+            "You write SQL for {}. The database schema is:\n{}\n\nWrite one read-only query (SELECT) that answers this question: {}\n\
+             Reply with only the SQL in a ```sql code block.",
+            db.backend, schema, question
+        )).await?;
+        let sql = extract_sql(&answer);
+        let statement = check_read_only(&sql)?;
+
+        // 2. Show it and run it only once confirmed
+        println!("Generated SQL:\n\n    {}\n", statement.replace('\n', "\n    "));
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Run this query (read-only)?")
+            .default(true)
+            .interact_opt();
+        match confirmed {
+            Ok(Some(true)) => {}
+            Ok(_) => return Ok("Query not run.".to_string()),
+            Err(e) => return Err(ReplError::Command(format!("Query not run; confirmation failed: {}", e))),
+        }
+        let result = db.query(statement).await?;
+        let table = result.to_table();
+
+        // 3. Summarize the rows in terms of the question
+        let summary = self.state.ask_current_model(&format!(
+            "Question: {}\n\nThis SQL was run:\n{}\n\nResult:\n{}\n\n\
+             Answer the question in a few sentences using only these results. Say so if they don't answer it.",
+            question, statement, table
+        )).await?;
+        Ok(format!("```sql\n{}\n```\n\n{}\n\n{}", statement, table, summary.trim()))
+    }
+
+    fn name(&self) -> &str { "sql" }
+    fn help(&self) -> &str { "Turn a question into a read-only SQL query, confirm and run it, and summarize the result." }
+    fn usage(&self) -> &str { "<question>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
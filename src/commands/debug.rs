@@ -0,0 +1,47 @@
+// src/commands/debug.rs
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    httplog::{self, DEBUG_LOG_FILE},
+};
+
+const USAGE: &str = "Usage: /debug http <on [file]|off>";
+
+pub struct DebugCommand;
+
+impl DebugCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Command for DebugCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut parts = args.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (None, ..) | (Some("http"), None, _) => Ok(match httplog::log_path() {
+                Some(path) => format!("HTTP logging: On ({})", path.display()),
+                None => "HTTP logging: Off".to_string(),
+            }),
+            (Some("http"), Some("on"), file) => {
+                let path = PathBuf::from(file.unwrap_or(DEBUG_LOG_FILE));
+                httplog::enable(path.clone());
+                Ok(format!("HTTP logging: On. Provider requests and responses (keys masked) are appended to {}.", path.display()))
+            }
+            (Some("http"), Some("off"), None) => {
+                httplog::disable();
+                Ok("HTTP logging: Off".to_string())
+            }
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "debug" }
+    fn help(&self) -> &str { "Log raw provider HTTP requests and responses, with keys masked, to a debug file (/debug http on [file], /debug http off)." }
+    fn usage(&self) -> &str { "http <on [file]|off>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
@@ -0,0 +1,183 @@
+// src/commands/diffmodels.rs
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::{self, Stream, StreamExt};
+use similar::{ChangeTag, TextDiff};
+use std::io::{self, Write};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    preferences::{Candidate, Preference},
+    render::{get_theme_resources, InterleavedRenderer},
+    state::{AppState, MarkdownMode},
+};
+
+const USAGE: &str = "Usage: /diffmodels <[provider:]model> <[provider:]model> <prompt>";
+
+type TextStream = Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>;
+
+// One side of the comparison.
+struct ModelAnswer {
+    label: String,
+    answer: String,
+    elapsed: Duration,
+}
+
+pub struct DiffModelsCommand {
+    state: AppState,
+}
+
+impl DiffModelsCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // Starts one side's response as a stream (a single chunk for providers that don't stream).
+    async fn open_stream(&self, spec: &str, prompt: &str) -> ReplResult<(String, TextStream)> {
+        let (provider_name, model) = self.state.resolve_model_spec(spec).await;
+        let provider = self.state.get_provider_by_name(&provider_name)
+            .ok_or_else(|| ReplError::UnknownProvider(provider_name.clone()))?;
+        let label = format!("{}:{}", provider_name, model);
+        let failed = |e: ReplError| ReplError::Command(format!("{} failed: {}", label, e));
+        let params = self.state.get_generation_params().await;
+        let stream = match provider.query_stream(&model, prompt, &params).await.map_err(failed)? {
+            Some(stream) => stream,
+            None => {
+                let answer = provider.query(&model, prompt, &params).await.map_err(failed)?;
+                Box::pin(stream::once(async move { Ok(answer) })) as TextStream
+            }
+        };
+        Ok((label, stream))
+    }
+
+    /// Streams both answers at once, printed as interleaved blocks labeled A and B.
+    async fn stream_answers(&self, sides: Vec<(String, TextStream)>) -> ReplResult<Vec<ModelAnswer>> {
+        let (_skin, palette) = get_theme_resources(self.state.get_theme().await);
+        let (labels, streams): (Vec<_>, Vec<_>) = sides.into_iter().unzip();
+        let mut renderer = InterleavedRenderer::new(labels.clone(), &palette);
+        // Each stream is tagged with its side and ends with a None marker, so its timing is known
+        let mut merged = stream::select_all(streams.into_iter().enumerate().map(|(index, stream)| {
+            stream.map(move |chunk| (index, Some(chunk)))
+                .chain(stream::once(async move { (index, None) }))
+                .boxed()
+        }));
+        let started = Instant::now();
+        let mut answers = vec![String::new(); labels.len()];
+        let mut elapsed = vec![Duration::ZERO; labels.len()];
+        let mut failures = Vec::new();
+        while let Some((index, chunk)) = merged.next().await {
+            let printable = match chunk {
+                Some(Ok(text)) => {
+                    answers[index].push_str(&text);
+                    renderer.push(index, &text)
+                }
+                Some(Err(e)) => {
+                    failures.push(format!("{} failed: {}", labels[index], e));
+                    String::new()
+                }
+                None => {
+                    elapsed[index] = started.elapsed();
+                    renderer.finish(index)
+                }
+            };
+            print!("{}", printable);
+            let _ = io::stdout().flush();
+        }
+        println!();
+        if !failures.is_empty() {
+            return Err(ReplError::Command(failures.join("; ")));
+        }
+
+        let limits = self.state.get_output_limits().await;
+        Ok(labels.into_iter().zip(answers).zip(elapsed)
+            .map(|((label, answer), elapsed)| ModelAnswer { label, answer: limits.apply(&answer), elapsed })
+            .collect())
+    }
+}
+
+// Wraps a changed run in markers, keeping its surrounding whitespace outside them.
+fn mark(run: &str, open: &str, close: &str) -> String {
+    let trimmed = run.trim();
+    if trimmed.is_empty() {
+        return run.to_string();
+    }
+    let leading = &run[..run.len() - run.trim_start().len()];
+    let trailing = &run[run.trim_end().len()..];
+    format!("{}{}{}{}{}", leading, open, trimmed, close, trailing)
+}
+
+/// Word-level diff of `old` against `new`. With `markdown`, removed words are struck through and
+/// added words bold; otherwise they are marked `[-removed-]` and `{+added+}` as in wdiff.
+fn word_diff(old: &str, new: &str, markdown: bool) -> String {
+    let (removed, added) = if markdown { (("~~", "~~"), ("**", "**")) } else { (("[-", "-]"), ("{+", "+}")) };
+    let diff = TextDiff::from_words(old, new);
+    let mut output = String::new();
+    let mut run = String::new();
+    let mut run_tag = ChangeTag::Equal;
+    let flush = |run: &mut String, tag: ChangeTag, output: &mut String| {
+        match tag {
+            ChangeTag::Equal => output.push_str(run),
+            ChangeTag::Delete => output.push_str(&mark(run, removed.0, removed.1)),
+            ChangeTag::Insert => output.push_str(&mark(run, added.0, added.1)),
+        }
+        run.clear();
+    };
+    for change in diff.iter_all_changes() {
+        // Whitespace between two changed words of the same kind stays inside the run
+        if change.tag() != run_tag && !(change.value().trim().is_empty() && run_tag != ChangeTag::Equal) {
+            flush(&mut run, run_tag, &mut output);
+            run_tag = change.tag();
+        }
+        run.push_str(change.value());
+    }
+    flush(&mut run, run_tag, &mut output);
+    output
+}
+
+#[async_trait]
+impl Command for DiffModelsCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut parts = args.trim().splitn(3, char::is_whitespace);
+        let (Some(first), Some(second), Some(prompt)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(ReplError::Command(USAGE.to_string()));
+        };
+        let config = self.state.get_config().await;
+        let sent_prompt = config.apply_prompt(prompt.trim());
+        println!("Asking {} and {}...", first, second);
+        let (left, right) = tokio::join!(self.open_stream(first, &sent_prompt), self.open_stream(second, &sent_prompt));
+        let mut answers = self.stream_answers(vec![left?, right?]).await?.into_iter();
+        let (Some(left), Some(right)) = (answers.next(), answers.next()) else {
+            return Err(ReplError::Command("Expected two answers.".to_string()));
+        };
+
+        let markdown = !config.ui.accessible && self.state.get_markdown_mode().await != MarkdownMode::Off;
+        let similarity = TextDiff::from_words(&left.answer, &right.answer).ratio() * 100.0;
+        let (removed, added) = if markdown { ("~~struck~~", "**bold**") } else { ("[-...-]", "{+...+}") };
+        let mut output = String::new();
+        for (side, answer) in [("A", &left), ("B", &right)] {
+            output.push_str(&format!(
+                "{}: {} ({:.1}s, {} words)\n",
+                side, answer.label, answer.elapsed.as_secs_f64(), answer.answer.split_whitespace().count()
+            ));
+        }
+        output.push_str(&format!("Similarity: {:.0}%. Only in A: {}; only in B: {}.\n\n", similarity, removed, added));
+        output.push_str(&word_diff(&left.answer, &right.answer, markdown));
+        output.push_str("\n\nWhich was better? /prefer a, /prefer b or /prefer tie records it in the preference log.");
+
+        self.state.set_last_comparison(Preference {
+            timestamp: Utc::now(),
+            prompt: prompt.trim().to_string(),
+            candidates: [left, right].into_iter().map(|side| Candidate { model: side.label, answer: side.answer }).collect(),
+            preferred: None,
+        }).await;
+        Ok(output)
+    }
+
+    fn name(&self) -> &str { "diffmodels" }
+    fn help(&self) -> &str { "Run one prompt against two models, streaming both answers as interleaved labeled blocks, then show a word-level diff." }
+    fn usage(&self) -> &str { "<a> <b> <prompt>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
@@ -0,0 +1,94 @@
+// src/commands/embed.rs
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const USAGE: &str = "Usage: /embed [--model <[provider:]model>] [--out <file.json>] [--lines <file>] [text]";
+
+// Parsed `/embed` arguments.
+struct EmbedArgs {
+    model: Option<String>,
+    out: Option<PathBuf>,
+    texts: Vec<String>,
+}
+
+fn parse_args(args: &str) -> ReplResult<EmbedArgs> {
+    let mut parsed = EmbedArgs { model: None, out: None, texts: Vec::new() };
+    let mut rest = args.trim();
+    while let Some(flag) = rest.strip_prefix("--") {
+        let (name, after) = flag.split_once(char::is_whitespace).unwrap_or((flag, ""));
+        let after = after.trim_start();
+        let (value, remaining) = after.split_once(char::is_whitespace).unwrap_or((after, ""));
+        if value.is_empty() {
+            return Err(ReplError::Command(USAGE.to_string()));
+        }
+        match name {
+            "model" => parsed.model = Some(value.to_string()),
+            "out" => parsed.out = Some(PathBuf::from(value)),
+            // One text per non-empty line
+            "lines" => {
+                let content = std::fs::read_to_string(value)?;
+                parsed.texts.extend(content.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from));
+            }
+            _ => return Err(ReplError::Command(format!("Unknown option --{}. {}", name, USAGE))),
+        }
+        rest = remaining.trim_start();
+    }
+    if !rest.is_empty() {
+        parsed.texts.push(rest.to_string());
+    }
+    if parsed.texts.is_empty() {
+        return Err(ReplError::Command(USAGE.to_string()));
+    }
+    Ok(parsed)
+}
+
+pub struct EmbedCommand {
+    state: AppState,
+}
+
+impl EmbedCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for EmbedCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let args = parse_args(args)?;
+        let (provider_name, model) = match &args.model {
+            Some(spec) => self.state.resolve_model_spec(spec).await,
+            None => (self.state.get_provider_name().await, self.state.get_model().await),
+        };
+        let provider = self.state.get_provider_by_name(&provider_name)
+            .ok_or_else(|| ReplError::UnknownProvider(provider_name.clone()))?;
+        let vectors = provider.embed(&model, &args.texts).await?;
+        let dimensions = vectors.first().map_or(0, Vec::len);
+        let mut output = format!(
+            "{} embedding{} from {}:{}, {} dimensions.",
+            vectors.len(), if vectors.len() == 1 { "" } else { "s" }, provider_name, model, dimensions
+        );
+        if let Some(path) = &args.out {
+            let records: Vec<_> = args.texts.iter().zip(&vectors).map(|(text, vector)| json!({ "text": text, "vector": vector })).collect();
+            let document = json!({ "provider": provider_name, "model": model, "dimensions": dimensions, "embeddings": records });
+            std::fs::write(path, serde_json::to_string_pretty(&document)?)?;
+            output.push_str(&format!("\nWrote vectors to {}.", path.display()));
+        } else if let Some(first) = vectors.first() {
+            let preview: Vec<String> = first.iter().take(5).map(|value| format!("{:.4}", value)).collect();
+            output.push_str(&format!("\nFirst vector: [{}{}]", preview.join(", "), if first.len() > 5 { ", ..." } else { "" }));
+        }
+        Ok(output)
+    }
+
+    fn name(&self) -> &str { "embed" }
+    fn help(&self) -> &str { "Embed text (or each line of a file) with an embedding model and show the dimensionality; --out writes the vectors to a JSON file." }
+    fn usage(&self) -> &str { "[--model <[provider:]model>] [--out <file.json>] [--lines <file>] [text]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
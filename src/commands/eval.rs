@@ -0,0 +1,204 @@
+// src/commands/eval.rs
+use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::{
+    commands::{report_progress, Command, CommandCategory, EventSender},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const USAGE: &str = "Usage: /eval run <suite.toml> [[provider:]model ...]";
+
+/// An evaluation suite file:
+///
+/// ```toml
+/// models = ["ollama:phi3", "groq:llama-3.1-8b-instant"]  # optional; defaults to the current model
+///
+/// [[case]]
+/// name = "capital"
+/// prompt = "What is the capital of France? One word."
+/// regex = ["(?i)paris"]          # every pattern must match
+/// not_regex = ["(?i)london"]     # no pattern may match
+///
+/// [[case]]
+/// name = "json-sum"
+/// prompt = 'Reply only with JSON like {"answer": <number>}: what is 2 + 2?'
+/// json = { "/answer" = 4 }       # the answer must be JSON; each pointer must hold the value
+/// ```
+#[derive(Debug, Deserialize)]
+struct EvalSuite {
+    #[serde(default)]
+    models: Vec<String>,
+    #[serde(rename = "case", default)]
+    cases: Vec<EvalCase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EvalCase {
+    name: String,
+    prompt: String,
+    #[serde(default)]
+    regex: Vec<String>,
+    #[serde(default)]
+    not_regex: Vec<String>,
+    json: Option<BTreeMap<String, Value>>,
+}
+
+// Patterns are compiled once per suite so a bad one is reported before any model is called.
+struct CompiledCase<'a> {
+    case: &'a EvalCase,
+    regex: Vec<Regex>,
+    not_regex: Vec<Regex>,
+}
+
+impl<'a> CompiledCase<'a> {
+    fn compile(case: &'a EvalCase) -> ReplResult<Self> {
+        let compile_all = |patterns: &[String]| -> ReplResult<Vec<Regex>> {
+            patterns.iter()
+                .map(|p| Regex::new(p).map_err(|e| ReplError::Command(format!("Case '{}': invalid regex '{}': {}", case.name, p, e))))
+                .collect()
+        };
+        Ok(Self { case, regex: compile_all(&case.regex)?, not_regex: compile_all(&case.not_regex)? })
+    }
+
+    // Reasons the answer fails this case; empty if it passes.
+    fn check(&self, answer: &str) -> Vec<String> {
+        let mut failures = Vec::new();
+        for re in self.regex.iter().filter(|re| !re.is_match(answer)) {
+            failures.push(format!("no match for /{}/", re.as_str()));
+        }
+        for re in self.not_regex.iter().filter(|re| re.is_match(answer)) {
+            failures.push(format!("unexpected match for /{}/", re.as_str()));
+        }
+        if let Some(expected) = &self.case.json {
+            match serde_json::from_str::<Value>(strip_code_fence(answer)) {
+                Ok(parsed) => {
+                    for (pointer, value) in expected {
+                        match parsed.pointer(pointer) {
+                            Some(actual) if actual == value => {}
+                            Some(actual) => failures.push(format!("{} is {}, expected {}", pointer, actual, value)),
+                            None => failures.push(format!("{} is missing", pointer)),
+                        }
+                    }
+                }
+                Err(e) => failures.push(format!("not valid JSON ({})", e)),
+            }
+        }
+        failures
+    }
+}
+
+// Models often wrap JSON in a ```json fence; assertions apply to what is inside.
+fn strip_code_fence(answer: &str) -> &str {
+    let trimmed = answer.trim();
+    match trimmed.strip_prefix("```").and_then(|rest| rest.strip_suffix("```")) {
+        Some(fenced) => fenced.split_once('\n').map_or(fenced, |(_language, body)| body).trim(),
+        None => trimmed,
+    }
+}
+
+// Result of one case against one model.
+struct CaseResult {
+    failures: Vec<String>,
+    elapsed: Duration,
+}
+
+pub struct EvalCommand {
+    state: AppState,
+}
+
+impl EvalCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn run_case(&self, case: &CompiledCase<'_>, provider_name: &str, model: &str) -> CaseResult {
+        let prompt = self.state.get_config().await.apply_prompt(&case.case.prompt);
+        let params = self.state.get_generation_params().await;
+        let started = Instant::now();
+        let answer = match self.state.get_provider_by_name(provider_name) {
+            Some(provider) => provider.query(model, &prompt, &params).await,
+            None => Err(ReplError::UnknownProvider(provider_name.to_string())),
+        };
+        let elapsed = started.elapsed();
+        let failures = match answer {
+            Ok(answer) => case.check(&answer),
+            Err(e) => vec![format!("query failed: {}", e)],
+        };
+        CaseResult { failures, elapsed }
+    }
+
+    async fn run_suite(&self, path: &str, model_args: &[&str], events: &EventSender) -> ReplResult<String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ReplError::Command(format!("Could not read suite '{}': {}", path, e)))?;
+        let suite: EvalSuite = toml::from_str(&text)
+            .map_err(|e| ReplError::Command(format!("Invalid suite '{}': {}", path, e)))?;
+        if suite.cases.is_empty() {
+            return Err(ReplError::Command(format!("Suite '{}' has no [[case]] entries.", path)));
+        }
+        let cases = suite.cases.iter().map(CompiledCase::compile).collect::<ReplResult<Vec<_>>>()?;
+
+        // Models from the command line win over the suite's; with neither, use the current model
+        let specs: Vec<String> = if !model_args.is_empty() {
+            model_args.iter().map(|m| m.to_string()).collect()
+        } else if !suite.models.is_empty() {
+            suite.models.clone()
+        } else {
+            vec![format!("{}:{}", self.state.get_provider_name().await, self.state.get_model().await)]
+        };
+
+        let total = (specs.len() * cases.len()) as u64;
+        let mut done = 0;
+        let mut report = format!("Eval suite '{}': {} case(s) x {} model(s)\n", path, cases.len(), specs.len());
+        let mut summary = Vec::new();
+        for spec in &specs {
+            let (provider_name, model) = self.state.resolve_model_spec(spec).await;
+            let label = format!("{}:{}", provider_name, model);
+            report.push_str(&format!("\n{}\n", label));
+            let (mut passed, mut latency) = (0, Duration::ZERO);
+            for case in &cases {
+                report_progress(events, &format!("{} / {}", label, case.case.name), done, Some(total));
+                let result = self.run_case(case, &provider_name, &model).await;
+                done += 1;
+                latency += result.elapsed;
+                let status = if result.failures.is_empty() { passed += 1; "PASS" } else { "FAIL" };
+                report.push_str(&format!("  {} {:<24} {:>6.2}s\n", status, case.case.name, result.elapsed.as_secs_f64()));
+                for failure in &result.failures {
+                    report.push_str(&format!("       - {}\n", failure));
+                }
+            }
+            let average = latency.as_secs_f64() / cases.len() as f64;
+            summary.push(format!("  {:<40} {}/{} passed, avg {:.2}s", label, passed, cases.len(), average));
+        }
+        report_progress(events, "Done", total, Some(total));
+        report.push_str(&format!("\nSummary:\n{}", summary.join("\n")));
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl Command for EvalCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (events, _progress) = mpsc::unbounded_channel();
+        self.execute_streaming(args, events).await
+    }
+
+    async fn execute_streaming(&self, args: &str, events: EventSender) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            ["run", path, models @ ..] => self.run_suite(path, models, &events).await,
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "eval" }
+    fn help(&self) -> &str { "Run an evaluation suite of prompts with regex/JSON assertions against one or more models." }
+    fn usage(&self) -> &str { "run <suite> [models]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
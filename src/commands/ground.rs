@@ -0,0 +1,55 @@
+// src/commands/ground.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const USAGE: &str = "Usage: /ground [on|off]";
+
+pub struct GroundCommand {
+    state: AppState,
+}
+
+impl GroundCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // The current provider if it is Gemini (built-in or a `type = "gemini"` instance), else the built-in one.
+    async fn gemini_provider(&self) -> String {
+        let current = self.state.get_provider_name().await;
+        let config = self.state.get_config().await;
+        let is_gemini = current == "gemini"
+            || config.providers.get(&current).and_then(|settings| settings.kind.as_deref()).is_some_and(|kind| kind.eq_ignore_ascii_case("gemini"));
+        if is_gemini { current } else { "gemini".to_string() }
+    }
+}
+
+#[async_trait]
+impl Command for GroundCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let provider = self.gemini_provider().await;
+        let mut settings = self.state.get_config().await.providers.get(&provider).cloned().unwrap_or_default();
+        match args.trim() {
+            "" => {}
+            "on" | "off" => {
+                settings.grounding = args.trim() == "on";
+                self.state.set_provider_settings(&provider, settings.clone()).await?;
+            }
+            _ => return Err(ReplError::Command(USAGE.to_string())),
+        }
+        Ok(if settings.grounding {
+            format!("Google Search grounding ({}): On. Answers list the web sources they used.", provider)
+        } else {
+            format!("Google Search grounding ({}): Off", provider)
+        })
+    }
+
+    fn name(&self) -> &str { "ground" }
+    fn help(&self) -> &str { "Toggle Google Search grounding for Gemini, for up-to-date answers that list their web sources (/ground on|off)." }
+    fn usage(&self) -> &str { "[on|off]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
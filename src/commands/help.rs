@@ -2,63 +2,81 @@
 use async_trait::async_trait;
 
 use crate::{
-    commands::Command, // Need Command trait for impl
+    commands::{Command, CommandCategory, HelpEntry}, // Need Command trait for impl
     error::ReplResult,
+    i18n::{tr, tr_args, try_tr_args},
     state::{AppState, MarkdownMode}, // Import state elements
 };
 
+// Rows the REPL handles itself, with the message id of their description.
+const REPL_ROWS: &[(CommandCategory, &str, &str)] = &[
+    (CommandCategory::Session, "/exit, /quit", "help-cmd-exit"),
+    (CommandCategory::Session, "/tui", "help-cmd-tui"),
+    (CommandCategory::Shell, "!<command> [args]", "help-shell"),
+];
+
 pub struct HelpCommand {
     state: AppState, // Store state to potentially show status info
+    entries: Vec<HelpEntry>, // Every other registered command
 }
 
 impl HelpCommand {
-    pub fn new(state: AppState) -> Self {
-        HelpCommand { state }
+    pub fn new(state: AppState, entries: Vec<HelpEntry>) -> Self {
+        HelpCommand { state, entries }
     }
 }
 
+// A Markdown table cell: one line, and `|` (common in usages like `[on|off]`) swapped for a
+// look-alike so it doesn't end the cell.
+fn cell(text: &str) -> String {
+    text.lines().map(str::trim).collect::<Vec<_>>().join(" ").replace('|', "\u{2223}")
+}
+
 #[async_trait]
 impl Command for HelpCommand {
     async fn execute(&self, _args: &str) -> ReplResult<String> {
         // Get current status for context
         let current_mode = self.state.get_markdown_mode().await;
-        let current_theme = self.state.get_theme().await; // Fetch current theme
+        let current_theme = format!("{:?}", self.state.get_theme().await); // Fetch current theme
         let mode_str = match current_mode {
-            MarkdownMode::AppendFormatted => "AppendFormatted (Stream raw, append formatted below)",
-            MarkdownMode::LiveStreaming => "LiveStreaming (Experimental, may flicker)",
-            MarkdownMode::Off => "Off (Raw text output only)",
+            MarkdownMode::AppendFormatted => tr("help-mode-append"),
+            MarkdownMode::LiveStreaming => tr("help-mode-live"),
+            MarkdownMode::Off => tr("help-mode-off"),
         };
+        let status_args = [("theme", current_theme.as_str()), ("mode", mode_str.as_str())];
 
-        // Construct the comprehensive help message
-        Ok(format!(r#"
-LLM REPL Commands:
-
-  /help                     Show this help message.
-  /provider [name]          Select LLM provider (interactive if name omitted).
-                            Available: ollama, groq, gemini (check API keys).
-  /model [name]             Select model for the current provider (interactive if name omitted).
-  /theme [name]             Select theme (interactive if name omitted).
-                            Names: default, nord, gruvbox, grayscale.
-  /theme_status             Show the current theme ({:?}).
-  /md                       Set Markdown Mode: Append Formatted (default).
-  /md_streaming             Set Markdown Mode: Live Streaming (Experimental).
-  /md_off                   Set Markdown Mode: Off (Raw text).
-  /md_status                Show current Markdown mode (Currently: {}).
-  /llmconvo                 Start an interactive setup for LLM-to-LLM conversation.
-  /reader                   Display the session output history in a read-only view.
-  /exit, /quit              Exit the REPL.
+        // One table per category, rows generated from the registered commands
+        let own = HelpEntry { name: self.name().to_string(), usage: String::new(), category: self.category(), help: self.help().to_string() };
+        let mut help_text = format!("## {}\n", tr("help-title"));
+        for category in CommandCategory::ALL {
+            let mut rows = std::iter::once(&own).chain(&self.entries)
+                .filter(|entry| entry.category == category)
+                .map(|entry| {
+                    let usage = format!("/{} {}", entry.name, entry.usage);
+                    let description = try_tr_args(&format!("help-cmd-{}", entry.name.replace('_', "-")), &status_args)
+                        .unwrap_or_else(|| entry.help.clone());
+                    (usage.trim_end().to_string(), description)
+                })
+                .collect::<Vec<_>>();
+            rows.extend(REPL_ROWS.iter()
+                .filter(|(row_category, _, _)| *row_category == category)
+                .map(|(_, usage, message_id)| (usage.to_string(), tr(message_id))));
+            if rows.is_empty() {
+                continue;
+            }
+            help_text.push_str(&format!("\n### {}\n\n| {} | {} |\n|---|---|\n", tr(category.message_id()), tr("help-column-command"), tr("help-column-description")));
+            for (usage, description) in rows {
+                help_text.push_str(&format!("| `{}` | {} |\n", cell(&usage), cell(&description)));
+            }
+        }
 
-Shell Execution:
-  !<command> [args]        Execute a shell command (e.g., !ls -l). Output is raw text.
+        help_text.push_str(&format!("\n### {}\n\n", tr("help-default-title")));
+        help_text.push_str(&format!("* {}\n", cell(&tr("help-default-query"))));
+        help_text.push_str(&format!("* `@provider:model <prompt>`: {}\n", cell(&tr("help-default-override"))));
+        help_text.push_str(&format!("* {}\n", cell(&tr("help-default-queue"))));
 
-Default Behavior:
-  Any other text input is sent as a query to the current LLM provider and model.
-
-Current Theme: {:?}
-Current Markdown Mode: {}
-"#, current_theme, mode_str, // Placeholders for status
-current_theme, mode_str // Actual values for status
-        ).trim().to_string())
+        help_text.push_str(&format!("\n{}  \n{}", tr_args("help-current-theme", &status_args), tr_args("help-current-mode", &status_args)));
+        Ok(help_text)
     }
 
     fn name(&self) -> &str {
@@ -68,4 +86,8 @@ current_theme, mode_str // Actual values for status
     fn help(&self) -> &str {
         "Show this help message."
     }
-}
\ No newline at end of file
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Session
+    }
+}
@@ -0,0 +1,69 @@
+// src/commands/history.rs
+use async_trait::async_trait;
+use std::path::Path;
+
+use crate::{
+    commands::{recall::PROMPT_HISTORY_FILE, Command, CommandCategory},
+    config::HistoryRetention,
+    error::{ReplError, ReplResult},
+    history::prune_prompt_file,
+    state::AppState,
+};
+
+pub struct HistoryCommand {
+    state: AppState,
+}
+
+impl HistoryCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+fn describe_limits(retention: &HistoryRetention) -> String {
+    if retention.is_unlimited() {
+        return "none".to_string();
+    }
+    let mut limits = Vec::new();
+    if let Some(entries) = retention.max_entries { limits.push(format!("{} entries", entries)); }
+    if let Some(days) = retention.max_age_days { limits.push(format!("{} days", days)); }
+    if let Some(bytes) = retention.max_bytes { limits.push(format!("{} bytes", bytes)); }
+    limits.join(", ")
+}
+
+fn describe_ignored(retention: &HistoryRetention) -> String {
+    let mut rules = Vec::new();
+    if retention.ignore_space { rules.push("input starting with a space".to_string()); }
+    if !retention.ignore_patterns.is_empty() { rules.push(format!("input matching {}", retention.ignore_patterns.join(", "))); }
+    if retention.ignore_dups { rules.push("consecutive duplicates".to_string()); }
+    if rules.is_empty() { "none".to_string() } else { rules.join("; ") }
+}
+
+#[async_trait]
+impl Command for HistoryCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let retention = self.state.get_config().await.history;
+        match args.trim().to_lowercase().as_str() {
+            "" => Ok(format!(
+                "Session history: {} entries. Retention limits: {}. Not recorded: {}.",
+                self.state.get_history().await.len(),
+                describe_limits(&retention),
+                describe_ignored(&retention)
+            )),
+            "prune" => {
+                let removed_entries = self.state.prune_history().await;
+                let removed_prompts = prune_prompt_file(Path::new(PROMPT_HISTORY_FILE), &retention)?;
+                Ok(format!(
+                    "Pruned {} session entries and {} saved prompts (limits: {}).",
+                    removed_entries, removed_prompts, describe_limits(&retention)
+                ))
+            }
+            other => Err(ReplError::Command(format!("Invalid argument '{}'. Use /history [prune].", other))),
+        }
+    }
+
+    fn name(&self) -> &str { "history" }
+    fn help(&self) -> &str { "Show history size and retention limits (/history) or apply the limits now (/history prune)." }
+    fn usage(&self) -> &str { "[prune]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,65 @@
+// src/commands/hooks.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    config::PromptHooks,
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+#[derive(Clone)]
+pub struct HooksCommand {
+    state: AppState,
+}
+
+impl HooksCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    fn format_status(hooks: &PromptHooks) -> String {
+        let show = |text: &str| if text.is_empty() { "(none)".to_string() } else { format!("\"{}\"", text) };
+        format!(
+            "Prompt hooks: {}\n  Prefix: {}\n  Suffix: {}",
+            if hooks.enabled { "On" } else { "Off" },
+            show(&hooks.prefix),
+            show(&hooks.suffix)
+        )
+    }
+}
+
+#[async_trait]
+impl Command for HooksCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        let (action, value) = args.trim().split_once(char::is_whitespace)
+            .map(|(a, v)| (a, v.trim()))
+            .unwrap_or((args.trim(), ""));
+
+        match action.to_lowercase().as_str() {
+            "" => {}
+            "on" => config.hooks.enabled = true,
+            "off" => config.hooks.enabled = false,
+            "prefix" => config.hooks.prefix = value.to_string(),
+            "suffix" => config.hooks.suffix = value.to_string(),
+            "clear" => {
+                config.hooks.prefix.clear();
+                config.hooks.suffix.clear();
+            }
+            other => {
+                return Err(ReplError::Command(format!(
+                    "Invalid argument '{}'. Use /hooks [on|off|prefix <text>|suffix <text>|clear].", other
+                )));
+            }
+        }
+        let status = Self::format_status(&config.hooks);
+        self.state.set_config(config).await;
+        Ok(status)
+    }
+
+    fn name(&self) -> &str { "hooks" }
+    fn help(&self) -> &str { "Show or toggle the prefix/suffix added to every prompt (/hooks [on|off|prefix <text>|suffix <text>|clear])." }
+    fn usage(&self) -> &str { "[on|off|prefix <text>|suffix <text>|clear]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
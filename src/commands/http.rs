@@ -0,0 +1,171 @@
+// src/commands/http.rs
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Client, Method, Url,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    httplog::is_secret,
+    state::{AppState, MarkdownMode, OutputSource},
+};
+
+const USAGE: &str = "Usage: /http <METHOD> <url> [body] | /http headers | /http header <name> [value]";
+// Response bodies beyond this many characters are cut off in the output.
+const MAX_BODY_CHARS: usize = 50_000;
+
+fn header_map(headers: impl IntoIterator<Item = (String, String)>) -> ReplResult<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| ReplError::Command(format!("Invalid header name '{}'.", name)))?;
+        let header_value = HeaderValue::from_str(&value)
+            .map_err(|_| ReplError::Command(format!("Invalid value for header '{}'.", name)))?;
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+pub struct HttpCommand {
+    state: AppState,
+    client: Client,
+}
+
+impl HttpCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state, client: Client::new() }
+    }
+
+    async fn list_headers(&self) -> String {
+        let config = self.state.get_config().await.http;
+        let show = |headers: &BTreeMap<String, String>| {
+            headers.iter()
+                .map(|(name, value)| format!("    {}: {}", name, if is_secret(name) { "***" } else { value }))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let mut sections = Vec::new();
+        if !config.headers.is_empty() {
+            sections.push(format!("  All requests:\n{}", show(&config.headers)));
+        }
+        for (host, headers) in &config.hosts {
+            sections.push(format!("  {}:\n{}", host, show(headers)));
+        }
+        if sections.is_empty() {
+            return "No /http headers configured. Add one with /http header <name> <value>.".to_string();
+        }
+        format!("Headers for /http:\n{}", sections.join("\n"))
+    }
+
+    async fn set_header(&self, name: &str, value: Option<&str>) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        let message = match value {
+            Some(value) => {
+                header_map([(name.to_string(), value.to_string())])?;
+                config.http.headers.insert(name.to_string(), value.to_string());
+                format!("Sending header '{}' with every /http request.", name)
+            }
+            None if config.http.headers.remove(name).is_some() => format!("Removed header '{}'.", name),
+            None => return Err(ReplError::Command(format!("No header named '{}' is set.", name))),
+        };
+        self.state.set_config(config).await;
+        Ok(message)
+    }
+
+    async fn request(&self, method: &str, url: &str, body: Option<&str>) -> ReplResult<String> {
+        let method = Method::from_bytes(method.to_uppercase().as_bytes())
+            .map_err(|_| ReplError::Command(format!("Invalid HTTP method '{}'.", method)))?;
+        let url = Url::parse(url).map_err(|e| ReplError::Command(format!("Invalid URL '{}': {}", url, e)))?;
+        let config = self.state.get_config().await.http;
+        let mut headers = header_map(config.headers_for(url.host_str().unwrap_or_default()))?;
+
+        let mut request = self.client.request(method.clone(), url.clone()).timeout(Duration::from_secs(config.timeout_secs));
+        if let Some(body) = body {
+            // JSON bodies get a JSON content type unless one is configured
+            if serde_json::from_str::<Value>(body).is_ok() && !headers.contains_key(CONTENT_TYPE) {
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            }
+            request = request.body(body.to_string());
+        }
+        let started = Instant::now();
+        let mut response = request.headers(headers).send().await?;
+        let status = response.status();
+        let content_type = response.headers().get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        // Read chunk by chunk, so a huge or endless body stops at the limit instead of filling memory
+        let mut bytes = Vec::new();
+        let mut cut_off = false;
+        while let Some(chunk) = response.chunk().await? {
+            let room = config.max_response_bytes.saturating_sub(bytes.len());
+            if chunk.len() > room {
+                bytes.extend_from_slice(&chunk[..room]);
+                cut_off = true;
+                break;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        let elapsed = started.elapsed();
+
+        let text = String::from_utf8_lossy(&bytes);
+        let markdown = self.state.get_markdown_mode_for(OutputSource::Command).await != MarkdownMode::Off;
+        let (body, language) = match serde_json::from_slice::<Value>(&bytes) {
+            Ok(json) => (serde_json::to_string_pretty(&json)?, "json"),
+            Err(_) => (text.into_owned(), ""),
+        };
+        let truncated = body.chars().count() > MAX_BODY_CHARS;
+        let mut body: String = body.chars().take(MAX_BODY_CHARS).collect();
+        if truncated {
+            body.push_str("\n... (truncated)");
+        }
+
+        let content_type = if content_type.is_empty() { String::new() } else { format!(", {}", content_type) };
+        let mut output = format!(
+            "{} {}\nHTTP {} in {} ms ({}{}{})",
+            method, url, status, elapsed.as_millis(), format_size(bytes.len()), content_type,
+            if cut_off { ", stopped at [http] max_response_bytes" } else { "" }
+        );
+        if !body.trim().is_empty() {
+            if markdown {
+                output.push_str(&format!("\n\n```{}\n{}\n```", language, body.trim_end()));
+            } else {
+                output.push_str(&format!("\n\n{}", body.trim_end()));
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl Command for HttpCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut parts = args.trim().splitn(3, char::is_whitespace);
+        match (parts.next().unwrap_or_default(), parts.next(), parts.next().map(str::trim)) {
+            ("headers", None, None) => Ok(self.list_headers().await),
+            ("header", Some(name), value) => self.set_header(name, value.filter(|v| !v.is_empty())).await,
+            (method, Some(url), body) if !method.is_empty() => self.request(method, url, body.filter(|b| !b.is_empty())).await,
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "http" }
+    fn help(&self) -> &str { "Send an HTTP request and show status, timing and the (pretty-printed JSON) body." }
+    fn usage(&self) -> &str { "<METHOD> <url> [body]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
@@ -0,0 +1,48 @@
+// src/commands/icons.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    icons::{self, IconStyle},
+    state::AppState,
+};
+
+#[derive(Clone)]
+pub struct IconsCommand {
+    state: AppState,
+}
+
+impl IconsCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for IconsCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        if !args.trim().is_empty() {
+            config.ui.icons = IconStyle::parse(args).ok_or_else(|| {
+                ReplError::Command(format!("Unknown icon style '{}'. Use /icons <nerd|emoji|ascii>.", args.trim()))
+            })?;
+        }
+        icons::apply(&config.ui);
+        let provider = self.state.get_provider_name().await;
+        let mut status = format!("Icons: {}", config.ui.icons.name());
+        if config.ui.accessible && config.ui.icons != IconStyle::Ascii {
+            status.push_str(" (ASCII while accessibility mode is on)");
+        } else if config.ui.icons != IconStyle::Ascii {
+            // A sample, so missing glyphs (boxes or blanks) show the font lacks them
+            status.push_str(&format!("  [{}{}]{}", icons::provider_for(&provider, &config), provider, icons::prompt_arrow()));
+        }
+        self.state.set_config(config).await;
+        Ok(status)
+    }
+
+    fn name(&self) -> &str { "icons" }
+    fn help(&self) -> &str { "Show provider and status icons in the prompt and history headers: nerd (needs a Nerd Font), emoji, or ascii (/icons <nerd|emoji|ascii>)." }
+    fn usage(&self) -> &str { "[nerd|emoji|ascii]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
+}
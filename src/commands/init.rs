@@ -0,0 +1,129 @@
+// src/commands/init.rs
+use async_trait::async_trait;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    config::PROJECT_CONTEXT_FILE,
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+// File paths listed to the model, so huge trees don't crowd out the key files.
+const MAX_LISTED_FILES: usize = 400;
+// Directories not worth describing when the tree isn't a git checkout.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "vendor", "__pycache__"];
+// Files that usually say what a project is and how it is built, read in this order.
+const KEY_FILES: &[&str] = &[
+    "README.md", "Readme.md", "readme.md", "README", "CONTRIBUTING.md", "Cargo.toml", "package.json",
+    "pyproject.toml", "setup.py", "go.mod", "pom.xml", "build.gradle", "Makefile", "Dockerfile", "docker-compose.yml",
+];
+// Each key file is cut to this many characters.
+const KEY_FILE_CHARS: usize = 6_000;
+
+fn clip(text: &str) -> &str {
+    text.char_indices().nth(KEY_FILE_CHARS).map_or(text, |(cut, _)| &text[..cut])
+}
+
+// Tracked files from git (which honours .gitignore), else a walk that skips hidden and build directories.
+fn project_files(root: &Path) -> Vec<PathBuf> {
+    if let Ok(output) = ProcessCommand::new("git").args(["ls-files"]).current_dir(root).output() {
+        if output.status.success() && !output.stdout.is_empty() {
+            return String::from_utf8_lossy(&output.stdout).lines().map(PathBuf::from).collect();
+        }
+    }
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(root.join(&dir)) else { continue };
+        let mut entries = entries.flatten().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let path = dir.join(&name);
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() && !SKIPPED_DIRS.contains(&name.as_str()) => pending.push(path),
+                Ok(kind) if kind.is_file() => files.push(path),
+                _ => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+pub struct InitCommand {
+    state: AppState,
+}
+
+impl InitCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // The prompt asking for the summary: the file list plus the key files' text.
+    fn scan(root: &Path) -> ReplResult<(String, usize)> {
+        let files = project_files(root);
+        if files.is_empty() {
+            return Err(ReplError::Command(format!("No files found under '{}'.", root.display())));
+        }
+        let mut listing = files.iter().take(MAX_LISTED_FILES).map(|path| path.display().to_string()).collect::<Vec<_>>().join("\n");
+        if files.len() > MAX_LISTED_FILES {
+            listing.push_str(&format!("\n... and {} more files", files.len() - MAX_LISTED_FILES));
+        }
+        let key_files = KEY_FILES.iter()
+            .filter(|name| files.iter().any(|path| path.as_os_str() == **name))
+            .filter_map(|name| fs::read_to_string(root.join(name)).ok().map(|text| format!("File `{}`:\n```\n{}\n```", name, clip(&text).trim_end())))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let prompt = format!(
+            "Write a project context file for an AI assistant that will help develop this repository. \
+             In Markdown and under 500 words, cover: what the project does, the languages and main dependencies, \
+             how the code is laid out (key directories and modules), how to build, run and test it, and any \
+             conventions worth following. Only state what the material below supports.\n\n\
+             Files in the repository:\n```\n{}\n```\n\n{}",
+            listing, key_files
+        );
+        Ok((prompt, files.len()))
+    }
+}
+
+#[async_trait]
+impl Command for InitCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let force = match args.trim() {
+            "" => false,
+            "force" => true,
+            other => return Err(ReplError::Command(format!("Invalid argument '{}'. Use /init [force].", other))),
+        };
+        let target = Path::new(PROJECT_CONTEXT_FILE);
+        if target.exists() && !force {
+            return Err(ReplError::Command(format!("{} already exists. Use /init force to regenerate it.", PROJECT_CONTEXT_FILE)));
+        }
+
+        let root = std::env::current_dir()?;
+        let (prompt, file_count) = Self::scan(&root)?;
+        let summary = self.state.ask_current_model(&prompt).await?;
+        if summary.trim().is_empty() {
+            return Err(ReplError::Command("The model returned an empty summary; nothing written.".to_string()));
+        }
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(target, format!("{}\n", summary.trim_end()))?;
+        Ok(format!(
+            "Wrote {} from {} files. It is included with every prompt started in this directory; edit it freely.",
+            PROJECT_CONTEXT_FILE, file_count
+        ))
+    }
+
+    fn name(&self) -> &str { "init" }
+    fn help(&self) -> &str { "Scan the project and have the model write .llm-repl/context.md, which is then sent with every prompt here." }
+    fn usage(&self) -> &str { "[force]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
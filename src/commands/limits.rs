@@ -0,0 +1,118 @@
+// src/commands/limits.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+// Allow typing escapes like "\n" for stop sequences on a single input line.
+fn unescape(input: &str) -> String {
+    input.replace("\\n", "\n").replace("\\t", "\t")
+}
+
+fn escape(input: &str) -> String {
+    input.replace('\n', "\\n").replace('\t', "\\t")
+}
+
+// --- Command for /stop (Stop Sequences) ---
+#[derive(Clone)]
+pub struct StopCommand {
+    state: AppState,
+}
+
+impl StopCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for StopCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut limits = self.state.get_output_limits().await;
+        let trimmed_args = args.trim();
+
+        match trimmed_args {
+            "" => {
+                if limits.stop_sequences.is_empty() {
+                    Ok("No stop sequences set.".to_string())
+                } else {
+                    let list = limits.stop_sequences.iter()
+                        .map(|s| format!("  \"{}\"", escape(s)))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    Ok(format!("Stop sequences:\n{}", list))
+                }
+            }
+            "clear" => {
+                limits.stop_sequences.clear();
+                self.state.set_output_limits(limits).await;
+                Ok("Stop sequences cleared.".to_string())
+            }
+            _ => {
+                let sequence = unescape(trimmed_args);
+                if !limits.stop_sequences.contains(&sequence) {
+                    limits.stop_sequences.push(sequence);
+                }
+                self.state.set_output_limits(limits).await;
+                Ok(format!("Added stop sequence: \"{}\"", trimmed_args))
+            }
+        }
+    }
+
+    fn name(&self) -> &str { "stop" }
+    fn help(&self) -> &str { "Show (/stop), add (/stop <sequence>) or clear (/stop clear) client-side stop sequences." }
+    fn usage(&self) -> &str { "[seq|clear]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
+
+
+// --- Command for /max_length (Output Length Cap) ---
+#[derive(Clone)]
+pub struct MaxLengthCommand {
+    state: AppState,
+}
+
+impl MaxLengthCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for MaxLengthCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut limits = self.state.get_output_limits().await;
+        let trimmed_args = args.trim();
+
+        match trimmed_args {
+            "" => match limits.max_chars {
+                Some(max) => Ok(format!("Maximum response length: {} characters.", max)),
+                None => Ok("Maximum response length: Off".to_string()),
+            },
+            "off" => {
+                limits.max_chars = None;
+                self.state.set_output_limits(limits).await;
+                Ok("Maximum response length disabled.".to_string())
+            }
+            _ => {
+                let max = trimmed_args.parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| ReplError::Command(format!(
+                        "Invalid length '{}'. Use a positive number of characters or 'off'.", trimmed_args
+                    )))?;
+                limits.max_chars = Some(max);
+                self.state.set_output_limits(limits).await;
+                Ok(format!("Maximum response length set to {} characters.", max))
+            }
+        }
+    }
+
+    fn name(&self) -> &str { "max_length" }
+    fn help(&self) -> &str { "Show or set the maximum response length in characters (/max_length <n|off>)." }
+    fn usage(&self) -> &str { "[n|off]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -11,9 +11,10 @@ use futures::StreamExt;
 use strum_macros::EnumIter; // Still need EnumIter for derive
 
 use crate::{
-    commands::Command,
+    commands::{Command, CommandCategory, CommandEvent, EventSender},
     error::{ReplError, ReplResult},
-    providers::LlmProvider,
+    limits::limit_stream,
+    providers::{LlmProvider, ModelInfo},
     state::{AppState, MarkdownMode, RenderTheme},
     render::{get_theme_resources}, // Removed unused ThemePalette import here
     signal::{is_stop_requested, reset_stop_flag},
@@ -41,6 +42,8 @@ pub enum SelectableTheme {
     Nord,
     Gruvbox,
     Grayscale,
+    Deuteranopia,
+    Protanopia,
 }
 
 impl std::fmt::Display for SelectableTheme {
@@ -50,6 +53,8 @@ impl std::fmt::Display for SelectableTheme {
             SelectableTheme::Nord => write!(f, "Nord (Cool, subdued blues)"),
             SelectableTheme::Gruvbox => write!(f, "Gruvbox (Warm retro - WIP)"),
             SelectableTheme::Grayscale => write!(f, "Grayscale (Minimal - WIP)"),
+            SelectableTheme::Deuteranopia => write!(f, "Deuteranopia (Colorblind-safe blue/orange)"),
+            SelectableTheme::Protanopia => write!(f, "Protanopia (Colorblind-safe blue/orange)"),
         }
     }
 }
@@ -61,6 +66,8 @@ impl From<SelectableTheme> for RenderTheme {
             SelectableTheme::Nord => RenderTheme::Nord,
             SelectableTheme::Gruvbox => RenderTheme::Gruvbox,
             SelectableTheme::Grayscale => RenderTheme::Grayscale,
+            SelectableTheme::Deuteranopia => RenderTheme::Deuteranopia,
+            SelectableTheme::Protanopia => RenderTheme::Protanopia,
         }
     }
 }
@@ -71,10 +78,19 @@ fn theme_to_index(state_theme: RenderTheme) -> usize {
         RenderTheme::Nord => 1,
         RenderTheme::Gruvbox => 2,
         RenderTheme::Grayscale => 3,
+        RenderTheme::Deuteranopia => 4,
+        RenderTheme::Protanopia => 5,
     }
 }
 // --- End Theme Selection Helpers ---
 
+// Sends text to the streaming output channel, if the command is being streamed.
+fn emit(output: Option<&EventSender>, text: &str) {
+    if let Some(sender) = output {
+        let _ = sender.send(CommandEvent::Output(text.to_string()));
+    }
+}
+
 
 #[derive(Clone)]
 pub struct LlmConvoCommand {
@@ -99,18 +115,18 @@ impl LlmConvoCommand {
 
         let provider = self.state.get_provider_by_name(provider_name)
             .ok_or_else(|| ReplError::UnknownProvider(provider_name.to_string()))?;
-        provider.check_readiness().await.map_err(|e|
+        self.state.check_provider_ready(provider_name, provider.as_ref()).await.map_err(|e|
             ReplError::Provider(format!("Provider '{}' is not ready: {}", provider_name, e))
         )?;
 
-        let models = provider.get_models().await.map_err(|e|
+        let models = self.state.provider_models(provider_name, provider.as_ref()).await.map_err(|e|
             ReplError::Command(format!("Could not list models for provider '{}': {}", provider_name, e))
         )?;
         if models.is_empty() { return Err(ReplError::Command(format!("No models available for provider '{}'.", provider_name))); }
         let model_selection_index = FuzzySelect::with_theme(&ColorfulTheme::default())
             .with_prompt(format!("Select model for {} LLM (Provider: {})", instance_name, provider_name))
-            .items(&models).default(0).interact().map_err(ReplError::from)?;
-        let model_name = models[model_selection_index].clone();
+            .items(&models.iter().map(ModelInfo::label).collect::<Vec<_>>()).default(0).interact().map_err(ReplError::from)?;
+        let model_name = models[model_selection_index].id.clone();
 
         println!("Define persona/instructions for {} LLM.", instance_name);
         println!("(Describe its role, personality, goals. End with Enter then Ctrl+D/Ctrl+Z)");
@@ -148,6 +164,7 @@ impl LlmConvoCommand {
     }
 
     // --- The Core Conversation Loop ---
+    #[allow(clippy::too_many_arguments)]
     async fn run_conversation_loop(
         &self,
         mut llm1: LlmInstance,
@@ -156,8 +173,11 @@ impl LlmConvoCommand {
         max_turns: u32,
         _markdown_mode: MarkdownMode, // Marked unused
         theme: RenderTheme,
+        output: Option<&EventSender>, // Streams each turn to REST clients when set
     ) -> ReplResult<()> {
         let (_skin, palette) = get_theme_resources(theme);
+        let limits = self.state.get_output_limits().await;
+        let params = self.state.get_generation_params().await;
 
         println!("\n--- Starting Conversation ---");
         println!("LLM 1 ({} - {}): {}", llm1.provider.get_name(), llm1.model, llm1.persona.lines().next().unwrap_or("..."));
@@ -188,16 +208,18 @@ impl LlmConvoCommand {
                 (&mut llm2, "LLM_2")
             };
 
-            println!( "\n{}", format!( "-- Turn {} | {} ({}:{}) thinking... --", turn + 1, speaker_role_str, current_llm.provider.get_name(), current_llm.model)
-                .truecolor(palette.info.0, palette.info.1, palette.info.2) );
+            let turn_header = format!( "-- Turn {} | {} ({}:{}) thinking... --", turn + 1, speaker_role_str, current_llm.provider.get_name(), current_llm.model);
+            println!( "\n{}", turn_header.truecolor(palette.info.0, palette.info.1, palette.info.2) );
+            emit(output, &format!("\n{}\n{}: ", turn_header, speaker_role_str));
 
             // !! Simplified Prompt Preparation !!
             let prompt_text = history.iter()
                 .map(|msg| format!("{}: {}", msg.role, msg.content))
                 .collect::<Vec<_>>().join("\n\n");
 
-            let response_result = match current_llm.provider.query_stream(&current_llm.model, &prompt_text).await {
+            let response_result = match current_llm.provider.query_stream(&current_llm.model, &prompt_text, &params).await {
                 Ok(Some(stream)) => {
+                    let stream = limit_stream(stream, limits.clone());
                     print!("{}: ", speaker_role_str.truecolor(palette.success.0, palette.success.1, palette.success.2));
                     let mut full_response = String::new();
                     let mut stream_pin = stream;
@@ -206,6 +228,7 @@ impl LlmConvoCommand {
                             Ok(chunk) => {
                                 print!("{}", chunk);
                                 io::stdout().flush().map_err(ReplError::Io)?;
+                                emit(output, &chunk);
                                 full_response.push_str(&chunk);
                             }
                             Err(e) => {
@@ -220,9 +243,11 @@ impl LlmConvoCommand {
                 },
                 Ok(None) | Err(_) => {
                     print!("{}: ", speaker_role_str.truecolor(palette.success.0, palette.success.1, palette.success.2));
-                    match current_llm.provider.query(&current_llm.model, &prompt_text).await {
+                    match current_llm.provider.query(&current_llm.model, &prompt_text, &params).await {
                          Ok(response) => {
+                             let response = limits.apply(&response);
                              println!("{}", response.trim());
+                             emit(output, response.trim());
                              Ok(response)
                          },
                          Err(e) => Err(e),
@@ -246,6 +271,7 @@ impl LlmConvoCommand {
                 }
             }
 
+            emit(output, "\n");
             current_speaker_idx = 1 - current_speaker_idx;
             sleep(Duration::from_millis(200)).await;
         }
@@ -255,12 +281,9 @@ impl LlmConvoCommand {
         reset_stop_flag();
         Ok(())
     }
-}
-
 
-#[async_trait]
-impl Command for LlmConvoCommand {
-    async fn execute(&self, _args: &str) -> ReplResult<String> {
+    // Shared by `execute` and `execute_streaming`; `output` receives the turns as they stream.
+    async fn run(&self, output: Option<EventSender>) -> ReplResult<String> {
         println!("{}", "Starting LLM Conversation setup...".yellow());
 
         let llm1 = self.select_llm_instance("first").await?;
@@ -272,7 +295,7 @@ impl Command for LlmConvoCommand {
         // Removed unused palette fetch here: let (_skin, palette) = get_theme_resources(theme);
 
         if let Err(e) = self.run_conversation_loop(
-                llm1, llm2, topic, max_turns, markdown_mode, theme
+                llm1, llm2, topic, max_turns, markdown_mode, theme, output.as_ref()
             ).await {
              return Err(ReplError::Command(format!("Conversation ended with error: {}", e)));
         }
@@ -280,9 +303,22 @@ impl Command for LlmConvoCommand {
         // Success message now printed inside run_conversation_loop
         Ok("Conversation completed.".to_string()) // Return simple confirmation string
     }
+}
+
+
+#[async_trait]
+impl Command for LlmConvoCommand {
+    async fn execute(&self, _args: &str) -> ReplResult<String> {
+        self.run(None).await
+    }
+
+    async fn execute_streaming(&self, _args: &str, output: EventSender) -> ReplResult<String> {
+        self.run(Some(output)).await
+    }
 
     fn name(&self) -> &str { "llmconvo" }
     fn help(&self) -> &str { "Start a conversation between two configured LLMs." }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
 }
 
 // Keep ThemeStatusCommand
@@ -2,11 +2,21 @@
 use async_trait::async_trait;
 
 use crate::{
-    commands::Command,
-    error::ReplResult,
-    state::{AppState, MarkdownMode}, // Import MarkdownMode
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::{AppState, MarkdownMode, OutputSource}, // Import MarkdownMode
 };
 
+fn describe_mode(mode: MarkdownMode) -> &'static str {
+    match mode {
+        MarkdownMode::AppendFormatted => "Append Formatted (Stream raw, append formatted below)",
+        MarkdownMode::LiveStreaming => "Live Streaming (Experimental, may flicker)",
+        MarkdownMode::Off => "Off (Raw text output only)",
+    }
+}
+
+const SOURCES: [(&str, OutputSource); 3] = [("llm", OutputSource::Llm), ("commands", OutputSource::Command), ("shell", OutputSource::Shell)];
+
 // --- Command for /md (Append Formatted) ---
 #[derive(Clone)]
 pub struct MdCommand {
@@ -28,6 +38,7 @@ impl Command for MdCommand {
 
     fn name(&self) -> &str { "md" }
     fn help(&self) -> &str { "Set Markdown rendering to stream raw text, then append formatted." }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
 }
 
 
@@ -46,12 +57,16 @@ impl MdStreamingCommand {
 #[async_trait]
 impl Command for MdStreamingCommand {
     async fn execute(&self, _args: &str) -> ReplResult<String> {
+        if self.state.get_config().await.ui.accessible {
+            return Err(ReplError::Command("Live Streaming is unavailable in accessibility mode (/accessibility off to enable it).".to_string()));
+        }
         self.state.set_markdown_mode(MarkdownMode::LiveStreaming).await;
         Ok("Markdown rendering mode set to: Live Streaming (Experimental, may flicker)".to_string())
     }
 
     fn name(&self) -> &str { "md_streaming" }
     fn help(&self) -> &str { "Set Markdown rendering to attempt live formatting (Experimental)." }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
 }
 
 
@@ -76,6 +91,7 @@ impl Command for MdOffCommand {
 
     fn name(&self) -> &str { "md_off" }
     fn help(&self) -> &str { "Disable all Markdown rendering." }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
 }
 
 
@@ -95,14 +111,94 @@ impl MdStatusCommand {
 impl Command for MdStatusCommand {
     async fn execute(&self, _args: &str) -> ReplResult<String> {
         let mode = self.state.get_markdown_mode().await;
-        let mode_str = match mode {
-            MarkdownMode::AppendFormatted => "Append Formatted (Stream raw, append formatted below)",
-            MarkdownMode::LiveStreaming => "Live Streaming (Experimental, may flicker)",
-            MarkdownMode::Off => "Off (Raw text output only)",
-        };
-        Ok(format!("Current Markdown rendering mode: {}", mode_str))
+        let mut status = format!("Current Markdown rendering mode: {}", describe_mode(mode));
+        for (label, source) in &SOURCES[1..] {
+            let source_mode = self.state.get_markdown_mode_for(*source).await;
+            status.push_str(&format!("\n  {:<9} {}", format!("{}:", label), if source_mode == MarkdownMode::Off { "Off" } else { "Formatted" }));
+        }
+        Ok(status)
     }
 
     fn name(&self) -> &str { "md_status" }
     fn help(&self) -> &str { "Show the current Markdown rendering mode." }
-}
\ No newline at end of file
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
+}
+
+
+// --- Command for /md_source (Per-Source Rendering) ---
+#[derive(Clone)]
+pub struct MdSourceCommand {
+    state: AppState,
+}
+
+impl MdSourceCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for MdSourceCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        const USAGE: &str = "Usage: /md_source <llm|commands|shell> <append|live|off|default>";
+        let mut parts = args.split_whitespace();
+        let (Some(source_name), Some(mode_name), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(ReplError::Command(USAGE.to_string()));
+        };
+        let (label, source) = SOURCES.iter()
+            .find(|(label, _)| label.eq_ignore_ascii_case(source_name))
+            .ok_or_else(|| ReplError::Command(USAGE.to_string()))?;
+        let mode = match mode_name.to_lowercase().as_str() {
+            "default" => None,
+            other => Some(MarkdownMode::parse(other).ok_or_else(|| ReplError::Command(USAGE.to_string()))?),
+        };
+        if mode == Some(MarkdownMode::LiveStreaming) && *source == OutputSource::Llm && self.state.get_config().await.ui.accessible {
+            return Err(ReplError::Command("Live Streaming is unavailable in accessibility mode (/accessibility off to enable it).".to_string()));
+        }
+        self.state.set_markdown_mode_for(*source, mode).await;
+        let current = self.state.get_markdown_mode_for(*source).await;
+        Ok(format!("Markdown rendering for {} output set to: {}", label, describe_mode(current)))
+    }
+
+    fn name(&self) -> &str { "md_source" }
+    fn help(&self) -> &str { "Set Markdown rendering for one output source (llm, commands, shell)." }
+    fn usage(&self) -> &str { "<source> <mode>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
+}
+
+
+// --- Command for /md_split (Side-by-Side Raw and Formatted) ---
+#[derive(Clone)]
+pub struct MdSplitCommand {
+    state: AppState,
+}
+
+impl MdSplitCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for MdSplitCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        match args.trim().to_lowercase().as_str() {
+            "" => {}
+            "on" => config.markdown.split = true,
+            "off" => config.markdown.split = false,
+            other => return Err(ReplError::Command(format!("Invalid argument '{}'. Use /md_split <on|off>.", other))),
+        }
+        let status = format!(
+            "Side-by-side raw and formatted responses: {} (Append Formatted mode, terminals at least 100 columns wide)",
+            if config.markdown.split { "On" } else { "Off" }
+        );
+        self.state.set_config(config).await;
+        Ok(status)
+    }
+
+    fn name(&self) -> &str { "md_split" }
+    fn help(&self) -> &str { "Show raw Markdown and its rendering side by side after each response, when the terminal is wide enough." }
+    fn usage(&self) -> &str { "[on|off]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
+}
@@ -0,0 +1,60 @@
+// src/commands/memory.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+pub struct MemoryCommand {
+    state: AppState,
+}
+
+impl MemoryCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+fn describe_limit(memory_turns: Option<usize>) -> String {
+    match memory_turns {
+        None => "all earlier turns are sent with each prompt".to_string(),
+        Some(0) => "off, each prompt is sent on its own".to_string(),
+        Some(turns) => format!("the last {} turns are sent with each prompt", turns),
+    }
+}
+
+#[async_trait]
+impl Command for MemoryCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        match args.trim().to_lowercase().as_str() {
+            "" => {}
+            "clear" => {
+                let turns = self.state.clear_conversation().await;
+                return Ok(format!("Forgot {} turns; the next prompt starts a new conversation.", turns));
+            }
+            "all" => config.session.memory_turns = None,
+            "off" => config.session.memory_turns = Some(0),
+            other => {
+                let turns = other.parse::<usize>().map_err(|_| ReplError::Command(format!(
+                    "Invalid argument '{}'. Use /memory [clear|off|all|<turns>].", other
+                )))?;
+                config.session.memory_turns = Some(turns);
+            }
+        }
+        let status = format!(
+            "Conversation: {} turns so far; {}.",
+            self.state.get_conversation().await.len() / 2,
+            describe_limit(config.session.memory_turns)
+        );
+        self.state.set_config(config).await;
+        Ok(status)
+    }
+
+    fn name(&self) -> &str { "memory" }
+    fn help(&self) -> &str { "Show the conversation sent with prompts, forget it (/memory clear), or set how many earlier turns are sent (/memory <turns|off|all>)." }
+    fn usage(&self) -> &str { "[clear|off|all|turns]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
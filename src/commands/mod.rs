@@ -1,28 +1,180 @@
 // src/commands/mod.rs
 use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::mpsc;
 use crate::{
     error::ReplResult,
     state::AppState,
 };
 
+/// A progress update from a long-running command (download, ingestion, benchmark...).
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressUpdate {
+    pub message: String,
+    pub current: u64,
+    pub total: Option<u64>, // None for open-ended work (rendered as a spinner)
+}
+
+/// Incremental events a command can emit while it runs.
+#[derive(Debug, Clone)]
+pub enum CommandEvent {
+    /// A chunk of output text (e.g. streamed to REST clients over SSE).
+    Output(String),
+    Progress(ProgressUpdate),
+}
+
+/// Channel for incremental command events.
+pub type EventSender = mpsc::UnboundedSender<CommandEvent>;
+
+/// Sends a progress update; the REPL renders it as a progress bar, the server as an SSE event.
+pub fn report_progress(events: &EventSender, message: &str, current: u64, total: Option<u64>) {
+    let _ = events.send(CommandEvent::Progress(ProgressUpdate { message: message.to_string(), current, total }));
+}
+
 // Declare the modules for each command
+pub mod accessibility;
+pub mod apikey;
+pub mod assistant;
+pub mod attach;
+pub mod buf;
+pub mod cache;
+pub mod checkpoint;
+pub mod config;
+pub mod context;
+pub mod continuation;
+pub mod data;
+pub mod db;
+pub mod debug;
+pub mod diffmodels;
+pub mod embed;
+pub mod eval;
+pub mod ground;
 pub mod help;
+pub mod history;
+pub mod hooks;
+pub mod http;
+pub mod icons;
+pub mod init;
+pub mod limits;
 pub mod llmconvo;
 pub mod markdown;
+pub mod memory;
 pub mod model;
+pub mod notify;
+pub mod ollama;
+pub mod ops;
+pub mod pace;
+pub mod pin;
+pub mod playground;
+pub mod prefer;
 pub mod provider;
 pub mod reader; // Include the reader module
+pub mod recall;
+pub mod replay;
+pub mod report;
+pub mod sessions;
+pub mod set;
+pub mod suggest;
+pub mod system;
+pub mod tag;
+pub mod tee;
 pub mod theme;
+pub mod tokens;
+pub mod usage;
+pub mod watch;
+pub mod workflow;
+
+// Edit distance (insertions, deletions, substitutions) between two strings, by characters.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The candidate closest to a mistyped command name, if it is close enough to be a likely typo
+/// (at most 2 edits, and fewer than the name's length).
+pub fn closest_command<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = 2.min(name.chars().count().saturating_sub(1));
+    candidates.into_iter()
+        .map(|candidate| (levenshtein(&name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The `/help` section a command is listed under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandCategory {
+    /// Choosing and comparing providers and models.
+    Providers,
+    /// How output looks: Markdown modes, themes, pacing, views of the session.
+    Rendering,
+    /// The conversation itself: history, checkpoints, limits, hooks.
+    Session,
+    /// Working with data and services: datasets, databases, HTTP, file watches.
+    Tools,
+    /// Running commands on the system.
+    Shell,
+}
+
+impl CommandCategory {
+    /// All categories, in `/help` order.
+    pub const ALL: [CommandCategory; 5] = [
+        CommandCategory::Providers,
+        CommandCategory::Rendering,
+        CommandCategory::Session,
+        CommandCategory::Tools,
+        CommandCategory::Shell,
+    ];
+
+    /// Message id of the section heading.
+    pub fn message_id(self) -> &'static str {
+        match self {
+            CommandCategory::Providers => "help-category-providers",
+            CommandCategory::Rendering => "help-category-rendering",
+            CommandCategory::Session => "help-category-session",
+            CommandCategory::Tools => "help-category-tools",
+            CommandCategory::Shell => "help-category-shell",
+        }
+    }
+}
+
+/// What `/help` shows for one command.
+#[derive(Debug, Clone)]
+pub struct HelpEntry {
+    pub name: String,
+    pub usage: String,
+    pub category: CommandCategory,
+    pub help: String, // English fallback when the locale has no `help-cmd-<name>` message
+}
 
 /// The core trait that all REPL commands must implement.
 #[async_trait]
 pub trait Command: Send + Sync {
     /// Executes the command logic.
     async fn execute(&self, args: &str) -> ReplResult<String>;
+    /// Executes the command, sending incremental output and progress to `events` as it is produced.
+    /// Long-running commands override this; the default just runs `execute`.
+    async fn execute_streaming(&self, args: &str, _events: EventSender) -> ReplResult<String> {
+        self.execute(args).await
+    }
     /// Returns the name of the command (e.g., "help", "model").
     fn name(&self) -> &str;
     /// Returns a short help string describing the command's purpose.
     fn help(&self) -> &str;
+    /// Arguments shown after the name in `/help` and prompt hints (e.g. `[name]`); empty if none.
+    fn usage(&self) -> &str { "" }
+    /// The `/help` section the command is listed under.
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
 }
 
 /// Holds all registered commands and provides methods to access them.
@@ -39,18 +191,79 @@ impl CommandRegistry {
 
         // --- Register all available commands here ---
         // Pass a clone of AppState to each command constructor that needs it.
-        registry.register(Box::new(help::HelpCommand::new(state.clone())));
         registry.register(Box::new(model::ModelCommand::new(state.clone())));
         registry.register(Box::new(provider::ProviderCommand::new(state.clone())));
+        registry.register(Box::new(apikey::ApiKeyCommand::new(state.clone())));
+        registry.register(Box::new(ground::GroundCommand::new(state.clone())));
         registry.register(Box::new(markdown::MdCommand::new(state.clone())));
         registry.register(Box::new(markdown::MdStreamingCommand::new(state.clone())));
         registry.register(Box::new(markdown::MdOffCommand::new(state.clone())));
         registry.register(Box::new(markdown::MdStatusCommand::new(state.clone())));
+        registry.register(Box::new(markdown::MdSourceCommand::new(state.clone())));
+        registry.register(Box::new(markdown::MdSplitCommand::new(state.clone())));
         registry.register(Box::new(theme::ThemeCommand::new(state.clone())));
         registry.register(Box::new(theme::ThemeStatusCommand::new(state.clone())));
         registry.register(Box::new(llmconvo::LlmConvoCommand::new(state.clone())));
+        registry.register(Box::new(diffmodels::DiffModelsCommand::new(state.clone())));
+        registry.register(Box::new(prefer::PreferCommand::new(state.clone())));
+        registry.register(Box::new(cache::CacheCommand::new(state.clone())));
+        registry.register(Box::new(ollama::PullCommand::new(state.clone())));
+        registry.register(Box::new(ollama::RmCommand::new(state.clone())));
+        registry.register(Box::new(ollama::ShowCommand::new(state.clone())));
+        registry.register(Box::new(ollama::OllamaCommand::new(state.clone())));
+        registry.register(Box::new(embed::EmbedCommand::new(state.clone())));
+        registry.register(Box::new(report::ReportCommand::new(state.clone())));
+        registry.register(Box::new(usage::UsageCommand::new(state.clone())));
+        registry.register(Box::new(eval::EvalCommand::new(state.clone())));
         registry.register(Box::new(reader::ReaderCommand::new(state.clone()))); // Register reader
+        registry.register(Box::new(replay::ReplayCommand::new(state.clone())));
+        registry.register(Box::new(recall::RecallCommand::new(state.clone())));
+        registry.register(Box::new(history::HistoryCommand::new(state.clone())));
+        registry.register(Box::new(memory::MemoryCommand::new(state.clone())));
+        registry.register(Box::new(init::InitCommand::new(state.clone())));
+        registry.register(Box::new(buf::BufCommand::new(state.clone())));
+        registry.register(Box::new(attach::AttachCommand::new(state.clone())));
+        registry.register(Box::new(system::SystemCommand::new(state.clone())));
+        registry.register(Box::new(pin::PinCommand::new(state.clone())));
+        registry.register(Box::new(pin::PinsCommand::new(state.clone())));
+        registry.register(Box::new(tokens::TokensCommand::new(state.clone())));
+        registry.register(Box::new(context::ContextCommand::new(state.clone())));
+        registry.register(Box::new(assistant::AssistantCommand::new(state.clone())));
+        registry.register(Box::new(continuation::ContinueCommand::new(state.clone())));
+        registry.register(Box::new(checkpoint::CheckpointCommand::new(state.clone())));
+        registry.register(Box::new(checkpoint::RollbackCommand::new(state.clone())));
+        registry.register(Box::new(sessions::SaveCommand::new(state.clone())));
+        registry.register(Box::new(sessions::LoadCommand::new(state.clone())));
+        registry.register(Box::new(sessions::SessionsCommand::new(state.clone())));
+        registry.register(Box::new(sessions::GrepCommand::new(state.clone())));
+        registry.register(Box::new(tag::TagCommand::new(state.clone())));
+        registry.register(Box::new(tag::BookmarksCommand::new(state.clone())));
+        registry.register(Box::new(data::DataCommand::new(state.clone())));
+        registry.register(Box::new(db::DbCommand::new(state.clone())));
+        registry.register(Box::new(db::SqlCommand::new(state.clone())));
+        registry.register(Box::new(http::HttpCommand::new(state.clone())));
+        registry.register(Box::new(debug::DebugCommand::new()));
+        registry.register(Box::new(tee::TeeCommand::new()));
+        registry.register(Box::new(playground::JqCommand::new(state.clone())));
+        registry.register(Box::new(playground::RegexCommand::new(state.clone())));
+        registry.register(Box::new(suggest::SuggestCommand::new(state.clone())));
+        registry.register(Box::new(ops::OpsCommand::new(state.clone())));
+        registry.register(Box::new(ops::K8sCommand::new(state.clone())));
+        registry.register(Box::new(limits::StopCommand::new(state.clone())));
+        registry.register(Box::new(limits::MaxLengthCommand::new(state.clone())));
+        registry.register(Box::new(set::SetCommand::new(state.clone())));
+        registry.register(Box::new(config::ConfigCommand::new(state.clone())));
+        registry.register(Box::new(notify::NotifyCommand::new(state.clone())));
+        registry.register(Box::new(hooks::HooksCommand::new(state.clone())));
+        registry.register(Box::new(pace::PaceCommand::new(state.clone())));
+        registry.register(Box::new(accessibility::AccessibilityCommand::new(state.clone())));
+        registry.register(Box::new(icons::IconsCommand::new(state.clone())));
+        registry.register(Box::new(watch::WatchCommand::new(state.clone())));
+        registry.register(Box::new(workflow::WorkflowCommand::new(state.clone())));
 
+        // /help lists everything registered above, so it is built last but listed first
+        let entries = registry.help_entries();
+        registry.commands.insert(0, Box::new(help::HelpCommand::new(state.clone(), entries)));
         registry
     }
 
@@ -72,8 +285,25 @@ impl CommandRegistry {
         self.commands.iter().find(|c| c.name() == name).map(|c| &**c)
     }
 
+    /// Suggests the registered command closest to a mistyped `name`.
+    pub fn suggest(&self, name: &str) -> Option<&str> {
+        closest_command(name, self.commands.iter().map(|c| c.name()))
+    }
+
     /// Returns a list of the names of all registered commands.
     pub fn list_commands(&self) -> Vec<&str> {
         self.commands.iter().map(|c| c.name()).collect()
     }
+
+    /// Names and argument usages of the commands that take arguments, for prompt hints.
+    pub fn list_usages(&self) -> Vec<(&str, &str)> {
+        self.commands.iter().filter(|c| !c.usage().is_empty()).map(|c| (c.name(), c.usage())).collect()
+    }
+
+    /// Name, usage, category and help text of every command, in registration order.
+    pub fn help_entries(&self) -> Vec<HelpEntry> {
+        self.commands.iter()
+            .map(|c| HelpEntry { name: c.name().to_string(), usage: c.usage().to_string(), category: c.category(), help: c.help().to_string() })
+            .collect()
+    }
 }
\ No newline at end of file
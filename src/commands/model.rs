@@ -3,8 +3,9 @@ use async_trait::async_trait;
 use dialoguer::{theme::ColorfulTheme, FuzzySelect};
 
 use crate::{
-    commands::Command,
+    commands::{Command, CommandCategory},
     error::{ReplError, ReplResult},
+    providers::ModelInfo,
     state::AppState,
 };
 
@@ -27,17 +28,19 @@ impl ModelCommand {
 
         let current_model = self.state.get_model().await;
         let current_index = models.iter()
-            .position(|m| m == &current_model)
+            .position(|m| m.id == current_model)
             .unwrap_or(0);
+        // Each item shows what the provider reports about the model, e.g. its context window
+        let labels: Vec<String> = models.iter().map(ModelInfo::label).collect();
 
         let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
             .with_prompt("Select model (use arrow keys, type to filter)")
-            .items(&models)
+            .items(&labels)
             .default(current_index)
             .interact()
             .map_err(|e| ReplError::Command(format!("Selection error: {}", e)))?;
 
-        Ok(models[selection].clone())
+        Ok(models[selection].id.clone())
     }
 }
 
@@ -54,6 +57,16 @@ impl Command for ModelCommand {
             Ok(format!("Model set to: {}", selected_model))
 
         } else {
+            // --- Alias from the config's [aliases], possibly switching provider ---
+            if self.state.get_config().await.aliases.contains_key(trimmed_args) {
+                let (provider_name, model) = self.state.resolve_model_spec(trimmed_args).await;
+                if provider_name != self.state.get_provider_name().await {
+                    self.state.set_provider(&provider_name).await?;
+                }
+                self.state.set_model(&model).await?;
+                return Ok(format!("Model set to: {} ({}:{})", trimmed_args, provider_name, model));
+            }
+
             // --- Direct Setting Mode (with Validation) ---
             let proposed_model = trimmed_args;
             let provider_name = self.state.get_provider_name().await; // Get for context
@@ -71,7 +84,7 @@ impl Command for ModelCommand {
             };
 
             // Check if the proposed model exists in the fetched list
-            if available_models.iter().any(|m| m == proposed_model) {
+            if available_models.iter().any(|m| m.id == proposed_model) {
                 // Model is valid, set it in the state
                 self.state.set_model(proposed_model).await?;
                 Ok(format!("Model set to: {}", proposed_model))
@@ -93,4 +106,12 @@ impl Command for ModelCommand {
     fn help(&self) -> &str {
         "Select a model (interactively with /model or directly with /model <name>)"
     }
+
+    fn usage(&self) -> &str {
+        "[name]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Providers
+    }
 }
\ No newline at end of file
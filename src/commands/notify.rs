@@ -0,0 +1,50 @@
+// src/commands/notify.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+#[derive(Clone)]
+pub struct NotifyCommand {
+    state: AppState,
+}
+
+impl NotifyCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for NotifyCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+
+        match args.trim().to_lowercase().as_str() {
+            "" => {}
+            "on" => config.notifications.enabled = true,
+            "off" => config.notifications.enabled = false,
+            other => {
+                let secs = other.parse::<u64>().map_err(|_| ReplError::Command(format!(
+                    "Invalid argument '{}'. Use /notify <on|off|seconds>.", other
+                )))?;
+                config.notifications.threshold_secs = secs;
+            }
+        }
+        let status = format!(
+            "Desktop notifications: {} (threshold: {}s)",
+            if config.notifications.enabled { "On" } else { "Off" },
+            config.notifications.threshold_secs
+        );
+        self.state.set_config(config).await;
+        Ok(status)
+    }
+
+    fn name(&self) -> &str { "notify" }
+    fn help(&self) -> &str { "Toggle desktop notifications for long responses (/notify <on|off>) or set the threshold (/notify <seconds>)." }
+    fn usage(&self) -> &str { "[on|off|secs]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,211 @@
+// src/commands/ollama.rs
+use async_trait::async_trait;
+use std::path::Path;
+use tokio::sync::mpsc;
+
+use crate::{
+    commands::{report_progress, Command, CommandCategory, EventSender},
+    error::{ReplError, ReplResult},
+    providers::{modelfile::Modelfile, ollama::{ModelInfo, OllamaProvider}},
+    state::AppState,
+};
+
+// The current provider if it is Ollama (built-in or a `type = "ollama"` instance), else the built-in one.
+async fn ollama_provider(state: &AppState) -> ReplResult<(String, OllamaProvider)> {
+    let current = state.get_provider_name().await;
+    let config = state.get_config().await;
+    let name = if config.is_ollama(&current) { current } else { "ollama".to_string() };
+    let settings = config.providers.get(&name).cloned().unwrap_or_default();
+    let provider = OllamaProvider::with_settings(&settings)?;
+    Ok((name, provider))
+}
+
+// A single model name, e.g. `llama3.1:8b`.
+fn model_arg<'a>(args: &'a str, usage: &str) -> ReplResult<&'a str> {
+    let model = args.trim();
+    if model.is_empty() || model.contains(char::is_whitespace) {
+        return Err(ReplError::Command(usage.to_string()));
+    }
+    Ok(model)
+}
+
+pub struct PullCommand {
+    state: AppState,
+}
+
+impl PullCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for PullCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (events, _progress) = mpsc::unbounded_channel();
+        self.execute_streaming(args, events).await
+    }
+
+    async fn execute_streaming(&self, args: &str, events: EventSender) -> ReplResult<String> {
+        let model = model_arg(args, "Usage: /pull <model>, e.g. /pull llama3.1:8b")?;
+        let (name, provider) = ollama_provider(&self.state).await?;
+        provider.pull_model(model, |progress| {
+            report_progress(&events, &progress.status, progress.completed.unwrap_or(0), progress.total);
+        }).await?;
+        // The model lists kept by /cache no longer include it
+        self.state.clear_lookups();
+        Ok(format!("Pulled '{}' to {}. Switch to it with /model {}:{}", model, name, name, model))
+    }
+
+    fn name(&self) -> &str { "pull" }
+    fn help(&self) -> &str { "Download a model to Ollama (the current Ollama instance, else the built-in one), with a progress bar." }
+    fn usage(&self) -> &str { "<model>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
+
+pub struct RmCommand {
+    state: AppState,
+}
+
+impl RmCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for RmCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let model = model_arg(args, "Usage: /rm <model>")?;
+        let (name, provider) = ollama_provider(&self.state).await?;
+        provider.delete_model(model).await?;
+        self.state.clear_lookups();
+        Ok(format!("Deleted '{}' from {}.", model, name))
+    }
+
+    fn name(&self) -> &str { "rm" }
+    fn help(&self) -> &str { "Delete a local Ollama model to free disk space." }
+    fn usage(&self) -> &str { "<model>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
+
+pub struct ShowCommand {
+    state: AppState,
+}
+
+impl ShowCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+fn describe(model: &str, provider: &str, info: &ModelInfo) -> String {
+    let mut output = format!("## {} ({})\n", model, provider);
+    let details = &info.details;
+    let summary = [
+        details.family.clone(),
+        details.parameter_size.as_ref().map(|size| format!("{} parameters", size)),
+        details.quantization_level.as_ref().map(|level| format!("{} quantization", level)),
+        details.format.clone(),
+    ];
+    let summary = summary.into_iter().flatten().collect::<Vec<_>>();
+    if !summary.is_empty() {
+        output.push_str(&format!("\n{}\n", summary.join(", ")));
+    }
+    if let Some(length) = info.context_length() {
+        output.push_str(&format!("Context length: {}\n", length));
+    }
+    if !info.capabilities.is_empty() {
+        output.push_str(&format!("Capabilities: {}\n", info.capabilities.join(", ")));
+    }
+    if let Some(modified) = &info.modified_at {
+        output.push_str(&format!("Modified: {}\n", modified));
+    }
+    if let Some(parameters) = info.parameters.as_deref().filter(|text| !text.trim().is_empty()) {
+        output.push_str(&format!("\nParameters:\n```\n{}\n```\n", parameters.trim_end()));
+    }
+    if let Some(system) = info.system.as_deref().filter(|text| !text.trim().is_empty()) {
+        output.push_str(&format!("\nSystem prompt: {}\n", system.trim()));
+    }
+    // Licenses run to pages; the first line names it
+    if let Some(license) = info.license.as_deref().and_then(|text| text.lines().find(|line| !line.trim().is_empty())) {
+        output.push_str(&format!("\nLicense: {}\n", license.trim()));
+    }
+    output
+}
+
+#[async_trait]
+impl Command for ShowCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (name, provider) = ollama_provider(&self.state).await?;
+        let model = match args.trim() {
+            // The current model, if it's from this Ollama
+            "" if self.state.get_provider_name().await == name => self.state.get_model().await,
+            "" => return Err(ReplError::Command("Usage: /show <model>".to_string())),
+            _ => model_arg(args, "Usage: /show [model]")?.to_string(),
+        };
+        let info = provider.show_model(&model).await?;
+        Ok(describe(&model, &name, &info))
+    }
+
+    fn name(&self) -> &str { "show" }
+    fn help(&self) -> &str { "Show an Ollama model's family, size, quantization, context length, parameters and license (default: the current model)." }
+    fn usage(&self) -> &str { "[model]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
+
+pub struct OllamaCommand {
+    state: AppState,
+}
+
+impl OllamaCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+const OLLAMA_USAGE: &str = "Usage: /ollama create <name> -f <Modelfile>";
+
+// `create <name> -f <Modelfile>`, with the flag before or after the name.
+fn create_args(args: &str) -> ReplResult<(&str, &str)> {
+    let mut words = args.split_whitespace();
+    if words.next() != Some("create") {
+        return Err(ReplError::Command(OLLAMA_USAGE.to_string()));
+    }
+    let (mut name, mut file) = (None, None);
+    while let Some(word) = words.next() {
+        match word {
+            "-f" | "--file" => file = words.next(),
+            _ if name.is_none() => name = Some(word),
+            _ => return Err(ReplError::Command(OLLAMA_USAGE.to_string())),
+        }
+    }
+    name.zip(file).ok_or_else(|| ReplError::Command(OLLAMA_USAGE.to_string()))
+}
+
+#[async_trait]
+impl Command for OllamaCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (events, _progress) = mpsc::unbounded_channel();
+        self.execute_streaming(args, events).await
+    }
+
+    async fn execute_streaming(&self, args: &str, events: EventSender) -> ReplResult<String> {
+        let (model, file) = create_args(args)?;
+        let text = tokio::fs::read_to_string(file).await
+            .map_err(|e| ReplError::Command(format!("Cannot read Modelfile '{}': {}", file, e)))?;
+        let dir = Path::new(file).parent().unwrap_or_else(|| Path::new("."));
+        let modelfile = Modelfile::parse(&text, dir)?;
+        let (name, provider) = ollama_provider(&self.state).await?;
+        provider.create_model(model, &modelfile, |progress| {
+            report_progress(&events, &progress.status, progress.completed.unwrap_or(0), progress.total);
+        }).await?;
+        self.state.clear_lookups();
+        Ok(format!("Created '{}' on {}. Switch to it with /model {}:{}", model, name, name, model))
+    }
+
+    fn name(&self) -> &str { "ollama" }
+    fn help(&self) -> &str { "Create an Ollama model from a Modelfile (base model or GGUF weights, system prompt, parameters), with progress." }
+    fn usage(&self) -> &str { "create <name> -f <Modelfile>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
@@ -0,0 +1,153 @@
+// src/commands/ops.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    ops::{diagnostics_plan, format_diagnostics, owned, run_diagnostic, Diagnostic},
+    state::AppState,
+};
+
+const OPS_USAGE: &str = "Usage: /ops [on|off|diagnose <k8s [namespace] | service <unit> | system>]";
+const K8S_USAGE: &str = "Usage: /k8s explain <kind | kind/name> [namespace]";
+
+async fn require_ops_mode(state: &AppState) -> ReplResult<()> {
+    if state.get_config().await.ops.enabled {
+        Ok(())
+    } else {
+        Err(ReplError::Command("Ops mode is off. Turn it on with /ops on (or [ops] enabled = true in the config).".to_string()))
+    }
+}
+
+// Runs the commands, then asks the model to analyze their output.
+async fn analyze(state: &AppState, commands: &[(&str, Vec<String>)], task: &str) -> ReplResult<String> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::with_capacity(commands.len());
+    for (program, args) in commands {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let diagnostic = run_diagnostic(program, &args)?;
+        println!("Ran: {}", diagnostic.command_line);
+        diagnostics.push(diagnostic);
+    }
+    let analysis = state.ask_current_model(&format!(
+        "{}\n\nCommand output:\n\n{}", task, format_diagnostics(&diagnostics)
+    )).await?;
+    Ok(analysis.trim().to_string())
+}
+
+pub struct OpsCommand {
+    state: AppState,
+}
+
+impl OpsCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for OpsCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] | ["on"] | ["off"] => {
+                let mut config = self.state.get_config().await;
+                if let Some(setting) = parts.first() {
+                    config.ops.enabled = *setting == "on";
+                }
+                let status = format!("Ops mode: {}", if config.ops.enabled { "On" } else { "Off" });
+                self.state.set_config(config).await;
+                Ok(status)
+            }
+            ["diagnose", target @ ..] => {
+                require_ops_mode(&self.state).await?;
+                let plan = diagnostics_plan(target)?;
+                analyze(
+                    &self.state,
+                    &plan,
+                    &format!(
+                        "Diagnose the health of '{}' from the read-only command output below. \
+                         List the problems you find, most severe first, with the likely cause and the next command or fix to try. \
+                         If everything looks healthy, say so.",
+                        target.join(" ")
+                    ),
+                ).await
+            }
+            _ => Err(ReplError::Command(OPS_USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "ops" }
+    fn help(&self) -> &str { "Toggle ops mode, or run allow-listed kubectl/systemctl/journalctl diagnostics and have the model analyze them." }
+    fn usage(&self) -> &str { "[on|off|diagnose <target>]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Shell }
+}
+
+// Whether `resource` names a Secret (`secret/name`, `secrets.v1/name`), whose YAML holds its
+// data; that must not go into a prompt for a remote provider.
+fn is_secret(resource: &str) -> bool {
+    resource.split(',').any(|part| {
+        let kind = part.split('/').next().unwrap_or_default();
+        matches!(kind.split('.').next().unwrap_or_default().to_lowercase().as_str(), "secret" | "secrets")
+    })
+}
+
+pub struct K8sCommand {
+    state: AppState,
+}
+
+impl K8sCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for K8sCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let (resource, namespace) = match parts.as_slice() {
+            ["explain", resource] => (*resource, None),
+            ["explain", resource, namespace] => (*resource, Some(*namespace)),
+            _ => return Err(ReplError::Command(K8S_USAGE.to_string())),
+        };
+        require_ops_mode(&self.state).await?;
+        let scope = namespace.map(|ns| owned(&["-n", ns])).unwrap_or_default();
+
+        if resource.contains('/') {
+            if is_secret(resource) {
+                return Err(ReplError::Command("Secrets aren't explained: their data would be sent to the model. Try /k8s explain secret for the kind.".to_string()));
+            }
+            // A live object: its spec and status, plus recent events from describe
+            let commands = [
+                ("kubectl", [owned(&["get", resource, "-o", "yaml"]), scope.clone()].concat()),
+                ("kubectl", [owned(&["describe", resource]), scope].concat()),
+            ];
+            analyze(
+                &self.state,
+                &commands,
+                &format!(
+                    "Explain the Kubernetes object {} in plain language: what it does, how it is configured, \
+                     and its current state. Point out anything that looks wrong or risky.",
+                    resource
+                ),
+            ).await
+        } else {
+            // A resource kind: the API documentation for it
+            let commands = [("kubectl", owned(&["explain", resource]))];
+            analyze(
+                &self.state,
+                &commands,
+                &format!(
+                    "Explain the Kubernetes resource '{}' in plain language using the API documentation below: \
+                     what it is for, its most important fields, and a short example manifest.",
+                    resource
+                ),
+            ).await
+        }
+    }
+
+    fn name(&self) -> &str { "k8s" }
+    fn help(&self) -> &str { "Explain a Kubernetes resource kind or a live object (kind/name) with the model (ops mode)." }
+    fn usage(&self) -> &str { "explain <resource> [namespace]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Shell }
+}
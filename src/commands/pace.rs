@@ -0,0 +1,48 @@
+// src/commands/pace.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+#[derive(Clone)]
+pub struct PaceCommand {
+    state: AppState,
+}
+
+impl PaceCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for PaceCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+
+        match args.trim().to_lowercase().as_str() {
+            "" => {}
+            "off" => config.ui.pace_chars_per_sec = None,
+            other => {
+                let chars_per_sec = other.parse::<u32>().ok().filter(|n| *n > 0).ok_or_else(|| ReplError::Command(format!(
+                    "Invalid argument '{}'. Use /pace <chars-per-second|off>.", other
+                )))?;
+                config.ui.pace_chars_per_sec = Some(chars_per_sec);
+            }
+        }
+        let status = match config.ui.pace_chars_per_sec {
+            Some(chars_per_sec) => format!("Output pacing: {} characters per second", chars_per_sec),
+            None => "Output pacing: Off".to_string(),
+        };
+        self.state.set_config(config).await;
+        Ok(status)
+    }
+
+    fn name(&self) -> &str { "pace" }
+    fn help(&self) -> &str { "Limit how fast streamed responses are shown (/pace <chars-per-second|off>)." }
+    fn usage(&self) -> &str { "[cps|off]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
+}
@@ -0,0 +1,127 @@
+// src/commands/pin.rs
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use crate::{
+    commands::{reader::entry_header, Command, CommandCategory},
+    config::PinnedContext,
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+// Characters of pinned text shown per pin in `/pins`.
+const PREVIEW_CHARS: usize = 60;
+
+pub struct PinCommand {
+    state: AppState,
+}
+
+impl PinCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // A history entry by its number in `/reader` (1-based); its text is copied, so the pin
+    // outlives history pruning.
+    async fn history_pin(&self, number: usize) -> ReplResult<PinnedContext> {
+        let history = self.state.get_history().await;
+        let entry = number.checked_sub(1).and_then(|index| history.get(index)).ok_or_else(|| {
+            ReplError::Command(format!("No history entry {}; the session has {} (numbered as in /reader).", number, history.len()))
+        })?;
+        Ok(PinnedContext { label: Some(entry_header(entry, number - 1)), file: None, text: Some(entry.content.clone()) })
+    }
+}
+
+#[async_trait]
+impl Command for PinCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let target = args.trim();
+        if target.is_empty() {
+            return Err(ReplError::Command("Usage: /pin <history-number|file>".to_string()));
+        }
+        let pin = match target.parse::<usize>() {
+            Ok(number) => self.history_pin(number).await?,
+            Err(_) => {
+                let path = PathBuf::from(target);
+                if !path.is_file() {
+                    return Err(ReplError::Command(format!("'{}' is neither a history entry number nor a file.", target)));
+                }
+                PinnedContext { label: None, file: Some(path), text: None }
+            }
+        };
+        let mut config = self.state.get_config().await;
+        if config.session.pins.contains(&pin) {
+            return Ok(format!("Already pinned: {}", pin.describe()));
+        }
+        let description = pin.describe();
+        config.session.pins.push(pin);
+        let count = config.session.pins.len();
+        self.state.set_config(config).await;
+        Ok(format!("Pinned {} (pin {}). It is included with every prompt until /pins remove {}.", description, count, count))
+    }
+
+    fn name(&self) -> &str { "pin" }
+    fn help(&self) -> &str { "Pin a history entry (by its /reader number) or a file so it is always included with prompts." }
+    fn usage(&self) -> &str { "<history-number|file>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
+
+pub struct PinsCommand {
+    state: AppState,
+}
+
+impl PinsCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+fn preview(pin: &PinnedContext) -> String {
+    let Some(text) = &pin.text else { return String::new() };
+    let first_line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or_default().trim();
+    let mut preview: String = first_line.chars().take(PREVIEW_CHARS).collect();
+    if first_line.chars().nth(PREVIEW_CHARS).is_some() {
+        preview.push_str("...");
+    }
+    format!(" ({} chars): {}", text.chars().count(), preview)
+}
+
+#[async_trait]
+impl Command for PinsCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        let mut parts = args.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (None, _) => {
+                if config.session.pins.is_empty() {
+                    return Ok("No pins. Pin a history entry or file with /pin <history-number|file>.".to_string());
+                }
+                let lines: Vec<String> = config.session.pins.iter().enumerate()
+                    .map(|(index, pin)| format!("{}. {}{}", index + 1, pin.describe(), preview(pin)))
+                    .collect();
+                Ok(format!("Pinned context, included with every prompt:\n{}", lines.join("\n")))
+            }
+            (Some("remove"), Some(number)) => {
+                let index = number.parse::<usize>().ok()
+                    .and_then(|number| number.checked_sub(1))
+                    .filter(|index| *index < config.session.pins.len())
+                    .ok_or_else(|| ReplError::Command(format!("No pin {}. /pins lists them.", number)))?;
+                let removed = config.session.pins.remove(index);
+                self.state.set_config(config).await;
+                Ok(format!("Unpinned {}.", removed.describe()))
+            }
+            (Some("clear"), None) => {
+                let count = config.session.pins.len();
+                config.session.pins.clear();
+                self.state.set_config(config).await;
+                Ok(format!("Removed {} pin(s).", count))
+            }
+            _ => Err(ReplError::Command("Usage: /pins [remove <number>|clear]".to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "pins" }
+    fn help(&self) -> &str { "List pinned context, remove a pin (/pins remove <number>) or clear them all (/pins clear)." }
+    fn usage(&self) -> &str { "[remove <n>|clear]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
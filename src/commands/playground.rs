@@ -0,0 +1,162 @@
+// src/commands/playground.rs
+//! Small data-wrangling helpers over earlier output: `/jq` and `/regex`.
+use async_trait::async_trait;
+use jaq_interpret::{Ctx, FilterT, ParseCtx, RcIter, Val};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::{AppState, HistoryContentType, HistoryEntry},
+};
+
+// Matches listed by /regex before the rest are only counted.
+const MAX_LISTED_MATCHES: usize = 100;
+
+// Finds JSON in an output: the whole text, a fenced code block, or everything from the first
+// `{` or `[` (e.g. /http output in plain mode, after the status line).
+fn find_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+    let fenced = trimmed.split("```").skip(1).step_by(2)
+        .map(|block| block.split_once('\n').map_or(block, |(_language, body)| body));
+    for block in fenced {
+        if let Ok(value) = serde_json::from_str(block.trim()) {
+            return Some(value);
+        }
+    }
+    let start = trimmed.find(['{', '['])?;
+    serde_json::from_str(&trimmed[start..]).ok()
+}
+
+// The output an entry shows: responses, command results and shell output, not prompts or errors.
+fn is_output(entry: &HistoryEntry) -> bool {
+    matches!(
+        entry.entry_type,
+        HistoryContentType::LlmResponse { .. } | HistoryContentType::CommandResult { .. } | HistoryContentType::ShellOutput { .. }
+    )
+}
+
+/// Applies a jq filter to a JSON value, returning each result.
+fn run_jq(filter: &str, input: Value) -> ReplResult<Vec<Value>> {
+    let mut defs = ParseCtx::new(Vec::new());
+    defs.insert_natives(jaq_core::core());
+    defs.insert_defs(jaq_std::std());
+    let (main, errors) = jaq_parse::parse(filter, jaq_parse::main());
+    let main = match main {
+        Some(main) if errors.is_empty() => main,
+        _ => {
+            let errors = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+            return Err(ReplError::Command(format!("Invalid jq filter '{}': {}", filter, errors.join("; "))));
+        }
+    };
+    let compiled = defs.compile(main);
+    if !defs.errs.is_empty() {
+        return Err(ReplError::Command(format!("Invalid jq filter '{}': unknown function or variable.", filter)));
+    }
+    let inputs = RcIter::new(core::iter::empty());
+    compiled.run((Ctx::new([], &inputs), Val::from(input)))
+        .map(|result| result.map(Value::from).map_err(|e| ReplError::Command(format!("jq error: {}", e))))
+        .collect()
+}
+
+pub struct JqCommand {
+    state: AppState,
+}
+
+impl JqCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for JqCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let filter = args.trim();
+        if filter.is_empty() {
+            return Err(ReplError::Command("Usage: /jq <filter> (e.g. /jq '.items[] | .name' without quotes)".to_string()));
+        }
+        let history = self.state.get_history().await;
+        let input = history.iter().rev().filter(|e| is_output(e)).find_map(|e| find_json(&e.content))
+            .ok_or_else(|| ReplError::Command("No JSON output found in this session's history.".to_string()))?;
+        let results = run_jq(filter, input)?;
+        if results.is_empty() {
+            return Ok("(no results)".to_string());
+        }
+        let rendered = results.iter()
+            .map(|value| match value {
+                // Strings print raw, like `jq -r`, so they can be copied
+                Value::String(s) => Ok(s.clone()),
+                other => serde_json::to_string_pretty(other),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rendered.join("\n"))
+    }
+
+    fn name(&self) -> &str { "jq" }
+    fn help(&self) -> &str { "Apply a jq filter to the most recent JSON output (a response, /http, or shell output)." }
+    fn usage(&self) -> &str { "<filter>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
+
+pub struct RegexCommand {
+    state: AppState,
+}
+
+impl RegexCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for RegexCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let pattern = args.trim();
+        if pattern.is_empty() {
+            return Err(ReplError::Command("Usage: /regex <pattern> (e.g. /regex (?i)error: (.+))".to_string()));
+        }
+        let re = Regex::new(pattern).map_err(|e| ReplError::Command(format!("Invalid regex: {}", e)))?;
+        let history = self.state.get_history().await;
+        let response = history.iter().rev()
+            .find(|e| matches!(e.entry_type, HistoryContentType::LlmResponse { .. }))
+            .ok_or_else(|| ReplError::Command("No LLM response in this session's history yet.".to_string()))?;
+
+        let mut lines = Vec::new();
+        let mut total = 0;
+        for (line_number, line) in response.content.lines().enumerate() {
+            for captures in re.captures_iter(line) {
+                total += 1;
+                if total > MAX_LISTED_MATCHES {
+                    continue;
+                }
+                let whole = captures.get(0).map_or("", |m| m.as_str());
+                let mut listed = format!("{:>4}: {}", line_number + 1, whole);
+                let groups = (1..captures.len())
+                    .map(|i| {
+                        let name = re.capture_names().nth(i).flatten().map_or_else(|| i.to_string(), str::to_string);
+                        format!("{}={}", name, captures.get(i).map_or("(none)", |m| m.as_str()))
+                    })
+                    .collect::<Vec<_>>();
+                if !groups.is_empty() {
+                    listed.push_str(&format!("   [{}]", groups.join(", ")));
+                }
+                lines.push(listed);
+            }
+        }
+        if total == 0 {
+            return Ok(format!("No matches for /{}/ in the last response.", pattern));
+        }
+        let more = if total > MAX_LISTED_MATCHES { format!(" (first {} listed)", MAX_LISTED_MATCHES) } else { String::new() };
+        Ok(format!("{} match(es) for /{}/ in the last response{}:\n{}", total, pattern, more, lines.join("\n")))
+    }
+
+    fn name(&self) -> &str { "regex" }
+    fn help(&self) -> &str { "Match a regular expression against the last response, listing matches with line numbers and groups." }
+    fn usage(&self) -> &str { "<pattern>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
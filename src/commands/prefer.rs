@@ -0,0 +1,87 @@
+// src/commands/prefer.rs
+use async_trait::async_trait;
+use std::path::Path;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    preferences::{export_pairs, load, record, scores, PREFERENCE_LOG_FILE},
+    state::AppState,
+};
+
+const USAGE: &str = "Use /prefer a|b|tie after /diffmodels, /prefer stats, or /prefer export <file>.";
+
+pub struct PreferCommand {
+    state: AppState,
+}
+
+impl PreferCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn judge(&self, preferred: Option<usize>) -> ReplResult<String> {
+        let Some(mut comparison) = self.state.take_last_comparison().await else {
+            return Err(ReplError::Command("No comparison to judge. Run /diffmodels first.".to_string()));
+        };
+        comparison.preferred = preferred;
+        record(Path::new(PREFERENCE_LOG_FILE), &comparison)?;
+        Ok(match preferred.and_then(|index| comparison.candidates.get(index)) {
+            Some(winner) => format!("Recorded {} as the better answer in {}.", winner.model, PREFERENCE_LOG_FILE),
+            None => format!("Recorded a tie in {}.", PREFERENCE_LOG_FILE),
+        })
+    }
+
+    fn stats() -> ReplResult<String> {
+        let preferences = load(Path::new(PREFERENCE_LOG_FILE))?;
+        if preferences.is_empty() {
+            return Ok("The preference log is empty. Judge a /diffmodels comparison with /prefer a|b|tie.".to_string());
+        }
+        let mut ranked = scores(&preferences).into_iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.win_rate().total_cmp(&a.1.win_rate()).then(b.1.comparisons.cmp(&a.1.comparisons)));
+        let mut output = format!(
+            "{} judged comparisons in {}.\n\n| Model | Compared | Won | Tied | Win rate |\n|---|---|---|---|---|\n",
+            preferences.len(), PREFERENCE_LOG_FILE
+        );
+        for (model, score) in &ranked {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {:.0}% |\n",
+                model, score.comparisons, score.wins, score.ties, score.win_rate() * 100.0
+            ));
+        }
+        if let Some((best, _)) = ranked.first() {
+            output.push_str(&format!("\nMost preferred: {}.", best));
+            if let Some((provider, model)) = best.split_once(':') {
+                output.push_str(&format!(" Switch with /provider {} and /model {}.", provider, model));
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl Command for PreferCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (action, rest) = args.trim().split_once(char::is_whitespace).unwrap_or((args.trim(), ""));
+        match action.to_lowercase().as_str() {
+            "a" => self.judge(Some(0)).await,
+            "b" => self.judge(Some(1)).await,
+            "tie" => self.judge(None).await,
+            "" | "stats" => Self::stats(),
+            "export" => {
+                let target = rest.trim();
+                if target.is_empty() {
+                    return Err(ReplError::Command("Usage: /prefer export <file>".to_string()));
+                }
+                let pairs = export_pairs(&load(Path::new(PREFERENCE_LOG_FILE))?, Path::new(target))?;
+                Ok(format!("Exported {} chosen/rejected pairs to {}.", pairs, target))
+            }
+            other => Err(ReplError::Command(format!("Unknown action '{}'. {}", other, USAGE))),
+        }
+    }
+
+    fn name(&self) -> &str { "prefer" }
+    fn help(&self) -> &str { "Mark the better /diffmodels answer (a, b or tie) in the preference log, show per-model win rates, or export fine-tuning pairs." }
+    fn usage(&self) -> &str { "[a|b|tie|stats|export <file>]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
@@ -3,7 +3,7 @@ use async_trait::async_trait;
 use dialoguer::{theme::ColorfulTheme, FuzzySelect};
 
 use crate::{
-    commands::Command,
+    commands::{Command, CommandCategory},
     error::{ReplError, ReplResult},
     state::AppState,
 };
@@ -72,4 +72,12 @@ impl Command for ProviderCommand {
     fn help(&self) -> &str {
         "Select the active LLM provider interactively (/provider) or directly (/provider <name>)"
     }
+
+    fn usage(&self) -> &str {
+        "[name]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Providers
+    }
 }
\ No newline at end of file
@@ -1,13 +1,59 @@
 // src/commands/reader.rs
 use async_trait::async_trait;
 use crate::{
-    commands::Command,
+    commands::{Command, CommandCategory},
     error::ReplResult,
-    state::{AppState, HistoryContentType}, // Import history types
+    i18n::{tr, tr_args},
+    icons,
+    state::{AppState, HistoryContentType, HistoryEntry}, // Import history types
     render::get_theme_resources, // For theming the reader output
+    usage::EntryUsage,
 };
+use chrono::Local;
 use colored::*; // For coloring headers/separators
 
+/// Builds the header line shown above a history entry (index is zero-based).
+pub fn entry_header(entry: &HistoryEntry, index: usize) -> String {
+    match &entry.entry_type {
+        HistoryContentType::LlmResponse { model } => format!("LLM Response ({}) [{}]", model, index + 1),
+        HistoryContentType::CommandResult { command } => format!("Cmd Output (/{} [{}])", command, index + 1),
+        HistoryContentType::ShellOutput { command } => format!("Shell Output (!{} [{}])", command, index + 1),
+        HistoryContentType::UserQuery => format!("User Query [{}]", index + 1),
+        HistoryContentType::Error { source } => format!("Error ({}) [{}]", source, index + 1),
+        HistoryContentType::Info => format!("Info [{}]", index + 1),
+    }
+}
+
+/// `entry_header` with the entry's icon in front, for display (see `[ui] icons`).
+pub fn display_header(entry: &HistoryEntry, index: usize) -> String {
+    format!("{}{}", icons::entry(&entry.entry_type), entry_header(entry, index))
+}
+
+/// Builds the metadata line for a history entry: local time, provider/model, duration, and the
+/// answer's prompt + completion tokens and cost.
+pub fn entry_metadata(entry: &HistoryEntry) -> String {
+    let mut parts = vec![entry.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string()];
+    match (&entry.provider, &entry.model) {
+        (Some(provider), Some(model)) => parts.push(format!("{}{}:{}", icons::provider(provider), provider, model)),
+        (Some(provider), None) => parts.push(format!("{}{}", icons::provider(provider), provider)),
+        (None, Some(model)) => parts.push(model.clone()),
+        (None, None) => {}
+    }
+    if let Some(duration_ms) = entry.duration_ms {
+        parts.push(format!("{:.1}s", duration_ms as f64 / 1000.0));
+    }
+    if let Some(usage) = &entry.usage {
+        parts.push(usage.describe());
+    }
+    if entry.cancelled {
+        parts.push("cancelled".to_string());
+    }
+    if !entry.tags.is_empty() {
+        parts.push(entry.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" "));
+    }
+    parts.join(" | ")
+}
+
 pub struct ReaderCommand {
     state: AppState,
 }
@@ -33,44 +79,50 @@ impl Command for ReaderCommand {
         // Clear screen or print separator for better view? Optional.
         // print!("\x1B[2J\x1B[1;1H"); // Clears screen - might be too aggressive
 
-        println!("\n{}", self.colorize("--- Session Reader ---", palette.info));
-        println!("{}", self.colorize("Scroll through history below.", palette.info));
-        println!("{}", self.colorize("Use your terminal's selection feature to copy blocks.", palette.info));
+        println!("\n{}", self.colorize(&tr("reader-title"), palette.info));
+        println!("{}", self.colorize(&tr("reader-scroll-hint"), palette.info));
+        println!("{}", self.colorize(&tr("reader-copy-hint"), palette.info));
         println!("----------------------\n");
 
 
         if history.is_empty() {
-            println!("{}", self.colorize("History is empty.", palette.info));
+            println!("{}", self.colorize(&tr("reader-empty"), palette.info));
         } else {
             for (index, entry) in history.iter().enumerate() {
-                let header_text = match &entry.entry_type {
-                    HistoryContentType::LlmResponse { model } => format!("LLM Response ({}) [{}]", model, index + 1),
-                    HistoryContentType::CommandResult { command } => format!("Cmd Output (/{} [{}])", command, index + 1),
-                    HistoryContentType::ShellOutput { command } => format!("Shell Output (!{} [{}])", command, index + 1),
-                    HistoryContentType::UserQuery => format!("User Query [{}]", index + 1),
-                    HistoryContentType::Error { source } => format!("Error ({}) [{}]", source, index + 1),
-                    HistoryContentType::Info => format!("Info [{}]", index + 1),
-                };
+                let header_text = display_header(entry, index);
 
                 // Print Header with theme color
                 println!("{}", self.colorize(&format!("--- {} ---", header_text), palette.prompt_separator)); // Use a distinct color
+                println!("{}", self.colorize(&entry_metadata(entry), palette.info));
 
                 // Print the stored content
                 // Since we stored the final string (raw or rendered), just print it.
                 println!("{}", entry.content.trim()); // Trim potential extra whitespace
 
                 // Print Footer/Separator
-                println!("{}\n", self.colorize("--- End ---", palette.prompt_separator));
+                println!("{}\n", self.colorize(&tr("reader-entry-end"), palette.prompt_separator));
 
             }
         }
 
-        println!("{}", self.colorize("--- End of History ---", palette.info));
+        // What all the answers used, when the usage metrics covered any of them
+        let mut total: Option<EntryUsage> = None;
+        for usage in history.iter().filter_map(|entry| entry.usage.as_ref()) {
+            match &mut total {
+                Some(total) => total.add(usage),
+                None => total = Some(*usage),
+            }
+        }
+        if let Some(total) = total {
+            println!("{}", self.colorize(&tr_args("reader-usage-total", &[("usage", &total.describe())]), palette.info));
+        }
+        println!("{}", self.colorize(&tr("reader-end"), palette.info));
 
         // Return a simple confirmation, the main output is printed directly
-        Ok("Reader view finished. Scroll up to see history.".to_string())
+        Ok(tr("reader-finished"))
     }
 
     fn name(&self) -> &str { "reader" }
     fn help(&self) -> &str { "Display the session output history in a read-only view." }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
 }
\ No newline at end of file
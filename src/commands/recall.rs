@@ -0,0 +1,125 @@
+// src/commands/recall.rs
+use async_trait::async_trait;
+use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+use std::collections::HashSet;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::{AppState, HistoryContentType},
+};
+
+/// Rustyline history file holding prompts from previous sessions.
+pub const PROMPT_HISTORY_FILE: &str = "history.txt";
+
+// Longest line shown for a single item in the finder.
+const MAX_LABEL_CHARS: usize = 100;
+
+// A recallable piece of text with the label shown in the finder.
+struct RecallItem {
+    label: String,
+    text: String,
+}
+
+fn make_label(tag: &str, text: &str) -> String {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut label: String = flattened.chars().take(MAX_LABEL_CHARS).collect();
+    if flattened.chars().count() > MAX_LABEL_CHARS {
+        label.push('…');
+    }
+    format!("[{}] {}", tag, label)
+}
+
+// Rustyline's file format escapes backslashes and newlines; undo that for display and insertion.
+fn unescape_history_line(line: &str) -> String {
+    let mut unescaped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Reads prompts saved by previous sessions from the rustyline history file (oldest first).
+pub fn saved_prompts() -> Vec<String> {
+    std::fs::read_to_string(PROMPT_HISTORY_FILE)
+        .map(|content| {
+            content.lines()
+                .filter(|line| !line.starts_with("#V"))
+                .map(unescape_history_line)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Opens a fuzzy finder over `prompts` (oldest first) and the session history, newest first,
+/// pre-filtered by `query`. Returns the selected text, or None if the finder was cancelled.
+pub async fn recall(state: &AppState, prompts: Vec<String>, query: &str) -> ReplResult<Option<String>> {
+    let mut items: Vec<RecallItem> = Vec::new();
+    for entry in state.get_history().await.into_iter().rev() {
+        let tag = match &entry.entry_type {
+            HistoryContentType::LlmResponse { .. } => "response",
+            HistoryContentType::CommandResult { .. } => "command",
+            HistoryContentType::ShellOutput { .. } => "shell",
+            HistoryContentType::UserQuery => "prompt",
+            HistoryContentType::Error { .. } | HistoryContentType::Info => continue,
+        };
+        // Tags go in the label, so typing a tag finds the entries carrying it
+        let tag = entry.tags.iter().fold(tag.to_string(), |label, added| format!("{} #{}", label, added));
+        items.push(RecallItem { label: make_label(&tag, &entry.content), text: entry.content.trim().to_string() });
+    }
+    for prompt in prompts.into_iter().rev() {
+        if prompt.trim().is_empty() { continue; }
+        items.push(RecallItem { label: make_label("prompt", &prompt), text: prompt });
+    }
+
+    // The same prompt is often sent many times; keep only its most recent occurrence
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.text.clone()));
+    if items.is_empty() {
+        return Err(ReplError::Command("Nothing to recall yet.".to_string()));
+    }
+
+    let labels: Vec<&str> = items.iter().map(|item| item.label.as_str()).collect();
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Recall (type to filter, Esc to cancel)")
+        .items(&labels)
+        .with_initial_text(query)
+        .interact_opt()
+        .map_err(|e| ReplError::Command(format!("Selection error: {}", e)))?;
+
+    Ok(selection.map(|index| items.swap_remove(index).text))
+}
+
+pub struct RecallCommand {
+    state: AppState,
+}
+
+impl RecallCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for RecallCommand {
+    // The REPL intercepts /recall to include this session's prompts and pre-fill the selection
+    // at the prompt; elsewhere the selected text is simply returned.
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let selected = recall(&self.state, saved_prompts(), args.trim()).await?;
+        Ok(selected.unwrap_or_default())
+    }
+
+    fn name(&self) -> &str { "recall" }
+    fn help(&self) -> &str { "Fuzzy-find past prompts and responses and insert the selection at the prompt (/recall [query], or Alt+R)." }
+    fn usage(&self) -> &str { "[query]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,101 @@
+// src/commands/replay.rs
+use async_trait::async_trait;
+use colored::*;
+use std::io::{self, Write};
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::{
+    commands::{reader::display_header, Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    render::{get_theme_resources, render_accessible},
+    signal::{is_stop_requested, reset_stop_flag},
+    state::AppState,
+};
+
+// Typing speed and pause between turns at 1x.
+const BASE_CHARS_PER_SEC: f64 = 400.0;
+const BASE_TURN_PAUSE: Duration = Duration::from_millis(800);
+const TICK: Duration = Duration::from_millis(20);
+
+pub struct ReplayCommand {
+    state: AppState,
+}
+
+impl ReplayCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // Helper to apply theme color
+    fn colorize(&self, text: &str, color: (u8, u8, u8)) -> colored::ColoredString {
+        text.truecolor(color.0, color.1, color.2)
+    }
+
+    // Accepts "2", "2x" or "0.5"; defaults to 1x.
+    fn parse_speed(args: &str) -> ReplResult<f64> {
+        let trimmed_args = args.trim().trim_end_matches(['x', 'X']);
+        if trimmed_args.is_empty() {
+            return Ok(1.0);
+        }
+        trimmed_args.parse::<f64>()
+            .ok()
+            .filter(|s| *s > 0.0 && s.is_finite())
+            .ok_or_else(|| ReplError::Command(format!("Invalid speed '{}'. Use a positive number, e.g. /replay 2x", args.trim())))
+    }
+
+    // Prints `text` a few characters per tick. Returns false if interrupted with Ctrl+C.
+    // In accessibility mode the text is printed at once, since screen readers stumble over typing effects.
+    async fn type_out(&self, text: &str, speed: f64) -> ReplResult<bool> {
+        if self.state.get_config().await.ui.accessible {
+            print!("{}", render_accessible(text));
+            return Ok(!is_stop_requested());
+        }
+        let chars: Vec<char> = text.chars().collect();
+        let per_tick = ((BASE_CHARS_PER_SEC * speed * TICK.as_secs_f64()).ceil() as usize).max(1);
+        for chunk in chars.chunks(per_tick) {
+            if is_stop_requested() {
+                return Ok(false);
+            }
+            print!("{}", chunk.iter().collect::<String>());
+            io::stdout().flush().map_err(ReplError::Io)?;
+            sleep(TICK).await;
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl Command for ReplayCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let speed = Self::parse_speed(args)?;
+        let history = self.state.get_history().await;
+        let (_skin, palette) = get_theme_resources(self.state.get_theme().await);
+
+        if history.is_empty() {
+            return Ok("History is empty, nothing to replay.".to_string());
+        }
+
+        println!("\n{}", self.colorize(&format!("--- Session Replay ({}x, Ctrl+C to stop) ---", speed), palette.info));
+        reset_stop_flag();
+
+        for (index, entry) in history.iter().enumerate() {
+            println!("\n{}", self.colorize(&format!("--- {} ---", display_header(entry, index)), palette.prompt_separator));
+            if !self.type_out(entry.content.trim(), speed).await? {
+                reset_stop_flag();
+                println!("\n{}", self.colorize("[ Replay Interrupted ]", palette.error));
+                return Ok(format!("Replay stopped at entry {} of {}.", index + 1, history.len()));
+            }
+            println!();
+            sleep(BASE_TURN_PAUSE.div_f64(speed)).await;
+        }
+
+        println!("\n{}", self.colorize("--- End of Replay ---", palette.info));
+        Ok(format!("Replayed {} entries.", history.len()))
+    }
+
+    fn name(&self) -> &str { "replay" }
+    fn help(&self) -> &str { "Re-render the session history turn by turn with typing pacing (/replay [speed], e.g. /replay 2x)." }
+    fn usage(&self) -> &str { "[speed]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
+}
@@ -0,0 +1,80 @@
+// src/commands/report.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+    usage::{self, build_report, USAGE_STATS_FILE},
+};
+
+// Days listed under "Busiest days".
+const BUSIEST_DAYS: usize = 5;
+
+pub struct ReportCommand {
+    state: AppState,
+}
+
+impl ReportCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+fn money(cost: Option<f64>) -> String {
+    cost.map_or_else(|| "-".to_string(), |cost| format!("{:.4}", cost))
+}
+
+#[async_trait]
+impl Command for ReportCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (days, period) = match args.trim().to_lowercase().as_str() {
+            "day" | "today" => (1, "today"),
+            "" | "week" => (7, "the last 7 days"),
+            "month" => (30, "the last 30 days"),
+            other => return Err(ReplError::Command(format!("Invalid period '{}'. Use /report [day|week|month].", other))),
+        };
+        let config = self.state.get_config().await;
+        let report = build_report(&usage::snapshot(), &config, days);
+        if report.totals.queries == 0 {
+            return Ok(format!("No queries recorded for {} (usage is kept in {}).", period, USAGE_STATS_FILE));
+        }
+
+        let totals = &report.totals;
+        let mut output = format!("## Usage report for {}\n\n", period);
+        output.push_str(&format!(
+            "{} queries ({} failed), about {} prompt and {} completion tokens, estimated cost {:.4}.\n\n",
+            totals.queries, totals.errors, totals.prompt_tokens, totals.completion_tokens, report.cost
+        ));
+
+        output.push_str("### By provider and model\n\n| Provider | Model | Queries | Errors | Avg latency | Prompt tokens | Completion tokens | Cost |\n|---|---|---|---|---|---|---|---|\n");
+        for model in &report.models {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} ms | {} | {} | {} |\n",
+                model.provider, model.model, model.totals.queries, model.totals.errors, model.totals.average_latency_ms(),
+                model.totals.prompt_tokens, model.totals.completion_tokens, money(model.cost)
+            ));
+        }
+
+        if days > 1 {
+            let mut busiest = report.daily.iter().collect::<Vec<_>>();
+            busiest.sort_by(|a, b| b.totals.queries.cmp(&a.totals.queries).then(b.day.cmp(&a.day)));
+            output.push_str("\n### Busiest days\n\n| Day | Queries | Tokens | Cost |\n|---|---|---|---|\n");
+            for day in busiest.into_iter().take(BUSIEST_DAYS) {
+                output.push_str(&format!(
+                    "| {} | {} | {} | {:.4} |\n",
+                    day.day, day.totals.queries, day.totals.prompt_tokens + day.totals.completion_tokens, day.cost
+                ));
+            }
+        }
+        if config.pricing.is_empty() {
+            output.push_str("\nCosts are 0 until prices are set under [pricing] in the config file.");
+        }
+        Ok(output)
+    }
+
+    fn name(&self) -> &str { "report" }
+    fn help(&self) -> &str { "Usage report for today, the last week or month: queries, tokens and cost per provider/model, and the busiest days." }
+    fn usage(&self) -> &str { "[day|week|month]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
@@ -0,0 +1,322 @@
+// src/commands/sessions.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
+use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+use regex::{Regex, RegexBuilder};
+use std::fs;
+
+use crate::{
+    commands::{reader::entry_header, Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    sessions::{self, SessionSummary},
+    state::{AppState, HistoryEntry},
+};
+
+const GREP_USAGE: &str = "Usage: /grep [--open] <pattern>";
+// Most matches /grep lists, and the text shown either side of each.
+const MAX_GREP_MATCHES: usize = 50;
+const GREP_CONTEXT_CHARS: usize = 40;
+
+// Session names are single words so they are easy to type back into /load and /sessions.
+fn session_name(args: &str, usage: &str) -> ReplResult<String> {
+    let name = args.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(ReplError::Command(usage.to_string()));
+    }
+    sessions::validate_name(name)?;
+    Ok(name.to_string())
+}
+
+fn local_time(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+fn describe(session: &SessionSummary, current: &str) -> String {
+    let marker = if session.name == current { "* " } else { "  " };
+    let title = session.title.as_deref().map(|title| format!(" - {}", title)).unwrap_or_default();
+    format!("{}{} ({}){}", marker, session.name, local_time(&session.saved), title)
+}
+
+// The saved sessions, with the current one first if it hasn't been saved yet.
+async fn all_sessions(state: &AppState) -> (String, Vec<SessionSummary>) {
+    let current = state.get_session().await;
+    let mut listed = sessions::list();
+    if !listed.iter().any(|session| session.name == current.name) {
+        let unsaved = state.save_session().await;
+        listed.insert(0, SessionSummary { name: current.name.clone(), saved: unsaved.saved, title: unsaved.display_title() });
+    }
+    (current.name, listed)
+}
+
+// Switches to the session called `name`, starting it empty if none is saved under that name. The
+// session switched away from is saved unless it is empty; if that fails, the switch is undone.
+async fn switch_to(state: &AppState, name: &str) -> ReplResult<String> {
+    if state.get_session().await.name == name {
+        return Ok(format!("Already in session '{}'.", name));
+    }
+    let incoming = if sessions::exists(name) { Some(sessions::load(name)?) } else { None };
+    let started = incoming.is_none();
+    let (previous, outgoing) = state.switch_session(name, incoming).await?;
+    let kept = if outgoing.is_empty() { String::new() } else { format!(" '{}' is saved.", previous) };
+    if !outgoing.is_empty() {
+        if let Err(e) = sessions::save(&previous, &outgoing) {
+            state.switch_session(&previous, Some(outgoing)).await?;
+            return Err(ReplError::Command(format!("Could not save session '{}', so it is still the current one: {}", previous, e)));
+        }
+    }
+    if started {
+        return Ok(format!("Started session '{}'.{}", name, kept));
+    }
+    let loaded = state.save_session().await;
+    Ok(format!(
+        "Switched to session '{}' ({}:{}, {} turns, {} history entries).{}",
+        name, loaded.provider, loaded.model, loaded.conversation.len() / 2, loaded.history.len(), kept
+    ))
+}
+
+pub struct SaveCommand {
+    state: AppState,
+}
+
+impl SaveCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for SaveCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        if !args.trim().is_empty() {
+            let name = session_name(args, "Usage: /save [name] (a single word)")?;
+            self.state.rename_session(&name).await;
+        }
+        let name = self.state.get_session().await.name;
+        let session = self.state.save_session().await;
+        let path = sessions::save(&name, &session)?;
+        Ok(format!(
+            "Saved session '{}' ({}:{}, {} turns, {} history entries) to {}.",
+            name, session.provider, session.model, session.conversation.len() / 2, session.history.len(), path.display()
+        ))
+    }
+
+    fn name(&self) -> &str { "save" }
+    fn help(&self) -> &str { "Save the session (conversation, history and settings) to disk, naming it if a name is given." }
+    fn usage(&self) -> &str { "[name]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
+
+pub struct LoadCommand {
+    state: AppState,
+}
+
+impl LoadCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for LoadCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        if args.trim().is_empty() {
+            let (current, listed) = all_sessions(&self.state).await;
+            let lines = listed.iter().map(|session| describe(session, &current)).collect::<Vec<_>>();
+            return Ok(format!("Sessions:\n{}\nUsage: /load <name>", lines.join("\n")));
+        }
+        let name = session_name(args, "Usage: /load <name>")?;
+        if !sessions::exists(&name) {
+            return Err(ReplError::Command(format!("No saved session named '{}'. /load lists them.", name)));
+        }
+        switch_to(&self.state, &name).await
+    }
+
+    fn name(&self) -> &str { "load" }
+    fn help(&self) -> &str { "Switch to a saved session, saving the current one first, or list the sessions." }
+    fn usage(&self) -> &str { "[name]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
+
+pub struct SessionsCommand {
+    state: AppState,
+}
+
+impl SessionsCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for SessionsCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (action, rest) = args.trim().split_once(char::is_whitespace).unwrap_or((args.trim(), ""));
+        match action {
+            "" => {
+                let (current, listed) = all_sessions(&self.state).await;
+                let labels = listed.iter().map(|session| describe(session, &current)).collect::<Vec<_>>();
+                let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Session (type to filter, Esc to cancel)")
+                    .items(&labels)
+                    .default(0)
+                    .interact_opt()
+                    .map_err(|e| ReplError::Command(format!("Selection error: {}", e)))?;
+                match selection {
+                    Some(index) => switch_to(&self.state, &listed[index].name).await,
+                    None => Ok(format!("Still in session '{}'.", current)),
+                }
+            }
+            "new" => {
+                let name = match rest.trim() {
+                    "" => sessions::CurrentSession::default().name,
+                    name => session_name(name, "Usage: /sessions new [name]")?,
+                };
+                if sessions::exists(&name) {
+                    return Err(ReplError::Command(format!("A session named '{}' exists; switch to it with /sessions {}.", name, name)));
+                }
+                switch_to(&self.state, &name).await
+            }
+            "title" => {
+                let title = Some(rest.trim().to_string()).filter(|title| !title.is_empty());
+                self.state.set_session_title(title.clone()).await;
+                Ok(match title {
+                    Some(title) => format!("Session title: {}", title),
+                    None => "Session title cleared; the first prompt stands in for it.".to_string(),
+                })
+            }
+            name if rest.is_empty() => {
+                let name = session_name(name, "Usage: /sessions [name | new [name] | title <text>]")?;
+                switch_to(&self.state, &name).await
+            }
+            _ => Err(ReplError::Command("Usage: /sessions [name | new [name] | title <text>]".to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "sessions" }
+    fn help(&self) -> &str { "Pick a session to switch to, switch by name (starting it if new), start a new one, or set the session's title." }
+    fn usage(&self) -> &str { "[name | new [name] | title <text>]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
+
+// History searched by /grep: a session by name, or a transcript `[on_exit] save_session` wrote
+// (by file name), which can't be switched to.
+struct Searched {
+    name: String,
+    session: bool,
+    history: Vec<HistoryEntry>,
+}
+
+// The current session as it is now, the other saved sessions (newest first), then the transcripts.
+async fn searched(state: &AppState) -> Vec<Searched> {
+    let current = state.get_session().await.name;
+    let mut searched = vec![Searched { name: current.clone(), session: true, history: state.get_history().await }];
+    for saved in sessions::list().into_iter().filter(|saved| saved.name != current) {
+        if let Ok(session) = sessions::load(&saved.name) {
+            searched.push(Searched { name: saved.name, session: true, history: session.history });
+        }
+    }
+    let Some(dir) = state.get_config().await.on_exit.save_session else { return searched };
+    let mut transcripts: Vec<_> = fs::read_dir(dir).into_iter().flatten().flatten().map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    transcripts.sort_by(|a, b| b.cmp(a));
+    for path in transcripts {
+        let history = fs::read_to_string(&path).ok().and_then(|text| serde_json::from_str(&text).ok());
+        if let (Some(history), Some(name)) = (history, path.file_name()) {
+            searched.push(Searched { name: name.to_string_lossy().into_owned(), session: false, history });
+        }
+    }
+    searched
+}
+
+// The first match in `text` with some text either side, on one line, the match in bold.
+fn match_snippet(re: &Regex, text: &str) -> Option<String> {
+    let found = re.find(text)?;
+    let before: Vec<char> = text[..found.start()].chars().rev().take(GREP_CONTEXT_CHARS + 1).collect();
+    let after: Vec<char> = text[found.end()..].chars().take(GREP_CONTEXT_CHARS + 1).collect();
+    let lead = if before.len() > GREP_CONTEXT_CHARS { "…" } else { "" };
+    let trail = if after.len() > GREP_CONTEXT_CHARS { "…" } else { "" };
+    let before: String = before.into_iter().take(GREP_CONTEXT_CHARS).rev().collect();
+    let after: String = after.into_iter().take(GREP_CONTEXT_CHARS).collect();
+    let flatten = |text: &str| text.replace(|c: char| c.is_whitespace(), " ");
+    Some(format!("{}{}**{}**{}{}", lead, flatten(&before), flatten(found.as_str()), flatten(&after), trail))
+}
+
+struct GrepMatch {
+    name: String,
+    session: bool,
+    index: usize,
+    line: String,
+}
+
+pub struct GrepCommand {
+    state: AppState,
+}
+
+impl GrepCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for GrepCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (open, pattern) = match args.trim().split_once(char::is_whitespace) {
+            Some(("--open" | "-o", pattern)) => (true, pattern.trim()),
+            _ => (false, args.trim()),
+        };
+        if pattern.is_empty() || pattern == "--open" || pattern == "-o" {
+            return Err(ReplError::Command(GREP_USAGE.to_string()));
+        }
+        let re = RegexBuilder::new(pattern).case_insensitive(true).build()
+            .map_err(|e| ReplError::Command(format!("Invalid regex: {}", e)))?;
+
+        let mut matches = Vec::new();
+        let mut total = 0;
+        for searched in searched(&self.state).await {
+            for (index, entry) in searched.history.iter().enumerate() {
+                let Some(snippet) = match_snippet(&re, &entry.content) else { continue };
+                total += 1;
+                if matches.len() < MAX_GREP_MATCHES {
+                    let date = entry.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+                    let line = format!("{} {} {}: {}", searched.name, date, entry_header(entry, index), snippet);
+                    matches.push(GrepMatch { name: searched.name.clone(), session: searched.session, index, line });
+                }
+            }
+        }
+        if matches.is_empty() {
+            return Ok(format!("No matches for /{}/ in any session.", pattern));
+        }
+
+        if open {
+            let labels = matches.iter().map(|found| found.line.replace("**", "")).collect::<Vec<_>>();
+            let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("Match to open (type to filter, Esc to cancel)")
+                .items(&labels)
+                .default(0)
+                .interact_opt()
+                .map_err(|e| ReplError::Command(format!("Selection error: {}", e)))?;
+            let Some(found) = selection.map(|index| &matches[index]) else {
+                return Ok(format!("Still in session '{}'.", self.state.get_session().await.name));
+            };
+            if !found.session {
+                return Err(ReplError::Command(format!("'{}' is an [on_exit] transcript, not a session, so it can't be opened.", found.name)));
+            }
+            let switched = switch_to(&self.state, &found.name).await?;
+            return Ok(format!("{}\nThe match is history entry {}; /reader shows it.", switched, found.index + 1));
+        }
+
+        let more = if total > matches.len() { format!(" (first {} listed)", matches.len()) } else { String::new() };
+        let lines = matches.iter().map(|found| format!("- {}", found.line)).collect::<Vec<_>>();
+        Ok(format!(
+            "{} match(es) for /{}/{}:\n{}\n\nOpen a session with /sessions <name>, or pick a match with /grep --open {}.",
+            total, pattern, more, lines.join("\n"), pattern
+        ))
+    }
+
+    fn name(&self) -> &str { "grep" }
+    fn help(&self) -> &str { "Search the history of every session (and [on_exit] transcripts) for a regular expression, optionally picking a match to switch to its session." }
+    fn usage(&self) -> &str { "[--open] <pattern>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,140 @@
+// src/commands/set.rs
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    providers::{generation::PARAM_NAMES, group, ollama::parse_keep_alive},
+    state::AppState,
+};
+
+pub struct SetCommand {
+    state: AppState,
+}
+
+// Per-provider settings that `/set` changes for the current provider.
+const TIMEOUT_NAMES: [&str; 2] = ["timeout", "connect_timeout"];
+// Ollama settings that `/set` changes when the current provider is Ollama.
+const OLLAMA_NAMES: [&str; 3] = ["num_ctx", "num_gpu", "keep_alive"];
+
+impl SetCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // Sets the current provider's read (`timeout`) or connect timeout, rebuilding its client.
+    async fn set_timeout(&self, name: &str, value: &str) -> ReplResult<String> {
+        let provider = self.state.get_provider_name().await;
+        if provider.starts_with(group::PREFIX) {
+            return Err(ReplError::Command(format!("{} is a provider group; set timeouts for its members under [providers.<member>]", provider)));
+        }
+        let seconds = if value.eq_ignore_ascii_case("default") {
+            None
+        } else {
+            Some(value.parse::<u64>().ok().filter(|secs| *secs > 0)
+                .ok_or_else(|| ReplError::Command(format!("{} must be a positive number of seconds, or 'default'", name)))?)
+        };
+        let mut settings = self.state.get_config().await.providers.get(&provider).cloned().unwrap_or_default();
+        match name {
+            "timeout" => settings.read_timeout_secs = seconds,
+            _ => settings.connect_timeout_secs = seconds,
+        }
+        self.state.set_provider_settings(&provider, settings).await?;
+        Ok(match seconds {
+            Some(secs) => format!("{} for {} set to {}s.", name, provider, secs),
+            None => format!("{} for {} removed; requests wait as long as they take.", name, provider),
+        })
+    }
+
+    // Sets the context length (`num_ctx`), GPU layers (`num_gpu`) or `keep_alive` of the current
+    // provider, which must be Ollama (built-in or a `type = "ollama"` instance).
+    async fn set_ollama(&self, name: &str, value: &str) -> ReplResult<String> {
+        let provider = self.state.get_provider_name().await;
+        let mut settings = self.state.get_config().await.providers.get(&provider).cloned().unwrap_or_default();
+        if provider != "ollama" && !settings.kind.as_deref().is_some_and(|kind| kind.eq_ignore_ascii_case("ollama")) {
+            return Err(ReplError::Command(format!("{} is an Ollama setting, but the current provider is {}. Switch with /provider ollama.", name, provider)));
+        }
+        let reset = value.eq_ignore_ascii_case("default");
+        match name {
+            "keep_alive" if reset => settings.keep_alive = None,
+            "keep_alive" => {
+                parse_keep_alive(value).map_err(|e| ReplError::Command(e.to_string()))?;
+                settings.keep_alive = Some(value.to_string());
+            }
+            _ if reset => { settings.params.remove(name); }
+            _ => {
+                // num_gpu 0 keeps the model on the CPU
+                let number = value.parse::<u64>().ok().filter(|number| *number > 0 || name == "num_gpu")
+                    .ok_or_else(|| ReplError::Command(format!("{} must be a whole number, or 'default'", name)))?;
+                settings.params.insert(name.to_string(), Value::from(number));
+            }
+        }
+        self.state.set_provider_settings(&provider, settings).await?;
+        Ok(if reset {
+            format!("{} for {} reset to Ollama's default.", name, provider)
+        } else {
+            format!("{} for {} set to {}.", name, provider, value)
+        })
+    }
+}
+
+#[async_trait]
+impl Command for SetCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut params = self.state.get_generation_params().await;
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] => {
+                let lines: Vec<String> = params.describe().into_iter()
+                    .map(|(name, value)| format!("  {:<18} {}", name, value))
+                    .collect();
+                let provider = self.state.get_provider_name().await;
+                let settings = self.state.get_config().await.providers.get(&provider).cloned().unwrap_or_default();
+                let seconds = |secs: Option<u64>| secs.map_or_else(|| "none".to_string(), |secs| format!("{}s", secs));
+                let mut output = format!(
+                    "Generation parameters:\n{}\n\nTimeouts for {}:\n  {:<18} {}\n  {:<18} {}",
+                    lines.join("\n"), provider,
+                    "timeout", seconds(settings.read_timeout_secs),
+                    "connect_timeout", seconds(settings.connect_timeout_secs),
+                );
+                if provider == "ollama" || settings.kind.as_deref().is_some_and(|kind| kind.eq_ignore_ascii_case("ollama")) {
+                    // The /set options first, then any others from the config's params
+                    let names = OLLAMA_NAMES[..2].iter().map(|name| name.to_string())
+                        .chain(settings.params.keys().filter(|name| !OLLAMA_NAMES.contains(&name.as_str())).cloned());
+                    output.push_str(&format!("\n\nOllama options for {}:", provider));
+                    for name in names {
+                        let value = settings.params.get(&name).map_or_else(|| "default".to_string(), |value| value.to_string());
+                        output.push_str(&format!("\n  {:<18} {}", name, value));
+                    }
+                    output.push_str(&format!("\n  {:<18} {}", "keep_alive", settings.keep_alive.as_deref().unwrap_or("default")));
+                }
+                Ok(output)
+            }
+            ["reset"] => {
+                self.state.set_generation_params(Default::default()).await;
+                Ok("Generation parameters reset to the provider defaults.".to_string())
+            }
+            [name, value] if TIMEOUT_NAMES.contains(name) => self.set_timeout(name, value).await,
+            [name, value] if OLLAMA_NAMES.contains(name) => self.set_ollama(name, value).await,
+            [name, value] => {
+                params.set(name, value)?;
+                self.state.set_generation_params(params).await;
+                Ok(if value.eq_ignore_ascii_case("default") {
+                    format!("{} reset to the provider default.", name)
+                } else {
+                    format!("{} set to {}.", name, value)
+                })
+            }
+            _ => Err(ReplError::Command(format!(
+                "Usage: /set [<param> <value|default> | reset]. Parameters: {}, {}; for Ollama: {}",
+                PARAM_NAMES.join(", "), TIMEOUT_NAMES.join(", "), OLLAMA_NAMES.join(", ")
+            ))),
+        }
+    }
+
+    fn name(&self) -> &str { "set" }
+    fn help(&self) -> &str { "Show or set generation parameters sent with every query (temperature, top_p, max_tokens, presence_penalty, frequency_penalty) the current provider's timeout and connect_timeout in seconds, and Ollama's num_ctx, num_gpu and keep_alive." }
+    fn usage(&self) -> &str { "[param value|reset]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
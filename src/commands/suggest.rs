@@ -0,0 +1,122 @@
+// src/commands/suggest.rs
+use async_trait::async_trait;
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    shell::execute_shell_command,
+    state::{AppState, HistoryContentType, HistoryEntry},
+};
+
+// Recent history entries given to the model so suggestions follow the conversation.
+const CONTEXT_ENTRIES: usize = 6;
+// Each context entry is cut to this many characters.
+const CONTEXT_ENTRY_CHARS: usize = 1_500;
+
+fn clip(text: &str) -> String {
+    match text.char_indices().nth(CONTEXT_ENTRY_CHARS) {
+        Some((cut, _)) => format!("{}...", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+// One history entry as conversation context, or None for entries that add nothing.
+fn describe_entry(entry: &HistoryEntry) -> Option<String> {
+    match &entry.entry_type {
+        HistoryContentType::LlmResponse { .. } => Some(match &entry.prompt {
+            Some(prompt) => format!("User: {}\nAssistant: {}", clip(prompt), clip(&entry.content)),
+            None => format!("Assistant: {}", clip(&entry.content)),
+        }),
+        HistoryContentType::ShellOutput { command } => Some(format!("Ran `{}`:\n{}", command, clip(&entry.content))),
+        HistoryContentType::CommandResult { command } => Some(format!("/{} output:\n{}", command, clip(&entry.content))),
+        HistoryContentType::Error { source } => Some(format!("Error from {}: {}", source, clip(&entry.content))),
+        HistoryContentType::UserQuery | HistoryContentType::Info => None,
+    }
+}
+
+/// Pulls the command out of a model answer: the first fenced code block, else the first
+/// non-empty line, without a leading `$ ` prompt.
+fn extract_command(answer: &str) -> String {
+    let command = match answer.split("```").nth(1) {
+        Some(block) => block.split_once('\n').map_or(block, |(_language, body)| body).trim(),
+        None => answer.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default(),
+    };
+    command.strip_prefix("$ ").unwrap_or(command).trim().to_string()
+}
+
+pub struct SuggestCommand {
+    state: AppState,
+}
+
+impl SuggestCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    async fn suggest(&self, goal: &str) -> ReplResult<String> {
+        let history = self.state.get_history().await;
+        let context = history.iter().rev().filter_map(describe_entry).take(CONTEXT_ENTRIES).collect::<Vec<_>>();
+        let context = if context.is_empty() {
+            String::new()
+        } else {
+            format!("Recent conversation (newest last):\n{}\n\n", context.into_iter().rev().collect::<Vec<_>>().join("\n\n"))
+        };
+        let shell = if cfg!(target_os = "windows") { "cmd.exe" } else { "sh" };
+        let answer = self.state.ask_current_model(&format!(
+            "{}Suggest one {} command for {} that accomplishes this goal: {}\n\
+             Prefer safe, non-destructive commands. Reply with only the command in a ```sh code block.",
+            context, shell, std::env::consts::OS, goal
+        )).await?;
+        let command = extract_command(&answer);
+        if command.is_empty() {
+            return Err(ReplError::Command("The model did not suggest a command.".to_string()));
+        }
+        Ok(command)
+    }
+}
+
+#[async_trait]
+impl Command for SuggestCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let goal = args.trim();
+        if goal.is_empty() {
+            return Err(ReplError::Command("Usage: /suggest <goal> (e.g. /suggest find the 10 largest files here)".to_string()));
+        }
+        let mut command = self.suggest(goal).await?;
+        println!("Suggested command:\n\n    {}\n", command);
+
+        // Nothing runs without an explicit choice; Esc or a non-interactive caller cancels
+        let theme = ColorfulTheme::default();
+        let choice = Select::with_theme(&theme)
+            .with_prompt("Run it?")
+            .items(&["Run", "Edit, then run", "Cancel"])
+            .default(0)
+            .interact_opt()
+            .map_err(|e| ReplError::Command(format!("Not run; confirmation failed: {}", e)))?;
+        match choice {
+            Some(0) => {}
+            Some(1) => {
+                command = Input::<String>::with_theme(&theme)
+                    .with_prompt("Command")
+                    .with_initial_text(command)
+                    .interact_text()
+                    .map_err(|e| ReplError::Command(format!("Not run: {}", e)))?;
+            }
+            _ => return Ok(format!("Not run. Suggested command: {}", command)),
+        }
+
+        // The result becomes part of the session history, so later prompts and suggestions see it
+        let output = match execute_shell_command(&command) {
+            Ok(output) if output.trim().is_empty() => "(no output)".to_string(),
+            Ok(output) => output,
+            Err(e) => e.to_string(),
+        };
+        Ok(format!("$ {}\n{}", command, output.trim_end()))
+    }
+
+    fn name(&self) -> &str { "suggest" }
+    fn help(&self) -> &str { "Ask the model for a shell command for a goal; it runs only after you confirm." }
+    fn usage(&self) -> &str { "<goal>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Shell }
+}
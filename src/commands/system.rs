@@ -0,0 +1,46 @@
+// src/commands/system.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::ReplResult,
+    state::AppState,
+};
+
+pub struct SystemCommand {
+    state: AppState,
+}
+
+impl SystemCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for SystemCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let mut config = self.state.get_config().await;
+        let text = args.trim();
+        if text.is_empty() {
+            return Ok(match config.session.system_prompt.as_deref().map(str::trim).filter(|prompt| !prompt.is_empty()) {
+                Some(prompt) => format!("System prompt:\n{}", prompt),
+                None => "No system prompt. Set one with /system <text>.".to_string(),
+            });
+        }
+        let message = if text.eq_ignore_ascii_case("clear") {
+            config.session.system_prompt = None;
+            "System prompt cleared.".to_string()
+        } else {
+            config.session.system_prompt = Some(text.to_string());
+            "System prompt set; it is sent with every prompt until /system clear.".to_string()
+        };
+        self.state.set_config(config).await;
+        Ok(message)
+    }
+
+    fn name(&self) -> &str { "system" }
+    fn help(&self) -> &str { "Show the system prompt sent with every prompt, set it (/system <text>) or remove it (/system clear)." }
+    fn usage(&self) -> &str { "[text|clear]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,109 @@
+// src/commands/tag.rs
+use async_trait::async_trait;
+use chrono::Local;
+
+use crate::{
+    commands::{reader::entry_header, Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    state::AppState,
+};
+
+const TAG_USAGE: &str = "Usage: /tag <history-number> <label> (or -<label> to remove it)";
+// Longest preview of an entry's text in /bookmarks.
+const PREVIEW_CHARS: usize = 80;
+
+// Labels are single words, typed with or without a leading '#'.
+fn label(text: &str) -> ReplResult<String> {
+    let label = text.trim_start_matches('#').to_lowercase();
+    if label.is_empty() {
+        return Err(ReplError::Command(TAG_USAGE.to_string()));
+    }
+    Ok(label)
+}
+
+fn preview(text: &str) -> String {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut preview: String = flattened.chars().take(PREVIEW_CHARS).collect();
+    if flattened.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+    preview
+}
+
+pub struct TagCommand {
+    state: AppState,
+}
+
+impl TagCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for TagCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let [number, tag] = parts.as_slice() else { return Err(ReplError::Command(TAG_USAGE.to_string())) };
+        let index = number.parse::<usize>().ok().and_then(|number| number.checked_sub(1))
+            .ok_or_else(|| ReplError::Command(TAG_USAGE.to_string()))?;
+        let (tag, remove) = match tag.strip_prefix('-') {
+            Some(tag) => (label(tag)?, true),
+            None => (label(tag)?, false),
+        };
+        let entry = self.state.tag_history_entry(index, &tag, remove).await?;
+        let tags = if entry.tags.is_empty() { "none".to_string() } else { entry.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ") };
+        Ok(format!("{}: tags {}", entry_header(&entry, index), tags))
+    }
+
+    fn name(&self) -> &str { "tag" }
+    fn help(&self) -> &str { "Label a history entry (numbered as in /reader) so it shows in /bookmarks; -<label> removes a label." }
+    fn usage(&self) -> &str { "<history-number> <label|-label>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
+
+pub struct BookmarksCommand {
+    state: AppState,
+}
+
+impl BookmarksCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Command for BookmarksCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let filter = match args.trim() {
+            "" => None,
+            text => Some(label(text)?),
+        };
+        let history = self.state.get_history().await;
+        let lines: Vec<String> = history.iter().enumerate()
+            .filter(|(_, entry)| match &filter {
+                Some(tag) => entry.tags.contains(tag),
+                None => !entry.tags.is_empty(),
+            })
+            .map(|(index, entry)| {
+                let tags = entry.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ");
+                format!(
+                    "- **{}** {} ({})\n  {}",
+                    entry_header(entry, index), tags, entry.timestamp.with_timezone(&Local).format("%H:%M"), preview(&entry.content)
+                )
+            })
+            .collect();
+        if lines.is_empty() {
+            return Ok(match filter {
+                Some(tag) => format!("No entries tagged #{}.", tag),
+                None => "No bookmarks. Tag a history entry with /tag <history-number> <label>.".to_string(),
+            });
+        }
+        Ok(format!("{}\n\nShow one in full with /reader, or pin it with /pin <history-number>.", lines.join("\n")))
+    }
+
+    fn name(&self) -> &str { "bookmarks" }
+    fn help(&self) -> &str { "List tagged history entries, optionally only those with one label." }
+    fn usage(&self) -> &str { "[label]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
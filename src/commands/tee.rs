@@ -0,0 +1,47 @@
+// src/commands/tee.rs
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    tee,
+};
+
+const USAGE: &str = "Usage: /tee <file> on|off";
+
+pub struct TeeCommand;
+
+impl TeeCommand {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Command for TeeCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] => Ok(match tee::path() {
+                Some(path) => format!("Tee: On ({})", path.display()),
+                None => "Tee: Off".to_string(),
+            }),
+            [file, "on"] => {
+                let path = PathBuf::from(file);
+                tee::enable(path.clone());
+                Ok(format!("Tee: On. Prompts, responses and command output are appended to {} as they are printed.", path.display()))
+            }
+            [_, "off"] | ["off"] => Ok(match tee::disable() {
+                Some(path) => format!("Tee: Off. The log is in {}.", path.display()),
+                None => "Tee: Off".to_string(),
+            }),
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "tee" }
+    fn help(&self) -> &str { "Mirror everything printed (prompts, responses, command output) to a text or Markdown file (/tee <file> on, /tee off)." }
+    fn usage(&self) -> &str { "<file> on|off" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
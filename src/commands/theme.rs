@@ -6,7 +6,7 @@ use strum::IntoEnumIterator;
 use strum_macros::EnumIter; 
 
 use crate::{
-    commands::Command,
+    commands::{Command, CommandCategory},
     error::{ReplError, ReplResult},
     state::{AppState, RenderTheme}, // Import state RenderTheme
 };
@@ -19,6 +19,8 @@ pub enum SelectableTheme {
     Nord,
     Gruvbox,
     Grayscale,
+    Deuteranopia,
+    Protanopia,
 }
 
 // How the themes will be displayed in the selection list
@@ -29,6 +31,8 @@ impl std::fmt::Display for SelectableTheme {
             SelectableTheme::Nord => write!(f, "Nord (Cool, subdued blues)"),
             SelectableTheme::Gruvbox => write!(f, "Gruvbox (Warm retro - WIP)"),
             SelectableTheme::Grayscale => write!(f, "Grayscale (Minimal - WIP)"),
+            SelectableTheme::Deuteranopia => write!(f, "Deuteranopia (Colorblind-safe blue/orange)"),
+            SelectableTheme::Protanopia => write!(f, "Protanopia (Colorblind-safe blue/orange)"),
         }
     }
 }
@@ -41,6 +45,8 @@ impl From<SelectableTheme> for RenderTheme {
             SelectableTheme::Nord => RenderTheme::Nord,
             SelectableTheme::Gruvbox => RenderTheme::Gruvbox,
             SelectableTheme::Grayscale => RenderTheme::Grayscale,
+            SelectableTheme::Deuteranopia => RenderTheme::Deuteranopia,
+            SelectableTheme::Protanopia => RenderTheme::Protanopia,
         }
     }
 }
@@ -52,6 +58,8 @@ fn theme_to_index(state_theme: RenderTheme) -> usize {
         RenderTheme::Nord => 1,
         RenderTheme::Gruvbox => 2,
         RenderTheme::Grayscale => 3,
+        RenderTheme::Deuteranopia => 4,
+        RenderTheme::Protanopia => 5,
         // Add future themes here
     }
 }
@@ -104,11 +112,13 @@ impl Command for ThemeCommand {
                 "nord" => RenderTheme::Nord,
                 "gruvbox" => RenderTheme::Gruvbox,
                 "grayscale" => RenderTheme::Grayscale,
+                "deuteranopia" => RenderTheme::Deuteranopia,
+                "protanopia" => RenderTheme::Protanopia,
                 // Add aliases if desired (e.g., "grey" for "grayscale")
                 _ => {
                     // Argument didn't match known themes
                     return Err(ReplError::Command(format!(
-                        "Unknown theme '{}'. Available: default, nord, gruvbox, grayscale", args
+                        "Unknown theme '{}'. Available: default, nord, gruvbox, grayscale, deuteranopia, protanopia", args
                     )));
                 }
             }
@@ -126,7 +136,15 @@ impl Command for ThemeCommand {
     }
 
     fn help(&self) -> &str {
-        "Select Markdown theme interactively (/theme) or by name (/theme <default|nord|gruvbox|grayscale>)"
+        "Select Markdown theme interactively (/theme) or by name (/theme <default|nord|gruvbox|grayscale|deuteranopia|protanopia>)"
+    }
+
+    fn usage(&self) -> &str {
+        "[name]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Rendering
     }
 }
 
@@ -143,4 +161,5 @@ impl Command for ThemeStatusCommand {
     }
     fn name(&self) -> &str { "theme_status" }
     fn help(&self) -> &str { "Show the current Markdown rendering theme." }
+    fn category(&self) -> CommandCategory { CommandCategory::Rendering }
 }
\ No newline at end of file
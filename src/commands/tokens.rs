@@ -0,0 +1,80 @@
+// src/commands/tokens.rs
+use async_trait::async_trait;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    documents::expand_file_references,
+    error::{ReplError, ReplResult},
+    providers::{tokens, ChatMessage, TokenCount},
+    state::AppState,
+};
+
+// Longest label shown for a context part.
+const MAX_LABEL_CHARS: usize = 60;
+
+// A context part named by its first line, e.g. "Context file `notes.md`:".
+fn label(part: &str) -> String {
+    let first = part.lines().next().unwrap_or_default().trim();
+    match first.char_indices().nth(MAX_LABEL_CHARS) {
+        Some((cut, _)) => format!("{}...", &first[..cut]),
+        None => first.to_string(),
+    }
+}
+
+pub struct TokensCommand {
+    state: AppState,
+}
+
+impl TokensCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    // The provider's count, or the estimate (with the reason) if it couldn't count them.
+    async fn count(&self, messages: &[ChatMessage]) -> ReplResult<(String, String)> {
+        let provider_name = self.state.get_provider_name().await;
+        let model = self.state.get_model().await;
+        let provider = self.state.get_provider_by_name(&provider_name)
+            .ok_or_else(|| ReplError::UnknownProvider(provider_name.clone()))?;
+        let target = format!("{}:{}", provider_name, model);
+        let counted = match provider.count_tokens(&model, messages).await {
+            Ok(count) => count.describe(),
+            Err(e) => format!("{}; {} could not count them: {}", TokenCount::estimate(messages).describe(), provider_name, e),
+        };
+        Ok((target, counted))
+    }
+}
+
+#[async_trait]
+impl Command for TokensCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let config = self.state.get_config().await;
+        let text = args.trim();
+        if !text.is_empty() {
+            // Count what a prompt would send: `@file` references included
+            let (expanded, _) = expand_file_references(text, &config.input_limits)?;
+            let (target, counted) = self.count(&[ChatMessage::user(expanded.as_str())]).await?;
+            return Ok(format!("{} for {} ({} characters).", counted, target, expanded.chars().count()));
+        }
+
+        let parts = config.session.context(&config.input_limits);
+        let hooks = config.hooks.apply("");
+        if parts.is_empty() && hooks.trim().is_empty() {
+            return Ok("No session context: no system prompt, pins, context files or prompt hooks. Prompts are sent on their own.".to_string());
+        }
+        let (target, counted) = self.count(&config.prompt_messages("")).await?;
+        let mut output = format!("Context sent with every prompt to {}: {}\n", target, counted);
+        for part in &parts {
+            output.push_str(&format!("\n  ~{:>7}  {}", tokens::estimate(part), label(part)));
+        }
+        if !hooks.trim().is_empty() {
+            output.push_str(&format!("\n  ~{:>7}  Prompt hooks (prefix and suffix)", tokens::estimate(&hooks)));
+        }
+        Ok(output)
+    }
+
+    fn name(&self) -> &str { "tokens" }
+    fn help(&self) -> &str { "Count the tokens of some text (with @file references), or of the session context sent with every prompt, for the current model." }
+    fn usage(&self) -> &str { "[text]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Session }
+}
@@ -0,0 +1,63 @@
+// src/commands/usage.rs
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::ReplResult,
+    exit_hooks,
+    state::AppState,
+};
+
+pub struct UsageCommand {
+    state: AppState,
+}
+
+impl UsageCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+fn money(cost: Option<f64>) -> String {
+    cost.map_or_else(|| "-".to_string(), |cost| format!("{:.4}", cost))
+}
+
+#[async_trait]
+impl Command for UsageCommand {
+    async fn execute(&self, _args: &str) -> ReplResult<String> {
+        let report = self.state.session_usage().await;
+        let elapsed = exit_hooks::duration(Duration::from_secs(report.duration_secs));
+        if report.models.is_empty() {
+            return Ok(format!("No queries yet this session ({}).", elapsed));
+        }
+        let totals = &report.totals;
+        let mut output = format!("## Session usage ({})\n\n", elapsed);
+        output.push_str(&format!(
+            "{} queries ({} failed), {} prompt and {} completion tokens, cost {}.\n",
+            totals.queries, totals.errors, totals.prompt_tokens, totals.completion_tokens, money(report.cost)
+        ));
+        if totals.reported < totals.queries {
+            output.push_str(&format!(
+                "Tokens of {} of the {} queries are estimated; the others were counted by the provider.\n",
+                totals.queries - totals.reported, totals.queries
+            ));
+        }
+        output.push_str("\n| Provider | Model | Queries | Errors | Avg latency | Prompt tokens | Completion tokens | Reported | Cost |\n|---|---|---|---|---|---|---|---|---|\n");
+        for model in &report.models {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} ms | {} | {} | {} | {} |\n",
+                model.provider, model.model, model.totals.queries, model.totals.errors, model.totals.average_latency_ms(),
+                model.totals.prompt_tokens, model.totals.completion_tokens, model.totals.reported, money(model.cost)
+            ));
+        }
+        if report.cost.is_none() {
+            output.push_str("\nSet prices under [pricing] in the config file to see costs.");
+        }
+        Ok(output)
+    }
+
+    fn name(&self) -> &str { "usage" }
+    fn help(&self) -> &str { "Show what this session used: queries, prompt and completion tokens (as reported by the provider where it can) and cost, per provider and model." }
+    fn category(&self) -> CommandCategory { CommandCategory::Providers }
+}
@@ -0,0 +1,206 @@
+// src/commands/watch.rs
+use async_trait::async_trait;
+use colored::Colorize;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::{
+    commands::{Command, CommandCategory},
+    error::{ReplError, ReplResult},
+    notify::notify_if_slow,
+    render::get_theme_resources,
+    state::{AppState, HistoryContentType, HistoryEntry, MarkdownMode},
+};
+
+// Editors often write a file in several steps; wait for events to settle before re-running.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// A running file watch job.
+struct WatchJob {
+    id: usize,
+    path: PathBuf,
+    template: String,
+    handle: JoinHandle<()>,
+}
+
+pub struct WatchCommand {
+    state: AppState,
+    jobs: Arc<Mutex<Vec<WatchJob>>>,
+    next_id: Arc<Mutex<usize>>,
+}
+
+impl WatchCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state, jobs: Arc::new(Mutex::new(Vec::new())), next_id: Arc::new(Mutex::new(1)) }
+    }
+
+    fn list_jobs(&self) -> String {
+        let jobs = self.jobs.lock().unwrap();
+        if jobs.is_empty() {
+            return "No active watches. Usage: /watch <file> <prompt-template>".to_string();
+        }
+        let lines = jobs.iter()
+            .map(|job| format!("  [{}] {} -> {}", job.id, job.path.display(), job.template))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("Active watches:\n{}", lines)
+    }
+
+    fn stop_jobs(&self, target: &str) -> ReplResult<String> {
+        let mut jobs = self.jobs.lock().unwrap();
+        if target == "all" {
+            let count = jobs.len();
+            for job in jobs.drain(..) { job.handle.abort(); }
+            return Ok(format!("Stopped {} watch(es).", count));
+        }
+        let id = target.parse::<usize>()
+            .map_err(|_| ReplError::Command(format!("Invalid watch id '{}'. Use /watch stop <id|all>.", target)))?;
+        let index = jobs.iter().position(|job| job.id == id)
+            .ok_or_else(|| ReplError::Command(format!("No watch with id {}.", id)))?;
+        let job = jobs.remove(index);
+        job.handle.abort();
+        Ok(format!("Stopped watch [{}] on {}.", job.id, job.path.display()))
+    }
+
+    fn start_job(&self, file: &str, template: &str) -> ReplResult<String> {
+        let path = PathBuf::from(file).canonicalize()
+            .map_err(|e| ReplError::Command(format!("Cannot watch '{}': {}", file, e)))?;
+        let parent = path.parent().map(PathBuf::from)
+            .ok_or_else(|| ReplError::Command(format!("Cannot watch '{}': no parent directory", file)))?;
+
+        // Watch the parent directory so editors that save by renaming are still picked up
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res { let _ = tx.send(event); }
+        }).map_err(|e| ReplError::Command(format!("Failed to create file watcher: {}", e)))?;
+        watcher.watch(&parent, RecursiveMode::NonRecursive)
+            .map_err(|e| ReplError::Command(format!("Failed to watch '{}': {}", parent.display(), e)))?;
+
+        let id = {
+            let mut next_id = self.next_id.lock().unwrap();
+            let id = *next_id;
+            *next_id += 1;
+            id
+        };
+        let job_state = self.state.clone();
+        let job_path = path.clone();
+        let job_template = template.to_string();
+        let handle = tokio::spawn(async move {
+            let _watcher = watcher; // Keep the watcher alive for as long as the job runs
+            run_watch_loop(id, job_state, job_path, job_template, rx).await;
+        });
+
+        self.jobs.lock().unwrap().push(WatchJob { id, path: path.clone(), template: template.to_string(), handle });
+        Ok(format!("Watching {} as job [{}]. The prompt re-runs on every change; stop with /watch stop {}.", path.display(), id, id))
+    }
+}
+
+// Fills the prompt template: `{file}` becomes the path and `{content}` the file contents.
+// Without a `{content}` placeholder the contents are appended below the template.
+fn render_template(template: &str, path: &Path, content: &str) -> String {
+    let filled = template.replace("{file}", &path.display().to_string());
+    if filled.contains("{content}") {
+        filled.replace("{content}", content)
+    } else {
+        format!("{}\n\n{}", filled, content)
+    }
+}
+
+// Waits for changes to `path` and re-runs the prompt each time, until the job is aborted.
+async fn run_watch_loop(
+    id: usize,
+    state: AppState,
+    path: PathBuf,
+    template: String,
+    mut rx: mpsc::UnboundedReceiver<notify::Event>,
+) {
+    let file_name = path.file_name().map(|n| n.to_os_string());
+    while let Some(event) = rx.recv().await {
+        let touches_file = !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+        if !touches_file { continue; }
+
+        // Debounce: swallow the burst of events that accompanies a single save
+        tokio::time::sleep(DEBOUNCE).await;
+        while rx.try_recv().is_ok() {}
+
+        let theme = state.get_theme().await;
+        let (skin, palette) = get_theme_resources(theme);
+        let header = format!("\n--- /watch [{}] {} changed ---", id, path.display());
+        println!("{}", header.truecolor(palette.info.0, palette.info.1, palette.info.2));
+
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(c) => c,
+            Err(e) => {
+                let err_msg = format!("Watch [{}] could not read {}: {}", id, path.display(), e);
+                eprintln!("{}", err_msg.truecolor(palette.error.0, palette.error.1, palette.error.2));
+                continue;
+            }
+        };
+        let config = state.get_config().await;
+        let content = config.input_limits.cap(&content, &path.display().to_string());
+        let prompt = config.apply_prompt(&render_template(&template, &path, &content));
+
+        let started = Instant::now();
+        let provider_name = state.get_provider_name().await;
+        let result = match state.get_current_provider().await {
+            Some(provider) => {
+                let model = state.get_model().await;
+                let params = state.get_generation_params().await;
+                provider.query(&model, &prompt, &params).await.map(|response| (model, response))
+            }
+            None => Err(ReplError::Provider(format!("Provider {} not found", provider_name))),
+        };
+        let notifications = state.get_config().await.notifications;
+
+        match result {
+            Ok((model, response)) => {
+                let response = state.get_output_limits().await.apply(&response);
+                if state.get_markdown_mode().await != MarkdownMode::Off {
+                    println!("{}", skin.term_text(&response));
+                } else {
+                    println!("{}", response);
+                }
+                notify_if_slow(&notifications, &format!("Watch [{}] response ready", id), &response, started.elapsed());
+                let entry = HistoryEntry::new(HistoryContentType::LlmResponse { model: model.clone() }, response).with_model(&provider_name, &model);
+                state.add_history_entry(entry.with_duration(started.elapsed())).await;
+            }
+            Err(e) => {
+                let err_msg = format!("Watch [{}] LLM Error: {}", id, e);
+                eprintln!("{}", err_msg.truecolor(palette.error.0, palette.error.1, palette.error.2));
+                let entry = HistoryEntry::new(HistoryContentType::Error { source: format!("/watch {}", id) }, err_msg);
+                state.add_history_entry(entry.with_duration(started.elapsed())).await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Command for WatchCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let trimmed_args = args.trim();
+        if trimmed_args.is_empty() {
+            return Ok(self.list_jobs());
+        }
+
+        let (file, template) = trimmed_args.split_once(char::is_whitespace)
+            .map(|(f, t)| (f, t.trim()))
+            .unwrap_or((trimmed_args, ""));
+        if file == "stop" {
+            return self.stop_jobs(template);
+        }
+        if template.is_empty() {
+            return Err(ReplError::Command("Usage: /watch <file> <prompt-template> (use {content} and {file} placeholders)".to_string()));
+        }
+        self.start_job(file, template)
+    }
+
+    fn name(&self) -> &str { "watch" }
+    fn help(&self) -> &str { "Re-run a prompt whenever a file changes (/watch <file> <prompt>), list (/watch) or stop (/watch stop <id|all>) watches." }
+    fn usage(&self) -> &str { "<file> <prompt>" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
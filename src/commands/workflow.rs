@@ -0,0 +1,370 @@
+// src/commands/workflow.rs
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use crate::{
+    commands::{report_progress, Command, CommandCategory, CommandRegistry, EventSender},
+    config::CommandOrigin,
+    error::{ReplError, ReplResult},
+    shell::execute_shell_command_with_env,
+    state::{AppState, HistoryContentType, HistoryEntry},
+};
+
+const USAGE: &str = "Usage: /workflow [list] | /workflow run <name|file.toml> [var=value ...]";
+
+/// Where `/workflow run <name>` looks for `<name>.toml`, relative to the working directory.
+pub const WORKFLOW_DIR: &str = ".llm-repl/workflows";
+
+// The output of the step that ran last; not usable as a step or variable name.
+const PREVIOUS: &str = "previous";
+
+lazy_static! {
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{\{\s*([A-Za-z0-9_-]+)\s*\}\}").expect("placeholder pattern is valid");
+}
+
+/// A workflow file: steps run in order, each a prompt, a shell command or a REPL command.
+/// `{{name}}` inserts a variable or the output of an earlier step, `{{previous}}` the last output.
+/// In shell steps it stands for `${WF_name}`, an environment variable holding the value.
+///
+/// ```toml
+/// description = "Review the staged changes"
+///
+/// [vars]                      # defaults; override with /workflow run review focus=security
+/// focus = "bugs"
+///
+/// [[step]]
+/// name = "diff"
+/// shell = "git diff --staged"
+///
+/// [[step]]
+/// name = "review"
+/// prompt = "Review this diff for {{focus}}. Start with BLOCKER if it must not be merged.\n\n{{diff}}"
+/// model = "groq:llama-3.1-70b-versatile"   # optional; defaults to the current model
+///
+/// [[step]]
+/// name = "notify"
+/// command = "/notify on"
+/// if_matches = "^BLOCKER"     # runs only if the output of `on` (default: the previous step) matches
+/// on = "review"               # unless_matches skips the step on a match instead
+/// ```
+#[derive(Debug, Deserialize)]
+struct Workflow {
+    description: Option<String>,
+    #[serde(default)]
+    vars: BTreeMap<String, String>,
+    #[serde(rename = "step", default)]
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Step {
+    name: String,
+    prompt: Option<String>,
+    model: Option<String>,
+    shell: Option<String>,
+    command: Option<String>,
+    on: Option<String>,
+    if_matches: Option<String>,
+    unless_matches: Option<String>,
+}
+
+enum Action<'a> {
+    Prompt(&'a str),
+    Shell(&'a str),
+    Command(&'a str),
+}
+
+// A step whose action and patterns have been checked before anything runs.
+struct CompiledStep<'a> {
+    step: &'a Step,
+    action: Action<'a>,
+    if_matches: Option<Regex>,
+    unless_matches: Option<Regex>,
+}
+
+impl<'a> CompiledStep<'a> {
+    fn compile(step: &'a Step) -> ReplResult<Self> {
+        let action = match (&step.prompt, &step.shell, &step.command) {
+            (Some(prompt), None, None) => Action::Prompt(prompt),
+            (None, Some(shell), None) => Action::Shell(shell),
+            (None, None, Some(command)) => Action::Command(command),
+            _ => return Err(ReplError::Command(format!("Step '{}' needs exactly one of prompt, shell or command.", step.name))),
+        };
+        if step.model.is_some() && !matches!(action, Action::Prompt(_)) {
+            return Err(ReplError::Command(format!("Step '{}': model only applies to prompt steps.", step.name)));
+        }
+        let compile = |pattern: &Option<String>| -> ReplResult<Option<Regex>> {
+            pattern.as_deref()
+                .map(|p| Regex::new(p).map_err(|e| ReplError::Command(format!("Step '{}': invalid regex '{}': {}", step.name, p, e))))
+                .transpose()
+        };
+        Ok(Self { step, action, if_matches: compile(&step.if_matches)?, unless_matches: compile(&step.unless_matches)? })
+    }
+
+    fn template(&self) -> &str {
+        match self.action {
+            Action::Prompt(text) | Action::Shell(text) | Action::Command(text) => text,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.action {
+            Action::Prompt(_) => "prompt",
+            Action::Shell(_) => "shell",
+            Action::Command(_) => "command",
+        }
+    }
+
+    // Why the step is skipped, judged on the output it is conditioned on; None if it runs.
+    fn skip_reason(&self, values: &BTreeMap<String, String>) -> Option<String> {
+        let on = self.step.on.as_deref().unwrap_or(PREVIOUS);
+        let output = values.get(on).map(String::as_str).unwrap_or_default();
+        if let Some(re) = self.if_matches.as_ref().filter(|re| !re.is_match(output)) {
+            return Some(format!("{} doesn't match /{}/", on, re.as_str()));
+        }
+        if let Some(re) = self.unless_matches.as_ref().filter(|re| re.is_match(output)) {
+            return Some(format!("{} matches /{}/", on, re.as_str()));
+        }
+        None
+    }
+}
+
+impl Workflow {
+    // Compiles every step and checks that names are unique and that placeholders and `on` only
+    // refer to variables and earlier steps, so mistakes surface before the first step runs.
+    fn compile(&self) -> ReplResult<Vec<CompiledStep<'_>>> {
+        if self.steps.is_empty() {
+            return Err(ReplError::Command("The workflow has no [[step]] entries.".to_string()));
+        }
+        let mut known: BTreeSet<&str> = self.vars.keys().map(String::as_str).collect();
+        if known.contains(PREVIOUS) {
+            return Err(ReplError::Command(format!("'{}' is reserved and can't be a variable name.", PREVIOUS)));
+        }
+        let mut steps = Vec::new();
+        for step in &self.steps {
+            let compiled = CompiledStep::compile(step)?;
+            for placeholder in PLACEHOLDER.captures_iter(compiled.template()) {
+                let name = &placeholder[1];
+                if name != PREVIOUS && !known.contains(name) {
+                    return Err(ReplError::Command(format!("Step '{}' uses {{{{{}}}}}, which is not a variable or an earlier step.", step.name, name)));
+                }
+            }
+            if let Some(on) = step.on.as_deref().filter(|on| !known.contains(on)) {
+                return Err(ReplError::Command(format!("Step '{}': on = \"{}\" is not an earlier step or a variable.", step.name, on)));
+            }
+            if step.name == PREVIOUS || !known.insert(&step.name) {
+                return Err(ReplError::Command(format!("Step name '{}' is reserved or already used.", step.name)));
+            }
+            steps.push(compiled);
+        }
+        Ok(steps)
+    }
+}
+
+fn render(template: &str, values: &BTreeMap<String, String>) -> String {
+    PLACEHOLDER.replace_all(template, |caps: &Captures| values.get(&caps[1]).cloned().unwrap_or_default()).into_owned()
+}
+
+// The environment variable a shell step reads a value from: `WF_` and the name, `-` as `_`.
+fn env_name(name: &str) -> String {
+    format!("WF_{}", name.replace('-', "_"))
+}
+
+// A shell step's command and environment. Values may be model output, so they are never pasted
+// into the command line: each placeholder becomes a reference to the variable holding its value.
+fn render_shell(template: &str, values: &BTreeMap<String, String>) -> (String, Vec<(String, String)>) {
+    let reference = |caps: &Captures| {
+        let name = env_name(&caps[1]);
+        if cfg!(target_os = "windows") { format!("%{}%", name) } else { format!("${{{}}}", name) }
+    };
+    let command = PLACEHOLDER.replace_all(template, reference).into_owned();
+    let vars = values.iter().map(|(name, value)| (env_name(name), value.clone())).collect();
+    (command, vars)
+}
+
+// A name from `.llm-repl/workflows`, or a path to a workflow file.
+fn locate(name: &str) -> PathBuf {
+    if name.ends_with(".toml") || name.contains(['/', '\\']) {
+        PathBuf::from(name)
+    } else {
+        Path::new(WORKFLOW_DIR).join(format!("{}.toml", name))
+    }
+}
+
+fn load(path: &Path) -> ReplResult<Workflow> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| ReplError::Command(format!("Could not read workflow '{}': {}", path.display(), e)))?;
+    toml::from_str(&text).map_err(|e| ReplError::Command(format!("Invalid workflow '{}': {}", path.display(), e)))
+}
+
+pub struct WorkflowCommand {
+    state: AppState,
+}
+
+impl WorkflowCommand {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    fn list(&self) -> ReplResult<String> {
+        let mut names = match fs::read_dir(WORKFLOW_DIR) {
+            Ok(entries) => entries.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect::<Vec<_>>(),
+            Err(_) => Vec::new(),
+        };
+        if names.is_empty() {
+            return Ok(format!("No workflows in {}. Add <name>.toml files there, then /workflow run <name>.", WORKFLOW_DIR));
+        }
+        names.sort();
+        let lines = names.iter().map(|path| {
+            let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+            match load(path) {
+                Ok(workflow) => format!(
+                    "  {:<20} {} step(s){}",
+                    name,
+                    workflow.steps.len(),
+                    workflow.description.map(|d| format!(": {}", d.trim())).unwrap_or_default()
+                ),
+                Err(e) => format!("  {:<20} {}", name, e),
+            }
+        });
+        Ok(format!("Workflows in {}:\n{}", WORKFLOW_DIR, lines.collect::<Vec<_>>().join("\n")))
+    }
+
+    async fn prompt_step(&self, prompt: &str, model_spec: Option<&str>) -> ReplResult<(String, String)> {
+        let (provider_name, model) = match model_spec {
+            Some(spec) => self.state.resolve_model_spec(spec).await,
+            None => (self.state.get_provider_name().await, self.state.get_model().await),
+        };
+        let provider = self.state.get_provider_by_name(&provider_name).ok_or_else(|| ReplError::UnknownProvider(provider_name.clone()))?;
+        let messages = self.state.get_config().await.prompt_messages(prompt);
+        let params = self.state.get_generation_params().await;
+        let started = Instant::now();
+        let answer = self.state.get_output_limits().await.apply(&provider.chat(&model, &messages, &params).await?);
+        self.state.add_history_entry(
+            HistoryEntry::new(HistoryContentType::LlmResponse { model: model.clone() }, answer.clone())
+                .with_model(&provider_name, &model)
+                .with_prompt(prompt)
+                .with_duration(started.elapsed()),
+        ).await;
+        Ok((answer, format!("{}:{}", provider_name, model)))
+    }
+
+    // Runs a REPL command. A workflow can be started from the REPL or over the API, so its
+    // commands must be enabled for both.
+    async fn command_step(&self, line: &str, registry: &CommandRegistry) -> ReplResult<String> {
+        let line = line.trim();
+        let command_line = line.strip_prefix('/').unwrap_or(line);
+        let (name, args) = command_line.split_once(char::is_whitespace).unwrap_or((command_line, ""));
+        if name == "workflow" {
+            return Err(ReplError::Command("Workflows can't start other workflows.".to_string()));
+        }
+        let command = registry.get_command(name).ok_or_else(|| ReplError::UnknownCommand(name.to_string()))?;
+        for origin in [CommandOrigin::Local, CommandOrigin::Remote] {
+            if !self.state.is_command_enabled(name, origin).await {
+                return Err(ReplError::CommandDisabled(name.to_string()));
+            }
+        }
+        command.execute(args.trim()).await
+    }
+
+    async fn run(&self, name: &str, overrides: &[&str], events: &EventSender) -> ReplResult<String> {
+        let path = locate(name);
+        let workflow = load(&path)?;
+        let steps = workflow.compile()?;
+        let mut values = workflow.vars.clone();
+        for assignment in overrides {
+            let (var, value) = assignment.split_once('=').ok_or_else(|| ReplError::Command(USAGE.to_string()))?;
+            if !values.contains_key(var) {
+                return Err(ReplError::Command(format!("Unknown variable '{}'; the workflow declares: {}", var, workflow.vars.keys().cloned().collect::<Vec<_>>().join(", "))));
+            }
+            values.insert(var.to_string(), value.to_string());
+        }
+        // Commands are created with a state whose registry is still empty, so command steps look
+        // commands up in a registry of their own, built on first use
+        let mut registry: Option<CommandRegistry> = None;
+
+        let total = steps.len() as u64;
+        let mut report = format!("## Workflow {}\n", name);
+        if let Some(description) = &workflow.description {
+            report.push_str(&format!("\n{}\n", description.trim()));
+        }
+        let (mut ran, mut skipped) = (0, 0);
+        for (index, step) in steps.iter().enumerate() {
+            let name = &step.step.name;
+            report_progress(events, &format!("{} ({})", name, step.kind()), index as u64, Some(total));
+            if let Some(reason) = step.skip_reason(&values) {
+                report.push_str(&format!("\n### {}. {}: skipped ({})\n", index + 1, name, reason));
+                values.insert(name.clone(), String::new());
+                skipped += 1;
+                continue;
+            }
+            let input = render(step.template(), &values);
+            let started = Instant::now();
+            let outcome = match step.action {
+                Action::Prompt(_) => self.prompt_step(&input, step.step.model.as_deref()).await,
+                // Later prompt steps may insert the output, so it's capped like other shell output
+                Action::Shell(_) => {
+                    let limits = self.state.get_config().await.input_limits;
+                    let source = format!("step {} output", name);
+                    let (command, vars) = render_shell(step.template(), &values);
+                    tokio::task::spawn_blocking(move || execute_shell_command_with_env(&command, &vars))
+                        .await
+                        .map_err(|e| ReplError::Command(format!("Shell step failed to run: {}", e)))
+                        .and_then(|output| output)
+                        .map(|output| (limits.cap(&output, &source), "shell".to_string()))
+                }
+                Action::Command(_) => {
+                    let registry = registry.get_or_insert_with(|| CommandRegistry::new(self.state.clone()));
+                    self.command_step(&input, registry).await.map(|output| (output, "command".to_string()))
+                }
+            };
+            match outcome {
+                Ok((output, label)) => {
+                    let output = output.trim_end().to_string();
+                    report.push_str(&format!("\n### {}. {} ({}, {:.1}s)\n\n{}\n", index + 1, name, label, started.elapsed().as_secs_f64(), output));
+                    values.insert(name.clone(), output.clone());
+                    values.insert(PREVIOUS.to_string(), output);
+                    ran += 1;
+                }
+                Err(e) => {
+                    report.push_str(&format!("\nStopped at step {} ({}): {}\n", index + 1, name, e));
+                    return Ok(report);
+                }
+            }
+        }
+        report_progress(events, "Done", total, Some(total));
+        report.push_str(&format!("\nDone: {} step(s) ran, {} skipped.", ran, skipped));
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl Command for WorkflowCommand {
+    async fn execute(&self, args: &str) -> ReplResult<String> {
+        let (events, _progress) = mpsc::unbounded_channel();
+        self.execute_streaming(args, events).await
+    }
+
+    async fn execute_streaming(&self, args: &str, events: EventSender) -> ReplResult<String> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] | ["list"] => self.list(),
+            ["run", name, overrides @ ..] => self.run(name, overrides, &events).await,
+            _ => Err(ReplError::Command(USAGE.to_string())),
+        }
+    }
+
+    fn name(&self) -> &str { "workflow" }
+    fn help(&self) -> &str { "Run a workflow file: prompts, shell and REPL commands in sequence, with conditions on earlier output." }
+    fn usage(&self) -> &str { "[list | run <name> [var=value ...]]" }
+    fn category(&self) -> CommandCategory { CommandCategory::Tools }
+}
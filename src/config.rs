@@ -0,0 +1,748 @@
+// src/config.rs
+use crate::context::ContextOverflow;
+use crate::icons::IconStyle;
+use crate::limits::InputLimits;
+use crate::providers::{group::GroupStrategy, ChatMessage};
+use crate::state::{MarkdownMode, RenderTheme};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::{BTreeMap, BTreeSet}, env, fs, path::{Path, PathBuf}};
+
+/// File in the current directory whose settings overlay the user's config file.
+pub const WORKSPACE_CONFIG_FILE: &str = ".llm-repl.toml";
+/// Project summary written by `/init`; included with every prompt in its workspace.
+pub const PROJECT_CONTEXT_FILE: &str = ".llm-repl/context.md";
+
+/// Settings loaded from the user's config file, overlaid by a `.llm-repl.toml` in the directory
+/// the REPL was started in. Every section is optional; missing values fall back to their defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub notifications: NotificationConfig,
+    pub hooks: PromptHooks,
+    pub ui: UiConfig,
+    pub server: ServerConfig,
+    pub commands: CommandPolicy,
+    pub history: HistoryRetention,
+    pub markdown: MarkdownConfig,
+    pub ops: OpsConfig,
+    pub http: HttpConfig,
+    pub session: SessionConfig,
+    pub bridge: BridgeConfig,
+    pub on_exit: ExitHooks,
+    /// Caps on file contents and shell output put into prompts.
+    pub input_limits: InputLimits,
+    /// Per-provider overrides, e.g. `[providers.ollama] base_url = "http://gpu-box:11434"`.
+    pub providers: BTreeMap<String, ProviderSettings>,
+    /// Model shortcuts: `fast = "groq:llama-3.1-8b-instant"` allows `/model fast` and `@fast <prompt>`.
+    pub aliases: BTreeMap<String, String>,
+    /// Token prices for the usage dashboard, keyed `provider:model` or just `provider`.
+    pub pricing: BTreeMap<String, ModelPrice>,
+}
+
+/// Price per million tokens, in whatever currency you track costs in.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Settings for one provider: overrides for a built-in one, or a named instance of a provider type.
+/// Unset values keep the defaults and environment variables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderSettings {
+    /// For extra instances (`[providers.gpu-box]`): which provider they are, e.g. `ollama` or `openai`.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub base_url: Option<String>,
+    /// Takes precedence over the provider's environment variable (e.g. `GROQ_API_KEY`).
+    pub api_key: Option<String>,
+    /// Azure OpenAI only: the `api-version` query parameter.
+    pub api_version: Option<String>,
+    /// Azure OpenAI only: deployment names, used as the model names.
+    pub deployments: Vec<String>,
+    /// vLLM, OpenAI and Gemini only: extra request-body fields, such as sampling parameters (`top_k = 40`,
+    /// `best_of = 3`). For Ollama, its `options` (`num_ctx = 8192`, `num_gpu = 20`; `/set num_ctx`).
+    pub params: BTreeMap<String, Value>,
+    /// Ollama only: how long the model stays loaded after a request, e.g. `10m`, `1h`, `0` to unload
+    /// it at once or `-1` to keep it (`/set keep_alive`). Ollama's default (5 minutes) if unset.
+    pub keep_alive: Option<String>,
+    /// Ollama only: load the selected model when the session starts or switches to it, and reload it
+    /// every `warm_up_interval_secs` so it stays loaded between prompts.
+    pub warm_up: bool,
+    /// Ollama only: seconds between warm-up reloads; 240 if unset, inside Ollama's default keep_alive.
+    pub warm_up_interval_secs: Option<u64>,
+    /// Gemini only: answer with Google Search grounding, listing the sources used (`/ground on|off`).
+    pub grounding: bool,
+    /// Seconds to wait for a connection to the provider (`/set connect_timeout`); no limit if unset.
+    pub connect_timeout_secs: Option<u64>,
+    /// Seconds the provider may send nothing: a response, the start of a stream, or its next chunk
+    /// (`/set timeout`). Bounds generations that hang; no limit if unset.
+    pub read_timeout_secs: Option<u64>,
+    /// Groups only (`type = "group"`): the providers queries are spread over, e.g. `["ollama", "gpu-box"]`.
+    pub members: Vec<String>,
+    /// Groups only: `round_robin` (the default) or `least_busy`.
+    pub strategy: GroupStrategy,
+}
+
+/// The starting model and the context sent with every prompt, typically set per project in a
+/// workspace `.llm-repl.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Starting model: an alias, `provider:model`, or a model of the default provider.
+    pub model: Option<String>,
+    /// Instructions placed before every prompt.
+    pub system_prompt: Option<String>,
+    /// Files (relative to the working directory) whose current text is included with every prompt.
+    pub context_files: Vec<PathBuf>,
+    /// Content that is always included, right after the system prompt (`/pin`, `/pins`).
+    pub pins: Vec<PinnedContext>,
+    /// How many earlier question/answer pairs are sent with each REPL prompt; all of them if unset,
+    /// 0 to send every prompt on its own (`/memory`).
+    pub memory_turns: Option<usize>,
+    /// What happens to earlier turns that no longer fit the model's context window (`/context`).
+    pub context_overflow: ContextOverflow,
+}
+
+/// A pinned file (read fresh for every prompt) or piece of text, such as a history entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PinnedContext {
+    /// Where the text came from, e.g. `LLM Response (llama3) [4]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl PinnedContext {
+    pub fn describe(&self) -> String {
+        match (&self.label, &self.file) {
+            (Some(label), _) => label.clone(),
+            (None, Some(file)) => format!("file {}", file.display()),
+            (None, None) => "pinned text".to_string(),
+        }
+    }
+
+    fn render(&self, limits: &InputLimits) -> Option<String> {
+        if let Some(path) = &self.file {
+            return match fs::read_to_string(path) {
+                Ok(text) => Some(format!("Pinned file `{}`:\n```\n{}\n```", path.display(), limits.cap(text.trim_end(), &path.display().to_string()))),
+                Err(e) => {
+                    eprintln!("WARN: Skipping pinned file '{}': {}", path.display(), e);
+                    None
+                }
+            };
+        }
+        let text = self.text.as_deref()?.trim_end();
+        Some(format!("Pinned {}:\n{}", self.describe(), text))
+    }
+}
+
+impl SessionConfig {
+    /// Returns the prompt preceded by the system prompt, the pins, the project summary from `/init`
+    /// (if the working directory has one) and the context files. Unreadable files are skipped with a warning.
+    pub fn apply(&self, prompt: &str, limits: &InputLimits) -> String {
+        let mut parts = self.context(limits);
+        parts.push(prompt.to_string());
+        parts.join("\n\n")
+    }
+
+    /// The parts `apply` puts before the prompt, in order. Files are capped by `limits`.
+    pub fn context(&self, limits: &InputLimits) -> Vec<String> {
+        let mut parts = Vec::new();
+        if let Some(system_prompt) = self.system_prompt.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            parts.push(system_prompt.to_string());
+        }
+        parts.extend(self.pins.iter().filter_map(|pin| pin.render(limits)));
+        let project_context = Path::new(PROJECT_CONTEXT_FILE);
+        let project_context = (project_context.is_file() && !self.context_files.iter().any(|path| path == project_context))
+            .then_some(project_context);
+        for path in project_context.into_iter().chain(self.context_files.iter().map(PathBuf::as_path)) {
+            match fs::read_to_string(path) {
+                Ok(text) => parts.push(format!("Context file `{}`:\n```\n{}\n```", path.display(), limits.cap(text.trim_end(), &path.display().to_string()))),
+                Err(e) => eprintln!("WARN: Skipping context file '{}': {}", path.display(), e),
+            }
+        }
+        parts
+    }
+}
+
+/// Markdown rendering per output source: `append`, `live` or `off`. Command and shell output are
+/// printed whole, so `live` renders them like `append`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownConfig {
+    /// Starting mode for LLM responses (changed at runtime with `/md`, `/md_streaming`, `/md_off`).
+    pub llm: Option<MarkdownMode>,
+    /// Command output; unset follows the LLM mode.
+    pub commands: Option<MarkdownMode>,
+    /// `!shell` output; unset prints it raw.
+    pub shell: Option<MarkdownMode>,
+    /// AppendFormatted only: show the raw and formatted answer side by side when the terminal is
+    /// wide enough (`/md_split`).
+    pub split: bool,
+}
+
+/// Limits on how much history is kept, applied to the session history and the saved prompt file,
+/// and which input is kept at all (like the shell's `HISTCONTROL`/`HISTIGNORE`). Unset limits don't apply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryRetention {
+    pub max_entries: Option<usize>,
+    /// Entries older than this are dropped (session history only; saved prompts have no timestamps).
+    pub max_age_days: Option<u64>,
+    /// Total size of the kept entries' text.
+    pub max_bytes: Option<u64>,
+    /// Input starting with a space is not recorded in either history.
+    pub ignore_space: bool,
+    /// Input matching any of these regexes is not recorded in either history.
+    pub ignore_patterns: Vec<String>,
+    /// Consecutive identical prompts and session entries are recorded once.
+    pub ignore_dups: bool,
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        Self { max_entries: None, max_age_days: None, max_bytes: None, ignore_space: false, ignore_patterns: Vec::new(), ignore_dups: true }
+    }
+}
+
+impl HistoryRetention {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_entries.is_none() && self.max_age_days.is_none() && self.max_bytes.is_none()
+    }
+}
+
+/// Where a command invocation comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandOrigin {
+    /// Typed in the interactive REPL.
+    Local,
+    /// Sent to the REST API (`POST /command`).
+    Remote,
+}
+
+/// Commands switched off per origin, e.g. `disabled_remote = ["watch"]` keeps `/watch` in the
+/// local REPL but refuses it over the API. Changeable at runtime through `PUT /commands/{name}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandPolicy {
+    pub disabled_local: BTreeSet<String>,
+    pub disabled_remote: BTreeSet<String>,
+    /// When set, only these commands run from either origin (`/help` always does).
+    pub allowed: Option<BTreeSet<String>>,
+}
+
+impl CommandPolicy {
+    fn disabled(&self, origin: CommandOrigin) -> &BTreeSet<String> {
+        match origin {
+            CommandOrigin::Local => &self.disabled_local,
+            CommandOrigin::Remote => &self.disabled_remote,
+        }
+    }
+
+    fn is_allowed(&self, command: &str) -> bool {
+        command == "help" || self.allowed.as_ref().is_none_or(|allowed| allowed.contains(command))
+    }
+
+    pub fn is_enabled(&self, command: &str, origin: CommandOrigin) -> bool {
+        self.is_allowed(command) && !self.disabled(origin).contains(command)
+    }
+
+    pub fn set_enabled(&mut self, command: &str, origin: CommandOrigin, enabled: bool) {
+        // Enabling a command outside the allow-list adds it there, but only for this origin
+        if enabled && !self.is_allowed(command) {
+            if let Some(allowed) = &mut self.allowed {
+                allowed.insert(command.to_string());
+            }
+            let other = match origin {
+                CommandOrigin::Local => &mut self.disabled_remote,
+                CommandOrigin::Remote => &mut self.disabled_local,
+            };
+            other.insert(command.to_string());
+        }
+        let disabled = match origin {
+            CommandOrigin::Local => &mut self.disabled_local,
+            CommandOrigin::Remote => &mut self.disabled_remote,
+        };
+        if enabled {
+            disabled.remove(command);
+        } else {
+            disabled.insert(command.to_string());
+        }
+    }
+}
+
+/// Ops helpers (`/k8s`, `/ops diagnose`), which run allow-listed `kubectl`, `systemctl` and
+/// `journalctl` commands. Off unless enabled here or with `/ops on`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpsConfig {
+    pub enabled: bool,
+}
+
+/// Requests made with `/http`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Headers sent with every request (also set at runtime with `/http header`).
+    pub headers: BTreeMap<String, String>,
+    /// Extra headers per host, e.g. `[http.hosts."api.github.com"]`; they override `headers`.
+    pub hosts: BTreeMap<String, BTreeMap<String, String>>,
+    pub timeout_secs: u64,
+    /// Response bodies are read up to this many bytes; the rest isn't downloaded.
+    pub max_response_bytes: usize,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self { headers: BTreeMap::new(), hosts: BTreeMap::new(), timeout_secs: 30, max_response_bytes: 5_000_000 }
+    }
+}
+
+impl HttpConfig {
+    /// The headers for a request to `host`: the global ones, overridden by the host's.
+    pub fn headers_for(&self, host: &str) -> BTreeMap<String, String> {
+        let mut headers = self.headers.clone();
+        if let Some(host_headers) = self.hosts.get(host) {
+            headers.extend(host_headers.iter().map(|(name, value)| (name.clone(), value.clone())));
+        }
+        headers
+    }
+}
+
+/// Chat bridges for `--bridge slack` and `--bridge discord`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BridgeConfig {
+    pub slack: BridgeSettings,
+    pub discord: BridgeSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BridgeSettings {
+    /// Bot token; takes precedence over `SLACK_BOT_TOKEN` / `DISCORD_BOT_TOKEN`.
+    pub token: Option<String>,
+    /// Channel ids to relay.
+    pub channels: Vec<String>,
+    /// Only answer messages that mention the bot.
+    pub mention_only: bool,
+    pub poll_interval_secs: u64,
+    /// Commands channel members may run (also subject to the remote policy); none by default, so
+    /// the bridge only answers prompts.
+    pub commands: BTreeSet<String>,
+    /// User ids whose messages are answered; everyone in the channels if empty.
+    pub users: BTreeSet<String>,
+}
+
+impl Default for BridgeSettings {
+    fn default() -> Self {
+        Self { token: None, channels: Vec::new(), mention_only: false, poll_interval_secs: 3, commands: BTreeSet::new(), users: BTreeSet::new() }
+    }
+}
+
+/// REST API server settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Append-only, hash-chained log of every API call.
+    pub audit_log: Option<PathBuf>,
+    /// When set, every call must present one of these keys; its id is recorded as the caller.
+    pub api_keys: Vec<ApiKey>,
+    /// URLs notified with a JSON payload when a query, command or shell call completes.
+    pub webhooks: Vec<Webhook>,
+    /// How many prompts are answered at once, and how many may wait.
+    pub queue: QueueConfig,
+}
+
+/// `[server.queue]`: limits on concurrent provider calls from `POST /query` and `/v1/chat/completions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueConfig {
+    /// Prompts answered at once (streams count until they end). Unlimited if unset.
+    pub max_concurrent: Option<usize>,
+    /// Prompts that may wait for a free slot; more get 429.
+    pub max_waiting: usize,
+    /// Sent as Retry-After with a 429.
+    pub retry_after_secs: u64,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self { max_concurrent: None, max_waiting: 32, retry_after_secs: 5 }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    /// Which completions to send: `query`, `command`, `shell`. Empty sends all of them.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Signs each payload: `X-LLM-REPL-Signature: sha256=<HMAC-SHA256 of the body, hex>`.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+impl Webhook {
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|wanted| wanted == event)
+    }
+}
+
+// Keeps secrets out of debug output and logs.
+impl std::fmt::Debug for Webhook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Webhook").field("url", &self.url).field("events", &self.events).field("secret", &self.secret.as_ref().map(|_| "<redacted>")).finish()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub key: String,
+    #[serde(default)]
+    pub scope: ApiScope,
+}
+
+// Keeps keys out of debug output and logs.
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKey").field("id", &self.id).field("key", &"<redacted>").field("scope", &self.scope).finish()
+    }
+}
+
+/// What an API key may do. Each scope includes the ones before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    /// Status, providers, models and history.
+    Read,
+    /// Read, plus sending prompts (`/query`, `/v1/chat/completions`) and cancelling them.
+    Query,
+    /// Everything, including `/command` and `/shell`.
+    #[default]
+    Admin,
+}
+
+/// Interface settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// UI language (e.g. "de"); defaults to the `LANG` environment variable.
+    pub locale: Option<String>,
+    /// Starting display theme; defaults to nord.
+    pub theme: Option<RenderTheme>,
+    /// Screen-reader friendly output: no colors or live redraws, structure announced in words.
+    pub accessible: bool,
+    /// Typewriter pacing for streamed responses (characters per second); None shows text as it arrives.
+    pub pace_chars_per_sec: Option<u32>,
+    /// Offer the earlier answer when a prompt repeats one answered within this many minutes (0 turns it off).
+    pub cached_answer_window_mins: u64,
+    /// Semantic colors that replace the theme's, whatever the theme.
+    pub colors: ColorOverrides,
+    /// Icons in the prompt and history headers: `nerd` (needs a Nerd Font), `emoji`, or `ascii`.
+    pub icons: IconStyle,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self { locale: None, theme: None, accessible: false, pace_chars_per_sec: None, cached_answer_window_mins: 60, colors: ColorOverrides::default(), icons: IconStyle::Ascii }
+    }
+}
+
+/// `[ui.colors]`: `#rrggbb` colors for error, success and info messages. The Markdown skin of
+/// the theme is unaffected.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorOverrides {
+    pub error: Option<String>,
+    pub success: Option<String>,
+    pub info: Option<String>,
+}
+
+/// Desktop notifications for long-running responses and jobs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    /// Only notify when a task took at least this many seconds.
+    pub threshold_secs: u64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self { enabled: false, threshold_secs: 20 }
+    }
+}
+
+/// What the REPL does when it quits, in this order: save the session, print a summary, run a command.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExitHooks {
+    /// Directory the session history is saved to, as `session-<date>-<time>.json`.
+    pub save_session: Option<PathBuf>,
+    /// Print the number of queries, estimated tokens and cost of the session.
+    pub summary: bool,
+    /// Shell command run last (e.g. to sync transcripts); `{session_file}` is replaced with the saved session's path.
+    pub command: Option<String>,
+}
+
+/// Text wrapped around every user prompt (e.g. "Answer concisely."), separate from any system prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptHooks {
+    pub enabled: bool,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl Default for PromptHooks {
+    fn default() -> Self {
+        Self { enabled: true, prefix: String::new(), suffix: String::new() }
+    }
+}
+
+impl PromptHooks {
+    /// Returns the prompt with the prefix and suffix added (separated by blank lines), if enabled.
+    pub fn apply(&self, prompt: &str) -> String {
+        if !self.enabled {
+            return prompt.to_string();
+        }
+        [self.prefix.trim(), prompt, self.suffix.trim()]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+// Overlays `overlay` onto `base`: tables are merged key by key, anything else is replaced.
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => { base.insert(key, value); }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+// `[session]` settings a workspace config may set. A `.llm-repl.toml` comes with whatever
+// directory the REPL starts in, such as a cloned repository, so it is not trusted with commands,
+// URLs, keys or headers.
+const WORKSPACE_SESSION_KEYS: &[&str] = &["model", "system_prompt", "context_files", "pins", "memory_turns", "context_overflow"];
+
+/// Merges the harmless part of a workspace config into `base`: the `[session]` settings above,
+/// and `[commands] allowed`, which can only narrow the commands the config file allows. Returns
+/// the keys that were left out.
+fn overlay_trusted(base: &mut Value, workspace: Value, root: &Path) -> Vec<String> {
+    let Value::Object(workspace) = workspace else { return Vec::new() };
+    let mut ignored = Vec::new();
+    for (section, value) in workspace {
+        match (section.as_str(), value) {
+            ("session", Value::Object(session)) => {
+                for (key, mut value) in session {
+                    if WORKSPACE_SESSION_KEYS.contains(&key.as_str()) {
+                        confine_files(&key, &mut value, root, &mut ignored);
+                        merge_values(base, json!({ "session": { key: value } }));
+                    } else {
+                        ignored.push(format!("session.{}", key));
+                    }
+                }
+            }
+            ("commands", Value::Object(commands)) => {
+                for (key, value) in commands {
+                    if key != "allowed" {
+                        ignored.push(format!("commands.{}", key));
+                        continue;
+                    }
+                    let narrowed = match (base.pointer("/commands/allowed").and_then(Value::as_array), value) {
+                        (Some(global), Value::Array(listed)) => Value::Array(listed.into_iter().filter(|command| global.contains(command)).collect()),
+                        (_, value) => value,
+                    };
+                    merge_values(base, json!({ "commands": { "allowed": narrowed } }));
+                }
+            }
+            (section, _) => ignored.push(section.to_string()),
+        }
+    }
+    ignored
+}
+
+// Whether a file named by the workspace config stays inside `root`: false for absolute paths,
+// paths (including symlinks) that lead outside it, and files that don't exist.
+fn inside_workspace(root: &Path, file: &str) -> bool {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        return false;
+    }
+    let (Ok(root), Ok(resolved)) = (root.canonicalize(), root.join(path).canonicalize()) else { return false };
+    resolved.starts_with(&root)
+}
+
+// Keeps the workspace's `context_files` and pinned files to files inside its directory, so a
+// cloned repository can't have `~/.ssh/id_rsa` or `../../.aws/credentials` sent with prompts.
+// The files refused are added to `ignored`.
+fn confine_files(key: &str, value: &mut Value, root: &Path, ignored: &mut Vec<String>) {
+    let Value::Array(items) = value else { return };
+    let file_of = |item: &Value| match key {
+        "context_files" => item.as_str().map(str::to_string),
+        "pins" => item.get("file").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    };
+    items.retain(|item| match file_of(item) {
+        Some(file) if !inside_workspace(root, &file) => {
+            ignored.push(format!("session.{} '{}' (outside the workspace)", key, file));
+            false
+        }
+        _ => true,
+    });
+}
+
+fn read_toml(path: &Path) -> Result<Value, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Could not read config file '{}': {}", path.display(), e))?;
+    toml::from_str(&text).map_err(|e| format!("Invalid config file '{}': {}", path.display(), e))
+}
+
+impl AppConfig {
+    /// The workspace config file, if the current directory has one.
+    pub fn workspace_path() -> Option<PathBuf> {
+        let path = env::current_dir().ok()?.join(WORKSPACE_CONFIG_FILE);
+        path.is_file().then_some(path)
+    }
+
+    /// Location of the config file: `$LLM_REPL_CONFIG`, else `$XDG_CONFIG_HOME/llm-repl/config.toml`,
+    /// else `~/.config/llm-repl/config.toml`.
+    pub fn config_path() -> Option<PathBuf> {
+        if let Ok(path) = env::var("LLM_REPL_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+        let config_dir = env::var("XDG_CONFIG_HOME")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var("HOME").ok().map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("llm-repl").join("config.toml"))
+    }
+
+    /// Loads the config file and the workspace overlay, falling back to defaults if they are
+    /// missing or invalid.
+    pub fn load() -> Self {
+        match Self::config_path() {
+            Some(path) => Self::read(&path),
+            None => Self::read_layers(None),
+        }
+        .unwrap_or_else(|e| {
+            eprintln!("WARN: {}. Using defaults.", e);
+            Self::default()
+        })
+    }
+
+    /// Reads and parses the config file at `path` (defaults if it doesn't exist), overlaid by the
+    /// workspace config file.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        Self::read_layers(Some(path).filter(|path| path.exists()))
+    }
+
+    /// Parses `text` as the contents of the config file, overlaid by the workspace config file as
+    /// `read` would, so an edit can be checked before it is saved.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let global = toml::from_str(text).map_err(|e| format!("Invalid TOML: {}", e))?;
+        Self::overlay_workspace(global)
+    }
+
+    fn read_layers(global: Option<&Path>) -> Result<Self, String> {
+        Self::overlay_workspace(match global {
+            Some(path) => read_toml(path)?,
+            None => Value::Object(Default::default()),
+        })
+    }
+
+    fn overlay_workspace(mut merged: Value) -> Result<Self, String> {
+        if let Some(workspace) = Self::workspace_path() {
+            let root = workspace.parent().unwrap_or(Path::new("."));
+            let ignored = overlay_trusted(&mut merged, read_toml(&workspace)?, root);
+            if !ignored.is_empty() {
+                eprintln!(
+                    "WARN: Ignoring {} in {}: a workspace config only sets [session] and narrows [commands] allowed.",
+                    ignored.join(", "), workspace.display()
+                );
+            }
+        }
+        serde_json::from_value(merged).map_err(|e| format!("Invalid config: {}", e))
+    }
+
+    /// The configured price of a model: its `provider:model` entry, else the provider's.
+    pub fn price_for(&self, provider: &str, model: &str) -> Option<&ModelPrice> {
+        self.pricing.get(&format!("{}:{}", provider, model)).or_else(|| self.pricing.get(provider))
+    }
+
+    /// Applies the session context (system prompt, context files) and the prompt hooks to a prompt.
+    pub fn apply_prompt(&self, prompt: &str) -> String {
+        self.session.apply(&self.hooks.apply(prompt), &self.input_limits)
+    }
+
+    /// `apply_prompt` as chat messages: the session context as a system message (if there is any)
+    /// and the hooked prompt as the user's. Providers without a chat API get it joined back together.
+    pub fn prompt_messages(&self, prompt: &str) -> Vec<ChatMessage> {
+        self.chat_messages(&[], prompt)
+    }
+
+    /// `prompt_messages` with the conversation's earlier turns between the session context and the
+    /// prompt, keeping the newest `[session] memory_turns` of them.
+    pub fn chat_messages(&self, turns: &[ChatMessage], prompt: &str) -> Vec<ChatMessage> {
+        let context = self.session.context(&self.input_limits);
+        let system = (!context.is_empty()).then(|| ChatMessage::system(context.join("\n\n")));
+        let kept = self.session.memory_turns.map_or(turns.len(), |pairs| turns.len().min(pairs * 2));
+        system.into_iter()
+            .chain(turns[turns.len() - kept..].iter().cloned())
+            .chain([ChatMessage::user(self.hooks.apply(prompt))])
+            .collect()
+    }
+
+    /// Whether `provider` is Ollama: the built-in one or a `type = "ollama"` instance.
+    pub fn is_ollama(&self, provider: &str) -> bool {
+        provider == "ollama"
+            || self.providers.get(provider).and_then(|settings| settings.kind.as_deref()).is_some_and(|kind| kind.eq_ignore_ascii_case("ollama"))
+    }
+
+    /// Names the settings that differ from `other`, one level deep (e.g. `ui.theme`,
+    /// `providers.groq`, `hooks`). Values are left out, since some are secrets.
+    pub fn changed_settings(&self, other: &AppConfig) -> Vec<String> {
+        let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(self), serde_json::to_value(other)) else {
+            return Vec::new();
+        };
+        let mut changed = Vec::new();
+        for (section, old_value) in &old {
+            let new_value = &new[section];
+            if old_value == new_value {
+                continue;
+            }
+            match (old_value, new_value) {
+                (Value::Object(old_fields), Value::Object(new_fields)) => {
+                    let names = old_fields.keys().chain(new_fields.keys()).collect::<BTreeSet<_>>();
+                    changed.extend(names.into_iter()
+                        .filter(|name| old_fields.get(*name) != new_fields.get(*name))
+                        .map(|name| format!("{}.{}", section, name)));
+                }
+                _ => changed.push(section.clone()),
+            }
+        }
+        changed
+    }
+}
@@ -0,0 +1,199 @@
+// src/context.rs
+//! Keeping a prompt inside the model's context window. When the session context, earlier turns
+//! and the prompt no longer fit the window the provider reports for the model (less room for the
+//! reply), the oldest turns are dropped, or summarized by the model into one turn
+//! (`[session] context_overflow`). `/context` shows what the next prompt will send.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{ReplError, ReplResult},
+    providers::{chat::Role, tokens, ChatMessage, TokenCount},
+    state::AppState,
+};
+
+/// What to do with earlier turns that don't fit the context window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextOverflow {
+    /// Leave out the oldest turns.
+    #[default]
+    Drop,
+    /// Replace the oldest turns with a summary written by the model, kept in the conversation.
+    Summarize,
+    /// Send everything and let the provider truncate or reject it.
+    Off,
+}
+
+impl ContextOverflow {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ContextOverflow::Drop => "drop the oldest turns",
+            ContextOverflow::Summarize => "summarize the oldest turns",
+            ContextOverflow::Off => "send everything",
+        }
+    }
+}
+
+// Room kept for the reply when `max_tokens` isn't set: an eighth of the window.
+const REPLY_SHARE: u64 = 8;
+// The question a summary turn answers; it marks the turn as a summary in /context.
+pub const SUMMARY_REQUEST: &str = "Summarize our conversation so far.";
+const SUMMARY_INSTRUCTIONS: &str = "Summarize the following conversation between a user and an assistant in a few short paragraphs. \
+Keep the facts, decisions, names, numbers and open questions that later messages may refer to. Reply with the summary only.";
+// The turns sent in one summary request take at most this share of the budget, leaving room for
+// the instructions and the summary of the parts before them.
+const SUMMARY_CHUNK_SHARE: u64 = 2;
+
+/// How the next prompt's messages fit the model's context window.
+#[derive(Debug, Clone)]
+pub struct ContextPlan {
+    /// The messages to send, after any turns were dropped.
+    pub messages: Vec<ChatMessage>,
+    /// The oldest earlier messages that don't fit, in order; dropped or summarized.
+    pub overflow: Vec<ChatMessage>,
+    pub window: Option<u64>,
+    /// Tokens available for the messages: the window less the room kept for the reply.
+    pub budget: Option<u64>,
+    pub tokens: u64,
+}
+
+impl ContextPlan {
+    /// Earlier turns (question and answer pairs) that don't fit.
+    pub fn overflow_turns(&self) -> usize {
+        self.overflow.len().div_ceil(2)
+    }
+}
+
+// The context window the provider reports for `model`, from its (cached) model list.
+async fn context_window(state: &AppState, provider_name: &str, model: &str) -> Option<u64> {
+    let provider = state.get_provider_by_name(provider_name)?;
+    let models = state.provider_models(provider_name, provider.as_ref()).await.ok()?;
+    models.into_iter().find(|info| info.id == model)?.context_window
+}
+
+/// Works out which of `messages` (session context, earlier turns and the prompt, as
+/// `AppConfig::chat_messages` builds them) fit the model's window. The session context and the
+/// prompt are always kept; earlier turns go oldest first, a question and its answer together.
+/// Nothing is left out if the window is unknown or `context_overflow` is off.
+pub async fn plan(state: &AppState, provider_name: &str, model: &str, messages: Vec<ChatMessage>) -> ContextPlan {
+    let window = context_window(state, provider_name, model).await;
+    let reserve = state.get_generation_params().await.max_tokens.map(u64::from);
+    let budget = window.map(|window| window.saturating_sub(reserve.unwrap_or(window / REPLY_SHARE)));
+    let overflow_mode = state.get_config().await.session.context_overflow;
+    let mut plan = ContextPlan { tokens: TokenCount::estimate(&messages).tokens, messages, overflow: Vec::new(), window, budget };
+    let Some(budget) = budget.filter(|_| overflow_mode != ContextOverflow::Off) else { return plan };
+
+    let first_turn = plan.messages.iter().take_while(|message| message.role == Role::System).count();
+    let prompt_at = plan.messages.len().saturating_sub(1);
+    let mut dropped = 0;
+    while plan.tokens > budget && first_turn + dropped < prompt_at {
+        // A question goes with its answer, so the turns left still start with a question
+        let pair = (prompt_at - first_turn - dropped).min(2);
+        dropped += pair;
+        let kept: Vec<ChatMessage> = plan.messages[..first_turn].iter().chain(&plan.messages[first_turn + dropped..]).cloned().collect();
+        plan.tokens = TokenCount::estimate(&kept).tokens;
+    }
+    plan.overflow = plan.messages.drain(first_turn..first_turn + dropped).collect();
+    plan
+}
+
+// A readable transcript of turns for the summary request.
+fn transcript(messages: &[ChatMessage]) -> String {
+    messages.iter()
+        .map(|message| {
+            let speaker = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                Role::System => "System",
+            };
+            format!("{}: {}", speaker, message.content.trim())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The messages to send for the next prompt: `plan` applied, with the turns that don't fit
+/// summarized when `context_overflow = "summarize"`. The summary replaces those turns in the
+/// conversation, so it is written once rather than for every prompt. A failed summary falls
+/// back to dropping them. Returns the messages and a note on what was left out, if anything.
+pub async fn fit(state: &AppState, provider_name: &str, model: &str, messages: Vec<ChatMessage>) -> (Vec<ChatMessage>, Option<String>) {
+    let plan = plan(state, provider_name, model, messages).await;
+    if plan.overflow.is_empty() {
+        return (plan.messages, None);
+    }
+    let turns = plan.overflow_turns();
+    let window = plan.window.unwrap_or_default();
+    let mut failure = None;
+    if state.get_config().await.session.context_overflow == ContextOverflow::Summarize {
+        match summarize(state, provider_name, model, &plan.overflow, plan.budget.unwrap_or(window)).await {
+            Ok(summary) => {
+                state.compact_conversation(&plan.overflow, SUMMARY_REQUEST, &summary).await;
+                // The summary takes the place of the left-out turns, right after the session context
+                let mut messages = plan.messages;
+                let first_turn = messages.iter().take_while(|message| message.role == Role::System).count();
+                messages.splice(first_turn..first_turn, [ChatMessage::user(SUMMARY_REQUEST), ChatMessage::assistant(summary)]);
+                let note = format!("Summarized {} earlier turns to fit {}'s {}-token context window.", turns, model, window);
+                return (messages, Some(note));
+            }
+            Err(e) => failure = Some(e),
+        }
+    }
+    let note = match failure {
+        Some(e) => format!(
+            "Could not summarize {} earlier turns ({}), so they were left out to fit {}'s {}-token context window.", turns, e, model, window
+        ),
+        None => format!("Left out {} earlier turns to fit {}'s {}-token context window (/context shows what is sent).", turns, model, window),
+    };
+    (plan.messages, Some(note))
+}
+
+// `message` as a transcript entry of at most `limit` tokens, keeping the start of longer text.
+fn bounded(message: &ChatMessage, limit: u64) -> ChatMessage {
+    let estimated = tokens::estimate(&message.content);
+    if estimated <= limit {
+        return message.clone();
+    }
+    let chars = message.content.chars().count() as u64 * limit / estimated;
+    let mut content: String = message.content.chars().take(chars as usize).collect();
+    content.push_str(" […cut]");
+    ChatMessage { content, ..message.clone() }
+}
+
+// Splits `turns` into runs of at most `limit` tokens; a message longer than that is cut to fit.
+fn chunks(turns: &[ChatMessage], limit: u64) -> Vec<Vec<ChatMessage>> {
+    let mut chunks: Vec<Vec<ChatMessage>> = Vec::new();
+    let mut size = 0;
+    for message in turns.iter().map(|message| bounded(message, limit)) {
+        let tokens = tokens::estimate(&message.content);
+        match chunks.last_mut() {
+            Some(chunk) if size + tokens <= limit => chunk.push(message),
+            _ => {
+                chunks.push(vec![message]);
+                size = 0;
+            }
+        }
+        size += tokens;
+    }
+    chunks
+}
+
+// Asks the model for a summary of `turns`, in parts that fit `budget`: each request carries one
+// part and the summary of the parts before it.
+async fn summarize(state: &AppState, provider_name: &str, model: &str, turns: &[ChatMessage], budget: u64) -> ReplResult<String> {
+    let provider = state.get_provider_by_name(provider_name).ok_or_else(|| ReplError::UnknownProvider(provider_name.to_string()))?;
+    let params = state.get_generation_params().await;
+    let mut summary: Option<String> = None;
+    for chunk in chunks(turns, (budget / SUMMARY_CHUNK_SHARE).max(1)) {
+        let text = match &summary {
+            Some(earlier) => format!("Summary of the conversation before this part:\n{}\n\n{}", earlier, transcript(&chunk)),
+            None => transcript(&chunk),
+        };
+        let request = [ChatMessage::system(SUMMARY_INSTRUCTIONS), ChatMessage::user(text)];
+        let written = provider.chat(model, &request, &params).await?;
+        summary = Some(written.trim().to_string()).filter(|written| !written.is_empty());
+        if summary.is_none() {
+            return Err(ReplError::Provider("the model returned an empty summary".to_string()));
+        }
+    }
+    summary.ok_or_else(|| ReplError::Provider("there was nothing to summarize".to_string()))
+}
@@ -0,0 +1,100 @@
+// src/dashboard.rs
+//! Usage reporting for the server: `GET /usage` (JSON) and `GET /dashboard` (an HTML page) show
+//! query volumes, tokens (reported by the provider, or estimated) and costs per provider and
+//! model, by day; `GET /usage` also includes the running session's usage.
+use crate::{
+    state::AppState,
+    usage::{self, build_report, SessionReport, UsageReport},
+};
+use axum::{
+    extract::{Query, State},
+    response::{Html, Json as AxumJson},
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_DAYS: i64 = 30;
+
+#[derive(Deserialize)] struct UsageQuery { days: Option<i64> }
+
+#[derive(Serialize)]
+struct UsageResponse {
+    #[serde(flatten)]
+    report: UsageReport,
+    session: SessionReport,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn render_html(report: &UsageReport) -> String {
+    let money = |cost: Option<f64>| cost.map_or_else(|| "&ndash;".to_string(), |cost| format!("{:.4}", cost));
+    let mut html = String::from(r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>llm-repl usage</title>
+<style>
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #2e3440; background: #eceff4; }
+h1 { margin-bottom: 0.2rem; } .note { color: #4c566a; margin-top: 0; }
+.cards { display: flex; gap: 1rem; flex-wrap: wrap; margin: 1.5rem 0; }
+.card { background: #fff; border-radius: 8px; padding: 1rem 1.4rem; min-width: 9rem; }
+.card b { display: block; font-size: 1.6rem; }
+table { border-collapse: collapse; background: #fff; margin-bottom: 2rem; }
+th, td { padding: 0.4rem 0.8rem; text-align: right; border-bottom: 1px solid #d8dee9; }
+th:first-child, td:first-child, .text { text-align: left; }
+.bar { background: #88c0d0; height: 0.8rem; border-radius: 3px; }
+</style></head><body>
+<h1>Provider usage</h1>
+"#);
+    html.push_str(&format!(
+        "<p class=\"note\">Last {} days. Tokens are estimated from text length; costs use the <code>[pricing]</code> config. \
+         <a href=\"?days=7\">7 days</a> &middot; <a href=\"?days=30\">30 days</a> &middot; <a href=\"?days=365\">1 year</a></p>\n",
+        report.days
+    ));
+    html.push_str(&format!(
+        "<div class=\"cards\"><div class=\"card\"><b>{}</b>queries</div><div class=\"card\"><b>{}</b>errors</div>\
+         <div class=\"card\"><b>{}</b>prompt tokens</div><div class=\"card\"><b>{}</b>completion tokens</div>\
+         <div class=\"card\"><b>{:.2}</b>cost</div></div>\n",
+        report.totals.queries, report.totals.errors, report.totals.prompt_tokens, report.totals.completion_tokens, report.cost
+    ));
+
+    html.push_str("<h2>By provider and model</h2>\n<table><tr><th>Provider</th><th class=\"text\">Model</th><th>Queries</th><th>Errors</th>\
+                   <th>Avg latency</th><th>Prompt tokens</th><th>Completion tokens</th><th>Cost</th></tr>\n");
+    for model in &report.models {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td class=\"text\">{}</td><td>{}</td><td>{}</td><td>{} ms</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape(&model.provider), escape(&model.model), model.totals.queries, model.totals.errors,
+            model.totals.average_latency_ms(), model.totals.prompt_tokens, model.totals.completion_tokens, money(model.cost)
+        ));
+    }
+    html.push_str("</table>\n");
+
+    let busiest = report.daily.iter().map(|day| day.totals.queries).max().unwrap_or(0).max(1);
+    html.push_str("<h2>By day</h2>\n<table><tr><th>Day</th><th class=\"text\">Queries</th><th></th><th>Tokens</th><th>Cost</th></tr>\n");
+    for day in report.daily.iter().rev() {
+        let width = day.totals.queries * 300 / busiest;
+        html.push_str(&format!(
+            "<tr><td>{}</td><td class=\"text\"><div class=\"bar\" style=\"width:{}px\"></div></td><td>{}</td><td>{}</td><td>{:.4}</td></tr>\n",
+            day.day, width, day.totals.queries, day.totals.prompt_tokens + day.totals.completion_tokens, day.cost
+        ));
+    }
+    html.push_str("</table>\n</body></html>\n");
+    html
+}
+
+async fn get_usage(State(state): State<AppState>, Query(query): Query<UsageQuery>) -> AxumJson<UsageResponse> {
+    let config = state.get_config().await;
+    let report = build_report(&usage::snapshot(), &config, query.days.unwrap_or(DEFAULT_DAYS));
+    AxumJson(UsageResponse { report, session: state.session_usage().await })
+}
+
+async fn get_dashboard(State(state): State<AppState>, Query(query): Query<UsageQuery>) -> Html<String> {
+    let config = state.get_config().await;
+    Html(render_html(&build_report(&usage::snapshot(), &config, query.days.unwrap_or(DEFAULT_DAYS))))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/usage", get(get_usage))
+        .route("/dashboard", get(get_dashboard))
+}
@@ -0,0 +1,380 @@
+// src/data.rs
+//! Tabular datasets loaded with `/data load` from CSV or JSON, summarized locally and referenced
+//! in prompts as `@data:<name>`.
+use crate::error::{ReplError, ReplResult};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Rows shown to the model (and by /data show) as a sample of the data.
+pub const SAMPLE_ROWS: usize = 5;
+// Most frequent values listed for text columns.
+const TOP_VALUES: usize = 3;
+// Cells longer than this are shortened in sample tables.
+const MAX_CELL_CHARS: usize = 60;
+
+/// The type inferred for a column from its non-empty values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Integer,
+    Number,
+    Boolean,
+    Text,
+    Json,
+    Mixed,
+    Empty,
+}
+
+impl fmt::Display for ColumnKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColumnKind::Integer => "integer",
+            ColumnKind::Number => "number",
+            ColumnKind::Boolean => "boolean",
+            ColumnKind::Text => "text",
+            ColumnKind::Json => "json",
+            ColumnKind::Mixed => "mixed",
+            ColumnKind::Empty => "empty",
+        };
+        f.write_str(name)
+    }
+}
+
+fn value_kind(value: &Value) -> Option<ColumnKind> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some(ColumnKind::Boolean),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some(ColumnKind::Integer),
+        Value::Number(_) => Some(ColumnKind::Number),
+        Value::String(_) => Some(ColumnKind::Text),
+        Value::Array(_) | Value::Object(_) => Some(ColumnKind::Json),
+    }
+}
+
+fn merge_kinds(a: ColumnKind, b: ColumnKind) -> ColumnKind {
+    match (a, b) {
+        (ColumnKind::Empty, kind) | (kind, ColumnKind::Empty) => kind,
+        (a, b) if a == b => a,
+        (ColumnKind::Integer, ColumnKind::Number) | (ColumnKind::Number, ColumnKind::Integer) => ColumnKind::Number,
+        _ => ColumnKind::Mixed,
+    }
+}
+
+// Text as shown in tables and counted for distinct values: strings unquoted, null empty.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Aggregate statistics of one column, computed locally over every row.
+#[derive(Debug, Clone)]
+pub struct ColumnStats {
+    pub name: String,
+    pub kind: ColumnKind,
+    pub count: usize,
+    pub nulls: usize,
+    pub distinct: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub sum: Option<f64>,
+    pub mean: Option<f64>,
+    pub top: Vec<(String, usize)>,
+}
+
+impl ColumnStats {
+    /// e.g. "price (number): 120 values, 3 empty, 87 distinct; min 1.5, max 99, mean 20.25, sum 2430".
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "{} ({}): {} values, {} empty, {} distinct",
+            self.name, self.kind, self.count, self.nulls, self.distinct
+        );
+        if let (Some(min), Some(max), Some(mean), Some(sum)) = (self.min, self.max, self.mean, self.sum) {
+            text.push_str(&format!("; min {}, max {}, mean {}, sum {}", format_number(min), format_number(max), format_number(mean), format_number(sum)));
+        }
+        if !self.top.is_empty() {
+            let top = self.top.iter().map(|(value, n)| format!("\"{}\" x{}", value, n)).collect::<Vec<_>>();
+            text.push_str(&format!("; most common {}", top.join(", ")));
+        }
+        text
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{:.4}", n).trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// A loaded table: column names with inferred types, and every row.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub name: String,
+    pub source: PathBuf,
+    pub columns: Vec<String>,
+    pub kinds: Vec<ColumnKind>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Dataset {
+    /// Loads a CSV/TSV file, a JSON array of objects, or JSON Lines (`.jsonl`/`.ndjson`).
+    pub fn load(name: &str, path: &Path) -> ReplResult<Self> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+        let (columns, rows) = match extension.as_str() {
+            "csv" => read_csv(path, b',')?,
+            "tsv" => read_csv(path, b'\t')?,
+            "json" => read_json(path)?,
+            "jsonl" | "ndjson" => read_json_lines(path)?,
+            _ => return Err(ReplError::Command(format!(
+                "Unsupported data file '{}'. Use .csv, .tsv, .json, .jsonl or .ndjson.", path.display()
+            ))),
+        };
+        if columns.is_empty() {
+            return Err(ReplError::Command(format!("'{}' has no columns.", path.display())));
+        }
+        let kinds = (0..columns.len())
+            .map(|i| rows.iter().filter_map(|row| value_kind(&row[i])).fold(ColumnKind::Empty, merge_kinds))
+            .collect();
+        Ok(Self { name: name.to_string(), source: path.to_path_buf(), columns, kinds, rows })
+    }
+
+    fn column_index(&self, column: &str) -> ReplResult<usize> {
+        self.columns.iter().position(|c| c == column)
+            .or_else(|| self.columns.iter().position(|c| c.eq_ignore_ascii_case(column)))
+            .ok_or_else(|| ReplError::Command(format!(
+                "Dataset '{}' has no column '{}'. Columns: {}", self.name, column, self.columns.join(", ")
+            )))
+    }
+
+    fn stats_for(&self, index: usize) -> ColumnStats {
+        let values: Vec<&Value> = self.rows.iter().map(|row| &row[index]).filter(|v| !v.is_null()).collect();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for value in &values {
+            *counts.entry(cell_text(value)).or_default() += 1;
+        }
+        let kind = self.kinds[index];
+        let numbers: Vec<f64> = values.iter().filter_map(|v| v.as_f64()).collect();
+        let numeric = matches!(kind, ColumnKind::Integer | ColumnKind::Number) && !numbers.is_empty();
+        let sum: f64 = numbers.iter().sum();
+        let top = if matches!(kind, ColumnKind::Text | ColumnKind::Boolean | ColumnKind::Mixed) {
+            let mut top: Vec<(String, usize)> = counts.iter().map(|(v, n)| (v.clone(), *n)).collect();
+            top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            top.truncate(TOP_VALUES);
+            top
+        } else {
+            Vec::new()
+        };
+        ColumnStats {
+            name: self.columns[index].clone(),
+            kind,
+            count: values.len(),
+            nulls: self.rows.len() - values.len(),
+            distinct: counts.len(),
+            min: numeric.then(|| numbers.iter().copied().fold(f64::INFINITY, f64::min)),
+            max: numeric.then(|| numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+            sum: numeric.then_some(sum),
+            mean: numeric.then(|| sum / numbers.len() as f64),
+            top,
+        }
+    }
+
+    /// Statistics for one column, or for every column when `column` is None.
+    pub fn stats(&self, column: Option<&str>) -> ReplResult<Vec<ColumnStats>> {
+        match column {
+            Some(column) => Ok(vec![self.stats_for(self.column_index(column)?)]),
+            None => Ok((0..self.columns.len()).map(|i| self.stats_for(i)).collect()),
+        }
+    }
+
+    /// Per-group row counts and, for a numeric `value_column`, sums and means: e.g. revenue by region.
+    pub fn group_stats(&self, group_column: &str, value_column: Option<&str>) -> ReplResult<String> {
+        let group = self.column_index(group_column)?;
+        let value = value_column.map(|c| self.column_index(c)).transpose()?;
+        let mut groups: HashMap<String, (usize, f64, usize)> = HashMap::new();
+        for row in &self.rows {
+            let entry = groups.entry(cell_text(&row[group])).or_default();
+            entry.0 += 1;
+            if let Some(n) = value.and_then(|i| row[i].as_f64()) {
+                entry.1 += n;
+                entry.2 += 1;
+            }
+        }
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by(|a, b| b.1.0.cmp(&a.1.0).then_with(|| a.0.cmp(&b.0)));
+        let mut lines = Vec::with_capacity(groups.len() + 1);
+        match value {
+            Some(i) => {
+                lines.push(format!("{} by {} ({} groups):", self.columns[i], self.columns[group], groups.len()));
+                for (key, (rows, sum, numbers)) in &groups {
+                    let mean = if *numbers > 0 { format_number(sum / *numbers as f64) } else { "-".to_string() };
+                    lines.push(format!("  {:<24} {} rows, sum {}, mean {}", display_key(key), rows, format_number(*sum), mean));
+                }
+            }
+            None => {
+                lines.push(format!("Rows by {} ({} groups):", self.columns[group], groups.len()));
+                for (key, (rows, _, _)) in &groups {
+                    lines.push(format!("  {:<24} {} rows", display_key(key), rows));
+                }
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// A markdown table of the first `limit` rows.
+    pub fn sample_table(&self, limit: usize) -> String {
+        let escape = |text: String| {
+            let text = text.replace('|', "\\|").replace('\n', " ");
+            if text.chars().count() > MAX_CELL_CHARS {
+                format!("{}...", text.chars().take(MAX_CELL_CHARS).collect::<String>())
+            } else {
+                text
+            }
+        };
+        let mut table = format!("| {} |\n|{}\n", self.columns.iter().map(|c| escape(c.clone())).collect::<Vec<_>>().join(" | "), "---|".repeat(self.columns.len()));
+        for row in self.rows.iter().take(limit) {
+            table.push_str(&format!("| {} |\n", row.iter().map(|v| escape(cell_text(v))).collect::<Vec<_>>().join(" | ")));
+        }
+        table
+    }
+
+    /// One-line summary, e.g. "sales (sales.csv): 1200 rows x 5 columns".
+    pub fn describe(&self) -> String {
+        let file = self.source.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        format!("{} ({}): {} rows x {} columns", self.name, file, self.rows.len(), self.columns.len())
+    }
+
+    /// What a prompt sees for `@data:<name>`: the schema with locally computed statistics and
+    /// sample rows, so the model can answer aggregate questions without inventing numbers.
+    pub fn prompt_context(&self) -> ReplResult<String> {
+        let stats = self.stats(None)?;
+        let columns = stats.iter().map(|s| format!("- {}", s.describe())).collect::<Vec<_>>().join("\n");
+        Ok(format!(
+            "Dataset {}.\nColumn statistics (computed over all rows; use these instead of estimating):\n{}\n\nFirst {} rows:\n{}",
+            self.describe(), columns, self.rows.len().min(SAMPLE_ROWS), self.sample_table(SAMPLE_ROWS).trim_end()
+        ))
+    }
+}
+
+fn display_key(key: &str) -> &str {
+    if key.is_empty() { "(empty)" } else { key }
+}
+
+fn read_error(path: &Path, e: &dyn fmt::Display) -> ReplError {
+    ReplError::Command(format!("Could not read '{}': {}", path.display(), e))
+}
+
+// CSV cells are all text; numbers and booleans are recognized so columns get useful types.
+fn parse_cell(cell: &str) -> Value {
+    let cell = cell.trim();
+    if cell.is_empty() {
+        Value::Null
+    } else if let Ok(n) = cell.parse::<i64>() {
+        Value::from(n)
+    } else if let Some(n) = cell.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+        Value::Number(n)
+    } else if cell.eq_ignore_ascii_case("true") || cell.eq_ignore_ascii_case("false") {
+        Value::Bool(cell.eq_ignore_ascii_case("true"))
+    } else {
+        Value::String(cell.to_string())
+    }
+}
+
+fn read_csv(path: &Path, delimiter: u8) -> ReplResult<(Vec<String>, Vec<Vec<Value>>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| read_error(path, &e))?;
+    let columns: Vec<String> = reader.headers().map_err(|e| read_error(path, &e))?.iter().map(|h| h.trim().to_string()).collect();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| read_error(path, &e))?;
+        // Short rows are padded and extra cells dropped, so every row matches the header
+        rows.push((0..columns.len()).map(|i| record.get(i).map(parse_cell).unwrap_or(Value::Null)).collect());
+    }
+    Ok((columns, rows))
+}
+
+// Columns are the union of the objects' keys, in order of first appearance.
+fn rows_from_objects(objects: Vec<Map<String, Value>>) -> (Vec<String>, Vec<Vec<Value>>) {
+    let mut columns = Vec::new();
+    let mut seen = HashSet::new();
+    for key in objects.iter().flat_map(|o| o.keys()) {
+        if seen.insert(key.clone()) {
+            columns.push(key.clone());
+        }
+    }
+    let rows = objects.into_iter()
+        .map(|mut object| columns.iter().map(|c| object.remove(c).unwrap_or(Value::Null)).collect())
+        .collect();
+    (columns, rows)
+}
+
+fn into_object(value: Value, path: &Path) -> ReplResult<Map<String, Value>> {
+    match value {
+        Value::Object(object) => Ok(object),
+        other => Err(ReplError::Command(format!(
+            "'{}' must contain objects (one per row), found: {}", path.display(), other
+        ))),
+    }
+}
+
+fn read_json(path: &Path) -> ReplResult<(Vec<String>, Vec<Vec<Value>>)> {
+    let text = std::fs::read_to_string(path).map_err(|e| read_error(path, &e))?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| read_error(path, &e))?;
+    let items = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+    let objects = items.into_iter().map(|item| into_object(item, path)).collect::<ReplResult<Vec<_>>>()?;
+    Ok(rows_from_objects(objects))
+}
+
+fn read_json_lines(path: &Path) -> ReplResult<(Vec<String>, Vec<Vec<Value>>)> {
+    let text = std::fs::read_to_string(path).map_err(|e| read_error(path, &e))?;
+    let objects = text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| read_error(path, &e)).and_then(|v| into_object(v, path)))
+        .collect::<ReplResult<Vec<_>>>()?;
+    Ok(rows_from_objects(objects))
+}
+
+/// Expands `@data:<name>` references in `prompt`: the reference becomes the dataset name. Returns
+/// the prompt, the datasets' context to append below it, and the names of the datasets included.
+/// The context is kept apart so `@file` references are expanded in the user's text only, never
+/// in the data.
+pub fn expand_dataset_references(prompt: &str, datasets: &[Arc<Dataset>]) -> ReplResult<(String, String, Vec<String>)> {
+    let mut expanded = String::with_capacity(prompt.len());
+    let mut attachments = String::new();
+    let mut included: Vec<String> = Vec::new();
+    let mut rest = prompt;
+    while !rest.is_empty() {
+        let word_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(word_len);
+        match word.strip_prefix("@data:") {
+            Some(reference) => {
+                let name = reference.trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'));
+                let dataset = datasets.iter().find(|d| d.name == name).ok_or_else(|| ReplError::Command(format!(
+                    "No dataset named '{}'. Load one with /data load <file> [name].", name
+                )))?;
+                if !included.contains(&dataset.name) {
+                    attachments.push_str(&format!("\n\n--- dataset {} ---\n{}\n--- end of dataset {} ---", name, dataset.prompt_context()?, name));
+                    included.push(dataset.name.clone());
+                }
+                expanded.push_str(&format!("the dataset '{}'", name));
+                expanded.push_str(&reference[name.len()..]);
+            }
+            None => expanded.push_str(word),
+        }
+        let space_len = after.find(|c: char| !c.is_whitespace()).unwrap_or(after.len());
+        expanded.push_str(&after[..space_len]);
+        rest = &after[space_len..];
+    }
+    Ok((expanded, attachments, included))
+}
@@ -0,0 +1,175 @@
+// src/documents.rs
+//! Text extraction from files referenced in prompts as `@path` (plain text, PDF, DOCX), with
+//! optional PDF page ranges: `@report.pdf#3-7`, `@report.pdf#5`, `@report.pdf#10-`.
+use crate::error::{ReplError, ReplResult};
+use crate::limits::InputLimits;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// Trailing characters that end a sentence rather than a path, e.g. "summarize @notes.md."
+const TRAILING_PUNCTUATION: &[char] = &[',', '.', ';', ':', '!', '?', ')', ']', '"', '\''];
+
+/// An inclusive, 1-based page range; `end` None means the last page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl PageRange {
+    /// Parses `5`, `3-7` or `10-`.
+    pub fn parse(spec: &str) -> ReplResult<Self> {
+        let invalid = || ReplError::Command(format!("Invalid page range '{}'. Use e.g. #5, #3-7 or #10-.", spec));
+        let parse_page = |page: &str| page.trim().parse::<usize>().ok().filter(|p| *p > 0).ok_or_else(invalid);
+        let range = match spec.split_once('-') {
+            None => { let page = parse_page(spec)?; Self { start: page, end: Some(page) } }
+            Some((start, "")) => Self { start: parse_page(start)?, end: None },
+            Some((start, end)) => Self { start: parse_page(start)?, end: Some(parse_page(end)?) },
+        };
+        match range.end {
+            Some(end) if end < range.start => Err(invalid()),
+            _ => Ok(range),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.end {
+            Some(end) if end == self.start => format!("page {}", self.start),
+            Some(end) => format!("pages {}-{}", self.start, end),
+            None => format!("pages {}-end", self.start),
+        }
+    }
+}
+
+/// A file whose text was added to a prompt.
+#[derive(Debug, Clone)]
+pub struct IncludedFile {
+    pub name: String,
+    pub pages: Option<PageRange>,
+    pub chars: usize,
+    pub truncated: bool,
+}
+
+impl IncludedFile {
+    /// e.g. "report.pdf (pages 3-7, 12345 characters)".
+    pub fn describe(&self) -> String {
+        let pages = self.pages.map(|p| format!("{}, ", p.describe())).unwrap_or_default();
+        let truncated = if self.truncated { ", truncated" } else { "" };
+        format!("{} ({}{} characters{})", self.name, pages, self.chars, truncated)
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase()
+}
+
+fn extract_pdf(path: &Path, pages: Option<PageRange>) -> ReplResult<String> {
+    let page_texts = pdf_extract::extract_text_by_pages(path)
+        .map_err(|e| ReplError::Command(format!("Could not read PDF '{}': {}", path.display(), e)))?;
+    let Some(range) = pages else {
+        return Ok(page_texts.join("\n"));
+    };
+    let end = range.end.unwrap_or(page_texts.len()).min(page_texts.len());
+    if range.start > end {
+        return Err(ReplError::Command(format!(
+            "'{}' has {} pages; {} is out of range.", path.display(), page_texts.len(), range.describe()
+        )));
+    }
+    Ok(page_texts[range.start - 1..end].join("\n"))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// Reads the paragraphs of `word/document.xml`: text runs (`<w:t>`), tabs, breaks and paragraph ends.
+fn extract_docx(path: &Path) -> ReplResult<String> {
+    let read_error = |e: &dyn std::fmt::Display| ReplError::Command(format!("Could not read DOCX '{}': {}", path.display(), e));
+    let mut archive = zip::ZipArchive::new(File::open(path)?).map_err(|e| read_error(&e))?;
+    let mut xml = String::new();
+    archive.by_name("word/document.xml").map_err(|e| read_error(&e))?.read_to_string(&mut xml)?;
+
+    let mut text = String::new();
+    let mut rest = xml.as_str();
+    while let Some(tag_start) = rest.find('<') {
+        let Some(tag_len) = rest[tag_start..].find('>') else { break };
+        let tag = &rest[tag_start + 1..tag_start + tag_len];
+        rest = &rest[tag_start + tag_len + 1..];
+        let name = tag.split_whitespace().next().unwrap_or_default().trim_end_matches('/');
+        match name {
+            "w:t" if !tag.ends_with('/') => {
+                let run_end = rest.find("</w:t>").unwrap_or(rest.len());
+                text.push_str(&unescape_xml(&rest[..run_end]));
+                rest = &rest[run_end..];
+            }
+            "w:tab" => text.push('\t'),
+            "w:br" | "/w:p" => text.push('\n'),
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+/// Extracts the text of a file: PDFs (optionally a page range) and DOCX documents are converted,
+/// anything else is read as UTF-8 text.
+pub fn extract_text(path: &Path, pages: Option<PageRange>) -> ReplResult<String> {
+    match extension(path).as_str() {
+        "pdf" => extract_pdf(path, pages),
+        _ if pages.is_some() => Err(ReplError::Command(format!("Page ranges only apply to PDFs, not '{}'.", path.display()))),
+        "docx" => extract_docx(path),
+        _ => std::fs::read_to_string(path)
+            .map_err(|e| ReplError::Command(format!("Could not read '{}' as text: {}", path.display(), e))),
+    }
+}
+
+// Splits `@path#pages` into an existing file path and its page range. Trailing punctuation is
+// dropped if the path only exists without it. Returns None if no such file exists.
+fn parse_reference(token: &str) -> Option<(&str, Option<&str>, &str)> {
+    let reference = token.strip_prefix('@')?;
+    let candidates = [reference, reference.trim_end_matches(TRAILING_PUNCTUATION)];
+    candidates.into_iter().find_map(|candidate| {
+        let trailing = &reference[candidate.len()..];
+        if Path::new(candidate).is_file() {
+            return Some((candidate, None, trailing));
+        }
+        let (path, pages) = candidate.rsplit_once('#')?;
+        Path::new(path).is_file().then_some((path, Some(pages), trailing))
+    })
+}
+
+/// Replaces `@path` references to existing files in `prompt` with the file name and appends each
+/// file's text below the prompt, capped by `limits`. Words starting with `@` that aren't files are left as typed.
+pub fn expand_file_references(prompt: &str, limits: &InputLimits) -> ReplResult<(String, Vec<IncludedFile>)> {
+    let mut expanded = String::with_capacity(prompt.len());
+    let mut attachments = String::new();
+    let mut included = Vec::new();
+    let mut rest = prompt;
+    while !rest.is_empty() {
+        let word_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(word_len);
+        match parse_reference(word) {
+            Some((path, pages, trailing)) => {
+                let pages = pages.map(PageRange::parse).transpose()?;
+                let text = extract_text(Path::new(path), pages)?;
+                let name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path).to_string();
+                let truncated = limits.exceeds(&text);
+                let text = limits.cap(&text, &name);
+                attachments.push_str(&format!("\n\n--- {} ---\n{}\n--- end of {} ---", name, text.trim(), name));
+                expanded.push_str(&name);
+                expanded.push_str(trailing);
+                included.push(IncludedFile { name, pages, chars: text.chars().count(), truncated });
+            }
+            None => expanded.push_str(word),
+        }
+        let space_len = after.find(|c: char| !c.is_whitespace()).unwrap_or(after.len());
+        expanded.push_str(&after[..space_len]);
+        rest = &after[space_len..];
+    }
+    expanded.push_str(&attachments);
+    Ok((expanded, included))
+}
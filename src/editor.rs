@@ -0,0 +1,150 @@
+// src/editor.rs
+//! Rustyline helper for the REPL prompt: greyed-out hints for command names, command arguments,
+//! and earlier prompts, and live coloring of input syntax, as the user types.
+use colored::Colorize;
+use rustyline::{
+    completion::Completer,
+    highlight::Highlighter,
+    hint::{Hint, Hinter},
+    history::{DefaultHistory, SearchDirection},
+    validate::Validator,
+    Context, Editor, Helper,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::render::ThemePalette;
+
+/// The REPL's line editor.
+pub type ReplEditor = Editor<ReplHelper, DefaultHistory>;
+
+/// A hint shown after the cursor. Only hints that continue what was typed can be accepted
+/// (with Right/End); usage hints like `<file> <prompt>` are for reading.
+pub struct ReplHint {
+    display: String,
+    completes: bool,
+}
+
+impl Hint for ReplHint {
+    fn display(&self) -> &str {
+        &self.display
+    }
+
+    fn completion(&self) -> Option<&str> {
+        self.completes.then_some(self.display.as_str())
+    }
+}
+
+pub struct ReplHelper {
+    commands: Vec<String>,
+    usages: HashMap<String, String>, // Argument hints by command name
+    enabled: bool,
+    palette: Option<ThemePalette>,
+}
+
+impl ReplHelper {
+    /// `commands` are the names (without `/`) offered as hints; `usages` their arguments (e.g. `[name]`).
+    pub fn new(mut commands: Vec<String>, usages: HashMap<String, String>) -> Self {
+        // Shorter names first, so `/md` is hinted before `/md_status`
+        commands.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        Self { commands, usages, enabled: true, palette: None }
+    }
+
+    /// Sets the colors for highlighting, and turns hints and highlighting off when `enabled` is
+    /// false (accessible mode, where screen readers would read hints out as typed text).
+    pub fn configure(&mut self, enabled: bool, palette: ThemePalette) {
+        self.enabled = enabled;
+        self.palette = Some(palette);
+    }
+
+    // Colors the syntax the REPL understands: the `/command` (known or not), a `!` shell command,
+    // and in prompts, `@...` references.
+    fn highlight_line(&self, line: &str, palette: &ThemePalette) -> String {
+        let paint = |text: &str, (r, g, b): (u8, u8, u8)| text.truecolor(r, g, b).to_string();
+        if let Some(typed) = line.strip_prefix('/') {
+            let name_len = typed.find(char::is_whitespace).unwrap_or(typed.len());
+            let known = self.commands.iter().any(|name| name == &typed[..name_len]);
+            let color = if known { palette.success } else { palette.error };
+            return format!("{}{}", paint(&line[..=name_len], color), &typed[name_len..]);
+        }
+        if line.starts_with('!') {
+            let end = line.find(char::is_whitespace).unwrap_or(line.len());
+            return format!("{}{}", paint(&line[..end], palette.info), &line[end..]);
+        }
+        let mut highlighted = String::with_capacity(line.len());
+        let mut rest = line;
+        while !rest.is_empty() {
+            let word_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let (word, after) = rest.split_at(word_len);
+            if word.len() > 1 && word.starts_with('@') {
+                highlighted.push_str(&paint(word, palette.prompt_provider));
+            } else {
+                highlighted.push_str(word);
+            }
+            let space_len = after.find(|c: char| !c.is_whitespace()).unwrap_or(after.len());
+            highlighted.push_str(&after[..space_len]);
+            rest = &after[space_len..];
+        }
+        highlighted
+    }
+
+    fn command_hint(&self, typed: &str) -> Option<ReplHint> {
+        match typed.split_once(' ') {
+            // `/na` -> `me`: the rest of the first command starting with what was typed
+            None => self.commands.iter()
+                .find(|name| name.len() > typed.len() && name.starts_with(typed))
+                .map(|name| ReplHint { display: name[typed.len()..].to_string(), completes: true }),
+            // `/name ` -> its arguments, until the user starts typing them
+            Some((name, "")) => self.usages.get(name).map(|usage| ReplHint { display: usage.clone(), completes: false }),
+            Some(_) => None,
+        }
+    }
+
+    // The most recent earlier line that starts with what was typed, as rustyline's HistoryHinter does.
+    fn history_hint(&self, line: &str, ctx: &Context<'_>) -> Option<ReplHint> {
+        let history = ctx.history();
+        let start = if ctx.history_index() == history.len() { ctx.history_index().saturating_sub(1) } else { ctx.history_index() };
+        let found = history.starts_with(line, start, SearchDirection::Reverse).ok().flatten()?;
+        (found.entry.len() > line.len()).then(|| ReplHint { display: found.entry[line.len()..].to_string(), completes: true })
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = ReplHint;
+
+    fn hint(&self, line: &str, pos: usize, ctx: &Context<'_>) -> Option<ReplHint> {
+        if !self.enabled || line.is_empty() || pos < line.len() {
+            return None;
+        }
+        match line.strip_prefix('/') {
+            Some(typed) => self.command_hint(typed).or_else(|| self.history_hint(line, ctx)),
+            None => self.history_hint(line, ctx),
+        }
+    }
+}
+
+impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        match &self.palette {
+            Some(palette) if self.enabled => Cow::Owned(self.highlight_line(line, palette)),
+            _ => Cow::Borrowed(line),
+        }
+    }
+
+    // Re-highlight on every keystroke, since typing can turn a word into a command or reference
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        self.enabled && self.palette.is_some()
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dimmed().to_string())
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
@@ -24,14 +24,30 @@ pub enum ReplError {
     #[error("Unknown command: {0}")]
     UnknownCommand(String),
 
+    #[error("Command '/{0}' is disabled for this caller")]
+    CommandDisabled(String),
+
     #[error("Unknown provider: {0}")]
     UnknownProvider(String),
 
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
     // #[error("Argument parsing error: {0}")] // Keep if clap is used
     // ArgumentParsing(#[from] clap::error::Error),
 
     #[error("Readline error: {0}")]
     Readline(String), // Store as String to avoid lifetime issues with ReadlineError directly
+
+    #[error("Bridge error: {0}")]
+    Bridge(String),
+}
+
+impl ReplError {
+    /// Network failures (dropped connection, timeout, truncated body) that may succeed on retry.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ReplError::Request(e) if e.is_connect() || e.is_timeout() || e.is_body() || e.is_decode())
+    }
 }
 
 // --- From Implementations ---
@@ -0,0 +1,88 @@
+// src/exit_hooks.rs
+//! `[on_exit]` actions, run when the REPL quits after the input loop ends: save the session
+//! history as JSON, print what the session used, and run a shell command (e.g. to sync transcripts).
+use crate::{
+    error::{ReplError, ReplResult},
+    shell::execute_shell_command,
+    state::{AppState, HistoryEntry},
+    usage::SessionReport,
+};
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub fn duration(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Queries, tokens and cost since the session started, with a line per model if several answered.
+pub fn summary(report: &SessionReport) -> String {
+    let elapsed = duration(Duration::from_secs(report.duration_secs));
+    if report.models.is_empty() {
+        return format!("Session: {}, no queries.", elapsed);
+    }
+    let totals = &report.totals;
+    let about = if totals.reported == totals.queries { "" } else { "about " };
+    let mut output = format!(
+        "Session: {}, {} queries ({} failed), {}{} prompt and {} completion tokens",
+        elapsed, totals.queries, totals.errors, about, totals.prompt_tokens, totals.completion_tokens
+    );
+    output.push_str(&report.cost.map(|cost| format!(", estimated cost {:.4}.", cost)).unwrap_or_else(|| ".".to_string()));
+    if report.models.len() > 1 {
+        for used in &report.models {
+            output.push_str(&format!("\n  {}:{}: {} queries, {} tokens", used.provider, used.model, used.totals.queries, used.totals.prompt_tokens + used.totals.completion_tokens));
+        }
+    }
+    output
+}
+
+/// Writes the session history to `dir` as `session-<date>-<time>.json`. Returns the file's path.
+pub fn save_session(dir: &Path, history: &[HistoryEntry]) -> ReplResult<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("session-{}.json", Local::now().format("%Y%m%d-%H%M%S")));
+    fs::write(&path, serde_json::to_string_pretty(history)?)?;
+    Ok(path)
+}
+
+/// Runs the configured actions and returns a message or error for each one that ran, in order.
+/// A failing action doesn't stop the ones after it.
+pub async fn run(state: &AppState) -> Vec<ReplResult<String>> {
+    let config = state.get_config().await;
+    let hooks = &config.on_exit;
+    let mut outcomes = Vec::new();
+
+    let mut session_file = None;
+    if let Some(dir) = &hooks.save_session {
+        let history = state.get_history().await;
+        if !history.is_empty() {
+            match save_session(dir, &history) {
+                Ok(path) => {
+                    outcomes.push(Ok(format!("Session saved to {}.", path.display())));
+                    session_file = Some(path);
+                }
+                Err(e) => outcomes.push(Err(ReplError::Command(format!("Could not save the session to '{}': {}", dir.display(), e)))),
+            }
+        }
+    }
+
+    if hooks.summary {
+        outcomes.push(Ok(summary(&state.session_usage().await)));
+    }
+
+    if let Some(command) = hooks.command.as_deref().filter(|command| !command.trim().is_empty()) {
+        let file = session_file.as_deref().map(|path| path.display().to_string()).unwrap_or_default();
+        let command = command.replace("{session_file}", &file);
+        match execute_shell_command(&command) {
+            Ok(output) if output.trim().is_empty() => {}
+            Ok(output) => outcomes.push(Ok(output.trim_end().to_string())),
+            Err(e) => outcomes.push(Err(ReplError::Command(format!("on_exit command failed: {}", e)))),
+        }
+    }
+    outcomes
+}
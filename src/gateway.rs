@@ -0,0 +1,164 @@
+// src/gateway.rs
+//! OpenAI-compatible `/v1/*` endpoints that route `provider/model` names to registered providers.
+use crate::{
+    cancel::Cancellable,
+    error::ReplError,
+    providers::{GenerationParams, LlmProvider},
+    request_id,
+    state::{AppState, HistoryContentType, HistoryEntry},
+};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{sse::{Event, KeepAlive, Sse}, IntoResponse, Json as AxumJson, Response},
+    routing::{get, post},
+    Router,
+};
+use futures::channel::mpsc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+// --- OpenAI Request/Response Structs ---
+#[derive(Deserialize)] struct ChatCompletionRequest { model: String, messages: Vec<ChatMessage>, #[serde(default)] stream: bool, #[serde(flatten)] sampling: GenerationParams }
+#[derive(Deserialize)] struct ChatMessage { role: String, content: Value }
+#[derive(Serialize)] struct ModelObject { id: String, object: &'static str, created: u64, owned_by: String }
+#[derive(Serialize)] struct ModelList { object: &'static str, data: Vec<ModelObject> }
+
+// --- OpenAI-Style Error Handling ---
+struct GatewayError { status: StatusCode, message: String }
+impl GatewayError {
+    fn bad_request(message: String) -> Self { Self { status: StatusCode::BAD_REQUEST, message } }
+}
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        let error_type = if self.status.is_client_error() { "invalid_request_error" } else { "api_error" };
+        (self.status, AxumJson(json!({ "error": { "message": self.message, "type": error_type } }))).into_response()
+    }
+}
+impl From<ReplError> for GatewayError {
+    fn from(err: ReplError) -> Self {
+        let status = match &err {
+            ReplError::UnknownProvider(_) => StatusCode::NOT_FOUND,
+            ReplError::Provider(msg) if msg.contains("API key is missing") => StatusCode::UNAUTHORIZED,
+            ReplError::Provider(_) | ReplError::Command(_) | ReplError::Json(_) => StatusCode::BAD_REQUEST,
+            ReplError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        Self { status, message: err.to_string() }
+    }
+}
+
+// --- Helpers ---
+fn unix_now() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) }
+fn completion_id() -> String { format!("chatcmpl-{}", SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0)) }
+
+/// Resolves `provider/model` (e.g. `groq/llama-3.1-70b`) to a ready provider and model id.
+/// A name without a provider prefix goes to the current provider.
+async fn resolve_model(state: &AppState, name: &str) -> Result<(Box<dyn LlmProvider>, String), GatewayError> {
+    let (provider_name, model) = match name.split_once('/') {
+        Some((p, m)) if state.list_providers().iter().any(|known| known == p) => (p.to_string(), m.to_string()),
+        _ => (state.get_provider_name().await, name.to_string()),
+    };
+    if model.is_empty() { return Err(GatewayError::bad_request(format!("Model name '{}' is missing a model id.", name))); }
+    let provider = state.get_provider_by_name(&provider_name).ok_or_else(|| ReplError::UnknownProvider(provider_name.clone()))?;
+    provider.check_readiness().await?;
+    Ok((provider, model))
+}
+
+// Message content is either a plain string or an array of typed parts; keep the text parts.
+fn message_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts.iter().filter_map(|p| p.get("text").and_then(Value::as_str)).collect::<Vec<_>>().join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Flattens chat messages into a single prompt, since providers take one prompt string.
+fn flatten_messages(messages: &[ChatMessage]) -> String {
+    match messages {
+        [only] if only.role == "user" => message_text(&only.content),
+        _ => messages.iter().map(|m| format!("{}: {}", m.role, message_text(&m.content))).collect::<Vec<_>>().join("\n\n"),
+    }
+}
+
+fn chunk_event(id: &str, model: &str, created: u64, content: Option<&str>, finish_reason: Option<&str>) -> Event {
+    let delta = match content { Some(text) => json!({ "content": text }), None => json!({}) };
+    let chunk = json!({ "id": id, "object": "chat.completion.chunk", "created": created, "model": model,
+        "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }] });
+    Event::default().data(chunk.to_string())
+}
+
+// --- Handlers ---
+async fn list_models(State(state): State<AppState>) -> AxumJson<ModelList> {
+    let mut data = Vec::new();
+    for provider_name in state.list_providers() {
+        let Some(provider) = state.get_provider_by_name(&provider_name) else { continue };
+        if state.check_provider_ready(&provider_name, provider.as_ref()).await.is_err() { continue; }
+        match state.provider_models(&provider_name, provider.as_ref()).await {
+            Ok(models) => data.extend(models.into_iter().map(|m| ModelObject { id: format!("{}/{}", provider_name, m.id), object: "model", created: 0, owned_by: m.owner.unwrap_or_else(|| provider_name.clone()) })),
+            Err(e) => warn!("Gateway: could not list models for '{}': {}", provider_name, e),
+        }
+    }
+    AxumJson(ModelList { object: "list", data })
+}
+
+async fn chat_completions(State(state): State<AppState>, AxumJson(payload): AxumJson<ChatCompletionRequest>) -> Result<Response, GatewayError> {
+    if payload.messages.is_empty() { return Err(GatewayError::bad_request("'messages' must not be empty.".to_string())); }
+    let (provider, model) = resolve_model(&state, &payload.model).await?;
+    let prompt = flatten_messages(&payload.messages);
+    // Sampling fields in the request win over the session's /set values
+    let params = payload.sampling.or(state.get_generation_params().await);
+    let id = completion_id();
+    let created = unix_now();
+
+    let started = Instant::now();
+    if !payload.stream {
+        let content = provider.query(&model, &prompt, &params).await?;
+        state.add_history_entry(HistoryEntry::new(HistoryContentType::LlmResponse { model: model.clone() }, content.clone()).with_model(provider.get_name(), &model).with_duration(started.elapsed())).await;
+        let body = json!({ "id": id, "object": "chat.completion", "created": created, "model": payload.model,
+            "choices": [{ "index": 0, "message": { "role": "assistant", "content": content }, "finish_reason": "stop" }],
+            "usage": { "prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0 } });
+        return Ok(AxumJson(body).into_response());
+    }
+
+    // Streaming: forward provider chunks through a channel so the full reply can be recorded once done.
+    // If the client disconnects, sending fails and the provider stream is dropped (cancelling upstream);
+    // `POST /query/{id}/cancel` with the X-Request-Id drops it the same way, ending the reply early.
+    let (tx, rx) = mpsc::unbounded::<Result<Event, Infallible>>();
+    let model_label = payload.model.clone();
+    tokio::spawn(request_id::inherit(async move {
+        let mut cancellable = Cancellable::register(&request_id::current().unwrap_or_default());
+        let mut full_response = String::new();
+        match provider.query_stream(&model, &prompt, &params).await {
+            Ok(Some(mut stream)) => {
+                while let Some(chunk_result) = cancellable.next(&mut stream).await {
+                    let event = match chunk_result {
+                        Ok(chunk) => { full_response.push_str(&chunk); chunk_event(&id, &model_label, created, Some(&chunk), None) }
+                        Err(e) => Event::default().data(json!({ "error": { "message": e.to_string(), "type": "api_error" } }).to_string()),
+                    };
+                    if tx.unbounded_send(Ok(event)).is_err() { return; }
+                }
+            }
+            Ok(None) | Err(_) => match provider.query(&model, &prompt, &params).await {
+                Ok(content) => { full_response = content; let _ = tx.unbounded_send(Ok(chunk_event(&id, &model_label, created, Some(&full_response), None))); }
+                Err(e) => { let _ = tx.unbounded_send(Ok(Event::default().data(json!({ "error": { "message": e.to_string(), "type": "api_error" } }).to_string()))); }
+            },
+        }
+        let _ = tx.unbounded_send(Ok(chunk_event(&id, &model_label, created, None, Some("stop"))));
+        let _ = tx.unbounded_send(Ok(Event::default().data("[DONE]")));
+        let entry = HistoryEntry::new(HistoryContentType::LlmResponse { model: model.clone() }, full_response).with_model(provider.get_name(), &model).with_cancelled(cancellable.is_cancelled());
+        state.add_history_entry(entry.with_duration(started.elapsed())).await;
+    }));
+    Ok(Sse::new(rx).keep_alive(KeepAlive::default()).into_response())
+}
+
+/// Routes for the OpenAI-compatible gateway, merged into the REST server with `--gateway`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/v1/models", get(list_models))
+        .route("/v1/chat/completions", post(chat_completions))
+}
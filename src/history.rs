@@ -0,0 +1,132 @@
+// src/history.rs
+//! Retention for the session history and the saved prompt file: drops the oldest entries
+//! until the `[history]` limits (entries, age, bytes) are met, and keeps ignored input out.
+use crate::{
+    config::HistoryRetention,
+    error::ReplResult,
+    state::{HistoryContentType, HistoryEntry},
+};
+use chrono::{Duration as ChronoDuration, Utc};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+// Index of the first item to keep so that at most `max_entries` items and `max_bytes` bytes
+// remain, counting from the newest item backwards.
+fn first_kept(sizes: &[usize], retention: &HistoryRetention) -> usize {
+    let mut start = sizes.len().saturating_sub(retention.max_entries.unwrap_or(usize::MAX));
+    if let Some(max_bytes) = retention.max_bytes {
+        let mut total = 0u64;
+        let mut kept_from = sizes.len();
+        for (index, size) in sizes.iter().enumerate().rev() {
+            total += *size as u64;
+            if total > max_bytes { break; }
+            kept_from = index;
+        }
+        start = start.max(kept_from);
+    }
+    start
+}
+
+// Prompt text compared when looking for repeats: case, whitespace and trailing punctuation
+// don't count, so "What is Rust?" matches "what is rust".
+fn normalize_prompt(prompt: &str) -> String {
+    prompt.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(|c: char| c.is_ascii_punctuation())
+        .to_lowercase()
+}
+
+/// Finds the newest LLM answer from `provider`/`model` to the same (normalized) prompt,
+/// recorded within `window`.
+pub fn find_cached_answer<'a>(entries: &'a [HistoryEntry], prompt: &str, provider: &str, model: &str, window: Duration) -> Option<&'a HistoryEntry> {
+    let wanted = normalize_prompt(prompt);
+    let cutoff = ChronoDuration::from_std(window).ok().and_then(|window| Utc::now().checked_sub_signed(window));
+    entries.iter().rev()
+        .take_while(|entry| cutoff.is_none_or(|cutoff| entry.timestamp >= cutoff))
+        .filter(|entry| matches!(entry.entry_type, HistoryContentType::LlmResponse { .. }))
+        .find(|entry| {
+            entry.provider.as_deref() == Some(provider)
+                && entry.model.as_deref() == Some(model)
+                && entry.prompt.as_deref().map(normalize_prompt).as_deref() == Some(wanted.as_str())
+        })
+}
+
+// Commands whose arguments can be secrets; they are never recorded.
+const SECRET_COMMANDS: [&str; 1] = ["/apikey"];
+
+/// `command_line` (a command without its `/`) as the session history records it: a secret
+/// command (`/apikey`) keeps only its name, so the key doesn't reach `GET /history`, webhooks,
+/// saved sessions or `/grep`.
+pub fn recorded_command(command_line: &str) -> String {
+    let command_line = command_line.trim();
+    let name = command_line.split_whitespace().next().unwrap_or_default();
+    if SECRET_COMMANDS.contains(&format!("/{}", name).as_str()) {
+        return name.to_string();
+    }
+    command_line.to_string()
+}
+
+/// Whether input stays out of both histories: it starts with a space (with `ignore_space`),
+/// matches one of the `ignore_patterns`, or sets a secret (`/apikey`). A pattern that isn't a
+/// valid regex matches as plain text.
+pub fn is_ignored(line: &str, retention: &HistoryRetention) -> bool {
+    if line.split_whitespace().next().is_some_and(|command| SECRET_COMMANDS.contains(&command)) {
+        return true;
+    }
+    if retention.ignore_space && line.starts_with(' ') {
+        return true;
+    }
+    retention.ignore_patterns.iter().any(|pattern| match Regex::new(pattern) {
+        Ok(re) => re.is_match(line),
+        Err(_) => line.contains(pattern.as_str()),
+    })
+}
+
+/// Whether `entry` repeats the newest entry (same kind, text and prompt), for `ignore_dups`.
+pub fn is_repeat(entries: &[HistoryEntry], entry: &HistoryEntry) -> bool {
+    entries.last().is_some_and(|last| {
+        last.entry_type == entry.entry_type && last.content == entry.content && last.prompt == entry.prompt
+    })
+}
+
+/// Removes session history entries beyond the retention limits. Returns how many were removed.
+pub fn prune_entries(entries: &mut Vec<HistoryEntry>, retention: &HistoryRetention) -> usize {
+    let before = entries.len();
+    if let Some(days) = retention.max_age_days {
+        let cutoff = Utc::now() - ChronoDuration::days(days as i64);
+        entries.retain(|entry| entry.timestamp >= cutoff);
+    }
+    let sizes: Vec<usize> = entries.iter().map(|entry| entry.content.len()).collect();
+    entries.drain(..first_kept(&sizes, retention));
+    before - entries.len()
+}
+
+/// Removes the oldest prompts from a rustyline history file beyond the entry and byte limits
+/// (its version header is kept). Returns how many prompts were removed; a missing file is empty.
+pub fn prune_prompt_file(path: &Path, retention: &HistoryRetention) -> ReplResult<usize> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let (header, prompts): (Vec<&str>, Vec<&str>) = content.lines().partition(|line| line.starts_with("#V"));
+    let sizes: Vec<usize> = prompts.iter().map(|line| line.len()).collect();
+    let start = first_kept(&sizes, retention);
+    if start == 0 {
+        return Ok(0);
+    }
+    let mut pruned = header.join("\n");
+    for line in &prompts[start..] {
+        if !pruned.is_empty() { pruned.push('\n'); }
+        pruned.push_str(line);
+    }
+    pruned.push('\n');
+    // Write beside the file and rename, so an interrupted prune can't truncate the history
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, pruned)?;
+    fs::rename(&temp_path, path)?;
+    Ok(start)
+}
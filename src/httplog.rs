@@ -0,0 +1,159 @@
+// src/httplog.rs
+//! Raw HTTP logging for provider calls (`/debug http on`). Requests and responses are appended to
+//! a debug file with API keys masked; response bodies are logged chunk by chunk as they arrive,
+//! so streamed responses keep streaming and the log shows exactly what the parser received.
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::Utc;
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use reqwest::{header::HeaderMap, Body, RequestBuilder, Response};
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use url::Url;
+
+use crate::request_id;
+
+pub const DEBUG_LOG_FILE: &str = "llm-repl-debug.log";
+const MASK: &str = "****";
+
+lazy_static! {
+    // The debug file while logging is on.
+    static ref HTTP_LOG: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+pub fn enable(path: PathBuf) {
+    *HTTP_LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(path);
+}
+
+pub fn disable() {
+    *HTTP_LOG.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// The debug file, if logging is on.
+pub fn log_path() -> Option<PathBuf> {
+    HTTP_LOG.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Whether a header, query parameter, JSON field or config setting with this name holds a secret.
+/// The debug log, `/http headers` and `/config` all mask by it.
+pub fn is_secret(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["authorization", "proxy-authorization", "cookie", "set-cookie"].contains(&name.as_str())
+        || ["token", "secret"].iter().any(|word| name.contains(word))
+        || ["key", "password"].iter().any(|suffix| name.ends_with(suffix))
+}
+
+// A request error names the URL it was for, which may carry a key (Gemini's `?key=`).
+fn mask_error(e: &reqwest::Error) -> String {
+    let text = e.to_string();
+    match e.url() {
+        Some(url) => text.replace(url.as_str(), &mask_url(url)),
+        None => text,
+    }
+}
+
+fn mask_headers(headers: &HeaderMap) -> String {
+    headers.iter()
+        .map(|(name, value)| {
+            let value = if is_secret(name.as_str()) { MASK.to_string() } else { String::from_utf8_lossy(value.as_bytes()).to_string() };
+            format!("  {}: {}\n", name, value)
+        })
+        .collect()
+}
+
+fn mask_url(url: &Url) -> String {
+    let mut masked = url.clone();
+    if url.query().is_some() {
+        let pairs: Vec<(String, String)> = url.query_pairs()
+            .map(|(name, value)| {
+                let value = if is_secret(&name) { MASK.to_string() } else { value.to_string() };
+                (name.to_string(), value)
+            })
+            .collect();
+        masked.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    masked.to_string()
+}
+
+fn mask_json(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if is_secret(name) && field.is_string() {
+                    *field = Value::String(MASK.to_string());
+                } else {
+                    mask_json(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(mask_json),
+        _ => {}
+    }
+}
+
+fn mask_body(body: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut json) => {
+            mask_json(&mut json);
+            serde_json::to_string_pretty(&json).unwrap_or_default()
+        }
+        Err(_) => String::from_utf8_lossy(body).to_string(),
+    }
+}
+
+// Logging must never break the call it describes, so write errors are ignored. Lines carry the
+// request ID, so `grep <id>` finds every call made for one query.
+fn write_entry(path: &Path, provider: &str, text: &str) {
+    let request = request_id::current().map(|id| format!(" [{}]", id)).unwrap_or_default();
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "[{}]{} {} {}", Utc::now().to_rfc3339(), request, provider, text);
+    }
+}
+
+#[async_trait]
+pub trait SendLogged {
+    /// Sends the request, logging it and its response to the debug file while logging is on.
+    async fn send_logged(self, provider: &str) -> reqwest::Result<Response>;
+}
+
+#[async_trait]
+impl SendLogged for RequestBuilder {
+    async fn send_logged(self, provider: &str) -> reqwest::Result<Response> {
+        let Some(path) = log_path() else { return self.send().await };
+        let (client, request) = self.build_split();
+        let request = request?;
+        let body = request.body().and_then(|body| body.as_bytes()).map(mask_body).unwrap_or_default();
+        write_entry(&path, provider, &format!(
+            "--> {} {}\n{}{}",
+            request.method(), mask_url(request.url()), mask_headers(request.headers()), body
+        ));
+        let response = match client.execute(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                write_entry(&path, provider, &format!("<-- failed: {}", mask_error(&e)));
+                return Err(e);
+            }
+        };
+        write_entry(&path, provider, &format!("<-- {}\n{}", response.status(), mask_headers(response.headers())));
+
+        // Rebuild the response around a body that logs each chunk on its way to the caller
+        let mut rebuilt = http::Response::builder().status(response.status()).version(response.version());
+        if let Some(headers) = rebuilt.headers_mut() {
+            *headers = response.headers().clone();
+        }
+        let chunk_provider = provider.to_string();
+        let chunks = response.bytes_stream().map(move |chunk: reqwest::Result<Bytes>| {
+            match &chunk {
+                Ok(bytes) => write_entry(&path, &chunk_provider, &format!("<-- chunk ({} bytes)\n{}", bytes.len(), String::from_utf8_lossy(bytes))),
+                Err(e) => write_entry(&path, &chunk_provider, &format!("<-- body error: {}", mask_error(e))),
+            }
+            chunk
+        });
+        let rebuilt = rebuilt.body(Body::wrap_stream(chunks)).expect("status and headers come from a valid response");
+        Ok(Response::from(rebuilt))
+    }
+}
@@ -0,0 +1,70 @@
+// src/i18n.rs
+//! User-facing REPL strings, looked up from the Fluent files in `locales/`.
+use crate::config::UiConfig;
+use fluent_templates::{fluent_bundle::FluentValue, static_loader, LanguageIdentifier, Loader};
+use std::collections::HashMap;
+use std::env;
+use std::sync::OnceLock;
+
+static_loader! {
+    static LOCALES = {
+        locales: "./locales",
+        fallback_language: "en-US",
+        // Unicode isolation marks around arguments show up as stray characters in terminals
+        customise: |bundle| bundle.set_use_isolating(false),
+    };
+}
+
+const DEFAULT_LOCALE: &str = "en-US";
+// Locales with a directory under `locales/`.
+const SUPPORTED_LOCALES: &[&str] = &["en-US", "de"];
+
+static LANGUAGE: OnceLock<LanguageIdentifier> = OnceLock::new();
+
+// Maps a requested locale (e.g. `de_DE.UTF-8`, `de-AT`, `en`) to a supported one by language.
+fn negotiate(requested: &str) -> Option<&'static str> {
+    let tag = requested.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+    let language = tag.split('-').next().unwrap_or_default().to_lowercase();
+    if language.is_empty() || language == "c" || language == "posix" {
+        return None;
+    }
+    SUPPORTED_LOCALES.iter()
+        .find(|supported| supported.eq_ignore_ascii_case(&tag))
+        .or_else(|| SUPPORTED_LOCALES.iter().find(|supported| supported.split('-').next() == Some(language.as_str())))
+        .copied()
+}
+
+/// Selects the UI language: the config's `ui.locale`, else `LC_ALL`/`LC_MESSAGES`/`LANG`,
+/// else English. Must be called before any string is looked up.
+pub fn init(config: &UiConfig) {
+    let requested = config.locale.clone()
+        .into_iter()
+        .chain(["LC_ALL", "LC_MESSAGES", "LANG"].iter().filter_map(|var| env::var(var).ok()));
+    let locale = requested.filter_map(|r| negotiate(&r)).next().unwrap_or(DEFAULT_LOCALE);
+    let _ = LANGUAGE.set(locale.parse().expect("supported locales are valid language ids"));
+}
+
+fn language() -> &'static LanguageIdentifier {
+    LANGUAGE.get_or_init(|| DEFAULT_LOCALE.parse().expect("default locale is a valid language id"))
+}
+
+/// Looks up a message by id.
+pub fn tr(id: &str) -> String {
+    LOCALES.lookup(language(), id)
+}
+
+fn fluent_args<'a>(args: &[(&str, &str)]) -> HashMap<String, FluentValue<'a>> {
+    args.iter()
+        .map(|(name, value)| (name.to_string(), FluentValue::from(value.to_string())))
+        .collect()
+}
+
+/// Looks up a message by id, filling its `{ $name }` placeables.
+pub fn tr_args(id: &str, args: &[(&str, &str)]) -> String {
+    LOCALES.lookup_with_args(language(), id, &fluent_args(args))
+}
+
+/// Like `tr_args`, but None if no locale has the message (e.g. for optional per-command text).
+pub fn try_tr_args(id: &str, args: &[(&str, &str)]) -> Option<String> {
+    LOCALES.try_lookup_with_args(language(), id, &fluent_args(args))
+}
@@ -0,0 +1,112 @@
+// src/icons.rs
+//! Icons for the prompt and history headers: Nerd Font glyphs (for terminals with a patched
+//! font), emoji, or plain ASCII (the default). Accessibility mode always uses ASCII, since screen
+//! readers spell out or skip the glyphs.
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+use crate::{config::{AppConfig, UiConfig}, state::HistoryContentType};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconStyle {
+    #[default]
+    Ascii,
+    Emoji,
+    #[serde(alias = "nerdfont", alias = "nerd-font")]
+    Nerd,
+}
+
+impl IconStyle {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "ascii" | "off" | "plain" => Some(Self::Ascii),
+            "emoji" => Some(Self::Emoji),
+            "nerd" | "nerdfont" | "nerd-font" => Some(Self::Nerd),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ascii => "ascii",
+            Self::Emoji => "emoji",
+            Self::Nerd => "nerd",
+        }
+    }
+}
+
+static ICON_STYLE: RwLock<IconStyle> = RwLock::new(IconStyle::Ascii);
+
+/// Uses the icon style from `[ui]`, or ASCII while accessibility mode is on.
+pub fn apply(ui: &UiConfig) {
+    let style = if ui.accessible { IconStyle::Ascii } else { ui.icons };
+    if let Ok(mut current) = ICON_STYLE.write() {
+        *current = style;
+    }
+}
+
+pub fn style() -> IconStyle {
+    ICON_STYLE.read().map(|style| *style).unwrap_or_default()
+}
+
+// (Nerd Font, emoji) per provider type.
+fn provider_glyphs(kind: &str) -> (&'static str, &'static str) {
+    match kind {
+        "ollama" => ("\u{f06a9}", "🦙"),          // nf-md-robot
+        "gemini" => ("\u{f1a0}", "✨"),           // nf-fa-google
+        "groq" => ("\u{f0e7}", "⚡"),             // nf-fa-bolt
+        "openai" => ("\u{f09d1}", "🧠"),          // nf-md-brain
+        "azure" => ("\u{f0805}", "☁"),           // nf-md-microsoft_azure
+        "lmstudio" => ("\u{f108}", "🖥"),         // nf-fa-desktop
+        "vllm" => ("\u{f233}", "🚀"),             // nf-fa-server
+        "perplexity" => ("\u{f002}", "🔎"),       // nf-fa-search
+        "xai" => ("\u{f00d}", "✖"),              // nf-fa-times
+        "group" => ("\u{f0e8}", "🔀"),            // nf-fa-sitemap
+        _ => ("\u{f1b2}", "🤖"),                  // nf-fa-cube
+    }
+}
+
+/// The icon for a provider, by its type (e.g. `ollama`) or name (`group:<name>` for groups),
+/// followed by a space; empty in ASCII style.
+pub fn provider(kind: &str) -> String {
+    let kind = if kind.starts_with("group:") { "group".to_string() } else { kind.to_lowercase() };
+    let (nerd, emoji) = provider_glyphs(&kind);
+    match style() {
+        IconStyle::Ascii => String::new(),
+        IconStyle::Emoji => format!("{} ", emoji),
+        IconStyle::Nerd => format!("{} ", nerd),
+    }
+}
+
+/// `provider`, with configured instances (`[providers.gpu-box] type = "ollama"`) shown by their type.
+pub fn provider_for(name: &str, config: &AppConfig) -> String {
+    let kind = config.providers.get(name).and_then(|settings| settings.kind.as_deref()).unwrap_or(name);
+    provider(kind)
+}
+
+/// The prompt's closing arrow.
+pub fn prompt_arrow() -> &'static str {
+    match style() {
+        IconStyle::Ascii => ">> ",
+        IconStyle::Emoji => "➜ ",
+        IconStyle::Nerd => "\u{f054} ", // nf-fa-chevron_right
+    }
+}
+
+/// A glyph for the kind of history entry, followed by a space; empty in ASCII style.
+pub fn entry(entry_type: &HistoryContentType) -> String {
+    let (nerd, emoji) = match entry_type {
+        HistoryContentType::LlmResponse { .. } => ("\u{f075}", "💬"), // nf-fa-comment
+        HistoryContentType::CommandResult { .. } => ("\u{f120}", "⚙"), // nf-fa-terminal
+        HistoryContentType::ShellOutput { .. } => ("\u{f489}", "🐚"), // nf-oct-terminal
+        HistoryContentType::UserQuery => ("\u{f007}", "👤"), // nf-fa-user
+        HistoryContentType::Error { .. } => ("\u{f057}", "❌"), // nf-fa-times_circle
+        HistoryContentType::Info => ("\u{f05a}", "ℹ"), // nf-fa-info_circle
+    };
+    match style() {
+        IconStyle::Ascii => String::new(),
+        IconStyle::Emoji => format!("{} ", emoji),
+        IconStyle::Nerd => format!("{} ", nerd),
+    }
+}
@@ -0,0 +1,210 @@
+// src/limits.rs
+use crate::error::ReplResult;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+
+/// User-configured limits applied to LLM output on the client side.
+/// These work for every provider, whether or not its API supports them natively.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputLimits {
+    pub stop_sequences: Vec<String>,
+    pub max_chars: Option<usize>,
+}
+
+/// Caps on shell output and file contents put into prompts (`[input_limits]`), so a stray
+/// `@big.log` doesn't fill the context window or the bill. Longer text keeps its head and tail
+/// around a notice of what was cut.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputLimits {
+    pub max_bytes: Option<usize>,
+    /// Estimated like the usage statistics, at about four characters per token.
+    pub max_tokens: Option<usize>,
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        Self { max_bytes: Some(200_000), max_tokens: None }
+    }
+}
+
+// Moves `index` back to a char boundary of `text`.
+fn floor_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+impl InputLimits {
+    /// The smaller of the two caps, in bytes.
+    pub fn budget(&self) -> Option<usize> {
+        let from_tokens = self.max_tokens.map(|tokens| tokens.saturating_mul(4));
+        match (self.max_bytes, from_tokens) {
+            (Some(bytes), Some(tokens)) => Some(bytes.min(tokens)),
+            (bytes, tokens) => bytes.or(tokens),
+        }
+    }
+
+    pub fn exceeds(&self, text: &str) -> bool {
+        self.budget().is_some_and(|budget| text.len() > budget)
+    }
+
+    /// Returns `text` within the budget: the first and last halves, cut at line breaks where
+    /// possible, around a line saying how much of `source` was left out.
+    pub fn cap(&self, text: &str, source: &str) -> String {
+        let Some(budget) = self.budget().filter(|budget| text.len() > *budget) else {
+            return text.to_string();
+        };
+        let half = budget / 2;
+        let mut head_end = floor_boundary(text, half);
+        // Prefer whole lines, unless that would drop most of the half
+        if let Some(newline) = text[..head_end].rfind('\n').filter(|newline| *newline >= half / 2) {
+            head_end = newline + 1;
+        }
+        let mut tail_start = floor_boundary(text, text.len() - half).max(head_end);
+        if let Some(newline) = text[tail_start..].find('\n').filter(|newline| *newline <= half / 2) {
+            tail_start += newline + 1;
+        }
+        let omitted = &text[head_end..tail_start];
+        format!(
+            "{}\n[... {} truncated: {} bytes (about {} tokens) omitted, {} lines; showing the first {} and last {} bytes ...]\n{}",
+            &text[..head_end],
+            source,
+            omitted.len(),
+            omitted.len().div_ceil(4),
+            omitted.lines().count(),
+            head_end,
+            text.len() - tail_start,
+            &text[tail_start..]
+        )
+    }
+}
+
+impl OutputLimits {
+    /// Returns true if any limit is configured.
+    pub fn is_active(&self) -> bool {
+        !self.stop_sequences.is_empty() || self.max_chars.is_some()
+    }
+
+    /// Applies the limits to a complete (non-streamed) response.
+    pub fn apply(&self, text: &str) -> String {
+        let mut enforcer = LimitEnforcer::new(self.clone());
+        let mut output = enforcer.push(text);
+        if !enforcer.finished {
+            output.push_str(&enforcer.flush());
+        }
+        output
+    }
+
+    // Byte offset of the earliest stop sequence in `text`, if any.
+    fn find_stop(&self, text: &str) -> Option<usize> {
+        self.stop_sequences
+            .iter()
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| text.find(s.as_str()))
+            .min()
+    }
+
+    // Bytes that must be held back so a stop sequence split across chunks is still detected.
+    fn holdback_len(&self) -> usize {
+        self.stop_sequences.iter().map(|s| s.len()).max().unwrap_or(0).saturating_sub(1)
+    }
+}
+
+// Incremental state for enforcing limits across stream chunks.
+struct LimitEnforcer {
+    limits: OutputLimits,
+    pending: String,
+    emitted_chars: usize,
+    finished: bool,
+}
+
+impl LimitEnforcer {
+    fn new(limits: OutputLimits) -> Self {
+        Self { limits, pending: String::new(), emitted_chars: 0, finished: false }
+    }
+
+    /// Feeds a chunk in and returns the text that is safe to emit now.
+    fn push(&mut self, chunk: &str) -> String {
+        self.pending.push_str(chunk);
+
+        if let Some(stop_idx) = self.limits.find_stop(&self.pending) {
+            self.pending.truncate(stop_idx);
+            self.finished = true;
+            let ready = std::mem::take(&mut self.pending);
+            return self.cap(ready);
+        }
+
+        let mut split = self.pending.len().saturating_sub(self.limits.holdback_len());
+        while !self.pending.is_char_boundary(split) {
+            split -= 1;
+        }
+        let ready: String = self.pending.drain(..split).collect();
+        self.cap(ready)
+    }
+
+    /// Returns whatever was held back once the underlying stream has ended.
+    fn flush(&mut self) -> String {
+        let rest = std::mem::take(&mut self.pending);
+        self.cap(rest)
+    }
+
+    // Truncates `text` so the total emitted length never exceeds `max_chars`.
+    fn cap(&mut self, text: String) -> String {
+        let max = match self.limits.max_chars {
+            Some(max) => max,
+            None => return text,
+        };
+        let remaining = max.saturating_sub(self.emitted_chars);
+        let truncated: String = text.chars().take(remaining).collect();
+        self.emitted_chars += truncated.chars().count();
+        if self.emitted_chars >= max {
+            self.finished = true;
+            self.pending.clear();
+        }
+        truncated
+    }
+}
+
+/// Wraps a provider stream so it ends at the first stop sequence or once `max_chars` is reached.
+/// Ending the stream drops the underlying response, which cancels the HTTP request.
+pub fn limit_stream(
+    stream: Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>,
+    limits: OutputLimits,
+) -> Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>> {
+    if !limits.is_active() {
+        return stream;
+    }
+
+    let limited = futures::stream::unfold(
+        (stream, LimitEnforcer::new(limits)),
+        |(mut stream, mut enforcer)| async move {
+            loop {
+                if enforcer.finished {
+                    return None; // Drops `stream`, cancelling the upstream request
+                }
+                match stream.next().await {
+                    Some(Ok(chunk)) => {
+                        let ready = enforcer.push(&chunk);
+                        if !ready.is_empty() {
+                            return Some((Ok(ready), (stream, enforcer)));
+                        }
+                    }
+                    Some(Err(e)) => return Some((Err(e), (stream, enforcer))),
+                    None => {
+                        enforcer.finished = true;
+                        let rest = enforcer.flush();
+                        if rest.is_empty() {
+                            return None;
+                        }
+                        return Some((Ok(rest), (stream, enforcer)));
+                    }
+                }
+            }
+        },
+    );
+
+    Box::pin(limited)
+}
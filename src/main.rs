@@ -1,15 +1,53 @@
 // src/main.rs
+mod admission;
+mod assistant;
+mod audit;
+mod bridge;
+mod cache;
+mod cancel;
 mod commands;
+mod config;
+mod context;
+mod dashboard;
+mod data;
+mod database;
+mod documents;
+mod editor;
 mod error;
+mod exit_hooks;
+mod gateway;
+mod history;
+mod httplog;
+mod i18n;
+mod icons;
+mod limits;
+mod notify;
+mod oneshot;
+mod ops;
+mod pacing;
+mod preferences;
+mod progress;
 mod providers;
+mod reload;
+mod request_id;
 mod repl;
 mod server; // <-- Add server module
+mod sessions;
 mod state;
 mod shell;
 mod render;
 mod signal;
+mod stdio;
+mod tee;
+mod terminal;
+mod tui;
+mod typeahead;
+mod usage;
+mod warmup;
+mod webhooks;
 
 use crate::{
+    commands::recall::PROMPT_HISTORY_FILE,
     error::ReplResult, // Use our result type
     repl::Repl,
     state::AppState, // Ensure AppState is imported
@@ -25,6 +63,45 @@ struct CliArgs {
     #[arg(long)]
     server: bool,
 
+    /// Run the server as an OpenAI-compatible gateway: adds `/v1/*` endpoints that accept
+    /// model names like `groq/llama-3.1-70b` or `ollama/phi3`. Implies --server.
+    #[arg(long)]
+    gateway: bool,
+
+    /// Serve editor plugins over stdin/stdout (JSON-RPC 2.0, one message per line) instead of
+    /// starting the REPL or the REST server.
+    #[arg(long, conflicts_with_all = ["server", "gateway"])]
+    stdio: bool,
+
+    /// Relay Slack or Discord channel messages to the current provider and post the answers back
+    /// (channels and token from `[bridge.slack]` / `[bridge.discord]`).
+    #[arg(long, value_enum, value_name = "PLATFORM", conflicts_with_all = ["server", "gateway", "stdio"])]
+    bridge: Option<bridge::BridgePlatform>,
+
+    /// Start the REPL in the full-screen chat view (the same as typing /tui).
+    #[arg(long, conflicts_with_all = ["server", "gateway", "stdio", "bridge"])]
+    tui: bool,
+
+    /// Send one prompt (`-` reads it from stdin) with the session context, print the answer and exit.
+    #[arg(long, short = 'p', value_name = "TEXT", conflicts_with_all = ["server", "gateway", "stdio", "bridge", "tui"])]
+    prompt: Option<String>,
+
+    /// Model for --prompt: `[provider:]model` or an alias. Defaults to the configured starting model.
+    #[arg(long, value_name = "MODEL", requires = "prompt")]
+    model: Option<String>,
+
+    /// How --prompt prints the answer: streamed text, or JSON with the provider, model and usage.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value = "text", requires = "prompt")]
+    output: oneshot::OutputFormat,
+
+    /// With --output json, print NDJSON events (start, token, done or error) as the answer streams.
+    #[arg(long, requires = "prompt")]
+    stream: bool,
+
+    /// Verify the hash chain of a server audit log and exit.
+    #[arg(long, value_name = "FILE")]
+    verify_audit: Option<std::path::PathBuf>,
+
     /// Host and port for the REST API server.
     #[arg(long, value_name = "HOST:PORT", default_value = "127.0.0.1:3000", env = "LLM_REPL_SERVER_ADDR")]
     addr: String,
@@ -35,26 +112,65 @@ struct CliArgs {
 async fn main() -> ReplResult<()> { // Return our result type
     let args = CliArgs::parse();
 
+    if let Some(path) = &args.verify_audit {
+        let (count, last_hash) = audit::verify_chain(path)?;
+        println!("Audit log '{}' is intact ({} records). Last record hash: {}", path.display(), count, last_hash);
+        return Ok(());
+    }
+
     // Register signal handlers (useful for both REPL and server)
     if let Err(e) = signal::register_signal_handlers() {
         eprintln!("WARN: Failed to register signal handlers: {}", e);
         // Decide if this is fatal? Probably not for now.
     }
+    // Put the terminal back (cursor, raw mode) before a panic message is printed
+    terminal::install_panic_hook();
 
     // Initialize shared state
     // AppState::new is sync, so we can call it here.
     // If it becomes async later, adjust accordingly.
     let app_state = AppState::new();
+    let ui_config = app_state.get_config().await.ui;
+    i18n::init(&ui_config);
+    render::apply_accessibility(ui_config.accessible);
+    render::set_color_overrides(&ui_config.colors);
+    icons::apply(&ui_config);
+    let retention = app_state.get_config().await.history;
+    match history::prune_prompt_file(std::path::Path::new(PROMPT_HISTORY_FILE), &retention) {
+        Ok(0) => {}
+        // In --stdio and --prompt modes stdout carries the output, so notes go to stderr
+        Ok(removed) if args.stdio || args.prompt.is_some() => eprintln!("Pruned {} old prompt(s) from {}.", removed, PROMPT_HISTORY_FILE),
+        Ok(removed) => println!("Pruned {} old prompt(s) from {}.", removed, PROMPT_HISTORY_FILE),
+        Err(e) => eprintln!("WARN: Failed to prune {}: {}", PROMPT_HISTORY_FILE, e),
+    }
+
+    if let Some(prompt) = &args.prompt {
+        return oneshot::run(app_state, prompt, args.model.as_deref(), args.output, args.stream).await;
+    }
+
+    if args.stdio {
+        tokio::spawn(reload::watch_config(app_state.clone()));
+        tokio::spawn(warmup::keep_warm(app_state.clone()));
+        return stdio::run_stdio(app_state).await;
+    }
+
+    if let Some(platform) = args.bridge {
+        tokio::spawn(reload::watch_config(app_state.clone()));
+        tokio::spawn(warmup::keep_warm(app_state.clone()));
+        return bridge::run_bridge(app_state, platform).await;
+    }
 
-    if args.server {
+    if args.server || args.gateway {
         // --- Run Server ---
         println!("Starting in server mode...");
+        tokio::spawn(reload::watch_config(app_state.clone()));
+        tokio::spawn(warmup::keep_warm(app_state.clone()));
         let socket_addr = SocketAddr::from_str(&args.addr).map_err(|e| {
             error::ReplError::Command(format!("Invalid server address '{}': {}", args.addr, e))
         })?;
 
         // Run the server - handle potential errors
-        if let Err(e) = server::run_server(app_state, socket_addr).await {
+        if let Err(e) = server::run_server(app_state, socket_addr, args.gateway).await {
             eprintln!("Server error: {}", e);
             // Convert Box<dyn Error> to ReplError if needed, or just exit
              return Err(error::ReplError::Command(format!("Server failed: {}", e))); // Example conversion
@@ -66,6 +182,9 @@ async fn main() -> ReplResult<()> { // Return our result type
         // Repl::new() is sync
         match Repl::new() {
             Ok(mut repl) => {
+                if args.tui {
+                    repl.start_in_tui();
+                }
                 // Repl::run is blocking in its current form (uses block_on internally)
                 // If run needs to be async later, adjust how it's called.
                  // For now, we wrap the potentially blocking call.
@@ -0,0 +1,27 @@
+// src/notify.rs
+use crate::config::NotificationConfig;
+use notify_rust::Notification;
+use std::time::Duration;
+
+// Longest body shown in the notification popup.
+const MAX_BODY_CHARS: usize = 120;
+
+/// Sends a desktop notification if `elapsed` exceeded the configured threshold.
+/// Runs on a separate thread so a slow notification daemon never blocks the REPL.
+pub fn notify_if_slow(config: &NotificationConfig, title: &str, body: &str, elapsed: Duration) {
+    if !config.enabled || elapsed < Duration::from_secs(config.threshold_secs) {
+        return;
+    }
+
+    let summary = format!("{} ({:.0}s)", title, elapsed.as_secs_f64());
+    let mut body_text: String = body.trim().chars().take(MAX_BODY_CHARS).collect();
+    if body.trim().chars().count() > MAX_BODY_CHARS {
+        body_text.push('…');
+    }
+
+    std::thread::spawn(move || {
+        if let Err(e) = Notification::new().appname("llm-repl").summary(&summary).body(&body_text).show() {
+            eprintln!("WARN: Failed to show desktop notification: {}", e);
+        }
+    });
+}
@@ -0,0 +1,125 @@
+// src/oneshot.rs
+//! One-shot mode (`--prompt`) for scripts: sends a single prompt with the session context (system
+//! prompt, pins, hooks, output limits), prints the answer and exits.
+//!
+//! `--output json` prints one object (`{"response", "provider", "model", "requestId", "usage"}`).
+//! With `--stream` it prints NDJSON events instead, one per line as the answer arrives:
+//! `{"type":"start", "provider", "model", "requestId"}`, then `{"type":"token", "text"}` for each
+//! chunk, and finally `{"type":"done", "usage", "latencyMs"}` or `{"type":"error", "message"}`.
+//! Token counts are estimates (about four characters each), as in the usage report.
+use crate::{
+    error::{ReplError, ReplResult},
+    limits::limit_stream,
+    providers::chat,
+    request_id,
+    state::AppState,
+    usage::estimate_tokens,
+};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+/// How `--prompt` prints the answer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// The answer as it streams.
+    #[default]
+    Text,
+    /// A JSON object, or NDJSON events with `--stream`.
+    Json,
+}
+
+fn print_line(value: &Value) {
+    let mut stdout = io::stdout().lock();
+    let _ = writeln!(stdout, "{}", value);
+    let _ = stdout.flush();
+}
+
+fn usage(prompt: &str, response: &str) -> Value {
+    json!({ "promptTokens": estimate_tokens(prompt), "completionTokens": estimate_tokens(response), "estimated": true })
+}
+
+/// Answers `prompt` (`-` reads it from stdin) with `model` (`[provider:]model` or an alias; the
+/// current model if None) and prints it in `format`.
+pub async fn run(state: AppState, prompt: &str, model: Option<&str>, format: OutputFormat, stream: bool) -> ReplResult<()> {
+    let prompt = if prompt == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        prompt.to_string()
+    };
+    if prompt.trim().is_empty() {
+        return Err(ReplError::Command("The prompt is empty.".to_string()));
+    }
+    let (provider_name, model) = match model {
+        Some(spec) => state.resolve_model_spec(spec).await,
+        None => (state.get_provider_name().await, state.get_model().await),
+    };
+    let events = format == OutputFormat::Json && stream;
+    let id = request_id::next();
+    let outcome = request_id::scope(id.clone(), answer(&state, &prompt, &provider_name, &model, format, events)).await;
+    if let Err(e) = &outcome {
+        match format {
+            OutputFormat::Json if events => print_line(&json!({ "type": "error", "message": e.to_string(), "requestId": id })),
+            OutputFormat::Json => print_line(&json!({ "error": e.to_string(), "provider": provider_name, "model": model, "requestId": id })),
+            OutputFormat::Text => {}
+        }
+    }
+    outcome
+}
+
+async fn answer(state: &AppState, prompt: &str, provider_name: &str, model: &str, format: OutputFormat, events: bool) -> ReplResult<()> {
+    let provider = state.get_provider_by_name(provider_name).ok_or_else(|| ReplError::UnknownProvider(provider_name.to_string()))?;
+    let messages = state.get_config().await.prompt_messages(prompt);
+    let full_prompt = chat::join(&messages);
+    let params = state.get_generation_params().await;
+    let limits = state.get_output_limits().await;
+    let started = Instant::now();
+    if events {
+        print_line(&json!({ "type": "start", "provider": provider_name, "model": model, "requestId": request_id::current() }));
+    }
+
+    // Text and NDJSON output show the answer as it arrives; a single JSON object waits for all of it
+    let streamed = if format == OutputFormat::Text || events { provider.chat_stream(model, &messages, &params).await? } else { None };
+    let response = match streamed {
+        Some(chunks) => {
+            let mut chunks = limit_stream(chunks, limits);
+            let mut response = String::new();
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk?;
+                if events {
+                    print_line(&json!({ "type": "token", "text": chunk }));
+                } else {
+                    let mut stdout = io::stdout().lock();
+                    let _ = write!(stdout, "{}", chunk);
+                    let _ = stdout.flush();
+                }
+                response.push_str(&chunk);
+            }
+            response
+        }
+        None => {
+            let response = limits.apply(&provider.chat(model, &messages, &params).await?);
+            match format {
+                OutputFormat::Json if events => print_line(&json!({ "type": "token", "text": response })),
+                OutputFormat::Json => {}
+                OutputFormat::Text => print!("{}", response),
+            }
+            response
+        }
+    };
+
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match format {
+        OutputFormat::Json if events => print_line(&json!({ "type": "done", "usage": usage(&full_prompt, &response), "latencyMs": latency_ms })),
+        OutputFormat::Json => print_line(&json!({
+            "response": response, "provider": provider_name, "model": model, "requestId": request_id::current(),
+            "usage": usage(&full_prompt, &response), "latencyMs": latency_ms,
+        })),
+        OutputFormat::Text if !response.ends_with('\n') => println!(),
+        OutputFormat::Text => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// src/ops.rs
+//! Allow-listed, read-only diagnostics (`kubectl`, `systemctl`, `journalctl`) run through the
+//! shell layer for the ops helpers.
+use crate::{
+    error::{ReplError, ReplResult},
+    shell::execute_shell_command,
+};
+
+// Output of a single command beyond this many characters is cut off before it goes to the model.
+const MAX_OUTPUT_CHARS: usize = 20_000;
+
+// Read-only subcommands per program. journalctl only reads, so any (checked) arguments are fine.
+const ALLOWED: &[(&str, &[&str])] = &[
+    ("kubectl", &["get", "describe", "logs", "explain", "top", "events", "version", "api-resources"]),
+    ("systemctl", &["status", "list-units", "list-timers", "is-active", "is-failed", "show"]),
+    ("journalctl", &[]),
+];
+
+// Arguments are joined into a shell command line, so anything a shell would interpret is refused.
+fn is_safe_arg(arg: &str) -> bool {
+    !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_.:/=,@+".contains(c))
+}
+
+/// Checks a command against the allow-list and returns it as a shell command line.
+pub fn allowed_command_line(program: &str, args: &[&str]) -> ReplResult<String> {
+    let (_, subcommands) = ALLOWED.iter().find(|(name, _)| *name == program)
+        .ok_or_else(|| ReplError::Command(format!("'{}' is not an allowed diagnostics program.", program)))?;
+    if !subcommands.is_empty() {
+        let subcommand = args.first().copied().unwrap_or_default();
+        if !subcommands.contains(&subcommand) {
+            return Err(ReplError::Command(format!(
+                "'{} {}' is not allowed; allowed: {}.", program, subcommand, subcommands.join(", ")
+            )));
+        }
+    }
+    if let Some(bad) = args.iter().find(|arg| !is_safe_arg(arg)) {
+        return Err(ReplError::Command(format!("Argument '{}' contains characters that are not allowed.", bad)));
+    }
+    Ok(std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" "))
+}
+
+/// The output of one diagnostics command; a failing command is reported rather than aborting
+/// the rest, since errors ("not found", "connection refused") are useful to the analysis too.
+pub struct Diagnostic {
+    pub command_line: String,
+    pub output: String,
+}
+
+/// Runs an allowed command and captures its output (or its error).
+pub fn run_diagnostic(program: &str, args: &[&str]) -> ReplResult<Diagnostic> {
+    let command_line = allowed_command_line(program, args)?;
+    // Keep stdout and stderr of failing commands too: `systemctl status` exits non-zero for a
+    // stopped unit but still prints the useful part.
+    let output = match execute_shell_command(&format!("{} 2>&1 || echo \"(exit status $?)\"", command_line)) {
+        Ok(output) if output.trim().is_empty() => "(no output)".to_string(),
+        Ok(output) => output,
+        Err(e) => e.to_string(),
+    };
+    let output = match output.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((cut, _)) => format!("{}\n... (output truncated)", &output[..cut]),
+        None => output,
+    };
+    Ok(Diagnostic { command_line, output })
+}
+
+/// Formats diagnostics for a prompt: each command line followed by its output.
+pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics.iter()
+        .map(|d| format!("$ {}\n{}", d.command_line, d.output.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Copies command arguments into owned strings.
+pub fn owned(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+/// A named diagnostics flow: the commands it runs for the given target.
+pub fn diagnostics_plan(target: &[&str]) -> ReplResult<Vec<(&'static str, Vec<String>)>> {
+    let plan = match target {
+        ["k8s", rest @ ..] if rest.len() <= 1 => {
+            let scope = match rest.first() {
+                Some(namespace) => owned(&["-n", namespace]),
+                None => owned(&["-A"]),
+            };
+            let scoped = |args: &[&str]| [owned(args), scope.clone()].concat();
+            vec![
+                ("kubectl", scoped(&["get", "pods", "-o", "wide"])),
+                ("kubectl", scoped(&["get", "events", "--sort-by=.lastTimestamp"])),
+                ("kubectl", scoped(&["get", "deployments"])),
+                ("kubectl", owned(&["get", "nodes"])),
+            ]
+        }
+        ["service", unit] => vec![
+            ("systemctl", owned(&["status", unit, "--no-pager"])),
+            ("journalctl", owned(&["-u", unit, "-n", "200", "--no-pager"])),
+        ],
+        ["system"] => vec![
+            ("systemctl", owned(&["list-units", "--failed", "--no-pager"])),
+            ("journalctl", owned(&["-p", "err", "-b", "-n", "100", "--no-pager"])),
+        ],
+        _ => return Err(ReplError::Command("Usage: /ops diagnose <k8s [namespace] | service <unit> | system>".to_string())),
+    };
+    Ok(plan)
+}
@@ -0,0 +1,46 @@
+// src/pacing.rs
+//! Typewriter pacing for streamed responses, so very fast providers don't dump a wall of text at once.
+use crate::error::ReplResult;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::time::Duration;
+
+// How often a paced piece is released.
+const TICK: Duration = Duration::from_millis(20);
+
+/// Re-chunks `stream` so that at most `chars_per_sec` characters are released per second.
+/// Text arriving slower than that passes through with no extra delay beyond one tick.
+pub fn pace_stream(
+    stream: Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>,
+    chars_per_sec: u32,
+) -> Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>> {
+    // Characters earned per tick; the fraction carries over, so slow rates are kept exactly
+    let chars_per_tick = chars_per_sec.max(1) as f64 * TICK.as_secs_f64();
+
+    let paced = futures::stream::unfold(
+        (stream, String::new(), 0.0),
+        move |(mut stream, mut pending, mut budget)| async move {
+            while pending.is_empty() {
+                match stream.next().await {
+                    Some(Ok(chunk)) => pending = chunk,
+                    Some(Err(e)) => return Some((Err(e), (stream, pending, budget))),
+                    None => return None,
+                }
+            }
+            loop {
+                tokio::time::sleep(TICK).await;
+                budget += chars_per_tick;
+                if budget >= 1.0 {
+                    break;
+                }
+            }
+            let allowed = budget as usize;
+            let split = pending.char_indices().nth(allowed).map(|(i, _)| i).unwrap_or(pending.len());
+            let piece: String = pending.drain(..split).collect();
+            // Whole characters left unused because the text ran out aren't saved up
+            budget = budget.fract();
+            Some((Ok(piece), (stream, pending, budget)))
+        },
+    );
+    Box::pin(paced)
+}
@@ -0,0 +1,104 @@
+// src/preferences.rs
+//! The preference log: which of two compared answers (`/diffmodels`) the user judged better,
+//! kept as JSONL for picking a default model or exporting as fine-tuning pairs.
+use crate::error::{ReplError, ReplResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+pub const PREFERENCE_LOG_FILE: &str = "preferences.jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candidate {
+    /// `provider:model`
+    pub model: String,
+    pub answer: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preference {
+    pub timestamp: DateTime<Utc>,
+    pub prompt: String,
+    pub candidates: Vec<Candidate>,
+    /// Index of the better candidate; None for a tie.
+    pub preferred: Option<usize>,
+}
+
+/// Per-model totals over the log.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelScore {
+    pub comparisons: usize,
+    pub wins: usize,
+    pub ties: usize,
+}
+
+impl ModelScore {
+    /// Wins count fully and ties half.
+    pub fn win_rate(&self) -> f64 {
+        if self.comparisons == 0 {
+            return 0.0;
+        }
+        (self.wins as f64 + self.ties as f64 / 2.0) / self.comparisons as f64
+    }
+}
+
+/// Appends a judgment to the log.
+pub fn record(path: &Path, preference: &Preference) -> ReplResult<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(preference)?)?;
+    Ok(())
+}
+
+/// Reads the log; a missing file is an empty log. Unreadable lines are skipped.
+pub fn load(path: &Path) -> ReplResult<Vec<Preference>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(path)?.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+/// Totals per model, ordered by model name.
+pub fn scores(preferences: &[Preference]) -> BTreeMap<String, ModelScore> {
+    let mut scores: BTreeMap<String, ModelScore> = BTreeMap::new();
+    for preference in preferences {
+        for (index, candidate) in preference.candidates.iter().enumerate() {
+            let score = scores.entry(candidate.model.clone()).or_default();
+            score.comparisons += 1;
+            match preference.preferred {
+                Some(preferred) if preferred == index => score.wins += 1,
+                None => score.ties += 1,
+                _ => {}
+            }
+        }
+    }
+    scores
+}
+
+/// Writes decided comparisons as `{"prompt", "chosen", "rejected"}` lines (the pairwise format
+/// used for preference fine-tuning, e.g. DPO), plus the models involved. Returns the pair count.
+pub fn export_pairs(preferences: &[Preference], path: &Path) -> ReplResult<usize> {
+    let mut lines = Vec::new();
+    for preference in preferences {
+        let Some(chosen) = preference.preferred.and_then(|index| preference.candidates.get(index)) else { continue };
+        for (index, rejected) in preference.candidates.iter().enumerate() {
+            if Some(index) == preference.preferred {
+                continue;
+            }
+            lines.push(serde_json::to_string(&serde_json::json!({
+                "prompt": preference.prompt,
+                "chosen": chosen.answer,
+                "rejected": rejected.answer,
+                "chosen_model": chosen.model,
+                "rejected_model": rejected.model,
+            }))?);
+        }
+    }
+    if lines.is_empty() {
+        return Err(ReplError::Command("No decided comparisons to export yet.".to_string()));
+    }
+    fs::write(path, format!("{}\n", lines.join("\n")))?;
+    Ok(lines.len())
+}
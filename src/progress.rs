@@ -0,0 +1,64 @@
+// src/progress.rs
+//! Renders `CommandEvent::Progress` updates from long-running commands as terminal progress bars.
+use crate::commands::{CommandEvent, ProgressUpdate};
+use crate::i18n::tr_args;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const BAR_TEMPLATE: &str = "{spinner} {msg} [{bar:40}] {pos}/{len} ({eta})";
+const SPINNER_TEMPLATE: &str = "{spinner} {msg} {pos}";
+
+// Creates the bar lazily on the first update and switches between bar and spinner
+// as updates with and without a known total arrive.
+fn apply_update(bar: &mut Option<ProgressBar>, update: ProgressUpdate) {
+    let bar = bar.get_or_insert_with(|| {
+        let new_bar = ProgressBar::new_spinner();
+        new_bar.enable_steady_tick(Duration::from_millis(100));
+        new_bar
+    });
+    let template = if update.total.is_some() { BAR_TEMPLATE } else { SPINNER_TEMPLATE };
+    if let Ok(style) = ProgressStyle::with_template(template) {
+        bar.set_style(style.progress_chars("=> "));
+    }
+    if let Some(total) = update.total {
+        bar.set_length(total);
+    }
+    bar.set_message(update.message);
+    bar.set_position(update.current);
+}
+
+// Accessibility mode: a plain line whenever the step changes or another quarter is done,
+// instead of a bar redrawn in place.
+fn announce_update(last: &mut Option<(String, u64)>, update: ProgressUpdate) {
+    let quarter = update.total.filter(|t| *t > 0).map(|t| update.current * 4 / t).unwrap_or(update.current);
+    if last.as_ref() == Some(&(update.message.clone(), quarter)) {
+        return;
+    }
+    let current = update.current.to_string();
+    let line = match update.total {
+        Some(total) => tr_args("a11y-progress", &[("message", &update.message), ("current", &current), ("total", &total.to_string())]),
+        None => tr_args("a11y-progress-open", &[("message", &update.message), ("current", &current)]),
+    };
+    println!("{}", line);
+    *last = Some((update.message, quarter));
+}
+
+/// Draws progress updates until the sending command finishes, then clears the bar.
+/// Output events are ignored: in the REPL, commands print their own output.
+pub async fn render_progress(mut events: mpsc::UnboundedReceiver<CommandEvent>, accessible: bool) {
+    let mut bar: Option<ProgressBar> = None;
+    let mut last_announced = None;
+    while let Some(event) = events.recv().await {
+        if let CommandEvent::Progress(update) = event {
+            if accessible {
+                announce_update(&mut last_announced, update);
+            } else {
+                apply_update(&mut bar, update);
+            }
+        }
+    }
+    if let Some(bar) = bar {
+        bar.finish_and_clear();
+    }
+}
@@ -0,0 +1,166 @@
+// src/providers/azure.rs
+//! Azure OpenAI: OpenAI's chat format, but requests go to a deployment
+//! (`{endpoint}/openai/deployments/{deployment}/chat/completions?api-version=...`) and
+//! authenticate with an `api-key` header. Deployments take the place of models.
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::env;
+use std::pin::Pin;
+use url::Url;
+
+use crate::config::ProviderSettings;
+use crate::error::{ReplError, ReplResult};
+use crate::httplog::SendLogged;
+use crate::providers::{groq::sse_text_stream, http_client, parse_base_url, ChatMessage, GenerationParams, LlmProvider, ModelInfo};
+
+const AZURE_DEFAULT_API_VERSION: &str = "2024-06-01";
+
+#[derive(Serialize, Debug)]
+struct AzureChatRequest<'a> {
+    messages: &'a [ChatMessage],
+    stream: bool,
+    #[serde(flatten)]
+    params: Map<String, Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AzureChatResponse {
+    choices: Vec<AzureChoice>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AzureChoice {
+    message: AzureResponseMessage,
+}
+
+#[derive(Deserialize, Debug)]
+struct AzureResponseMessage {
+    content: Option<String>, // null when the content filter stopped the answer
+}
+
+#[derive(Debug, Clone)]
+pub struct AzureOpenAiProvider {
+    client: Client,
+    endpoint: Option<Url>,
+    api_key: Option<String>,
+    api_version: String,
+    deployments: Vec<String>,
+}
+
+impl AzureOpenAiProvider {
+    /// Creates the provider from `[providers.azure]`, falling back to the AZURE_OPENAI_ENDPOINT,
+    /// AZURE_OPENAI_API_KEY, AZURE_OPENAI_API_VERSION and AZURE_OPENAI_DEPLOYMENTS (comma-separated)
+    /// environment variables. Without an endpoint or key it is registered but not ready.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Self> {
+        let from_env = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let endpoint = settings.base_url.clone().or_else(|| from_env("AZURE_OPENAI_ENDPOINT"));
+        let api_key = settings.api_key.clone().or_else(|| from_env("AZURE_OPENAI_API_KEY")).filter(|k| !k.is_empty());
+        if endpoint.is_some() != api_key.is_some() {
+            eprintln!("INFO: Azure OpenAI needs both an endpoint and an API key (AZURE_OPENAI_ENDPOINT/AZURE_OPENAI_API_KEY or [providers.azure]); it is unavailable until both are set.");
+        }
+        let deployments = if settings.deployments.is_empty() {
+            from_env("AZURE_OPENAI_DEPLOYMENTS")
+                .map(|list| list.split(',').map(str::trim).filter(|d| !d.is_empty()).map(String::from).collect())
+                .unwrap_or_default()
+        } else {
+            settings.deployments.clone()
+        };
+        Ok(Self {
+            client: http_client(settings)?,
+            endpoint: endpoint.map(|endpoint| parse_base_url("Azure OpenAI", &endpoint)).transpose()?,
+            api_key,
+            api_version: settings.api_version.clone()
+                .or_else(|| from_env("AZURE_OPENAI_API_VERSION"))
+                .unwrap_or_else(|| AZURE_DEFAULT_API_VERSION.to_string()),
+            deployments,
+        })
+    }
+
+    fn credentials(&self) -> ReplResult<(&Url, &str)> {
+        match (&self.endpoint, &self.api_key) {
+            (Some(endpoint), Some(api_key)) => Ok((endpoint, api_key)),
+            (None, _) => Err(ReplError::Provider("Azure OpenAI endpoint is missing. Set AZURE_OPENAI_ENDPOINT or [providers.azure] base_url.".to_string())),
+            (_, None) => Err(ReplError::Provider("Azure OpenAI API key is missing. Set AZURE_OPENAI_API_KEY or [providers.azure] api_key.".to_string())),
+        }
+    }
+
+    // POST to the deployment's chat completions endpoint.
+    fn chat_request(&self, deployment: &str, messages: &[ChatMessage], params: &GenerationParams, stream: bool) -> ReplResult<RequestBuilder> {
+        let (endpoint, api_key) = self.credentials()?;
+        let mut url = endpoint.join(&format!("openai/deployments/{}/chat/completions", deployment))
+            .map_err(|e| ReplError::Provider(format!("Failed to build Azure OpenAI URL: {}", e)))?;
+        url.query_pairs_mut().append_pair("api-version", &self.api_version);
+        let body = AzureChatRequest { messages, stream, params: params.openai_fields() };
+        Ok(self.client.post(url).header("api-key", api_key).json(&body))
+    }
+
+    async fn handle_api_error(response: Response) -> ReplError {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+        ReplError::Provider(format!("Azure OpenAI API error: {} - {}", status, body))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AzureOpenAiProvider {
+    fn get_name(&self) -> &str {
+        "azure"
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+
+    async fn check_readiness(&self) -> ReplResult<()> {
+        self.credentials()?;
+        Ok(())
+    }
+
+    /// The configured deployments; Azure's data-plane API has no endpoint that lists them.
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        if self.deployments.is_empty() {
+            return Err(ReplError::Provider("No Azure OpenAI deployments configured. Set AZURE_OPENAI_DEPLOYMENTS or [providers.azure] deployments.".to_string()));
+        }
+        Ok(self.deployments.iter().map(ModelInfo::new).collect())
+    }
+
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let response = self.chat_request(model, messages, params, false)?.send_logged("azure").await?;
+        if !response.status().is_success() {
+            return Err(Self::handle_api_error(response).await);
+        }
+        let completion = response.json::<AzureChatResponse>().await?;
+        completion.choices.into_iter().next()
+            .and_then(|choice| choice.message.content)
+            .ok_or_else(|| ReplError::Provider("Azure OpenAI returned no content (possibly filtered).".to_string()))
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let response = self.chat_request(model, messages, params, true)?.send_logged("azure").await?;
+        if !response.status().is_success() {
+            return Err(Self::handle_api_error(response).await);
+        }
+        Ok(Some(sse_text_stream(response)))
+    }
+}
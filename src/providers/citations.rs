@@ -0,0 +1,64 @@
+// src/providers/citations.rs
+//! The sources an answer is based on (Perplexity's citations, Gemini's grounding metadata).
+//! Providers end the answer with them as numbered footnotes, so every front end shows them; the
+//! history reads them back into the entry's `citations` metadata.
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+const FOOTNOTES_HEADING: &str = "\n\n**Sources**\n\n";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl Citation {
+    /// A source with an optional page title; titles are kept to one line without brackets so the
+    /// footnote link stays valid.
+    pub fn new(url: &str, title: Option<&str>) -> Self {
+        let title = title
+            .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" ").replace('[', "(").replace(']', ")"))
+            .filter(|title| !title.is_empty());
+        Self { url: url.trim().to_string(), title }
+    }
+
+    /// The link text: the title, or the host and path, which read better than the full URL.
+    pub fn label(&self) -> String {
+        self.title.clone().unwrap_or_else(|| url_label(&self.url))
+    }
+}
+
+fn url_label(url: &str) -> String {
+    Url::parse(url).ok()
+        .and_then(|parsed| parsed.host_str().map(|host| format!("{}{}", host.trim_start_matches("www."), parsed.path().trim_end_matches('/'))))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// The sources as numbered Markdown footnotes for the end of an answer, empty without sources.
+/// The numbers match the `[1]`, `[2]` markers providers put in the text.
+pub fn footnotes(citations: &[Citation]) -> String {
+    if citations.is_empty() {
+        return String::new();
+    }
+    let mut list = String::from(FOOTNOTES_HEADING);
+    for (number, citation) in citations.iter().enumerate() {
+        list.push_str(&format!("{}. [{}]({})\n", number + 1, citation.label(), citation.url));
+    }
+    list
+}
+
+/// The sources listed in an answer's footnotes (as written by `footnotes`), in order.
+pub fn from_footnotes(answer: &str) -> Vec<Citation> {
+    let Some(start) = answer.rfind(FOOTNOTES_HEADING) else { return Vec::new() };
+    answer[start + FOOTNOTES_HEADING.len()..].lines()
+        .map_while(|line| {
+            let (number, link) = line.split_once(". ")?;
+            number.parse::<usize>().ok()?;
+            let (label, url) = link.strip_prefix('[')?.strip_suffix(')')?.split_once("](")?;
+            let title = (label != url_label(url)).then_some(label);
+            Some(Citation::new(url, title))
+        })
+        .collect()
+}
@@ -4,17 +4,18 @@ use bytes::BytesMut;
 use futures::{Stream, StreamExt};
 use reqwest::{Client, Response};
 use serde::{de::DeserializeSeed, Deserialize, Serialize};
-use serde_json::StreamDeserializer;
+use serde_json::{json, StreamDeserializer, Value};
+use std::collections::BTreeMap;
 use std::env;
 use std::pin::Pin;
 use url::Url;
 
+use crate::config::ProviderSettings;
 use crate::error::{ReplError, ReplResult};
-use crate::providers::LlmProvider;
+use crate::httplog::SendLogged;
+use crate::providers::{chat::Role, citations::{footnotes, Citation}, http_client, parse_base_url, ChatMessage, GenerationParams, ImageAttachment, LlmProvider, Modalities, ModelInfo, TokenCount};
 
 // --- Gemini API Specific Structs ---
-#[derive(Serialize, Debug)]
-struct GeminiGenerateContentRequest { contents: Vec<Content> }
 
 // --- CORRECTED Content Struct ---
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -45,6 +46,28 @@ struct Candidate {
     finish_reason: Option<String>,
     safety_ratings: Option<Vec<SafetyRating>>,
     #[allow(dead_code)] token_count: Option<u32>,
+    grounding_metadata: Option<GroundingMetadata>, // Present when answered with the google_search tool
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GroundingMetadata { grounding_chunks: Option<Vec<GroundingChunk>> }
+
+#[derive(Deserialize, Debug, Clone)]
+struct GroundingChunk { web: Option<WebSource> }
+
+#[derive(Deserialize, Debug, Clone)]
+struct WebSource { uri: String, title: Option<String> }
+
+impl Candidate {
+    /// The web sources the answer was grounded in, if any.
+    fn citations(&self) -> Vec<Citation> {
+        self.grounding_metadata.iter()
+            .flat_map(|metadata| metadata.grounding_chunks.iter().flatten())
+            .filter_map(|chunk| chunk.web.as_ref())
+            .map(|web| Citation::new(&web.uri, web.title.as_deref()))
+            .collect()
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -53,7 +76,7 @@ struct SafetyRating { #[allow(dead_code)] category: String, probability: String,
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct GeminiModel { name: String, display_name: Option<String>, description: Option<String>, supported_generation_methods: Option<Vec<String>>, }
+struct GeminiModel { name: String, display_name: Option<String>, description: Option<String>, supported_generation_methods: Option<Vec<String>>, input_token_limit: Option<u64>, }
 
 #[derive(Deserialize, Debug)]
 struct GeminiModelList { models: Vec<GeminiModel> }
@@ -72,15 +95,28 @@ pub struct GeminiProvider {
     client: Client,
     api_key: Option<String>,
     base_url: Url,
+    params: BTreeMap<String, Value>,
+    grounding: bool, // Adds the google_search tool to every request
 }
 
+#[derive(Deserialize, Debug)]
+struct GeminiEmbedResponse { #[serde(default)] embeddings: Vec<GeminiEmbedding> }
+
+#[derive(Deserialize, Debug)]
+struct GeminiEmbedding { values: Vec<f32> }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCountTokensResponse { total_tokens: u64 }
+
 // --- impl GeminiProvider (Helpers remain the same) ---
 impl GeminiProvider {
-    pub fn new() -> Self {
-        let api_key_opt = env::var("GOOGLE_API_KEY").ok().filter(|k| !k.is_empty());
-        if api_key_opt.is_none() { println!("INFO: GOOGLE_API_KEY env var not set or empty. Gemini provider will be unavailable until set and app restarted."); }
-        let base_url = Url::parse(GEMINI_API_BASE_URL).expect("Static Gemini base URL should be valid");
-        Self { client: Client::new(), api_key: api_key_opt, base_url }
+    /// Creates the provider with config overrides; the configured API key wins over GOOGLE_API_KEY.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Self> {
+        let api_key_opt = settings.api_key.clone().or_else(|| env::var("GOOGLE_API_KEY").ok()).filter(|k| !k.is_empty());
+        if api_key_opt.is_none() { eprintln!("INFO: GOOGLE_API_KEY env var not set or empty. Gemini provider will be unavailable until a key is set (env var or [providers.gemini] api_key)."); }
+        let base_url = parse_base_url("Gemini", settings.base_url.as_deref().unwrap_or(GEMINI_API_BASE_URL))?;
+        Ok(Self { client: http_client(settings)?, api_key: api_key_opt, base_url, params: settings.params.clone(), grounding: settings.grounding })
     }
     fn build_action_url(&self, model_id: &str, action: &str, api_key: &str) -> ReplResult<Url> {
         let clean_model_id = model_id.strip_prefix("models/").unwrap_or(model_id);
@@ -106,15 +142,66 @@ impl GeminiProvider {
         }
     }
     fn get_api_key(&self) -> ReplResult<&String> {
-        self.api_key.as_ref().ok_or_else(|| ReplError::Provider("Google API key is missing. Set GOOGLE_API_KEY environment variable and restart.".to_string()))
+        self.api_key.as_ref().ok_or_else(|| ReplError::Provider("Google API key is missing. Set the GOOGLE_API_KEY environment variable or [providers.gemini] api_key in the config file.".to_string()))
     }
-    // Corrected format_single_prompt for the modified Content struct
-    fn format_single_prompt(&self, prompt: &str) -> Vec<Content> {
-        vec![Content {
-            role: "user".to_string(),
-            // Ensure parts is Some when constructing the request
-            parts: Some(vec![Part { text: prompt.to_string() }]),
-        }]
+    /// The messages as Gemini `contents` (earlier answers in the `model` role), with the system
+    /// messages joined into its `systemInstruction`.
+    fn format_messages(messages: &[ChatMessage]) -> (Vec<Content>, Option<Value>) {
+        let system = messages.iter().filter(|message| message.role == Role::System).map(|message| message.content.as_str()).collect::<Vec<_>>();
+        let instruction = (!system.is_empty()).then(|| json!({ "parts": [{ "text": system.join("\n\n") }] }));
+        let contents = messages.iter()
+            .filter(|message| message.role != Role::System)
+            .map(|message| Content {
+                role: if message.role == Role::Assistant { "model" } else { "user" }.to_string(),
+                // Ensure parts is Some when constructing the request
+                parts: Some(vec![Part { text: message.content.clone() }]),
+            })
+            .collect();
+        (contents, instruction)
+    }
+    /// The request body, with the `/set` parameters as its `generationConfig`. `[providers.gemini.params]`
+    /// are added but can't replace either. With grounding on, the google_search tool joins any configured `tools`.
+    /// `images` follow the prompt (the last message) as `inline_data` parts.
+    fn request_body(&self, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> Value {
+        let (contents, instruction) = Self::format_messages(messages);
+        let mut body = json!({ "contents": contents });
+        if let Some(instruction) = instruction {
+            body["systemInstruction"] = instruction;
+        }
+        if let Some(parts) = body["contents"].as_array_mut().and_then(|contents| contents.last_mut()).and_then(|last| last["parts"].as_array_mut()) {
+            parts.extend(images.iter().map(|image| json!({ "inline_data": { "mime_type": image.mime_type, "data": image.data } })));
+        }
+        let Value::Object(fields) = &mut body else { return body };
+        let generation_config = params.gemini_config();
+        if !generation_config.is_empty() {
+            fields.insert("generationConfig".to_string(), Value::Object(generation_config));
+        }
+        for (name, value) in &self.params {
+            fields.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+        if self.grounding {
+            if let Value::Array(tools) = fields.entry("tools").or_insert_with(|| json!([])) {
+                if !tools.iter().any(|tool| tool.get("google_search").is_some()) {
+                    tools.push(json!({ "google_search": {} }));
+                }
+            }
+        }
+        body
+    }
+    // The first candidate's text, with footnotes for any grounding sources.
+    fn answer(response_body: GeminiGenerateContentResponse) -> ReplResult<String> {
+        let candidate = response_body.candidates.and_then(|cands| cands.into_iter().next());
+        let notes = candidate.as_ref().map(|cand| footnotes(&cand.citations())).unwrap_or_default();
+        let text = candidate
+            .and_then(|cand| cand.content)
+            .and_then(|cont| cont.parts) // cont.parts is now Option<Vec<Part>>
+            .and_then(|parts_vec| parts_vec.into_iter().next()) // Get first part from the Vec
+            .map(|part| part.text);
+
+        match text {
+            Some(t) => Ok(t + &notes),
+            None => Err(ReplError::Provider("Gemini non-streaming response missing expected text content.".to_string())),
+        }
     }
 }
 
@@ -124,59 +211,106 @@ impl LlmProvider for GeminiProvider {
     fn get_name(&self) -> &str { "gemini" }
     async fn check_readiness(&self) -> ReplResult<()> { self.get_api_key()?; Ok(()) }
     fn clone_box(&self) -> Box<dyn LlmProvider> { Box::new(self.clone()) }
-    async fn get_models(&self) -> ReplResult<Vec<String>> { /* ... No changes ... */
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
         let api_key = self.get_api_key()?;
         let url = self.build_list_models_url(api_key)?;
-        let response = self.client.get(url).send().await.map_err(ReplError::Request)?;
+        let response = self.client.get(url).send_logged("gemini").await.map_err(ReplError::Request)?;
         if !response.status().is_success() { return Err(Self::handle_api_error(response).await); }
         let response_bytes = response.bytes().await.map_err(ReplError::Request)?;
         match serde_json::from_slice::<GeminiModelList>(&response_bytes) {
-            Ok(model_list_response) => { let model_names = model_list_response.models.into_iter().filter(|m| m.supported_generation_methods.as_ref().map_or(false, |methods| methods.contains(&"generateContent".to_string()) || methods.contains(&"streamGenerateContent".to_string()))).map(|m| m.name).collect(); Ok(model_names) }
+            Ok(model_list_response) => { let models = model_list_response.models.into_iter().filter(|m| m.supported_generation_methods.as_ref().map_or(false, |methods| methods.contains(&"generateContent".to_string()) || methods.contains(&"streamGenerateContent".to_string()))).map(|m| {
+                // Gemini models read images and audio as well as text
+                let modalities = Modalities { image: m.name.contains("gemini"), audio: m.name.contains("gemini"), ..Modalities::default() };
+                ModelInfo::new(m.name).with_context_window(m.input_token_limit).with_modalities(modalities).with_owner(Some("google".to_string()))
+            }).collect(); Ok(models) }
             Err(e) => { let body_text = String::from_utf8_lossy(&response_bytes); eprintln!("--- Gemini Model List Raw Response ---"); eprintln!("{}", body_text); eprintln!("------------------------------------"); Err(ReplError::Json(e)) }
         }
     }
 
-    // --- Corrected query to handle optional parts ---
-    async fn query(&self, model: &str, prompt: &str) -> ReplResult<String> {
+    // All texts in one batchEmbedContents request, e.g. with text-embedding-004
+    async fn embed(&self, model: &str, texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
         let api_key = self.get_api_key()?;
-        let url = self.build_action_url(model, "generateContent", api_key)?;
-        let contents = self.format_single_prompt(prompt);
-        let body = GeminiGenerateContentRequest { contents };
-        let response = self.client.post(url).json(&body).send().await.map_err(ReplError::Request)?;
+        let url = self.build_action_url(model, "batchEmbedContents", api_key)?;
+        let model_name = format!("models/{}", model.strip_prefix("models/").unwrap_or(model));
+        let requests: Vec<Value> = texts.iter().map(|text| json!({ "model": model_name, "content": { "parts": [{ "text": text }] } })).collect();
+        let response = self.client.post(url).json(&json!({ "requests": requests })).send_logged("gemini").await.map_err(ReplError::Request)?;
         if !response.status().is_success() { return Err(Self::handle_api_error(response).await); }
-        let response_body = response.json::<GeminiGenerateContentResponse>().await.map_err(ReplError::Request)?;
-
-        // --- Adjusted text extraction ---
-        let text = response_body.candidates
-            .and_then(|cands| cands.into_iter().next())
-            .and_then(|cand| cand.content)
-            .and_then(|cont| cont.parts) // cont.parts is now Option<Vec<Part>>
-            .and_then(|parts_vec| parts_vec.into_iter().next()) // Get first part from the Vec
-            .map(|part| part.text);
+        let response_body = response.json::<GeminiEmbedResponse>().await.map_err(ReplError::Request)?;
+        if response_body.embeddings.len() != texts.len() {
+            return Err(ReplError::Provider(format!("Gemini returned {} embeddings for {} texts.", response_body.embeddings.len(), texts.len())));
+        }
+        Ok(response_body.embeddings.into_iter().map(|embedding| embedding.values).collect())
+    }
 
-        match text {
-            Some(t) => Ok(t),
-            None => Err(ReplError::Provider("Gemini non-streaming response missing expected text content.".to_string())),
+    // Counted as the request they'd be sent as, system instruction included
+    async fn count_tokens(&self, model: &str, messages: &[ChatMessage]) -> ReplResult<TokenCount> {
+        let api_key = self.get_api_key()?;
+        let url = self.build_action_url(model, "countTokens", api_key)?;
+        let (contents, instruction) = Self::format_messages(messages);
+        let mut request = json!({ "model": format!("models/{}", model.strip_prefix("models/").unwrap_or(model)), "contents": contents });
+        if let Some(instruction) = instruction {
+            request["systemInstruction"] = instruction;
         }
+        let body = json!({ "generateContentRequest": request });
+        let response = self.client.post(url).json(&body).send_logged("gemini").await.map_err(ReplError::Request)?;
+        if !response.status().is_success() { return Err(Self::handle_api_error(response).await); }
+        let response_body = response.json::<GeminiCountTokensResponse>().await.map_err(ReplError::Request)?;
+        Ok(TokenCount::exact(response_body.total_tokens))
+    }
+
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
     }
 
-    // --- Corrected query_stream to handle optional parts ---
     async fn query_stream(
         &self,
         model: &str,
         prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    // --- Corrected chat to handle optional parts ---
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let api_key = self.get_api_key()?;
+        let url = self.build_action_url(model, "generateContent", api_key)?;
+        let body = self.request_body(messages, &[], params);
+        let response = self.client.post(url).json(&body).send_logged("gemini").await.map_err(ReplError::Request)?;
+        if !response.status().is_success() { return Err(Self::handle_api_error(response).await); }
+        let response_body = response.json::<GeminiGenerateContentResponse>().await.map_err(ReplError::Request)?;
+        Self::answer(response_body)
+    }
+
+    // Sent like `chat`, with the images after the prompt
+    async fn chat_with_images(&self, model: &str, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> ReplResult<String> {
+        let api_key = self.get_api_key()?;
+        let url = self.build_action_url(model, "generateContent", api_key)?;
+        let body = self.request_body(messages, images, params);
+        let response = self.client.post(url).json(&body).send_logged("gemini").await.map_err(ReplError::Request)?;
+        if !response.status().is_success() { return Err(Self::handle_api_error(response).await); }
+        let response_body = response.json::<GeminiGenerateContentResponse>().await.map_err(ReplError::Request)?;
+        Self::answer(response_body)
+    }
+
+    // --- Corrected chat_stream to handle optional parts ---
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
     ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
         let api_key = self.get_api_key()?;
         let url = self.build_action_url(model, "streamGenerateContent", api_key)?;
-        let contents = self.format_single_prompt(prompt); // Uses corrected format_single_prompt
-        let body = GeminiGenerateContentRequest { contents };
-        let response = self.client.post(url).json(&body).send().await.map_err(ReplError::Request)?;
+        let body = self.request_body(messages, &[], params);
+        let response = self.client.post(url).json(&body).send_logged("gemini").await.map_err(ReplError::Request)?;
         if !response.status().is_success() { return Err(Self::handle_api_error(response).await); }
 
-        let byte_stream = response.bytes_stream();
+        // Fused so it can be polled again after the sources are sent at its end
+        let byte_stream = response.bytes_stream().fuse();
         let stream = futures::stream::unfold(
-            (byte_stream, BytesMut::new()),
-            |(mut stream, mut buffer)| async move {
+            (byte_stream, BytesMut::new(), Vec::<Citation>::new()),
+            |(mut stream, mut buffer, mut citations)| async move {
                 loop {
                     let mut stream_deserializer = StreamDeserializer::<_, Vec<GeminiStreamChunk>>::new(serde_json::de::IoRead::new(buffer.as_ref()));
                     match stream_deserializer.next() {
@@ -186,6 +320,8 @@ impl LlmProvider for GeminiProvider {
                             for chunk in chunk_vec {
                                 if let Some(candidates) = chunk.candidates {
                                     for candidate in candidates {
+                                        let grounded = candidate.citations();
+                                        if !grounded.is_empty() { citations = grounded; }
                                         if let Some(reason) = &candidate.finish_reason { if reason.to_uppercase() == "SAFETY" { eprintln!("\n[WARN: Potential safety block/filter by Gemini]"); } }
                                         // --- Handle optional parts here ---
                                         if let Some(content) = &candidate.content {
@@ -201,21 +337,26 @@ impl LlmProvider for GeminiProvider {
                             }
                             let _ = buffer.split_to(consumed);
                             if !combined_text_for_event.is_empty() {
-                                return Some((Ok(combined_text_for_event), (stream, buffer)));
+                                return Some((Ok(combined_text_for_event), (stream, buffer, citations)));
                             } else { continue; }
                         }
                         Some(Err(e)) if e.is_eof() => { break; }
                         Some(Err(e)) => {
                             eprintln!("ERROR: Gemini stream JSON parsing error: {}", e); eprintln!("Buffer content causing error: {:?}", String::from_utf8_lossy(&buffer));
-                            let error = ReplError::Json(e); buffer.clear(); return Some((Err(error), (stream, buffer)));
+                            let error = ReplError::Json(e); buffer.clear(); return Some((Err(error), (stream, buffer, citations)));
                         }
                         None => { if buffer.is_empty() { break; } else { eprintln!("WARN: StreamDeserializer<Vec<Chunk>> yielded None despite non-empty buffer: {:?}", String::from_utf8_lossy(&buffer)); buffer.clear(); break; } }
                     }
                 } // End inner loop
                 match stream.next().await {
-                    Some(Ok(bytes_chunk)) => { buffer.extend_from_slice(&bytes_chunk); Some((Ok(String::new()), (stream, buffer))) }
-                    Some(Err(e)) => { let error = ReplError::Request(e); return Some((Err(error), (stream, buffer))); }
-                    None => { if !buffer.is_empty() { eprintln!("WARN: Gemini stream ended with final unprocessed buffer: {:?}", String::from_utf8_lossy(&buffer)); } return None; }
+                    Some(Ok(bytes_chunk)) => { buffer.extend_from_slice(&bytes_chunk); Some((Ok(String::new()), (stream, buffer, citations))) }
+                    Some(Err(e)) => { let error = ReplError::Request(e); return Some((Err(error), (stream, buffer, citations))); }
+                    None => {
+                        if !buffer.is_empty() { eprintln!("WARN: Gemini stream ended with final unprocessed buffer: {:?}", String::from_utf8_lossy(&buffer)); buffer.clear(); }
+                        let notes = footnotes(&std::mem::take(&mut citations));
+                        if notes.is_empty() { return None; }
+                        Some((Ok(notes), (stream, buffer, citations)))
+                    }
                 }
             },
         )
@@ -0,0 +1,99 @@
+// src/providers/generation.rs
+//! Sampling parameters changed with `/set` and sent with every query. Unset parameters are left out
+//! of requests, so the provider's (or model's) own default applies.
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::{ReplError, ReplResult};
+
+pub const PARAM_NAMES: [&str; 5] = ["temperature", "top_p", "max_tokens", "presence_penalty", "frequency_penalty"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationParams {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub max_tokens: Option<u32>,
+    pub presence_penalty: Option<f64>,
+    pub frequency_penalty: Option<f64>,
+}
+
+impl GenerationParams {
+    /// Sets `name` from its text value, or back to the provider default with `default`. Values
+    /// outside the range the APIs accept are rejected.
+    pub fn set(&mut self, name: &str, value: &str) -> ReplResult<()> {
+        let reset = value.eq_ignore_ascii_case("default");
+        let number = |min: f64, max: f64| -> ReplResult<Option<f64>> {
+            if reset {
+                return Ok(None);
+            }
+            match value.parse::<f64>() {
+                Ok(number) if (min..=max).contains(&number) => Ok(Some(number)),
+                _ => Err(ReplError::Command(format!("{} must be a number from {} to {}, or 'default'", name, min, max))),
+            }
+        };
+        match name {
+            "temperature" => self.temperature = number(0.0, 2.0)?,
+            "top_p" => self.top_p = number(0.0, 1.0)?,
+            "presence_penalty" => self.presence_penalty = number(-2.0, 2.0)?,
+            "frequency_penalty" => self.frequency_penalty = number(-2.0, 2.0)?,
+            "max_tokens" if reset => self.max_tokens = None,
+            "max_tokens" => {
+                let tokens = value.parse::<u32>().ok().filter(|tokens| *tokens > 0)
+                    .ok_or_else(|| ReplError::Command("max_tokens must be a positive whole number, or 'default'".to_string()))?;
+                self.max_tokens = Some(tokens);
+            }
+            _ => return Err(ReplError::Command(format!("Unknown parameter '{}'. Parameters: {}", name, PARAM_NAMES.join(", ")))),
+        }
+        Ok(())
+    }
+
+    /// These parameters, with any unset ones taken from `fallback`.
+    pub fn or(self, fallback: GenerationParams) -> GenerationParams {
+        GenerationParams {
+            temperature: self.temperature.or(fallback.temperature),
+            top_p: self.top_p.or(fallback.top_p),
+            max_tokens: self.max_tokens.or(fallback.max_tokens),
+            presence_penalty: self.presence_penalty.or(fallback.presence_penalty),
+            frequency_penalty: self.frequency_penalty.or(fallback.frequency_penalty),
+        }
+    }
+
+    /// Each parameter with its value, or `default` when unset.
+    pub fn describe(&self) -> Vec<(&'static str, String)> {
+        let values = self.values().map(|value| value.map_or_else(|| "default".to_string(), |value| value.to_string()));
+        PARAM_NAMES.into_iter().zip(values).collect()
+    }
+
+    fn values(&self) -> [Option<Value>; 5] {
+        [
+            self.temperature.map(Value::from),
+            self.top_p.map(Value::from),
+            self.max_tokens.map(Value::from),
+            self.presence_penalty.map(Value::from),
+            self.frequency_penalty.map(Value::from),
+        ]
+    }
+
+    // The set parameters under each API's field names, given in PARAM_NAMES order.
+    fn fields(&self, names: [&str; 5]) -> Map<String, Value> {
+        names.into_iter().zip(self.values())
+            .filter_map(|(name, value)| Some((name.to_string(), value?)))
+            .collect()
+    }
+
+    /// Fields for OpenAI-style chat requests (OpenAI, Azure, Groq, LM Studio, vLLM, xAI, Perplexity).
+    pub fn openai_fields(&self) -> Map<String, Value> {
+        self.fields(PARAM_NAMES)
+    }
+
+    /// Gemini's `generationConfig`.
+    pub fn gemini_config(&self) -> Map<String, Value> {
+        self.fields(["temperature", "topP", "maxOutputTokens", "presencePenalty", "frequencyPenalty"])
+    }
+
+    /// Ollama's `options`.
+    pub fn ollama_options(&self) -> Map<String, Value> {
+        self.fields(["temperature", "top_p", "num_predict", "presence_penalty", "frequency_penalty"])
+    }
+}
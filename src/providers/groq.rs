@@ -3,12 +3,16 @@ use async_trait::async_trait;
 use futures::{Stream, StreamExt};
 use reqwest::{Client, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::env;
 use std::pin::Pin;
 use url::Url;
 
+use crate::config::ProviderSettings;
 use crate::error::{ReplError, ReplResult};
-use crate::providers::LlmProvider;
+use crate::httplog::SendLogged;
+use crate::providers::{chat::Role, http_client, parse_base_url, ChatMessage, GenerationParams, LlmProvider, ModelInfo};
+use crate::usage;
 
 // --- Structs for Groq API (OpenAI Compatible) ---
 // Request Structures (These should be correct)
@@ -17,21 +21,8 @@ struct ChatCompletionRequest {
     model: String,
     messages: Vec<ChatMessage>,
     stream: bool,
-    // Add other optional parameters here if needed: temperature, top_p, etc.
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct ChatMessage {
-    role: Role,
-    content: String,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(rename_all = "lowercase")]
-enum Role {
-    System,
-    User,
-    Assistant,
+    #[serde(flatten)]
+    params: Map<String, Value>, // temperature, top_p, ... as set with /set
 }
 
 // Streaming Response Structures (These should be generally correct for OpenAI format)
@@ -43,6 +34,8 @@ struct ChatCompletionChunk {
     // model: String, // Model used
     choices: Vec<DeltaChoice>,
     // system_fingerprint: Option<String>, // Optional fingerprint
+    usage: Option<UsageStats>, // OpenAI-style: a last chunk with `stream_options.include_usage`
+    x_groq: Option<XGroq>,     // Groq: the last chunk carries `x_groq.usage`
 }
 
 #[derive(Deserialize, Debug)]
@@ -61,6 +54,7 @@ struct Delta {
 
 // Non-Streaming Response Structures (Meticulously matched to example)
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)] // Deserialized in full; only some fields are used
 struct ChatCompletionResponse {
     id: String,
     object: String, // e.g., "chat.completion"
@@ -73,6 +67,7 @@ struct ChatCompletionResponse {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)] // Deserialized in full; only some fields are used
 struct ResponseMessageChoice {
     index: u32,
     message: ResponseMessage,
@@ -81,25 +76,36 @@ struct ResponseMessageChoice {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)] // Deserialized in full; only some fields are used
 struct ResponseMessage {
     role: Role,
     content: String,
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)] // Deserialized in full; only some fields are used
 struct UsageStats {
     queue_time: Option<f64>, // Make queue time optional, might not always be present
     prompt_tokens: u64,
     prompt_time: Option<f64>, // Make prompt time optional
     completion_tokens: u64,
-    completion_time: f64,
+    completion_time: Option<f64>, // Timings are Groq's; other OpenAI-style APIs only send the counts
     total_tokens: u64,
-    total_time: f64,
+    total_time: Option<f64>,
+}
+
+impl UsageStats {
+    // Recorded in the usage metrics in place of estimates.
+    fn report(&self) {
+        usage::report_tokens(self.prompt_tokens, self.completion_tokens);
+    }
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)] // Deserialized in full; only some fields are used
 struct XGroq { // Structure for the vendor-specific x_groq field
     id: Option<String>, // Make internal fields optional too
+    usage: Option<UsageStats>,
 }
 
 // Model Listing Structures (These should be correct)
@@ -130,27 +136,25 @@ pub struct GroqProvider {
 }
 
 impl GroqProvider {
-    /// Creates a new Groq provider instance.
-    /// Attempts to load the API key from GROQ_API_KEY env var.
-    /// Prints an INFO message if the key is missing/empty but still creates the provider.
-    pub fn new() -> Self {
-        let api_key = env::var("GROQ_API_KEY").ok().filter(|k| !k.is_empty());
+    /// Creates the provider with config overrides; the configured API key wins over GROQ_API_KEY.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Self> {
+        let api_key = settings.api_key.clone()
+            .or_else(|| env::var("GROQ_API_KEY").ok())
+            .filter(|k| !k.is_empty());
 
         if api_key.is_none() {
-            println!(
-                "INFO: GROQ_API_KEY not set or empty. Groq provider initialized but unusable until key is set and app is restarted."
+            eprintln!(
+                "INFO: GROQ_API_KEY not set or empty. Groq provider initialized but unusable until a key is set (env var or [providers.groq] api_key)."
             );
         }
 
-        // Static URL parsing is unlikely to fail, use expect for simplicity
-        let base_url = Url::parse(GROQ_API_BASE_URL)
-            .expect("Static Groq base URL should be valid");
+        let base_url = parse_base_url("Groq", settings.base_url.as_deref().unwrap_or(GROQ_API_BASE_URL))?;
 
-        Self {
-            client: Client::new(),
+        Ok(Self {
+            client: http_client(settings)?,
             api_key, // Store None if key wasn't found/valid
             base_url,
-        }
+        })
     }
 
     /// Helper to build a full URL for a given API endpoint.
@@ -182,7 +186,7 @@ impl GroqProvider {
     fn get_api_key(&self) -> ReplResult<&String> {
         self.api_key.as_ref().ok_or_else(|| {
             ReplError::Provider(
-                "Groq API key is missing. Set the GROQ_API_KEY environment variable and restart."
+                "Groq API key is missing. Set the GROQ_API_KEY environment variable or [providers.groq] api_key in the config file."
                     .to_string(),
             )
         })
@@ -203,112 +207,146 @@ impl LlmProvider for GroqProvider {
         Box::new(self.clone())
     }
 
-    async fn get_models(&self) -> ReplResult<Vec<String>> {
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
         let api_key = self.get_api_key()?; // Check for API key first
         let url = self.build_url("models")?;
-        let response = self.add_auth(self.client.get(url), api_key).send().await?;
+        let response = self.add_auth(self.client.get(url), api_key).send_logged("groq").await?;
 
         if !response.status().is_success() {
             return Err(Self::handle_api_error(response).await);
         }
 
         let model_list_response = response.json::<GroqModelList>().await?;
-        let model_names = model_list_response.data.into_iter()
-            .map(|m| m.id)
+        let models = model_list_response.data.into_iter()
+            .map(|m| ModelInfo::new(m.id).with_context_window(Some(u64::from(m.context_window))).with_owner(Some(m.owned_by)))
             .collect();
-        Ok(model_names)
+        Ok(models)
     }
 
-// --- Inside impl LlmProvider for GroqProvider ---
-
-    // Keep get_name, clone_box, get_models, query as they are
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
 
     async fn query_stream(
         &self,
         model: &str,
         prompt: &str,
+        params: &GenerationParams,
     ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    // The messages go to the chat completions API as they are, so earlier turns keep their roles
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
         let api_key = self.get_api_key()?;
         let url = self.build_url("chat/completions")?;
-        let messages = vec![ChatMessage { role: Role::User, content: prompt.to_string() }];
-        let body = ChatCompletionRequest { model: model.to_string(), messages, stream: true };
+        let body = ChatCompletionRequest { model: model.to_string(), messages: messages.to_vec(), stream: false, params: params.openai_fields() };
 
-        let response = self.add_auth(self.client.post(url).json(&body), api_key).send().await?;
+        let response = self.add_auth(self.client.post(url).json(&body), api_key).send_logged("groq").await?;
 
         if !response.status().is_success() {
             return Err(Self::handle_api_error(response).await);
         }
 
-        let byte_stream = response.bytes_stream();
-
-        // Use a state machine approach to reassemble potentially fragmented SSE messages
-        let stream = futures::stream::unfold(
-            (byte_stream, String::new()), // State: (underlying stream, leftover buffer from previous chunk)
-            |(mut stream, mut buffer)| async move {
-                loop {
-                    // Check buffer first for complete messages
-                    if let Some(end_idx) = buffer.find("\n\n") {
-                        let message = buffer.drain(..end_idx + 2).collect::<String>(); // Consume message + delimiters
-                        if let Some(content) = process_sse_message(&message) {
-                            // Yield content if message parsed successfully
-                            return Some((Ok(content), (stream, buffer)));
-                        }
-                        // If processing failed or yielded no content, continue loop to get more data or check buffer again
-                        continue;
+        let completion = response.json::<ChatCompletionResponse>().await?;
+        completion.usage.report();
+        completion.choices.into_iter().next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| ReplError::Provider("Groq returned no choices.".to_string()))
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let api_key = self.get_api_key()?;
+        let url = self.build_url("chat/completions")?;
+        let body = ChatCompletionRequest { model: model.to_string(), messages: messages.to_vec(), stream: true, params: params.openai_fields() };
+
+        let response = self.add_auth(self.client.post(url).json(&body), api_key).send_logged("groq").await?;
+
+        if !response.status().is_success() {
+            return Err(Self::handle_api_error(response).await);
+        }
+
+        Ok(Some(sse_text_stream(response)))
+    } // <-- End of chat_stream function
+
+// --- Keep the rest of the impl block ---
+} // <-- End of impl LlmProvider
+
+/// Turns an OpenAI-compatible streaming response (SSE `data:` lines with `choices[].delta.content`)
+/// into a stream of text chunks. Shared with other OpenAI-style providers (e.g. Azure).
+pub(crate) fn sse_text_stream(response: Response) -> Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>> {
+    let byte_stream = response.bytes_stream();
+
+    // Use a state machine approach to reassemble potentially fragmented SSE messages
+    let stream = futures::stream::unfold(
+        (byte_stream, String::new()), // State: (underlying stream, leftover buffer from previous chunk)
+        |(mut stream, mut buffer)| async move {
+            loop {
+                // Check buffer first for complete messages
+                if let Some(end_idx) = buffer.find("\n\n") {
+                    let message = buffer.drain(..end_idx + 2).collect::<String>(); // Consume message + delimiters
+                    if let Some(content) = process_sse_message(&message) {
+                        // Yield content if message parsed successfully
+                        return Some((Ok(content), (stream, buffer)));
                     }
+                    // If processing failed or yielded no content, continue loop to get more data or check buffer again
+                    continue;
+                }
 
-                    // Buffer doesn't have a complete message, read more from the network stream
-                    match stream.next().await {
-                        Some(Ok(bytes)) => {
-                            // Append new data to buffer
-                            match String::from_utf8(bytes.to_vec()) {
-                                Ok(text) => buffer.push_str(&text),
-                                Err(e) => {
-                                    // UTF-8 error in chunk, yield error and stop
-                                    let err = ReplError::Provider(format!("Stream chunk not valid UTF-8: {}", e));
-                                    return Some((Err(err), (stream, buffer)));
-                                }
-                            };
-                            // Loop back to check buffer again with new data
-                        }
-                        Some(Err(e)) => {
-                            // Network error reading stream, yield error and stop
-                            let err = ReplError::Request(e);
-                            return Some((Err(err), (stream, buffer)));
-                        }
-                        None => {
-                            // End of network stream
-                            // Process any remaining data in the buffer
-                            if !buffer.is_empty() {
-                                if let Some(content) = process_sse_message(&buffer) {
-                                    buffer.clear(); // Clear buffer after processing
-                                    return Some((Ok(content), (stream, buffer)));
-                                } else {
-                                     // Remaining buffer couldn't be processed or was empty content
-                                    return None; // End the stream
-                                }
+                // Buffer doesn't have a complete message, read more from the network stream
+                match stream.next().await {
+                    Some(Ok(bytes)) => {
+                        // Append new data to buffer
+                        match String::from_utf8(bytes.to_vec()) {
+                            Ok(text) => buffer.push_str(&text),
+                            Err(e) => {
+                                // UTF-8 error in chunk, yield error and stop
+                                let err = ReplError::Provider(format!("Stream chunk not valid UTF-8: {}", e));
+                                return Some((Err(err), (stream, buffer)));
+                            }
+                        };
+                        // Loop back to check buffer again with new data
+                    }
+                    Some(Err(e)) => {
+                        // Network error reading stream, yield error and stop
+                        let err = ReplError::Request(e);
+                        return Some((Err(err), (stream, buffer)));
+                    }
+                    None => {
+                        // End of network stream
+                        // Process any remaining data in the buffer
+                        if !buffer.is_empty() {
+                            if let Some(content) = process_sse_message(&buffer) {
+                                buffer.clear(); // Clear buffer after processing
+                                return Some((Ok(content), (stream, buffer)));
                             } else {
-                                // Buffer is empty and stream ended
+                                 // Remaining buffer couldn't be processed or was empty content
                                 return None; // End the stream
                             }
+                        } else {
+                            // Buffer is empty and stream ended
+                            return None; // End the stream
                         }
                     }
                 }
-            },
-        )
-        .filter_map(|res| async move { // Keep filtering empty strings and propagate errors
-             match res {
-                 Ok(s) if !s.is_empty() => Some(Ok(s)),
-                 Ok(_) => None,
-                 Err(e) => Some(Err(e)),
-             }
-         });
-
-        Ok(Some(Box::pin(stream)))
-    } // <-- End of query_stream function
-
-// --- Keep the rest of the impl block ---
-} // <-- End of impl LlmProvider
+            }
+        },
+    )
+    .filter_map(|res| async move { // Keep filtering empty strings and propagate errors
+         match res {
+             Ok(s) if !s.is_empty() => Some(Ok(s)),
+             Ok(_) => None,
+             Err(e) => Some(Err(e)),
+         }
+     });
+
+    Box::pin(stream)
+}
 
 /// Helper function to process a potential complete SSE message block
 /// Returns Some(content) if parsing is successful and yields content,
@@ -329,6 +367,10 @@ fn process_sse_message(message_block: &str) -> Option<String> {
 
             match serde_json::from_str::<ChatCompletionChunk>(data) {
                 Ok(parsed_chunk) => {
+                    let usage = parsed_chunk.usage.as_ref().or(parsed_chunk.x_groq.as_ref().and_then(|x| x.usage.as_ref()));
+                    if let Some(usage) = usage {
+                        usage.report();
+                    }
                     for choice in parsed_chunk.choices {
                         if let Some(content) = choice.delta.content {
                             content_acc.push_str(&content);
@@ -0,0 +1,240 @@
+// src/providers/group.rs
+//! Provider groups (`[providers.lan] type = "group"`) spread queries over several configured
+//! providers, such as Ollama on a few machines. A group is selected as `group:<name>`; each query
+//! goes to one member and moves on to the next if that member fails.
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::error::{ReplError, ReplResult};
+use crate::providers::{ChatMessage, GenerationParams, ImageAttachment, LlmProvider, ModelInfo, TokenCount};
+
+/// Prefix of group names, so groups can't clash with provider names.
+pub const PREFIX: &str = "group:";
+
+/// How a group picks the member for a query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupStrategy {
+    /// Each member in turn.
+    #[default]
+    RoundRobin,
+    /// The member with the fewest queries (and streams) still running.
+    LeastBusy,
+}
+
+// Shared by a group's clones, so the rotation and running counts hold across requests.
+struct Balance {
+    next: AtomicUsize,
+    running: Vec<AtomicUsize>,
+}
+
+// Counts a query as running on a member until dropped; streams hold it until they end.
+struct Running {
+    balance: Arc<Balance>,
+    member: usize,
+}
+
+impl Running {
+    fn start(balance: &Arc<Balance>, member: usize) -> Self {
+        balance.running[member].fetch_add(1, Ordering::SeqCst);
+        Self { balance: balance.clone(), member }
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.balance.running[self.member].fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub struct ProviderGroup {
+    name: String,
+    members: Vec<Box<dyn LlmProvider>>,
+    strategy: GroupStrategy,
+    balance: Arc<Balance>,
+}
+
+impl ProviderGroup {
+    /// A group named `group:<name>` over `members`, which must not be empty.
+    pub fn new(name: &str, members: Vec<Box<dyn LlmProvider>>, strategy: GroupStrategy) -> Self {
+        let running = members.iter().map(|_| AtomicUsize::new(0)).collect();
+        Self {
+            name: format!("{}{}", PREFIX, name),
+            members,
+            strategy,
+            balance: Arc::new(Balance { next: AtomicUsize::new(0), running }),
+        }
+    }
+
+    // Member indexes in the order to try them: the chosen member first, then the others after it.
+    // Ties between equally busy members are broken in turn, so an idle group still rotates.
+    fn order(&self) -> Vec<usize> {
+        let count = self.members.len();
+        let offset = self.balance.next.fetch_add(1, Ordering::SeqCst);
+        let first = match self.strategy {
+            GroupStrategy::RoundRobin => offset % count,
+            GroupStrategy::LeastBusy => (0..count)
+                .map(|i| (offset + i) % count)
+                .min_by_key(|&i| self.balance.running[i].load(Ordering::SeqCst))
+                .unwrap_or(0),
+        };
+        (0..count).map(|i| (first + i) % count).collect()
+    }
+
+    fn failed(&self, errors: Vec<String>) -> ReplError {
+        ReplError::Provider(format!("Every member of {} failed: {}", self.name, errors.join("; ")))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ProviderGroup {
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let mut errors = Vec::new();
+        for member in self.order() {
+            let _running = Running::start(&self.balance, member);
+            let provider = &self.members[member];
+            match provider.chat(model, messages, params).await {
+                Ok(answer) => return Ok(answer),
+                Err(e) => {
+                    warn!("{}: {} failed, trying the next member: {}", self.name, provider.get_name(), e);
+                    errors.push(format!("{}: {}", provider.get_name(), e));
+                }
+            }
+        }
+        Err(self.failed(errors))
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let mut errors = Vec::new();
+        for member in self.order() {
+            let running = Running::start(&self.balance, member);
+            let provider = &self.members[member];
+            match provider.chat_stream(model, messages, params).await {
+                Ok(Some(stream)) => {
+                    let stream = stream.map(move |chunk| {
+                        let _running = &running;
+                        chunk
+                    });
+                    return Ok(Some(Box::pin(stream)));
+                }
+                // The caller falls back to query(), which picks a member again
+                Ok(None) => return Ok(None),
+                Err(e) => {
+                    warn!("{}: {} failed, trying the next member: {}", self.name, provider.get_name(), e);
+                    errors.push(format!("{}: {}", provider.get_name(), e));
+                }
+            }
+        }
+        Err(self.failed(errors))
+    }
+
+    async fn embed(&self, model: &str, texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
+        let mut errors = Vec::new();
+        for member in self.order() {
+            let _running = Running::start(&self.balance, member);
+            let provider = &self.members[member];
+            match provider.embed(model, texts).await {
+                Ok(vectors) => return Ok(vectors),
+                Err(e) => {
+                    warn!("{}: {} failed, trying the next member: {}", self.name, provider.get_name(), e);
+                    errors.push(format!("{}: {}", provider.get_name(), e));
+                }
+            }
+        }
+        Err(self.failed(errors))
+    }
+
+    // Members may tokenize differently; the first one's count stands for the group
+    async fn chat_with_images(&self, model: &str, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> ReplResult<String> {
+        let mut errors = Vec::new();
+        for member in self.order() {
+            let _running = Running::start(&self.balance, member);
+            let provider = &self.members[member];
+            match provider.chat_with_images(model, messages, images, params).await {
+                Ok(answer) => return Ok(answer),
+                Err(e) => {
+                    warn!("{}: {} failed, trying the next member: {}", self.name, provider.get_name(), e);
+                    errors.push(format!("{}: {}", provider.get_name(), e));
+                }
+            }
+        }
+        Err(self.failed(errors))
+    }
+
+    async fn count_tokens(&self, model: &str, messages: &[ChatMessage]) -> ReplResult<TokenCount> {
+        match self.members.first() {
+            Some(provider) => provider.count_tokens(model, messages).await,
+            None => Ok(TokenCount::estimate(messages)),
+        }
+    }
+
+    /// The models of all reachable members, each listed once (as the first member reports it).
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        let mut models: Vec<ModelInfo> = Vec::new();
+        let mut errors = Vec::new();
+        for provider in &self.members {
+            match provider.get_models().await {
+                Ok(listed) => {
+                    for model in listed {
+                        if !models.iter().any(|known| known.id == model.id) {
+                            models.push(model);
+                        }
+                    }
+                }
+                Err(e) => errors.push(format!("{}: {}", provider.get_name(), e)),
+            }
+        }
+        if models.is_empty() && !errors.is_empty() {
+            return Err(self.failed(errors));
+        }
+        Ok(models)
+    }
+
+    /// Ready while at least one member is.
+    async fn check_readiness(&self) -> ReplResult<()> {
+        let mut errors = Vec::new();
+        for provider in &self.members {
+            match provider.check_readiness().await {
+                Ok(()) => return Ok(()),
+                Err(e) => errors.push(format!("{}: {}", provider.get_name(), e)),
+            }
+        }
+        Err(self.failed(errors))
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(ProviderGroup {
+            name: self.name.clone(),
+            members: self.members.iter().map(|member| member.clone_box()).collect(),
+            strategy: self.strategy,
+            balance: self.balance.clone(),
+        })
+    }
+}
@@ -0,0 +1,76 @@
+// src/providers/images.rs
+//! Images sent with a prompt to vision models (Gemini, Ollama's llava), base64-encoded as both
+//! APIs expect them. They come from a file or from base64 data, such as a `data:` URL.
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::fs;
+use std::path::Path;
+
+use crate::error::{ReplError, ReplResult};
+
+// Gemini's limit for images sent inline with a request.
+pub const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageAttachment {
+    /// The file name, or "pasted image" for base64 data.
+    pub name: String,
+    pub mime_type: String,
+    /// Base64 (standard alphabet, padded).
+    pub data: String,
+    /// Size of the decoded image.
+    pub bytes: usize,
+}
+
+// The image type named by a file extension.
+fn mime_type_for(extension: &str) -> Option<&'static str> {
+    match extension.to_lowercase().as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "heic" => Some("image/heic"),
+        _ => None,
+    }
+}
+
+fn check_size(name: &str, bytes: usize) -> ReplResult<()> {
+    if bytes > MAX_IMAGE_BYTES {
+        return Err(ReplError::Command(format!(
+            "Image '{}' is {} bytes; images sent with a prompt can be at most {} bytes.",
+            name, bytes, MAX_IMAGE_BYTES
+        )));
+    }
+    Ok(())
+}
+
+impl ImageAttachment {
+    /// Reads a PNG, JPEG, GIF, WebP or HEIC file.
+    pub fn from_path(path: &Path) -> ReplResult<Self> {
+        let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| path.display().to_string());
+        let mime_type = path.extension().and_then(|extension| mime_type_for(&extension.to_string_lossy())).ok_or_else(|| {
+            ReplError::Command(format!("'{}' is not a supported image; use a .png, .jpg, .gif, .webp or .heic file.", path.display()))
+        })?;
+        let content = fs::read(path)?;
+        check_size(&name, content.len())?;
+        Ok(Self { name, mime_type: mime_type.to_string(), bytes: content.len(), data: STANDARD.encode(content) })
+    }
+
+    /// Base64 image data of the given type; checked by decoding it.
+    pub fn from_base64(data: &str, mime_type: &str) -> ReplResult<Self> {
+        let data: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+        let decoded = STANDARD.decode(&data).map_err(|e| ReplError::Command(format!("Invalid base64 image data: {}", e)))?;
+        let name = "pasted image".to_string();
+        check_size(&name, decoded.len())?;
+        Ok(Self { name, mime_type: mime_type.to_string(), bytes: decoded.len(), data })
+    }
+
+    /// A `data:image/<type>;base64,...` URL, or else a file path.
+    pub fn load(source: &str) -> ReplResult<Self> {
+        let source = source.trim();
+        match source.strip_prefix("data:").and_then(|rest| rest.split_once(";base64,")) {
+            Some((mime_type, data)) if mime_type.starts_with("image/") => Self::from_base64(data, mime_type),
+            Some((mime_type, _)) => Err(ReplError::Command(format!("'{}' data is not an image.", mime_type))),
+            None => Self::from_path(Path::new(source)),
+        }
+    }
+}
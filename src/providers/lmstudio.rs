@@ -0,0 +1,119 @@
+// src/providers/lmstudio.rs
+//! LM Studio's local server, which speaks the OpenAI chat API (default `http://localhost:1234`).
+//! Models are whatever `/v1/models` reports as available in the running app.
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::{Client, RequestBuilder, Response};
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::time::Duration;
+use url::Url;
+
+use crate::config::ProviderSettings;
+use crate::error::{ReplError, ReplResult};
+use crate::httplog::SendLogged;
+use super::{
+    groq::sse_text_stream,
+    openai_compat::{chat_body, ChatResponse, ModelList},
+    http_client, parse_base_url, ChatMessage, GenerationParams, LlmProvider, ModelInfo,
+};
+
+const LMSTUDIO_DEFAULT_URL: &str = "http://localhost:1234";
+// Model listing is also the readiness probe, so a stopped app is reported quickly.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone)]
+pub struct LmStudioProvider {
+    client: Client,
+    base_url: Url,
+}
+
+impl LmStudioProvider {
+    /// Creates the provider; `[providers.lmstudio] base_url` points it at another host or port.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Self> {
+        let base_url = parse_base_url("LM Studio", settings.base_url.as_deref().unwrap_or(LMSTUDIO_DEFAULT_URL))?;
+        Ok(Self { client: http_client(settings)?, base_url })
+    }
+
+    fn build_url(&self, endpoint: &str) -> ReplResult<Url> {
+        self.base_url.join(endpoint)
+            .map_err(|e| ReplError::Provider(format!("Failed to build URL: {}", e)))
+    }
+
+    // A connection failure most likely means the app (or its server) isn't running.
+    fn unreachable(&self, error: reqwest::Error) -> ReplError {
+        if error.is_connect() || error.is_timeout() {
+            ReplError::Provider(format!(
+                "LM Studio is not reachable at {}. Start LM Studio and its local server (Developer tab, or `lms server start`), or set [providers.lmstudio] base_url.",
+                self.base_url
+            ))
+        } else {
+            ReplError::Provider(format!("Failed to send request to LM Studio: {}", error))
+        }
+    }
+
+    fn chat_request(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams, stream: bool) -> ReplResult<RequestBuilder> {
+        let body = chat_body(model, messages, stream, params, &BTreeMap::new());
+        Ok(self.client.post(self.build_url("v1/chat/completions")?).json(&body))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> ReplResult<Response> {
+        let response = request.send_logged("lmstudio").await.map_err(|e| self.unreachable(e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(ReplError::Provider(format!("LM Studio returned an error: {} - {}", status, error_body)));
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LmStudioProvider {
+    fn get_name(&self) -> &str {
+        "lmstudio"
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+
+    async fn check_readiness(&self) -> ReplResult<()> {
+        self.get_models().await.map(|_| ())
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        let request = self.client.get(self.build_url("v1/models")?).timeout(PROBE_TIMEOUT);
+        let models = self.send(request).await?.json::<ModelList>().await
+            .map_err(|e| ReplError::Provider(format!("Failed to parse LM Studio model list: {}", e)))?;
+        Ok(models.into_models())
+    }
+
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let response = self.send(self.chat_request(model, messages, params, false)?).await?;
+        response.json::<ChatResponse>().await?.into_text("LM Studio")
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let response = self.send(self.chat_request(model, messages, params, true)?).await?;
+        Ok(Some(sse_text_stream(response)))
+    }
+}
@@ -0,0 +1,149 @@
+// src/providers/metered.rs
+//! Wraps every registered provider so its calls are recorded in the usage metrics, with the
+//! token counts the provider reports where it does.
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::time::Instant;
+
+use crate::error::ReplResult;
+use crate::request_id;
+use crate::providers::{chat, ChatMessage, GenerationParams, ImageAttachment, LlmProvider, ModelInfo, TokenCount};
+use crate::usage::{self, TokenReport};
+
+pub struct MeteredProvider {
+    inner: Box<dyn LlmProvider>,
+}
+
+impl MeteredProvider {
+    pub fn new(inner: Box<dyn LlmProvider>) -> Self {
+        Self { inner }
+    }
+}
+
+// Records a streamed call when the stream is dropped, whether it finished, failed or was cancelled.
+struct StreamUsage {
+    provider: String,
+    model: String,
+    prompt: String,
+    completion: String,
+    failed: bool,
+    started: Instant,
+    report: TokenReport,
+    // The request the stream was started for; it may be dropped outside it
+    request_id: Option<String>,
+}
+
+impl StreamUsage {
+    fn observe(&mut self, chunk: &ReplResult<String>) {
+        match chunk {
+            Ok(text) => self.completion.push_str(text),
+            Err(_) => self.failed = true,
+        }
+    }
+}
+
+impl Drop for StreamUsage {
+    fn drop(&mut self) {
+        request_id::within(self.request_id.take(), || {
+            usage::record(&self.provider, &self.model, &self.prompt, &self.completion, self.started.elapsed(), self.failed, self.report.take())
+        });
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MeteredProvider {
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let prompt = chat::join(messages);
+        let started = Instant::now();
+        let report = TokenReport::default();
+        let result = report.scope(self.inner.chat(model, messages, params)).await;
+        let completion = result.as_deref().unwrap_or_default();
+        usage::record(self.get_name(), model, &prompt, completion, started.elapsed(), result.is_err(), report.take());
+        result
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let prompt = chat::join(messages);
+        let started = Instant::now();
+        let stream = match self.inner.chat_stream(model, messages, params).await {
+            Ok(Some(stream)) => stream,
+            other => {
+                // Failed to start, or no stream (the caller falls back to `query`, which is recorded then)
+                if other.is_err() {
+                    usage::record(self.get_name(), model, &prompt, "", started.elapsed(), true, None);
+                }
+                return other;
+            }
+        };
+        let mut call = StreamUsage {
+            provider: self.get_name().to_string(),
+            model: model.to_string(),
+            prompt,
+            completion: String::new(),
+            failed: false,
+            started,
+            report: TokenReport::default(),
+            request_id: request_id::current(),
+        };
+        let stream = call.report.stream(stream);
+        Ok(Some(Box::pin(stream.inspect(move |chunk| call.observe(chunk)))))
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        self.inner.get_models().await
+    }
+
+    // Embeddings are billed by input, so the texts count as the prompt
+    async fn embed(&self, model: &str, texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
+        let started = Instant::now();
+        let result = self.inner.embed(model, texts).await;
+        usage::record(self.get_name(), model, &texts.join("\n"), "", started.elapsed(), result.is_err(), None);
+        result
+    }
+
+    // Counting isn't a query, so it isn't recorded
+    async fn chat_with_images(&self, model: &str, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> ReplResult<String> {
+        let prompt = chat::join(messages);
+        let started = Instant::now();
+        let report = TokenReport::default();
+        let result = report.scope(self.inner.chat_with_images(model, messages, images, params)).await;
+        let completion = result.as_deref().unwrap_or_default();
+        usage::record(self.get_name(), model, &prompt, completion, started.elapsed(), result.is_err(), report.take());
+        result
+    }
+
+    async fn count_tokens(&self, model: &str, messages: &[ChatMessage]) -> ReplResult<TokenCount> {
+        self.inner.count_tokens(model, messages).await
+    }
+
+    async fn check_readiness(&self) -> ReplResult<()> {
+        self.inner.check_readiness().await
+    }
+
+    fn get_name(&self) -> &str {
+        self.inner.get_name()
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(MeteredProvider::new(self.inner.clone_box()))
+    }
+}
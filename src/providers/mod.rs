@@ -1,19 +1,48 @@
 // src/providers/mod.rs
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::pin::Pin;
+use std::time::Duration;
 use async_trait::async_trait;
 use futures::Stream;
+use reqwest::Client;
+use url::Url;
+use crate::config::ProviderSettings;
 use crate::error::ReplResult;
 use crate::error::ReplError;
 
+pub mod azure;
+pub mod chat;
+pub mod citations;
 pub mod ollama;
+pub mod openai;
 pub mod groq;
 pub mod gemini;
+pub mod generation;
+pub mod group;
+pub mod images;
+pub mod lmstudio;
+pub mod openai_compat;
+pub mod perplexity;
+pub mod vllm;
+pub mod xai;
+pub mod metered;
+pub mod modelfile;
+pub mod models;
+pub mod named;
+pub mod resume;
+pub mod timeout;
+pub mod tokens;
+
+pub use chat::ChatMessage;
+pub use generation::GenerationParams;
+pub use images::ImageAttachment;
+pub use models::{ModelInfo, Modalities};
+pub use tokens::TokenCount;
 /// Core provider trait for LLM interactions
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
-    /// Standard query that returns complete response
-    async fn query(&self, _model: &str, _prompt: &str) -> ReplResult<String> {
+    /// Standard query that returns complete response; `params` are the sampling settings from `/set`
+    async fn query(&self, _model: &str, _prompt: &str, _params: &GenerationParams) -> ReplResult<String> {
         unimplemented!()
     }
     
@@ -21,16 +50,49 @@ pub trait LlmProvider: Send + Sync {
         &self,
         _model: &str,
         _prompt: &str,
+        _params: &GenerationParams,
     ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
         unimplemented!()
     }
     
-    async fn get_models(&self) -> ReplResult<Vec<String>> {
+    /// Like `query`, with the conversation as messages: the session context, earlier turns, then
+    /// the prompt. Providers without a chat API receive them joined into one prompt.
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        self.query(model, &chat::join(messages), params).await
+    }
+
+    /// Streaming form of `chat`.
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.query_stream(model, &chat::join(messages), params).await
+    }
+
+    /// Like `chat`, with `images` attached to the prompt (the last message), for vision models
+    /// such as Gemini or Ollama's llava. Not streamed.
+    async fn chat_with_images(&self, _model: &str, _messages: &[ChatMessage], _images: &[ImageAttachment], _params: &GenerationParams) -> ReplResult<String> {
+        Err(ReplError::Provider(format!("{} does not support image input", self.get_name())))
+    }
+
+    /// The models to choose from, with what the provider reports about each.
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
                  Err(ReplError::Provider(format!(
                     "get_models not implemented for provider {}",
                     self.get_name()
                 )))
              }
+    /// Embedding vectors for `texts`, one per text and in the same order.
+    async fn embed(&self, _model: &str, _texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
+        Err(ReplError::Provider(format!("{} does not support embeddings", self.get_name())))
+    }
+    /// Tokens the messages take up as a request to `model`; estimated unless the provider can count them.
+    async fn count_tokens(&self, _model: &str, messages: &[ChatMessage]) -> ReplResult<TokenCount> {
+        Ok(TokenCount::estimate(messages))
+    }
+
     async fn check_readiness(&self) -> ReplResult<()> {
             Ok(()) // Default implementation: provider is always ready
     }
@@ -58,21 +120,79 @@ pub struct ProviderRegistry {
 }
 
 impl ProviderRegistry {
-    /// Create new registry with default providers
+    /// Create new registry with default providers. This is also the fallback when the config
+    /// can't be used, so a provider whose environment settings are invalid (e.g. a malformed
+    /// `VLLM_BASE_URL`) is left out with a warning rather than stopping the REPL.
     pub fn new() -> Self {
         let mut registry = ProviderRegistry {
             providers: HashMap::new(),
         };
-        // Register default providers
-        registry.register(Box::new(ollama::OllamaProvider::default()));
-        // Attempt to register Groq if API key is available
-        registry.register(Box::new(groq::GroqProvider::new()));
-        registry.register(Box::new(gemini::GeminiProvider::new()));
+        for kind in BUILT_IN_PROVIDERS {
+            match build(kind, &ProviderSettings::default()) {
+                Ok(Some(provider)) => registry.register(provider),
+                Ok(None) => {} // Needs an API key that isn't set
+                Err(e) => eprintln!("WARN: Provider '{}' is unavailable: {}", kind, e),
+            }
+        }
         registry
     }
-    
+
+    /// Create the registry from the `[providers]` config: `[providers.<built-in>]` overrides a
+    /// built-in provider, and any other name declares an extra instance of the provider `type`
+    /// it names, e.g. a second Ollama host or an OpenAI-compatible proxy.
+    pub fn from_config(settings: &BTreeMap<String, ProviderSettings>) -> ReplResult<Self> {
+        let settings_for = |name: &str| settings.get(name).cloned().unwrap_or_default();
+        let mut registry = ProviderRegistry {
+            providers: HashMap::new(),
+        };
+        for kind in BUILT_IN_PROVIDERS {
+            let settings = settings_for(kind);
+            if let Some(other) = settings.kind.as_deref().filter(|other| other != kind) {
+                return Err(ReplError::Provider(format!("[providers.{}] can't have type '{}'; declare another instance under its own name", kind, other)));
+            }
+            if let Some(provider) = build(kind, &settings)? {
+                registry.register(provider);
+            }
+        }
+        let mut groups = Vec::new();
+        for (name, settings) in settings.iter().filter(|(name, _)| !BUILT_IN_PROVIDERS.contains(&name.as_str())) {
+            if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+                return Err(ReplError::Provider(format!("Invalid provider name '{}' in [providers] config: use lowercase letters, digits, '-' and '_'", name)));
+            }
+            let kind = settings.kind.as_deref().ok_or_else(|| ReplError::Provider(format!(
+                "Unknown provider '{}' in [providers] config; to declare an instance, set its type (one of: {}, or group)", name, BUILT_IN_PROVIDERS.join(", ")
+            )))?;
+            if kind == "group" {
+                groups.push((name, settings));
+                continue;
+            }
+            let provider = build(kind, settings)?.ok_or_else(|| ReplError::Provider(format!(
+                "[providers.{}] needs an api_key (or base_url) for type '{}'", name, kind
+            )))?;
+            registry.register(Box::new(named::NamedProvider::new(name, provider)));
+        }
+        // Groups are built last, from the providers registered above
+        for (name, settings) in groups {
+            if settings.members.is_empty() {
+                return Err(ReplError::Provider(format!("[providers.{}] is a group without members; list them, e.g. members = [\"ollama\", \"gpu-box\"]", name)));
+            }
+            let members = settings.members.iter()
+                .map(|member| registry.providers.get(member).map(|provider| provider.clone_box()).ok_or_else(|| ReplError::Provider(format!(
+                    "[providers.{}] lists unknown member '{}'; members must be providers, not groups", name, member
+                ))))
+                .collect::<ReplResult<Vec<_>>>()?;
+            // Members are already metered and resumed, so the group isn't wrapped again
+            let group = group::ProviderGroup::new(name, members, settings.strategy);
+            registry.providers.insert(group.get_name().to_string(), Box::new(group));
+        }
+        Ok(registry)
+    }
+
     /// Register a new provider
     pub fn register(&mut self, provider: Box<dyn LlmProvider>) {
+        // Every call through the registry is counted in the usage metrics (each resumed attempt
+        // separately), and streams cut off by network errors are resumed
+        let provider: Box<dyn LlmProvider> = Box::new(resume::ResumingProvider::new(Box::new(metered::MeteredProvider::new(provider))));
         self.providers.insert(provider.get_name().to_string(), provider);
     }
     
@@ -85,9 +205,62 @@ impl ProviderRegistry {
     pub fn list_providers(&self) -> Vec<&str> {
         self.providers.keys().map(|k| k.as_str()).collect()
     }
+
+    /// Splits `provider:model` at the registered provider name it starts with, so both
+    /// `gpu-box:llama3:latest` and `group:lan:llama3` work; None if no provider name matches.
+    pub fn split_model_spec(&self, spec: &str) -> Option<(String, String)> {
+        let lower = spec.to_lowercase();
+        self.providers.keys()
+            .filter(|name| lower.starts_with(&format!("{}:", name)))
+            .max_by_key(|name| name.len())
+            .and_then(|name| Some((name.clone(), spec.get(name.len() + 1..)?.to_string())))
+            .filter(|(_, model)| !model.is_empty())
+    }
     
 }
 
+/// Provider types that can be configured under `[providers.<type>]` or used as an instance's `type`.
+pub const BUILT_IN_PROVIDERS: &[&str] = &["ollama", "groq", "gemini", "azure", "lmstudio", "vllm", "xai", "perplexity", "openai"];
+
+// Creates a provider of the given type; None for types that are only available with an API key.
+// A configured read timeout wraps the provider, so it bounds every call.
+fn build(kind: &str, settings: &ProviderSettings) -> ReplResult<Option<Box<dyn LlmProvider>>> {
+    fn boxed(provider: impl LlmProvider + 'static) -> Option<Box<dyn LlmProvider>> {
+        Some(Box::new(provider))
+    }
+    let provider = match kind {
+        "ollama" => boxed(ollama::OllamaProvider::with_settings(settings)?),
+        "groq" => boxed(groq::GroqProvider::with_settings(settings)?),
+        "gemini" => boxed(gemini::GeminiProvider::with_settings(settings)?),
+        "azure" => boxed(azure::AzureOpenAiProvider::with_settings(settings)?),
+        "lmstudio" => boxed(lmstudio::LmStudioProvider::with_settings(settings)?),
+        "vllm" => boxed(vllm::VllmProvider::with_settings(settings)?),
+        "xai" => xai::XaiProvider::with_settings(settings)?.and_then(boxed),
+        "perplexity" => perplexity::PerplexityProvider::with_settings(settings)?.and_then(boxed),
+        "openai" => openai::OpenAiProvider::with_settings(settings)?.and_then(boxed),
+        other => return Err(ReplError::Provider(format!("Unknown provider type '{}'; use one of: {}", other, BUILT_IN_PROVIDERS.join(", ")))),
+    };
+    Ok(match settings.read_timeout_secs {
+        Some(secs) => provider.map(|provider| Box::new(timeout::TimeoutProvider::new(provider, Duration::from_secs(secs))) as Box<dyn LlmProvider>),
+        None => provider,
+    })
+}
+
+/// The HTTP client for a provider, with its configured connect timeout.
+pub fn http_client(settings: &ProviderSettings) -> ReplResult<Client> {
+    let mut builder = Client::builder();
+    if let Some(secs) = settings.connect_timeout_secs {
+        builder = builder.connect_timeout(Duration::from_secs(secs));
+    }
+    Ok(builder.build()?)
+}
+
+/// Parses a configured base URL, adding the trailing slash that relative endpoint paths need.
+pub fn parse_base_url(provider: &str, base_url: &str) -> ReplResult<Url> {
+    let base_url = if base_url.ends_with('/') { base_url.to_string() } else { format!("{}/", base_url) };
+    Url::parse(&base_url).map_err(|e| ReplError::Provider(format!("Invalid {} base URL '{}': {}", provider, base_url, e)))
+}
+
 impl Default for ProviderRegistry {
     fn default() -> Self {
         Self::new()
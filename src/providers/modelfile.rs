@@ -0,0 +1,164 @@
+// src/providers/modelfile.rs
+//! Ollama Modelfiles (`FROM`, `PARAMETER`, `SYSTEM`, ...) read into the fields of an `api/create`
+//! request, which takes those fields rather than the Modelfile's text (`/ollama create`).
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::error::{ReplError, ReplResult};
+
+/// What a Modelfile's `FROM` names: a model Ollama has, or a local weights file (e.g. GGUF) that
+/// is uploaded before the model is created.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaseModel {
+    Model(String),
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Modelfile {
+    pub from: Option<BaseModel>,
+    /// LoRA adapter files (`ADAPTER`), uploaded like local weights.
+    pub adapters: Vec<PathBuf>,
+    pub system: Option<String>,
+    pub template: Option<String>,
+    pub license: Vec<String>,
+    /// `PARAMETER` values as numbers, booleans or text; `stop` collects its values in a list.
+    pub parameters: Map<String, Value>,
+    /// `MESSAGE` lines, as `{ role, content }`.
+    pub messages: Vec<Value>,
+}
+
+// A quoted value without its quotes: `"""multi-line"""` or `"text"`.
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if let Some(inner) = value.strip_prefix("\"\"\"").and_then(|rest| rest.strip_suffix("\"\"\"")) {
+        return inner.to_string();
+    }
+    match value.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        Some(inner) if value.len() >= 2 => inner.to_string(),
+        _ => value.to_string(),
+    }
+}
+
+// Parameter values are sent typed, as Ollama's options expect them.
+fn typed(value: &str) -> Value {
+    if let Ok(number) = value.parse::<i64>() {
+        return Value::from(number);
+    }
+    if let Ok(number) = value.parse::<f64>() {
+        return Value::from(number);
+    }
+    match value {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::from(value),
+    }
+}
+
+// `FROM` and `ADAPTER` paths are relative to the Modelfile, like `ollama create` reads them.
+fn local_path(value: &str, dir: &Path) -> Option<PathBuf> {
+    let path = Path::new(value);
+    let path = if path.is_absolute() { path.to_path_buf() } else { dir.join(path) };
+    path.exists().then_some(path)
+}
+
+impl Modelfile {
+    /// Parses a Modelfile whose relative paths are resolved against `dir`. Instructions are
+    /// case-insensitive and `"""` values may span lines; unknown instructions are errors.
+    pub fn parse(text: &str, dir: &Path) -> ReplResult<Self> {
+        let mut modelfile = Modelfile::default();
+        let mut lines = text.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| ReplError::Command(format!("Modelfile line {}: {}", index + 1, message));
+            let (instruction, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let mut argument = rest.trim().to_string();
+            // An opening """ without its closing one continues on the following lines
+            if argument.matches("\"\"\"").count() == 1 {
+                loop {
+                    let (_, next) = lines.next().ok_or_else(|| error("unterminated \"\"\" quote"))?;
+                    argument.push('\n');
+                    argument.push_str(next);
+                    if next.contains("\"\"\"") {
+                        break;
+                    }
+                }
+            }
+            match instruction.to_uppercase().as_str() {
+                "FROM" => {
+                    let from = unquote(&argument);
+                    modelfile.from = Some(match local_path(&from, dir) {
+                        Some(path) if path.is_file() => BaseModel::File(path),
+                        Some(path) => return Err(error(&format!("'{}' is a directory; only single weights files (e.g. GGUF) can be imported", path.display()))),
+                        None => BaseModel::Model(from),
+                    });
+                }
+                "ADAPTER" => {
+                    let adapter = unquote(&argument);
+                    let path = local_path(&adapter, dir).filter(|path| path.is_file())
+                        .ok_or_else(|| error(&format!("adapter file '{}' not found", adapter)))?;
+                    modelfile.adapters.push(path);
+                }
+                "SYSTEM" => modelfile.system = Some(unquote(&argument)),
+                "TEMPLATE" => modelfile.template = Some(unquote(&argument)),
+                "LICENSE" => modelfile.license.push(unquote(&argument)),
+                "PARAMETER" => {
+                    let (name, value) = argument.split_once(char::is_whitespace).ok_or_else(|| error("PARAMETER needs a name and a value"))?;
+                    let value = unquote(value);
+                    if name == "stop" {
+                        if let Value::Array(stops) = modelfile.parameters.entry("stop").or_insert_with(|| json!([])) {
+                            stops.push(Value::from(value));
+                        }
+                    } else {
+                        modelfile.parameters.insert(name.to_string(), typed(&value));
+                    }
+                }
+                "MESSAGE" => {
+                    let (role, content) = argument.split_once(char::is_whitespace).ok_or_else(|| error("MESSAGE needs a role and its text"))?;
+                    if !["system", "user", "assistant"].contains(&role) {
+                        return Err(error(&format!("unknown MESSAGE role '{}'; use system, user or assistant", role)));
+                    }
+                    modelfile.messages.push(json!({ "role": role, "content": unquote(content) }));
+                }
+                other => return Err(error(&format!("unknown instruction '{}'", other))),
+            }
+        }
+        if modelfile.from.is_none() {
+            return Err(ReplError::Command("The Modelfile has no FROM line naming the base model or weights file.".to_string()));
+        }
+        Ok(modelfile)
+    }
+
+    /// The `api/create` body for a model called `name`. Local weights and adapters are referred to
+    /// by the digests of their uploaded blobs, keyed by file name.
+    pub fn create_body(&self, name: &str, files: &Map<String, Value>, adapters: &Map<String, Value>) -> Value {
+        let mut body = json!({ "model": name, "stream": true });
+        match &self.from {
+            Some(BaseModel::Model(model)) => body["from"] = Value::from(model.as_str()),
+            Some(BaseModel::File(_)) => body["files"] = Value::Object(files.clone()),
+            None => {}
+        }
+        if !adapters.is_empty() {
+            body["adapters"] = Value::Object(adapters.clone());
+        }
+        if let Some(system) = &self.system {
+            body["system"] = Value::from(system.as_str());
+        }
+        if let Some(template) = &self.template {
+            body["template"] = Value::from(template.as_str());
+        }
+        if !self.license.is_empty() {
+            body["license"] = json!(self.license);
+        }
+        if !self.parameters.is_empty() {
+            body["parameters"] = Value::Object(self.parameters.clone());
+        }
+        if !self.messages.is_empty() {
+            body["messages"] = Value::Array(self.messages.clone());
+        }
+        body
+    }
+}
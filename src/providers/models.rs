@@ -0,0 +1,114 @@
+// src/providers/models.rs
+//! What `get_models` reports about a model: its id plus whatever the provider's model list says
+//! about it (context window, the inputs it takes, who publishes it). Anything the list doesn't
+//! say is left unknown rather than guessed, except the input types, which fall back to the id.
+use serde::Serialize;
+
+/// The kinds of input a model takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Modalities {
+    pub text: bool,
+    pub image: bool,
+    pub audio: bool,
+    /// An embedding model (for /embed, /rag), not one to chat with.
+    pub embedding: bool,
+}
+
+impl Default for Modalities {
+    fn default() -> Self {
+        Self { text: true, image: false, audio: false, embedding: false }
+    }
+}
+
+impl Modalities {
+    /// Guesses from a model id, for providers whose lists don't say: `embed` in the name means an
+    /// embedding model, `vision`, `llava` or `-vl` an image model, `whisper` a speech model.
+    pub fn from_id(id: &str) -> Self {
+        let id = id.to_lowercase();
+        if id.contains("embed") {
+            return Self { text: true, image: false, audio: false, embedding: true };
+        }
+        if id.contains("whisper") {
+            return Self { text: false, image: false, audio: true, embedding: false };
+        }
+        let image = ["vision", "llava", "-vl", "gpt-4o", "gpt-4.1", "gemini", "llama-4"].iter().any(|marker| id.contains(marker));
+        Self { image, ..Self::default() }
+    }
+
+    /// The inputs besides text, e.g. `["image"]`; `["embedding"]` for embedding models.
+    pub fn labels(&self) -> Vec<&'static str> {
+        let mut labels = Vec::new();
+        if self.embedding {
+            labels.push("embedding");
+        }
+        if self.image {
+            labels.push("image");
+        }
+        if self.audio {
+            labels.push("audio");
+        }
+        if !self.text {
+            labels.push("no text");
+        }
+        labels
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModelInfo {
+    /// The name to pass to `/model` and the provider's API.
+    pub id: String,
+    /// Input tokens the model accepts, if the provider reports it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+    pub modalities: Modalities,
+    /// Who publishes the model (e.g. "meta", "openai"), if the provider reports it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+impl ModelInfo {
+    /// A model known only by its id; its input types are guessed from the id.
+    pub fn new(id: impl Into<String>) -> Self {
+        let id = id.into();
+        Self { modalities: Modalities::from_id(&id), id, context_window: None, owner: None }
+    }
+
+    pub fn with_context_window(mut self, context_window: Option<u64>) -> Self {
+        self.context_window = context_window;
+        self
+    }
+
+    pub fn with_owner(mut self, owner: Option<String>) -> Self {
+        self.owner = owner.filter(|owner| !owner.is_empty());
+        self
+    }
+
+    pub fn with_modalities(mut self, modalities: Modalities) -> Self {
+        self.modalities = modalities;
+        self
+    }
+
+    /// The known metadata on one line, e.g. "128k context · image · meta"; empty if none is known.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(tokens) = self.context_window {
+            // Windows are given both as 131072 and as 128000
+            let thousands = if tokens % 1024 == 0 { tokens / 1024 } else { tokens / 1000 };
+            parts.push(if thousands > 0 { format!("{}k context", thousands) } else { format!("{} context", tokens) });
+        }
+        parts.extend(self.modalities.labels().into_iter().map(String::from));
+        if let Some(owner) = &self.owner {
+            parts.push(owner.clone());
+        }
+        parts.join(" · ")
+    }
+
+    /// The id followed by the summary, for pickers: "llama-3.3-70b (128k context · meta)".
+    pub fn label(&self) -> String {
+        match self.summary() {
+            summary if summary.is_empty() => self.id.clone(),
+            summary => format!("{} ({})", self.id, summary),
+        }
+    }
+}
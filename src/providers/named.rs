@@ -0,0 +1,77 @@
+// src/providers/named.rs
+//! Gives a provider instance declared in the config (e.g. `[providers.gpu-box] type = "ollama"`)
+//! its own name, so it can be selected, listed and metered separately from the built-in one.
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+
+use crate::error::ReplResult;
+use crate::providers::{ChatMessage, GenerationParams, ImageAttachment, LlmProvider, ModelInfo, TokenCount};
+
+pub struct NamedProvider {
+    name: String,
+    inner: Box<dyn LlmProvider>,
+}
+
+impl NamedProvider {
+    pub fn new(name: &str, inner: Box<dyn LlmProvider>) -> Self {
+        Self { name: name.to_string(), inner }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for NamedProvider {
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        self.inner.chat(model, messages, params).await
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.inner.chat_stream(model, messages, params).await
+    }
+
+    async fn chat_with_images(&self, model: &str, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> ReplResult<String> {
+        self.inner.chat_with_images(model, messages, images, params).await
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        self.inner.get_models().await
+    }
+
+    async fn embed(&self, model: &str, texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
+        self.inner.embed(model, texts).await
+    }
+
+    async fn count_tokens(&self, model: &str, messages: &[ChatMessage]) -> ReplResult<TokenCount> {
+        self.inner.count_tokens(model, messages).await
+    }
+
+    async fn check_readiness(&self) -> ReplResult<()> {
+        self.inner.check_readiness().await
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(NamedProvider::new(&self.name, self.inner.clone_box()))
+    }
+}
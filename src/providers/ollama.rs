@@ -1,24 +1,53 @@
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::pin::Pin;
 use async_trait::async_trait;
 use futures::{Stream, StreamExt};
 use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Map, Value};
+use lazy_static::lazy_static;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
 use url::Url;
 
 
+use crate::config::ProviderSettings;
 use crate::error::{ReplError, ReplResult};
-use super::LlmProvider;
-
-#[derive(Serialize, Deserialize, Debug)]
-struct OllamaResponse {
-    response: String,
-}
+use crate::httplog::SendLogged;
+use crate::usage;
+use super::modelfile::{BaseModel, Modelfile};
+use super::{http_client, parse_base_url, ChatMessage, GenerationParams, ImageAttachment, LlmProvider, Modalities, ModelInfo as ListedModel};
 
 #[derive(Serialize, Deserialize, Debug)]
 struct OllamaModel {
     name: String,
-    
+    details: Option<OllamaModelDetails>,
+}
+
+// The architecture of a local model, as `api/tags` reports it.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct OllamaModelDetails {
+    family: Option<String>,
+    families: Option<Vec<String>>,
+}
+
+impl OllamaModel {
+    // Vision models carry a `clip` (or `mllama`) projector family; embedding models are BERT-based.
+    fn into_listed(self) -> ListedModel {
+        let details = self.details.unwrap_or_default();
+        let families: Vec<String> = details.family.into_iter().chain(details.families.unwrap_or_default()).collect();
+        let mut modalities = Modalities::from_id(&self.name);
+        modalities.image |= families.iter().any(|family| family == "clip" || family == "mllama");
+        modalities.embedding |= families.iter().any(|family| family.contains("bert"));
+        ListedModel::new(self.name).with_modalities(modalities)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbeddingResponse {
+    embedding: Vec<f32>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -26,18 +55,118 @@ struct OllamaListResponse {
     models: Vec<OllamaModel>,
 }
 
+#[derive(Debug, Deserialize)]
+struct OllamaChatMessage {
+    content: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct OllamaResponseChunk {
-    response: Option<String>, 
+    message: Option<OllamaChatMessage>,
     done: bool,
-    model: Option<String>,
-    created_at: Option<String>,
+    // Token counts, in the final chunk
+    prompt_eval_count: Option<u64>,
+    eval_count: Option<u64>,
+}
+
+impl OllamaResponseChunk {
+    // Hands the final chunk's token counts to the usage metrics, then returns the text.
+    fn text(self) -> String {
+        if self.done {
+            if let (Some(prompt), Some(completion)) = (self.prompt_eval_count, self.eval_count) {
+                usage::report_tokens(prompt, completion);
+            }
+        }
+        self.message.and_then(|message| message.content).unwrap_or_default()
+    }
+}
+
+
+/// A status line from `api/pull`: the step (`pulling manifest`, `pulling <digest>`, `verifying
+/// sha256 digest`, `success`) and, while a layer downloads, its size and how much has arrived.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullProgress {
+    pub status: String,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+/// What `api/show` reports about a local model.
+#[derive(Debug, Deserialize)]
+pub struct ModelInfo {
+    #[serde(default)]
+    pub details: ModelDetails,
+    /// Modelfile parameters, one per line (e.g. `stop "<|eot_id|>"`).
+    pub parameters: Option<String>,
+    pub system: Option<String>,
+    pub license: Option<String>,
+    pub modified_at: Option<String>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Architecture details keyed like `llama.context_length`.
+    #[serde(default)]
+    pub model_info: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ModelDetails {
+    pub format: Option<String>,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization_level: Option<String>,
+}
+
+impl ModelInfo {
+    /// The context window from `<architecture>.context_length`, if reported.
+    pub fn context_length(&self) -> Option<u64> {
+        self.model_info.iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+    }
+}
+
+const OLLAMA_DEFAULT_URL: &str = "http://localhost:11434";
+// Read size for hashing and uploading weights files to `api/blobs`.
+const BLOB_CHUNK: usize = 1 << 20;
+
+// An /api/chat request. Options from the config (`num_ctx`, `num_gpu`, ...) go in its `options`
+// with the `/set` parameters, which win where both are set.
+fn chat_body(model: &str, messages: &[ChatMessage], params: &GenerationParams, options: &BTreeMap<String, Value>, keep_alive: Option<&Value>) -> Value {
+    let mut body = json!({
+        "model": model,
+        "messages": messages,
+        "stream": true
+    });
+    let mut merged: Map<String, Value> = options.iter().map(|(name, value)| (name.clone(), value.clone())).collect();
+    merged.extend(params.ollama_options());
+    if !merged.is_empty() {
+        body["options"] = Value::Object(merged);
+    }
+    if let Some(keep_alive) = keep_alive {
+        body["keep_alive"] = keep_alive.clone();
+    }
+    body
 }
 
+/// Checks a `keep_alive` value: seconds (`300`, `-1` to keep the model loaded) or a duration such
+/// as `10m` or `1h30m`. Returns it as Ollama expects it, a number or a string.
+pub fn parse_keep_alive(text: &str) -> ReplResult<Value> {
+    lazy_static! {
+        static ref DURATION: Regex = Regex::new(r"^-?(\d+(\.\d+)?(ms|s|m|h))+$").expect("duration pattern is valid");
+    }
+    let text = text.trim();
+    if let Ok(seconds) = text.parse::<i64>() {
+        return Ok(Value::from(seconds));
+    }
+    if DURATION.is_match(text) {
+        return Ok(Value::from(text));
+    }
+    Err(ReplError::Provider(format!("Invalid keep_alive '{}': use seconds (e.g. 300, -1 to keep the model loaded) or a duration such as 10m or 1h", text)))
+}
 
 impl Default for OllamaProvider {
     fn default() -> Self {
-        Self::new("http://localhost:11434").expect("Failed to create default Ollama provider")
+        Self::new(OLLAMA_DEFAULT_URL).expect("Failed to create default Ollama provider")
     }
 }
 
@@ -50,19 +179,215 @@ impl OllamaProvider {
         Ok(Self {
             client: Client::new(),
             base_url,
+            options: BTreeMap::new(),
+            keep_alive: None,
         })
     }
 
+    /// Create the provider with config overrides (Ollama needs no API key)
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Self> {
+        let base_url = parse_base_url("Ollama", settings.base_url.as_deref().unwrap_or(OLLAMA_DEFAULT_URL))?;
+        let keep_alive = settings.keep_alive.as_deref().map(parse_keep_alive).transpose()?;
+        Ok(Self { client: http_client(settings)?, base_url, options: settings.params.clone(), keep_alive })
+    }
+
     fn build_url(&self, endpoint: &str) -> Result<Url, ReplError> {
         self.base_url.join(endpoint)
             .map_err(|e| ReplError::Provider(format!("Failed to build URL: {}", e)))
     }
 
-    async fn fetch_models_from_api(&self) -> ReplResult<Vec<String>> {
+    // Passes successful responses through; a 404 means the model isn't there.
+    async fn model_response(response: Response, model: &str) -> ReplResult<Response> {
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(ReplError::Provider(format!("Model '{}' not found on Ollama", model)));
+        }
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(ReplError::Provider(format!("Ollama API returned an error: {} - {}", status, error_body)));
+        }
+        Ok(response)
+    }
+
+    // One line of an `api/pull` or `api/create` stream; None for blank lines.
+    fn status_line(line: &[u8], action: &str) -> ReplResult<Option<PullProgress>> {
+        let line = String::from_utf8_lossy(line);
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        let value: Value = serde_json::from_str(line.trim())?;
+        if let Some(error) = value.get("error").and_then(|error| error.as_str()) {
+            return Err(ReplError::Provider(format!("Ollama could not {} the model: {}", action, error)));
+        }
+        Ok(Some(serde_json::from_value(value)?))
+    }
+
+    // Passes each status line of a streamed `api/pull` or `api/create` response to `on_progress`;
+    // true if the last one was `success`.
+    async fn stream_status(response: Response, action: &str, mut on_progress: impl FnMut(&PullProgress) + Send) -> ReplResult<bool> {
+        let mut stream = response.bytes_stream();
+
+        // Status lines can be split across chunks, so they are only parsed once complete
+        let mut pending = Vec::new();
+        let mut succeeded = false;
+        let mut handle = |line: &[u8]| -> ReplResult<()> {
+            if let Some(progress) = Self::status_line(line, action)? {
+                succeeded = progress.status == "success";
+                on_progress(&progress);
+            }
+            Ok(())
+        };
+        while let Some(chunk) = stream.next().await {
+            pending.extend_from_slice(&chunk?);
+            while let Some(end) = pending.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                handle(&line)?;
+            }
+        }
+        handle(&pending)?;
+        Ok(succeeded)
+    }
+
+    /// Downloads `model` from the Ollama library (`api/pull`), passing each status line to
+    /// `on_progress` as it arrives.
+    pub async fn pull_model(&self, model: &str, on_progress: impl FnMut(&PullProgress) + Send) -> ReplResult<()> {
+        let url = self.build_url("api/pull")?;
+        let response = self.client
+            .post(url)
+            .json(&json!({ "model": model, "stream": true }))
+            .send_logged("ollama")
+            .await?;
+        let response = Self::model_response(response, model).await?;
+        if Self::stream_status(response, "pull", on_progress).await? {
+            Ok(())
+        } else {
+            Err(ReplError::Provider(format!("Ollama stopped pulling '{}' before it finished", model)))
+        }
+    }
+
+    /// Uploads a local file as a blob (`api/blobs/sha256:<digest>`) unless Ollama already has it,
+    /// and returns the digest that `api/create` refers to it by.
+    pub async fn push_blob(&self, path: &Path) -> ReplResult<String> {
+        // Weights files run to gigabytes, so they are hashed and sent in chunks rather than read whole
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; BLOB_CHUNK];
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        let digest = format!("sha256:{:x}", hasher.finalize());
+        let url = self.build_url(&format!("api/blobs/{}", digest))?;
+        if self.client.head(url.clone()).send_logged("ollama").await?.status().is_success() {
+            return Ok(digest);
+        }
+
+        let file = tokio::fs::File::open(path).await?;
+        let chunks = futures::stream::unfold(file, |mut file| async move {
+            let mut buffer = vec![0u8; BLOB_CHUNK];
+            match file.read(&mut buffer).await {
+                Ok(0) => None,
+                Ok(read) => {
+                    buffer.truncate(read);
+                    Some((Ok::<_, std::io::Error>(buffer), file))
+                }
+                Err(error) => Some((Err(error), file)),
+            }
+        });
+        let response = self.client
+            .post(url)
+            .body(reqwest::Body::wrap_stream(chunks))
+            .send_logged("ollama")
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(ReplError::Provider(format!("Ollama rejected the upload of {}: {} - {}", path.display(), status, error_body)));
+        }
+        Ok(digest)
+    }
+
+    /// Creates `name` from a Modelfile (`api/create`), first uploading any local weights and
+    /// adapters it names, and passes each status line to `on_progress` as it arrives.
+    pub async fn create_model(&self, name: &str, modelfile: &Modelfile, mut on_progress: impl FnMut(&PullProgress) + Send) -> ReplResult<()> {
+        let mut upload = |path: &Path| {
+            on_progress(&PullProgress { status: format!("uploading {}", path.display()), total: None, completed: None });
+        };
+        let file_name = |path: &Path| path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let mut files = Map::new();
+        if let Some(BaseModel::File(path)) = &modelfile.from {
+            upload(path);
+            files.insert(file_name(path), Value::from(self.push_blob(path).await?));
+        }
+        let mut adapters = Map::new();
+        for path in &modelfile.adapters {
+            upload(path);
+            adapters.insert(file_name(path), Value::from(self.push_blob(path).await?));
+        }
+
+        let url = self.build_url("api/create")?;
+        let response = self.client
+            .post(url)
+            .json(&modelfile.create_body(name, &files, &adapters))
+            .send_logged("ollama")
+            .await?;
+        // A 404 here is about the base model, not the one being created
+        let base = match &modelfile.from {
+            Some(BaseModel::Model(model)) => model.as_str(),
+            _ => name,
+        };
+        let response = Self::model_response(response, base).await?;
+        if Self::stream_status(response, "create", on_progress).await? {
+            Ok(())
+        } else {
+            Err(ReplError::Provider(format!("Ollama stopped creating '{}' before it finished", name)))
+        }
+    }
+
+    /// Deletes a local model (`api/delete`).
+    pub async fn delete_model(&self, model: &str) -> ReplResult<()> {
+        let url = self.build_url("api/delete")?;
+        let response = self.client
+            .delete(url)
+            .json(&json!({ "model": model }))
+            .send_logged("ollama")
+            .await?;
+        Self::model_response(response, model).await?;
+        Ok(())
+    }
+
+    /// Details of a local model (`api/show`): family, size, quantization, parameters, license.
+    pub async fn show_model(&self, model: &str) -> ReplResult<ModelInfo> {
+        let url = self.build_url("api/show")?;
+        let response = self.client
+            .post(url)
+            .json(&json!({ "model": model }))
+            .send_logged("ollama")
+            .await?;
+        Ok(Self::model_response(response, model).await?.json::<ModelInfo>().await?)
+    }
+
+    /// Loads `model` into memory without generating anything (an `api/chat` request without
+    /// messages), which also restarts its `keep_alive` timer.
+    pub async fn load_model(&self, model: &str) -> ReplResult<()> {
+        let url = self.build_url("api/chat")?;
+        let mut body = json!({ "model": model, "messages": [], "stream": false });
+        if let Some(keep_alive) = &self.keep_alive {
+            body["keep_alive"] = keep_alive.clone();
+        }
+        let response = self.client.post(url).json(&body).send_logged("ollama").await?;
+        Self::model_response(response, model).await?;
+        Ok(())
+    }
+
+    async fn fetch_models_from_api(&self) -> ReplResult<Vec<ListedModel>> {
         let url = self.build_url("api/tags")?;
         let response: Response = self.client
             .get(url)
-            .send()
+            .send_logged("ollama")
             .await
             .map_err(|e| ReplError::Provider(format!("Failed to send request to Ollama: {}", e)))?;
 
@@ -75,25 +400,16 @@ impl OllamaProvider {
 
         let ollama_response = response.json::<OllamaListResponse>().await.map_err(|e| ReplError::Provider(format!("Failed to parse Ollama API response: {}", e)))?;
 
-        let model_names = ollama_response.models.into_iter().map(|model| model.name).collect();
-        Ok(model_names)
+        Ok(ollama_response.models.into_iter().map(OllamaModel::into_listed).collect())
     }
-}
-
-#[async_trait]
-impl LlmProvider for OllamaProvider {
-    async fn query(&self, model: &str, prompt: &str) -> ReplResult<String> {
-        let url = self.build_url("api/generate")?;
-        let body = json!({
-            "model": model,
-            "prompt": prompt,
-            "stream": true
-        });
 
+    // Posts an /api/chat request and collects the streamed answer.
+    async fn collect_chat(&self, body: &Value) -> ReplResult<String> {
+        let url = self.build_url("api/chat")?;
         let response = self.client
             .post(url)
-            .json(&body)
-            .send()
+            .json(body)
+            .send_logged("ollama")
             .await?;
 
         let status = response.status(); // Get the status code here
@@ -111,10 +427,9 @@ impl LlmProvider for OllamaProvider {
             if !chunk.is_empty() {
                 match serde_json::from_slice::<OllamaResponseChunk>(&chunk) {
                     Ok(response_part) => {
-                        if let Some(response) = response_part.response {
-                            full_response.push_str(&response);
-                        }
-                        if response_part.done {
+                        let done = response_part.done;
+                        full_response.push_str(&response_part.text());
+                        if done {
                             break;
                         }
                     }
@@ -128,22 +443,51 @@ impl LlmProvider for OllamaProvider {
 
         Ok(full_response)
     }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
     async fn query_stream(
         &self,
         model: &str,
         prompt: &str,
+        params: &GenerationParams,
     ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
-        let url = self.build_url("api/generate")?;
-        let body = json!({
-            "model": model,
-            "prompt": prompt,
-            "stream": true
-        });
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let body = chat_body(model, messages, params, &self.options, self.keep_alive.as_ref());
+        self.collect_chat(&body).await
+    }
+
+    // Images go in the last message's `images`, as base64 without a data: prefix (e.g. for llava)
+    async fn chat_with_images(&self, model: &str, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> ReplResult<String> {
+        let mut body = chat_body(model, messages, params, &self.options, self.keep_alive.as_ref());
+        let encoded: Vec<&str> = images.iter().map(|image| image.data.as_str()).collect();
+        match body["messages"].as_array_mut().and_then(|messages| messages.last_mut()) {
+            Some(last) => last["images"] = json!(encoded),
+            None => body["messages"] = json!([{ "role": "user", "content": "", "images": encoded }]),
+        }
+        self.collect_chat(&body).await
+    }
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let url = self.build_url("api/chat")?;
+        let body = chat_body(model, messages, params, &self.options, self.keep_alive.as_ref());
 
         let response = self.client
             .post(url)
             .json(&body)
-            .send()
+            .send_logged("ollama")
             .await?;
 
         let stream = response
@@ -154,14 +498,32 @@ impl LlmProvider for OllamaProvider {
                         .map_err(|e| ReplError::Json(serde_json::Error::io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))))?;
                     let chunk: OllamaResponseChunk = serde_json::from_str(&s)
                         .map_err(|e| ReplError::Json(e))?;
-                    Ok(chunk.response.unwrap_or_default())
+                    Ok(chunk.text())
                 }
                 Err(e) => Err(ReplError::Request(e)),
             });
 
         Ok(Some(Box::pin(stream)))
     }
-    async fn get_models(&self) -> ReplResult<Vec<String>> {
+    // `api/embeddings` takes one text per request
+    async fn embed(&self, model: &str, texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
+        let url = self.build_url("api/embeddings")?;
+        let mut vectors = Vec::with_capacity(texts.len());
+        for text in texts {
+            let mut body = json!({ "model": model, "prompt": text });
+            if let Some(keep_alive) = &self.keep_alive {
+                body["keep_alive"] = keep_alive.clone();
+            }
+            let response = self.client.post(url.clone()).json(&body).send_logged("ollama").await?;
+            let response = Self::model_response(response, model).await?.json::<OllamaEmbeddingResponse>().await?;
+            if response.embedding.is_empty() {
+                return Err(ReplError::Provider(format!("Ollama returned no embedding; is '{}' an embedding model?", model)));
+            }
+            vectors.push(response.embedding);
+        }
+        Ok(vectors)
+    }
+    async fn get_models(&self) -> ReplResult<Vec<ListedModel>> {
         self.fetch_models_from_api().await
     }
 
@@ -177,4 +539,7 @@ impl LlmProvider for OllamaProvider {
 pub struct OllamaProvider {
     client: Client,
     base_url: Url,
+    // `options` and `keep_alive` from `[providers.<name>]`
+    options: BTreeMap<String, Value>,
+    keep_alive: Option<Value>,
 }
\ No newline at end of file
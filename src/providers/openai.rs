@@ -0,0 +1,120 @@
+// src/providers/openai.rs
+//! OpenAI's chat completions API, or any proxy or gateway that speaks it (set `base_url`). The
+//! built-in `openai` provider is registered when `OPENAI_API_KEY` (or `[providers.openai]`
+//! `api_key` or `base_url`) is set; named instances with `type = "openai"` use their own settings.
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::{Client, RequestBuilder, Response};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::pin::Pin;
+use url::Url;
+
+use crate::config::ProviderSettings;
+use crate::error::{ReplError, ReplResult};
+use crate::httplog::SendLogged;
+use super::{
+    groq::sse_text_stream,
+    openai_compat::{chat_body, ChatResponse, ModelList},
+    http_client, parse_base_url, ChatMessage, GenerationParams, LlmProvider, ModelInfo,
+};
+
+const OPENAI_DEFAULT_URL: &str = "https://api.openai.com/v1/";
+
+#[derive(Debug, Clone)]
+pub struct OpenAiProvider {
+    client: Client,
+    base_url: Url,
+    api_key: Option<String>, // Proxies inside a corporate network may not need one
+    params: BTreeMap<String, Value>,
+}
+
+impl OpenAiProvider {
+    /// Creates the provider from its settings, falling back to the OPENAI_API_KEY and
+    /// OPENAI_BASE_URL environment variables; None when neither a key nor a URL is set.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Option<Self>> {
+        let from_env = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let api_key = settings.api_key.clone().or_else(|| from_env("OPENAI_API_KEY"));
+        let base_url = settings.base_url.clone().or_else(|| from_env("OPENAI_BASE_URL"));
+        if api_key.is_none() && base_url.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            client: http_client(settings)?,
+            base_url: parse_base_url("OpenAI", base_url.as_deref().unwrap_or(OPENAI_DEFAULT_URL))?,
+            api_key,
+            params: settings.params.clone(),
+        }))
+    }
+
+    fn build_url(&self, endpoint: &str) -> ReplResult<Url> {
+        self.base_url.join(endpoint)
+            .map_err(|e| ReplError::Provider(format!("Failed to build URL: {}", e)))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> ReplResult<Response> {
+        let request = match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        };
+        let response = request.send_logged("openai").await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(ReplError::Provider(format!("OpenAI API at {} returned an error: {} - {}", self.base_url, status, error_body)));
+        }
+        Ok(response)
+    }
+
+    fn chat_request(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams, stream: bool) -> ReplResult<RequestBuilder> {
+        let body = chat_body(model, messages, stream, params, &self.params);
+        Ok(self.client.post(self.build_url("chat/completions")?).json(&body))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn get_name(&self) -> &str {
+        "openai"
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        let response = self.send(self.client.get(self.build_url("models")?)).await?;
+        let models = response.json::<ModelList>().await
+            .map_err(|e| ReplError::Provider(format!("Failed to parse OpenAI model list: {}", e)))?;
+        Ok(models.into_models())
+    }
+
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let response = self.send(self.chat_request(model, messages, params, false)?).await?;
+        response.json::<ChatResponse>().await?.into_text("OpenAI")
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let response = self.send(self.chat_request(model, messages, params, true)?).await?;
+        Ok(Some(sse_text_stream(response)))
+    }
+}
@@ -0,0 +1,71 @@
+// src/providers/openai_compat.rs
+//! Request and response shapes shared by servers that speak the OpenAI chat API (LM Studio, vLLM, xAI,
+//! Perplexity).
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::error::{ReplError, ReplResult};
+use super::{ChatMessage, GenerationParams, ModelInfo};
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatResponseMessage {
+    content: Option<String>,
+}
+
+impl ChatResponse {
+    /// The first choice's text.
+    pub(crate) fn into_text(self, provider: &str) -> ReplResult<String> {
+        self.choices.into_iter().next()
+            .and_then(|choice| choice.message.content)
+            .ok_or_else(|| ReplError::Provider(format!("{} returned no content.", provider)))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ModelEntry {
+    id: String,
+    owned_by: Option<String>,
+    // vLLM's context window
+    max_model_len: Option<u64>,
+}
+
+impl ModelList {
+    pub(crate) fn into_models(self) -> Vec<ModelInfo> {
+        self.data.into_iter()
+            .map(|model| ModelInfo::new(model.id).with_context_window(model.max_model_len).with_owner(model.owned_by))
+            .collect()
+    }
+}
+
+/// A chat request with the `/set` sampling parameters. `extra` fields (e.g. from
+/// `[providers.vllm.params]`) are added to the body but can't replace any of those.
+pub(crate) fn chat_body(model: &str, messages: &[ChatMessage], stream: bool, params: &GenerationParams, extra: &BTreeMap<String, Value>) -> Value {
+    let mut body = json!({
+        "model": model,
+        "messages": messages,
+        "stream": stream,
+    });
+    if let Value::Object(fields) = &mut body {
+        fields.extend(params.openai_fields());
+        for (name, value) in extra {
+            fields.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    body
+}
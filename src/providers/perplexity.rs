@@ -0,0 +1,224 @@
+// src/providers/perplexity.rs
+//! Perplexity's search-backed Sonar models (`https://api.perplexity.ai`, OpenAI-compatible chat).
+//! The web sources Perplexity returns as `citations` (titled from its `search_results`) end the
+//! answer as numbered footnotes, matching the `[1]`, `[2]` markers in its text. The provider is only
+//! registered when an API key is set (`PERPLEXITY_API_KEY` or `[providers.perplexity] api_key`).
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
+use reqwest::{Client, RequestBuilder, Response};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::pin::Pin;
+use url::Url;
+
+use crate::config::ProviderSettings;
+use crate::error::{ReplError, ReplResult};
+use crate::httplog::SendLogged;
+use super::{
+    citations::{footnotes, Citation},
+    openai_compat::{chat_body, ChatResponse},
+    http_client, parse_base_url, ChatMessage, GenerationParams, LlmProvider, ModelInfo,
+};
+
+const PERPLEXITY_DEFAULT_URL: &str = "https://api.perplexity.ai/";
+// Perplexity has no model listing endpoint.
+// Perplexity has no model listing endpoint; these are its Sonar models and their context windows.
+const PERPLEXITY_MODELS: &[(&str, u64)] = &[
+    ("sonar", 128_000),
+    ("sonar-pro", 200_000),
+    ("sonar-reasoning", 128_000),
+    ("sonar-reasoning-pro", 128_000),
+    ("sonar-deep-research", 128_000),
+];
+
+#[derive(Deserialize, Debug)]
+struct PerplexityResponse {
+    #[serde(flatten)]
+    chat: ChatResponse,
+    #[serde(default)]
+    citations: Vec<String>,
+    #[serde(default)]
+    search_results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PerplexityChunk {
+    #[serde(default)]
+    choices: Vec<PerplexityChunkChoice>,
+    #[serde(default)]
+    citations: Vec<String>,
+    #[serde(default)]
+    search_results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchResult {
+    url: String,
+    title: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PerplexityChunkChoice {
+    delta: PerplexityDelta,
+}
+
+#[derive(Deserialize, Debug)]
+struct PerplexityDelta {
+    content: Option<String>,
+}
+
+/// The cited URLs with the titles of the matching search results; just the search results when
+/// the response has no citations.
+fn sources(citations: &[String], search_results: &[SearchResult]) -> Vec<Citation> {
+    if citations.is_empty() {
+        return search_results.iter().map(|result| Citation::new(&result.url, result.title.as_deref())).collect();
+    }
+    citations.iter()
+        .map(|url| {
+            let title = search_results.iter().find(|result| &result.url == url).and_then(|result| result.title.as_deref());
+            Citation::new(url, title)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct PerplexityProvider {
+    client: Client,
+    base_url: Url,
+    api_key: String,
+}
+
+impl PerplexityProvider {
+    /// Creates the provider from `[providers.perplexity]`, falling back to the PERPLEXITY_API_KEY
+    /// and PERPLEXITY_BASE_URL environment variables; None without an API key.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Option<Self>> {
+        let from_env = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let Some(api_key) = settings.api_key.clone().or_else(|| from_env("PERPLEXITY_API_KEY")) else { return Ok(None) };
+        let base_url = settings.base_url.clone()
+            .or_else(|| from_env("PERPLEXITY_BASE_URL"))
+            .unwrap_or_else(|| PERPLEXITY_DEFAULT_URL.to_string());
+        Ok(Some(Self {
+            client: http_client(settings)?,
+            base_url: parse_base_url("Perplexity", &base_url)?,
+            api_key,
+        }))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> ReplResult<Response> {
+        let response = request.bearer_auth(&self.api_key).send_logged("perplexity").await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(ReplError::Provider(format!("Perplexity API returned an error: {} - {}", status, error_body)));
+        }
+        Ok(response)
+    }
+
+    fn chat_request(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams, stream: bool) -> ReplResult<RequestBuilder> {
+        let url = self.base_url.join("chat/completions")
+            .map_err(|e| ReplError::Provider(format!("Failed to build URL: {}", e)))?;
+        Ok(self.client.post(url).json(&chat_body(model, messages, stream, params, &BTreeMap::new())))
+    }
+}
+
+// Reads the SSE stream, yielding answer text as it arrives and the sources list once it ends.
+// Every chunk repeats the sources found so far, so the last one seen is complete.
+fn answer_stream(response: Response) -> Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>> {
+    struct Reader {
+        bytes: Pin<Box<dyn Stream<Item = reqwest::Result<bytes::Bytes>> + Send>>,
+        buffer: String,
+        sources: Vec<Citation>,
+        done: bool,
+    }
+    let reader = Reader { bytes: Box::pin(response.bytes_stream()), buffer: String::new(), sources: Vec::new(), done: false };
+    Box::pin(stream::unfold(reader, |mut reader| async move {
+        loop {
+            if reader.done {
+                return None;
+            }
+            if let Some(end) = reader.buffer.find("\n\n") {
+                let message: String = reader.buffer.drain(..end + 2).collect();
+                let mut text = String::new();
+                for data in message.lines().filter_map(|line| line.strip_prefix("data:")).map(str::trim) {
+                    if data.is_empty() || data == "[DONE]" {
+                        continue;
+                    }
+                    match serde_json::from_str::<PerplexityChunk>(data) {
+                        Ok(chunk) => {
+                            if !chunk.citations.is_empty() || !chunk.search_results.is_empty() {
+                                reader.sources = sources(&chunk.citations, &chunk.search_results);
+                            }
+                            text.extend(chunk.choices.into_iter().filter_map(|choice| choice.delta.content));
+                        }
+                        Err(e) => return Some((Err(ReplError::Json(e)), reader)),
+                    }
+                }
+                if text.is_empty() {
+                    continue;
+                }
+                return Some((Ok(text), reader));
+            }
+            match reader.bytes.next().await {
+                Some(Ok(bytes)) => reader.buffer.push_str(&String::from_utf8_lossy(&bytes).replace("\r\n", "\n")),
+                Some(Err(e)) => {
+                    reader.done = true;
+                    return Some((Err(ReplError::Request(e)), reader));
+                }
+                None if !reader.buffer.trim().is_empty() => reader.buffer.push_str("\n\n"),
+                None => {
+                    reader.done = true;
+                    let notes = footnotes(&reader.sources);
+                    return (!notes.is_empty()).then(|| (Ok(notes), reader));
+                }
+            }
+        }
+    }))
+}
+
+#[async_trait]
+impl LlmProvider for PerplexityProvider {
+    fn get_name(&self) -> &str {
+        "perplexity"
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        Ok(PERPLEXITY_MODELS.iter()
+            .map(|(id, context_window)| ModelInfo::new(*id).with_context_window(Some(*context_window)).with_owner(Some("perplexity".to_string())))
+            .collect())
+    }
+
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let response = self.send(self.chat_request(model, messages, params, false)?).await?;
+        let answer = response.json::<PerplexityResponse>().await?;
+        let notes = footnotes(&sources(&answer.citations, &answer.search_results));
+        Ok(answer.chat.into_text("Perplexity")? + &notes)
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let response = self.send(self.chat_request(model, messages, params, true)?).await?;
+        Ok(Some(answer_stream(response)))
+    }
+}
@@ -0,0 +1,237 @@
+// src/providers/resume.rs
+//! Resumes streamed answers that die mid-way with a network error: the prompt is sent again with
+//! the partial answer as context and a request to continue, and text the model repeats from the
+//! partial answer is dropped, so the caller sees one uninterrupted answer.
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::error::ReplResult;
+use crate::providers::{ChatMessage, GenerationParams, ImageAttachment, LlmProvider, ModelInfo, TokenCount};
+
+type TextStream = Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>;
+
+const MAX_RESUMES: u32 = 2;
+// Waits grow linearly with each attempt.
+const RESUME_DELAY: Duration = Duration::from_secs(1);
+// How much of the partial answer's end a continuation is checked against for repeats.
+const OVERLAP_WINDOW: usize = 300;
+// Shorter matches are likely coincidence (a shared word), not a repeat.
+const MIN_OVERLAP: usize = 12;
+
+pub struct ResumingProvider {
+    inner: Box<dyn LlmProvider>,
+}
+
+impl ResumingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>) -> Self {
+        Self { inner }
+    }
+}
+
+/// Asks for the rest of an answer that stopped at `partial`.
+pub(crate) fn continuation_prompt(prompt: &str, partial: &str) -> String {
+    format!(
+        "{}\n\n---\nYour answer to this was cut off. This is what you had written:\n\n{}\n\n---\nContinue exactly where it stopped. Do not repeat any of it and do not add an introduction.",
+        prompt, partial
+    )
+}
+
+// Holds back the start of a continuation until it's clear whether it repeats the partial answer,
+// either from the beginning (the model answered again) or from somewhere near its end.
+struct Dedup {
+    partial: String,
+    pending: String,
+}
+
+impl Dedup {
+    fn new(partial: &str) -> Self {
+        Self { partial: partial.to_string(), pending: String::new() }
+    }
+
+    fn tail(&self) -> &str {
+        let partial = self.partial.as_str();
+        partial.char_indices().rev().nth(OVERLAP_WINDOW - 1).map_or(partial, |(start, _)| &partial[start..])
+    }
+
+    /// Adds a chunk; returns the text to show once the repeat (if any) has been decided.
+    fn push(&mut self, text: &str) -> Option<String> {
+        self.pending.push_str(text);
+        self.resolve(false)
+    }
+
+    /// Text still held back when the continuation ends.
+    fn finish(&mut self) -> String {
+        self.resolve(true).unwrap_or_default()
+    }
+
+    fn resolve(&self, ended: bool) -> Option<String> {
+        let pending = self.pending.as_str();
+        if let Some(rest) = pending.strip_prefix(self.partial.as_str()) {
+            return Some(rest.to_string());
+        }
+        if self.partial.starts_with(pending) {
+            return if ended { Some(String::new()) } else { None };
+        }
+        let tail = self.tail();
+        let may_still_overlap = tail.char_indices().any(|(start, _)| tail.len() - start > pending.len() && tail[start..].starts_with(pending));
+        if may_still_overlap && !ended {
+            return None;
+        }
+        let overlap = pending.char_indices().map(|(end, _)| end).chain([pending.len()])
+            .filter(|&end| end >= MIN_OVERLAP && tail.ends_with(&pending[..end]))
+            .max()
+            .unwrap_or(0);
+        Some(pending[overlap..].to_string())
+    }
+}
+
+/// Drops the start of a continuation stream where it repeats `partial`.
+pub(crate) fn skip_repeat(stream: TextStream, partial: &str) -> TextStream {
+    let dedup = Some(Dedup::new(partial));
+    Box::pin(stream::unfold((stream, dedup), |(mut stream, mut dedup)| async move {
+        loop {
+            match stream.next().await {
+                Some(Ok(text)) => {
+                    let text = match &mut dedup {
+                        Some(pending) => match pending.push(&text) {
+                            Some(text) => {
+                                dedup = None;
+                                text
+                            }
+                            None => continue,
+                        },
+                        None => text,
+                    };
+                    if !text.is_empty() {
+                        return Some((Ok(text), (stream, dedup)));
+                    }
+                }
+                Some(Err(e)) => return Some((Err(e), (stream, dedup))),
+                None => {
+                    let rest = dedup.take().map(|mut pending| pending.finish()).unwrap_or_default();
+                    // The finished stream mustn't be polled again
+                    let done: TextStream = Box::pin(stream::empty());
+                    return (!rest.is_empty()).then(|| (Ok(rest), (done, None)));
+                }
+            }
+        }
+    }))
+}
+
+/// Drops the start of a complete continuation where it repeats `partial`.
+pub(crate) fn strip_repeat(partial: &str, continuation: &str) -> String {
+    let mut dedup = Dedup::new(partial);
+    dedup.push(continuation).unwrap_or_else(|| dedup.finish())
+}
+
+struct Resume {
+    provider: Box<dyn LlmProvider>,
+    model: String,
+    messages: Vec<ChatMessage>,
+    params: GenerationParams,
+    stream: TextStream,
+    // Everything passed on to the caller so far.
+    shown: String,
+    resumes: u32,
+}
+
+impl Resume {
+    async fn next(mut self) -> Option<(ReplResult<String>, Self)> {
+        loop {
+            match self.stream.next().await {
+                Some(Ok(text)) => {
+                    self.shown.push_str(&text);
+                    return Some((Ok(text), self));
+                }
+                Some(Err(e)) if e.is_transient() && self.resumes < MAX_RESUMES => {
+                    self.resumes += 1;
+                    eprintln!("\n[Connection lost ({}); resuming the answer, attempt {}/{}...]", e, self.resumes, MAX_RESUMES);
+                    tokio::time::sleep(RESUME_DELAY * self.resumes).await;
+                    // The request to continue goes into the last message; earlier ones are resent as they were
+                    let mut messages = self.messages.clone();
+                    if let Some(last) = messages.last_mut().filter(|_| !self.shown.is_empty()) {
+                        last.content = continuation_prompt(&last.content, &self.shown);
+                    }
+                    self.stream = match self.provider.chat_stream(&self.model, &messages, &self.params).await {
+                        Ok(Some(stream)) if self.shown.is_empty() => stream,
+                        Ok(Some(stream)) => skip_repeat(stream, &self.shown),
+                        Ok(None) => Box::pin(stream::once(async move { Err(e) })),
+                        // Reopening can fail transiently too; the next turn of the loop decides
+                        Err(reopen) => Box::pin(stream::once(async move { Err(reopen) })),
+                    };
+                }
+                Some(Err(e)) => return Some((Err(e), self)),
+                None => return None,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ResumingProvider {
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        self.inner.chat(model, messages, params).await
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let Some(stream) = self.inner.chat_stream(model, messages, params).await? else { return Ok(None) };
+        let resume = Resume {
+            provider: self.inner.clone_box(),
+            model: model.to_string(),
+            messages: messages.to_vec(),
+            params: *params,
+            stream,
+            shown: String::new(),
+            resumes: 0,
+        };
+        Ok(Some(Box::pin(stream::unfold(resume, Resume::next))))
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        self.inner.get_models().await
+    }
+
+    async fn embed(&self, model: &str, texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
+        self.inner.embed(model, texts).await
+    }
+
+    async fn chat_with_images(&self, model: &str, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> ReplResult<String> {
+        self.inner.chat_with_images(model, messages, images, params).await
+    }
+
+    async fn count_tokens(&self, model: &str, messages: &[ChatMessage]) -> ReplResult<TokenCount> {
+        self.inner.count_tokens(model, messages).await
+    }
+
+    async fn check_readiness(&self) -> ReplResult<()> {
+        self.inner.check_readiness().await
+    }
+
+    fn get_name(&self) -> &str {
+        self.inner.get_name()
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(ResumingProvider::new(self.inner.clone_box()))
+    }
+}
@@ -0,0 +1,102 @@
+// src/providers/timeout.rs
+//! Bounds how long a provider may go without answering (`read_timeout_secs`): a response, the
+//! start of a stream, and each streamed chunk must arrive within it, so a generation that hangs
+//! ends with an error instead of blocking forever. Connect timeouts are set on the HTTP client.
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::error::{ReplError, ReplResult};
+use crate::providers::{ChatMessage, GenerationParams, ImageAttachment, LlmProvider, ModelInfo, TokenCount};
+
+pub struct TimeoutProvider {
+    inner: Box<dyn LlmProvider>,
+    read: Duration,
+}
+
+impl TimeoutProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, read: Duration) -> Self {
+        Self { inner, read }
+    }
+
+    async fn bounded<T>(&self, call: impl Future<Output = ReplResult<T>>) -> ReplResult<T> {
+        tokio::time::timeout(self.read, call).await.unwrap_or_else(|_| Err(timed_out(self.inner.get_name(), self.read)))
+    }
+}
+
+fn timed_out(provider: &str, read: Duration) -> ReplError {
+    ReplError::Timeout(format!("{} sent nothing for {}s (read_timeout_secs)", provider, read.as_secs()))
+}
+
+#[async_trait]
+impl LlmProvider for TimeoutProvider {
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        self.bounded(self.inner.chat(model, messages, params)).await
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let Some(chunks) = self.bounded(self.inner.chat_stream(model, messages, params)).await? else { return Ok(None) };
+        let (provider, read) = (self.inner.get_name().to_string(), self.read);
+        // Ends after reporting the timeout, so a stalled stream isn't polled again
+        let bounded = stream::unfold(Some(chunks), move |chunks| {
+            let provider = provider.clone();
+            async move {
+                let mut chunks = chunks?;
+                match tokio::time::timeout(read, chunks.next()).await {
+                    Ok(Some(chunk)) => Some((chunk, Some(chunks))),
+                    Ok(None) => None,
+                    Err(_) => Some((Err(timed_out(&provider, read)), None)),
+                }
+            }
+        });
+        Ok(Some(Box::pin(bounded)))
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        self.bounded(self.inner.get_models()).await
+    }
+
+    async fn embed(&self, model: &str, texts: &[String]) -> ReplResult<Vec<Vec<f32>>> {
+        self.bounded(self.inner.embed(model, texts)).await
+    }
+
+    async fn chat_with_images(&self, model: &str, messages: &[ChatMessage], images: &[ImageAttachment], params: &GenerationParams) -> ReplResult<String> {
+        self.bounded(self.inner.chat_with_images(model, messages, images, params)).await
+    }
+
+    async fn count_tokens(&self, model: &str, messages: &[ChatMessage]) -> ReplResult<TokenCount> {
+        self.bounded(self.inner.count_tokens(model, messages)).await
+    }
+
+    async fn check_readiness(&self) -> ReplResult<()> {
+        self.bounded(self.inner.check_readiness()).await
+    }
+
+    fn get_name(&self) -> &str {
+        self.inner.get_name()
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(TimeoutProvider::new(self.inner.clone_box(), self.read))
+    }
+}
@@ -0,0 +1,132 @@
+// src/providers/vllm.rs
+//! vLLM's OpenAI-compatible server (`vllm serve`, default `http://localhost:8000`), e.g. on a
+//! self-hosted inference cluster. vLLM's extra sampling parameters (`top_k`, `best_of`,
+//! `repetition_penalty`, ...) come from `[providers.vllm.params]` and are sent with every request.
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::{Client, RequestBuilder, Response};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::pin::Pin;
+use url::Url;
+
+use crate::config::ProviderSettings;
+use crate::error::{ReplError, ReplResult};
+use crate::httplog::SendLogged;
+use super::{
+    groq::sse_text_stream,
+    openai_compat::{chat_body, ChatResponse, ModelList},
+    http_client, parse_base_url, ChatMessage, GenerationParams, LlmProvider, ModelInfo,
+};
+
+const VLLM_DEFAULT_URL: &str = "http://localhost:8000";
+
+#[derive(Debug, Clone)]
+pub struct VllmProvider {
+    client: Client,
+    base_url: Url,
+    api_key: Option<String>, // Only needed when the server runs with --api-key
+    params: BTreeMap<String, Value>,
+}
+
+impl VllmProvider {
+    /// Creates the provider from `[providers.vllm]`, falling back to the VLLM_BASE_URL and
+    /// VLLM_API_KEY environment variables.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Self> {
+        let from_env = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let base_url = settings.base_url.clone()
+            .or_else(|| from_env("VLLM_BASE_URL"))
+            .unwrap_or_else(|| VLLM_DEFAULT_URL.to_string());
+        Ok(Self {
+            client: http_client(settings)?,
+            base_url: parse_base_url("vLLM", &base_url)?,
+            api_key: settings.api_key.clone().or_else(|| from_env("VLLM_API_KEY")),
+            params: settings.params.clone(),
+        })
+    }
+
+    fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        }
+    }
+
+    fn build_url(&self, endpoint: &str) -> ReplResult<Url> {
+        self.base_url.join(endpoint)
+            .map_err(|e| ReplError::Provider(format!("Failed to build URL: {}", e)))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> ReplResult<Response> {
+        let response = self.request(request).send_logged("vllm").await.map_err(|e| {
+            ReplError::Provider(format!("Failed to reach vLLM at {}: {}", self.base_url, e))
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(ReplError::Provider(format!("vLLM returned an error: {} - {}", status, error_body)));
+        }
+        Ok(response)
+    }
+
+    fn chat_request(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams, stream: bool) -> ReplResult<RequestBuilder> {
+        let body = chat_body(model, messages, stream, params, &self.params);
+        Ok(self.client.post(self.build_url("v1/chat/completions")?).json(&body))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for VllmProvider {
+    fn get_name(&self) -> &str {
+        "vllm"
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+
+    async fn check_readiness(&self) -> ReplResult<()> {
+        self.get_models().await.map(|_| ())
+    }
+
+    /// The models the server was started with (usually one).
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        let response = self.send(self.client.get(self.build_url("v1/models")?)).await?;
+        let models = response.json::<ModelList>().await
+            .map_err(|e| ReplError::Provider(format!("Failed to parse vLLM model list: {}", e)))?;
+        Ok(models.into_models())
+    }
+
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let response = self.send(self.chat_request(model, messages, params, false)?).await?;
+        response.json::<ChatResponse>().await?.into_text("vLLM")
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        // best_of can't be streamed; such requests are answered in one piece instead
+        if self.params.get("best_of").and_then(Value::as_u64).is_some_and(|best_of| best_of > 1) {
+            return Ok(None);
+        }
+        let response = self.send(self.chat_request(model, messages, params, true)?).await?;
+        Ok(Some(sse_text_stream(response)))
+    }
+}
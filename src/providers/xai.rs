@@ -0,0 +1,111 @@
+// src/providers/xai.rs
+//! xAI's Grok models through the OpenAI-compatible API at `https://api.x.ai/v1`. The provider is
+//! only registered when an API key is set (`XAI_API_KEY` or `[providers.xai] api_key`).
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::{Client, RequestBuilder, Response};
+use std::collections::BTreeMap;
+use std::env;
+use std::pin::Pin;
+use url::Url;
+
+use crate::config::ProviderSettings;
+use crate::error::{ReplError, ReplResult};
+use crate::httplog::SendLogged;
+use super::{
+    groq::sse_text_stream,
+    openai_compat::{chat_body, ChatResponse, ModelList},
+    http_client, parse_base_url, ChatMessage, GenerationParams, LlmProvider, ModelInfo,
+};
+
+const XAI_DEFAULT_URL: &str = "https://api.x.ai/v1/";
+
+#[derive(Debug, Clone)]
+pub struct XaiProvider {
+    client: Client,
+    base_url: Url,
+    api_key: String,
+}
+
+impl XaiProvider {
+    /// Creates the provider from `[providers.xai]`, falling back to the XAI_API_KEY and
+    /// XAI_BASE_URL environment variables; None without an API key.
+    pub fn with_settings(settings: &ProviderSettings) -> ReplResult<Option<Self>> {
+        let from_env = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+        let Some(api_key) = settings.api_key.clone().or_else(|| from_env("XAI_API_KEY")) else { return Ok(None) };
+        let base_url = settings.base_url.clone()
+            .or_else(|| from_env("XAI_BASE_URL"))
+            .unwrap_or_else(|| XAI_DEFAULT_URL.to_string());
+        Ok(Some(Self {
+            client: http_client(settings)?,
+            base_url: parse_base_url("xAI", &base_url)?,
+            api_key,
+        }))
+    }
+
+    fn build_url(&self, endpoint: &str) -> ReplResult<Url> {
+        self.base_url.join(endpoint)
+            .map_err(|e| ReplError::Provider(format!("Failed to build URL: {}", e)))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> ReplResult<Response> {
+        let response = request.bearer_auth(&self.api_key).send_logged("xai").await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Failed to read error body".to_string());
+            return Err(ReplError::Provider(format!("xAI API returned an error: {} - {}", status, error_body)));
+        }
+        Ok(response)
+    }
+
+    fn chat_request(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams, stream: bool) -> ReplResult<RequestBuilder> {
+        let body = chat_body(model, messages, stream, params, &BTreeMap::new());
+        Ok(self.client.post(self.build_url("chat/completions")?).json(&body))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for XaiProvider {
+    fn get_name(&self) -> &str {
+        "xai"
+    }
+
+    fn clone_box(&self) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+
+    async fn get_models(&self) -> ReplResult<Vec<ModelInfo>> {
+        let response = self.send(self.client.get(self.build_url("models")?)).await?;
+        let models = response.json::<ModelList>().await
+            .map_err(|e| ReplError::Provider(format!("Failed to parse xAI model list: {}", e)))?;
+        Ok(models.into_models())
+    }
+
+    async fn query(&self, model: &str, prompt: &str, params: &GenerationParams) -> ReplResult<String> {
+        self.chat(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn query_stream(
+        &self,
+        model: &str,
+        prompt: &str,
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        self.chat_stream(model, &[ChatMessage::user(prompt)], params).await
+    }
+
+    async fn chat(&self, model: &str, messages: &[ChatMessage], params: &GenerationParams) -> ReplResult<String> {
+        let response = self.send(self.chat_request(model, messages, params, false)?).await?;
+        response.json::<ChatResponse>().await?.into_text("xAI")
+    }
+
+    async fn chat_stream(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        params: &GenerationParams,
+    ) -> ReplResult<Option<Pin<Box<dyn Stream<Item = ReplResult<String>> + Send>>>> {
+        let response = self.send(self.chat_request(model, messages, params, true)?).await?;
+        Ok(Some(sse_text_stream(response)))
+    }
+}
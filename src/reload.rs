@@ -0,0 +1,104 @@
+// src/reload.rs
+//! Hot reload of the config file and the workspace `.llm-repl.toml`: provider base URLs and keys,
+//! theme, aliases and the other sections apply to the running session when either file is saved.
+use crate::{
+    config::{AppConfig, WORKSPACE_CONFIG_FILE},
+    icons,
+    render::{apply_accessibility, get_theme_resources, set_color_overrides},
+    state::AppState,
+};
+use colored::Colorize;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::{env, ffi::OsStr, path::Path, time::Duration};
+use tokio::sync::mpsc;
+
+// Editors often write a file in several steps; wait for events to settle before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+// Settings read only at startup; changing them is reported but needs a restart.
+const RESTART_ONLY: &[&str] = &["ui.locale", "server.audit_log", "server.api_keys", "server.queue", "bridge"];
+
+fn needs_restart(setting: &str) -> bool {
+    RESTART_ONLY.iter().any(|name| setting == *name || setting.starts_with(&format!("{}.", name)))
+}
+
+/// Watches the config file and applies edits to `state` until the task is dropped. Changes are
+/// announced on stderr (stdout may carry `--stdio` messages) and as a `configReloaded` event.
+pub async fn watch_config(state: AppState) {
+    let Some(path) = AppConfig::config_path() else { return };
+    let (Some(parent), Some(file_name)) = (path.parent(), path.file_name().map(|n| n.to_os_string())) else { return };
+    if !parent.is_dir() {
+        return; // Nothing to watch until the config directory exists
+    }
+
+    // Watch the directory so editors that save by renaming (and a newly created file) are picked up
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res { let _ = tx.send(event); }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("WARN: Config hot reload unavailable: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(parent, RecursiveMode::NonRecursive) {
+        eprintln!("WARN: Config hot reload unavailable: could not watch '{}': {}", parent.display(), e);
+        return;
+    }
+    if let Ok(workspace_dir) = env::current_dir() {
+        if workspace_dir != parent {
+            if let Err(e) = watcher.watch(&workspace_dir, RecursiveMode::NonRecursive) {
+                eprintln!("WARN: Workspace config hot reload unavailable: could not watch '{}': {}", workspace_dir.display(), e);
+            }
+        }
+    }
+
+    while let Some(event) = rx.recv().await {
+        let touches_file = !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|p| p.file_name() == Some(file_name.as_os_str()) || p.file_name() == Some(OsStr::new(WORKSPACE_CONFIG_FILE)));
+        if !touches_file { continue; }
+
+        // Debounce: swallow the burst of events that accompanies a single save
+        tokio::time::sleep(DEBOUNCE).await;
+        while rx.try_recv().is_ok() {}
+
+        let (_skin, palette) = get_theme_resources(state.get_theme().await);
+        match reload(&state, &path).await {
+            Ok(None) => {}
+            Ok(Some(message)) => eprintln!("\n{}", message.truecolor(palette.info.0, palette.info.1, palette.info.2)),
+            Err(e) => {
+                let message = format!("Config not reloaded: {}. Keeping the current settings.", e);
+                eprintln!("\n{}", message.truecolor(palette.error.0, palette.error.1, palette.error.2));
+            }
+        }
+    }
+}
+
+/// Re-reads the config file at `path` (with the workspace overlay) and applies the settings edited
+/// since the last reload. Returns what to announce, or None if nothing changed.
+pub async fn reload(state: &AppState, path: &Path) -> Result<Option<String>, String> {
+    let updated = AppConfig::read(path)?;
+    let changed = state.reload_config(&updated).await.map_err(|e| e.to_string())?;
+    if changed.is_empty() {
+        return Ok(None);
+    }
+    if changed.iter().any(|name| name == "ui.accessible") {
+        apply_accessibility(updated.ui.accessible);
+    }
+    if changed.iter().any(|name| name == "ui.icons" || name == "ui.accessible") {
+        icons::apply(&updated.ui);
+    }
+    if changed.iter().any(|name| name == "ui.colors") {
+        set_color_overrides(&updated.ui.colors);
+    }
+    let (applied, restart): (Vec<_>, Vec<_>) = changed.into_iter().partition(|name| !needs_restart(name));
+    let mut message = String::from("Config reloaded.");
+    if !applied.is_empty() {
+        message.push_str(&format!(" Applied: {}.", applied.join(", ")));
+    }
+    if !restart.is_empty() {
+        message.push_str(&format!(" Takes effect after a restart: {}.", restart.join(", ")));
+    }
+    Ok(Some(message))
+}
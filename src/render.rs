@@ -1,6 +1,12 @@
 // src/render.rs
+use colored::Colorize;
+use std::sync::RwLock;
 use termimad::{MadSkin, crossterm::style::{Color, Attribute}};
-use crate::state::RenderTheme;
+use crate::{
+    config::ColorOverrides,
+    i18n::{tr, tr_args},
+    state::RenderTheme,
+};
 
 // --- Define Type Alias FIRST ---
 type Rgb = (u8, u8, u8); // Define the alias for (u8, u8, u8) tuple
@@ -16,6 +22,18 @@ const NORD_BLUE: Rgb = (129, 161, 193); // nord9
 const NORD_RED: Rgb = (191, 97, 106);  // nord11
 const NORD_GREEN: Rgb = (163, 190, 140); // nord14
 
+// --- Colorblind-safe colors (Okabe-Ito) ---
+const CB_ORANGE: Rgb = (230, 159, 0);
+const CB_SKY_BLUE: Rgb = (86, 180, 233);
+const CB_BLUE: Rgb = (0, 114, 178);
+const CB_YELLOW: Rgb = (240, 228, 66);
+const CB_VERMILLION: Rgb = (213, 94, 0);
+const CB_GRAY: Rgb = (150, 150, 150);
+const CB_TEXT: Rgb = (225, 225, 225);
+
+// Semantic colors from `[ui.colors]`, laid over every theme's palette.
+static COLOR_OVERRIDES: RwLock<(Option<Rgb>, Option<Rgb>, Option<Rgb>)> = RwLock::new((None, None, None));
+
 // --- Define ThemePalette Struct THIRD ---
 // Uses the Rgb type alias defined above
 #[derive(Debug, Clone, Copy)]
@@ -63,6 +81,68 @@ pub fn get_default_palette() -> ThemePalette {
     }
 }
 
+// Red and green look alike with deuteranopia; errors are vermillion and successes blue instead.
+pub fn get_deuteranopia_palette() -> ThemePalette {
+    ThemePalette {
+        prompt_bracket: CB_GRAY,
+        prompt_separator: CB_GRAY,
+        prompt_provider: CB_SKY_BLUE,
+        prompt_model: CB_YELLOW,
+        prompt_arrow: CB_GRAY,
+        error: CB_VERMILLION,
+        info: CB_GRAY,
+        success: CB_BLUE,
+        command_output_raw: CB_TEXT,
+    }
+}
+
+// With protanopia reds also look dark, so errors use the brighter orange.
+pub fn get_protanopia_palette() -> ThemePalette {
+    ThemePalette {
+        error: CB_ORANGE,
+        prompt_model: CB_SKY_BLUE,
+        prompt_provider: CB_BLUE,
+        success: CB_SKY_BLUE,
+        ..get_deuteranopia_palette()
+    }
+}
+
+/// Parses `#rrggbb` (the `#` is optional).
+pub fn parse_hex_color(text: &str) -> Option<Rgb> {
+    let hex = text.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Sets the `[ui.colors]` overrides for error, success and info text. Values that aren't
+/// `#rrggbb` colors are reported and leave the theme's color in place.
+pub fn set_color_overrides(colors: &ColorOverrides) {
+    let parse = |name: &str, value: &Option<String>| {
+        let value = value.as_deref()?;
+        let color = parse_hex_color(value);
+        if color.is_none() {
+            eprintln!("WARN: Ignoring ui.colors.{} = '{}': expected a color like \"#d55e00\".", name, value);
+        }
+        color
+    };
+    let overrides = (parse("error", &colors.error), parse("success", &colors.success), parse("info", &colors.info));
+    if let Ok(mut current) = COLOR_OVERRIDES.write() {
+        *current = overrides;
+    }
+}
+
+fn with_overrides(mut palette: ThemePalette) -> ThemePalette {
+    if let Ok(overrides) = COLOR_OVERRIDES.read() {
+        let (error, success, info) = *overrides;
+        palette.error = error.unwrap_or(palette.error);
+        palette.success = success.unwrap_or(palette.success);
+        palette.info = info.unwrap_or(palette.info);
+    }
+    palette
+}
 
 // --- MadSkin Creation Functions FIFTH ---
 // These functions use the constants defined above
@@ -123,6 +203,27 @@ pub fn create_nord_skin() -> MadSkin {
     skin
 }
 
+// Headings and emphasis in the palette's blue and yellow, which stay distinct with red-green color blindness.
+pub fn create_colorblind_skin(palette: &ThemePalette) -> MadSkin {
+    let mut skin = MadSkin::default();
+    let term = |(r, g, b): Rgb| Color::Rgb { r, g, b };
+    skin.paragraph.set_fg(term(CB_TEXT));
+    skin.table.set_fg(term(CB_TEXT));
+    skin.inline_code.set_fg(term(palette.prompt_model));
+    skin.code_block.set_fg(term(CB_TEXT));
+    for (level, header) in skin.headers.iter_mut().enumerate() {
+        header.set_fg(term(if level < 2 { palette.prompt_provider } else { palette.prompt_model }));
+        if level < 3 {
+            header.add_attr(Attribute::Bold);
+        }
+    }
+    skin.bold.add_attr(Attribute::Bold);
+    skin.bold.set_fg(term(CB_TEXT));
+    skin.italic.add_attr(Attribute::Italic);
+    skin.horizontal_rule.set_fg(term(CB_GRAY));
+    skin
+}
+
 // --- Keep placeholder functions ---
 pub fn create_gruvbox_skin() -> MadSkin {
     println!("WARN: Gruvbox theme not fully implemented, using Nord.");
@@ -133,12 +234,252 @@ pub fn create_grayscale_skin() -> MadSkin {
     create_nord_skin()
 }
 
-/// Selects and returns the appropriate skin AND palette based on the theme enum.
+/// Selects and returns the appropriate skin AND palette based on the theme enum. The palette's
+/// semantic colors follow `[ui.colors]` where set; the skin is left as the theme has it.
 pub fn get_theme_resources(theme: RenderTheme) -> (MadSkin, ThemePalette) {
-     match theme {
+     let (skin, palette) = match theme {
         RenderTheme::Nord => (create_nord_skin(), get_nord_palette()),
         RenderTheme::Gruvbox => (create_gruvbox_skin(), get_default_palette()), // Use default palette for WIP
         RenderTheme::Grayscale => (create_grayscale_skin(), get_default_palette()), // Use default palette for WIP
         RenderTheme::Default => (MadSkin::default(), get_default_palette()),
+        RenderTheme::Deuteranopia => { let palette = get_deuteranopia_palette(); (create_colorblind_skin(&palette), palette) }
+        RenderTheme::Protanopia => { let palette = get_protanopia_palette(); (create_colorblind_skin(&palette), palette) }
+    };
+    (skin, with_overrides(palette))
+}
+// --- Interleaved Streams ---
+/// Shows several responses streaming at once as interleaved, labeled blocks. Text is released a
+/// line at a time, and a label line is printed whenever the output switches to another stream,
+/// so every block can be attributed without redrawing the screen.
+pub struct InterleavedRenderer {
+    labels: Vec<String>,
+    colors: Vec<Rgb>,
+    pending: Vec<String>, // Incomplete last line per stream
+    current: Option<usize>, // Stream whose block is open
+}
+
+impl InterleavedRenderer {
+    pub fn new(labels: Vec<String>, palette: &ThemePalette) -> Self {
+        let colors = vec![palette.prompt_provider, palette.success, palette.prompt_model, palette.info];
+        let pending = vec![String::new(); labels.len()];
+        Self { labels, colors, pending, current: None }
+    }
+
+    /// Adds text streamed by stream `index` and returns what can be printed now.
+    pub fn push(&mut self, index: usize, chunk: &str) -> String {
+        self.pending[index].push_str(chunk);
+        match self.pending[index].rfind('\n') {
+            Some(newline) => {
+                let lines: String = self.pending[index].drain(..=newline).collect();
+                self.block(index, &lines)
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Releases the rest of stream `index` once it has ended.
+    pub fn finish(&mut self, index: usize) -> String {
+        let rest = std::mem::take(&mut self.pending[index]);
+        if rest.is_empty() {
+            return String::new();
+        }
+        self.block(index, &format!("{}\n", rest))
+    }
+
+    // The lines, preceded by the stream's label if another stream printed last.
+    fn block(&mut self, index: usize, lines: &str) -> String {
+        let mut output = String::new();
+        if self.current != Some(index) {
+            let (r, g, b) = self.colors[index % self.colors.len()];
+            let label = format!("[{}] {}", (b'A' + (index % 26) as u8) as char, self.labels[index]);
+            if self.current.is_some() {
+                output.push('\n');
+            }
+            output.push_str(&format!("{}\n", label.truecolor(r, g, b).bold()));
+            self.current = Some(index);
+        }
+        output.push_str(lines);
+        output
+    }
+}
+
+// --- Accessible Rendering ---
+/// Turns accessibility mode on or off for colored terminal output: colors are dropped while it is
+/// on, and terminal detection decides again once it is off.
+pub fn apply_accessibility(enabled: bool) {
+    if enabled {
+        colored::control::set_override(false);
+    } else {
+        colored::control::unset_override();
+    }
+}
+
+// Drops emphasis markers and inline code ticks, and spells out links as "text (link: url)".
+fn plain_inline(text: &str) -> String {
+    let stripped = text.replace("**", "").replace("__", "").replace('`', "");
+    let mut output = String::with_capacity(stripped.len());
+    let mut rest = stripped.as_str();
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find("](").map(|i| open + i) else { break };
+        let Some(end) = rest[close..].find(')').map(|i| close + i) else { break };
+        output.push_str(&rest[..open]);
+        output.push_str(&tr_args("a11y-link", &[("text", &rest[open + 1..close]), ("url", &rest[close + 2..end])]));
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+// `---`, `***` or `___` (optionally spaced) on a line of its own.
+fn is_thematic_break(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0])
+}
+
+/// Converts Markdown to plain, sequential text for screen readers: structure is announced in words
+/// ("Heading:", "Code block:") instead of drawn with colors, boxes or cursor movement.
+/// Works line by line, so streamed responses are converted as each line completes.
+#[derive(Default)]
+pub struct AccessibleRenderer {
+    pending: String, // Incomplete last line of the stream
+    in_code_block: bool,
+    in_table: bool,
+}
+
+impl AccessibleRenderer {
+    pub fn new() -> Self {
+        Self::default()
     }
-}
\ No newline at end of file
+
+    /// Adds streamed text and returns the converted complete lines.
+    pub fn push(&mut self, chunk: &str) -> String {
+        self.pending.push_str(chunk);
+        let mut output = String::new();
+        while let Some(newline) = self.pending.find('\n') {
+            let line: String = self.pending.drain(..=newline).collect();
+            if let Some(converted) = self.convert_line(line.trim_end_matches(['\n', '\r'])) {
+                output.push_str(&converted);
+                output.push('\n');
+            }
+        }
+        output
+    }
+
+    /// Converts the rest of the text once the stream ends, closing any open block.
+    pub fn finish(&mut self) -> String {
+        let rest = std::mem::take(&mut self.pending);
+        let mut output = String::new();
+        if !rest.is_empty() {
+            if let Some(converted) = self.convert_line(&rest) {
+                output.push_str(&converted);
+                output.push('\n');
+            }
+        }
+        if std::mem::take(&mut self.in_code_block) {
+            output.push_str(&format!("{}\n", tr("a11y-code-end")));
+        }
+        if std::mem::take(&mut self.in_table) {
+            output.push_str(&format!("{}\n", tr("a11y-table-end")));
+        }
+        output
+    }
+
+    // Returns None for lines that carry no content once announced (e.g. table separators).
+    fn convert_line(&mut self, line: &str) -> Option<String> {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```").or_else(|| trimmed.strip_prefix("~~~")) {
+            self.in_code_block = !self.in_code_block;
+            return Some(match (self.in_code_block, info.trim()) {
+                (false, _) => tr("a11y-code-end"),
+                (true, "") => tr("a11y-code-start"),
+                (true, language) => tr_args("a11y-code-start-language", &[("language", language)]),
+            });
+        }
+        if self.in_code_block {
+            return Some(line.to_string());
+        }
+
+        let mut prefix = String::new();
+        if trimmed.starts_with('|') {
+            let cells: Vec<&str> = trimmed.trim_matches('|').split('|').map(str::trim).collect();
+            if cells.iter().all(|cell| !cell.is_empty() && cell.chars().all(|c| matches!(c, '-' | ':'))) {
+                return None; // Header separator row
+            }
+            if !self.in_table {
+                self.in_table = true;
+                prefix = format!("{}\n", tr("a11y-table-start"));
+            }
+            let row = cells.iter().map(|cell| plain_inline(cell)).collect::<Vec<_>>().join(", ");
+            return Some(format!("{}{}", prefix, tr_args("a11y-table-row", &[("cells", &row)])));
+        }
+        if std::mem::take(&mut self.in_table) {
+            prefix = format!("{}\n", tr("a11y-table-end"));
+        }
+
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        let converted = if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            tr_args("a11y-heading", &[("level", &level.to_string()), ("text", &plain_inline(trimmed[level..].trim()))])
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            tr_args("a11y-quote", &[("text", &plain_inline(quote.trim()))])
+        } else if is_thematic_break(trimmed) {
+            tr("a11y-separator")
+        } else {
+            plain_inline(line)
+        };
+        Some(format!("{}{}", prefix, converted))
+    }
+}
+
+/// Converts a complete Markdown text for accessibility mode (see `AccessibleRenderer`).
+pub fn render_accessible(markdown: &str) -> String {
+    let mut renderer = AccessibleRenderer::new();
+    let mut output = renderer.push(markdown);
+    output.push_str(&renderer.finish());
+    output
+}
+// --- End Accessible Rendering ---
+
+// --- Side-by-Side Rendering ---
+// Narrower terminals keep the raw and formatted answers one after the other.
+const MIN_SPLIT_WIDTH: usize = 100;
+const SPLIT_DIVIDER: &str = " │ ";
+
+/// Breaks text into lines of at most `width` characters, keeping its own line breaks.
+pub fn wrap_lines(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut wrapped = Vec::new();
+    for line in text.lines() {
+        let chars: Vec<char> = line.replace('\t', "    ").chars().collect();
+        if chars.is_empty() {
+            wrapped.push(String::new());
+        }
+        wrapped.extend(chars.chunks(width).map(|chunk| chunk.iter().collect::<String>()));
+    }
+    wrapped
+}
+
+/// Terminal rows `text` takes when printed `width` columns wide.
+pub fn printed_rows(text: &str, width: usize) -> usize {
+    wrap_lines(text, width).len()
+}
+
+/// The raw Markdown (left) next to its rendering (right), each half the terminal `width`; None when
+/// the terminal is too narrow for two readable columns.
+pub fn side_by_side(markdown: &str, skin: &MadSkin, width: usize) -> Option<String> {
+    if width < MIN_SPLIT_WIDTH {
+        return None;
+    }
+    let column = (width - SPLIT_DIVIDER.chars().count()) / 2;
+    let raw = wrap_lines(markdown, column);
+    let formatted = skin.text(markdown, Some(column)).to_string();
+    let formatted: Vec<&str> = formatted.lines().collect();
+    let rows = raw.len().max(formatted.len());
+    let mut output = String::new();
+    for row in 0..rows {
+        let left = raw.get(row).map(String::as_str).unwrap_or("");
+        let right = formatted.get(row).copied().unwrap_or("");
+        output.push_str(&format!("{:<column$}{}{}\n", left, SPLIT_DIVIDER.dimmed(), right));
+    }
+    Some(output)
+}
+// --- End Side-by-Side Rendering ---
@@ -1,22 +1,120 @@
 // src/repl.rs
 use crate::{
-    commands::CommandRegistry,
+    commands::{buf::send_target, closest_command, continuation::Continuation, recall::{recall, PROMPT_HISTORY_FILE}, CommandRegistry},
+    config::{AppConfig, CommandOrigin},
+    context,
+    data::expand_dataset_references,
+    documents::expand_file_references,
+    editor::{ReplEditor, ReplHelper},
     error::{ReplError, ReplResult},
-    render::get_theme_resources, // Theme resources
+    exit_hooks,
+    history::{is_ignored, prune_prompt_file},
+    icons,
+    i18n::{tr, tr_args},
+    limits::limit_stream,
+    notify::notify_if_slow,
+    pacing::pace_stream,
+    progress::render_progress,
+    providers::{resume::{skip_repeat, strip_repeat}, ChatMessage},
+    reload::watch_config,
+    request_id,
+    render::{get_theme_resources, printed_rows, render_accessible, side_by_side, AccessibleRenderer}, // Theme resources
+    sessions,
     shell::execute_shell_command,
-    state::{AppState, HistoryContentType, HistoryEntry, MarkdownMode, RenderTheme}, // Added History types
+    tee,
+    state::{AppState, HistoryContentType, HistoryEntry, MarkdownMode, OutputSource, RenderTheme}, // Added History types
+    terminal::HiddenCursor,
+    tui::{self, TuiExit},
+    typeahead::{PromptQueue, TypeaheadCapture},
+    warmup::keep_warm,
 };
+use chrono::Utc;
 use colored::*; // For applying colors
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
 use futures::StreamExt;
-use rustyline::{error::ReadlineError, DefaultEditor};
+use rustyline::{config::Configurer, error::ReadlineError, Cmd, ConditionalEventHandler, Event, EventContext, EventHandler, KeyEvent, RepeatCount};
 use std::io::{self, Write}; // Added io::Write
-use tokio::runtime::Runtime;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, Runtime};
+use tokio::sync::mpsc;
+
+// Commands handled by the REPL loop itself rather than the command registry.
+const REPL_ONLY_COMMANDS: &[&str] = &["exit", "quit", "tui"];
+
+// --- Per-Query Override ---
+/// A one-off provider/model target parsed from an `@provider:model` prompt prefix.
+/// It applies to a single query and leaves the session defaults untouched.
+struct QueryTarget {
+    provider: String,
+    model: String,
+}
+// --- End Per-Query Override ---
+
+// --- Recall Keybinding ---
+/// Alt+R: accepts the current line as a `/recall` query (flagged so the REPL
+/// doesn't send it as a prompt). Ctrl+R stays rustyline's own line search.
+struct RecallKeyHandler {
+    requested: Arc<AtomicBool>,
+}
+
+impl ConditionalEventHandler for RecallKeyHandler {
+    fn handle(&self, _evt: &Event, _n: RepeatCount, _positive: bool, _ctx: &EventContext) -> Option<Cmd> {
+        self.requested.store(true, Ordering::SeqCst);
+        Some(Cmd::AcceptLine)
+    }
+}
+// --- End Recall Keybinding ---
+
+// --- Paste Response Keybindings ---
+/// Alt+Y inserts the last LLM response at the cursor, Alt+K only its last fenced code block,
+/// so either can be edited and resent without leaving the prompt. Alt+Y replaces rustyline's
+/// yank-pop (Ctrl+Y still yanks).
+struct PasteResponseKeyHandler {
+    state: AppState,
+    runtime: Handle,
+    code_block_only: bool,
+}
+
+impl ConditionalEventHandler for PasteResponseKeyHandler {
+    fn handle(&self, _evt: &Event, _n: RepeatCount, _positive: bool, _ctx: &EventContext) -> Option<Cmd> {
+        let history = self.runtime.block_on(self.state.get_history());
+        let response = history.iter().rev().find(|e| matches!(e.entry_type, HistoryContentType::LlmResponse { .. }));
+        let text = match response {
+            Some(entry) if self.code_block_only => last_code_block(&entry.content),
+            Some(entry) => Some(entry.content.trim()),
+            None => None,
+        };
+        match text {
+            Some(text) if !text.is_empty() => Some(Cmd::Insert(1, text.to_string())),
+            _ => Some(Cmd::Noop),
+        }
+    }
+}
+
+// The body of the last fenced code block in a response, without its fences and language tag.
+fn last_code_block(text: &str) -> Option<&str> {
+    let parts = text.split("```").collect::<Vec<_>>();
+    // Odd-numbered parts are inside fences; an unclosed trailing fence doesn't count
+    let last_closed = parts.len().checked_sub(2)?;
+    let index = if last_closed % 2 == 1 { last_closed } else { last_closed.checked_sub(1)? };
+    let block = parts[index];
+    Some(block.split_once('\n').map_or(block, |(_language, body)| body).trim_end())
+}
+// --- End Paste Response Keybindings ---
 
 // --- Repl Struct Definition ---
 pub struct Repl {
     command_registry: CommandRegistry,
     state: AppState,
     runtime: Runtime,
+    prompt_queue: PromptQueue, // Lines typed while a response was streaming
+    skip_history: bool, // The current line matched `[history]` ignore rules; record nothing from it
+    request_id: String, // ID of the current line's query or command, in its history entries, logs and errors
+    start_in_tui: bool, // --tui: open the full-screen view before the first prompt
 }
 // --- End Struct Definition ---
 
@@ -30,9 +128,18 @@ impl Repl {
             command_registry,
             state,
             runtime,
+            prompt_queue: PromptQueue::new(),
+            skip_history: false,
+            request_id: request_id::next(),
+            start_in_tui: false,
         })
     }
 
+    /// Opens the full-screen view (`/tui`) when the REPL starts; leaving it returns to the prompt.
+    pub fn start_in_tui(&mut self) {
+        self.start_in_tui = true;
+    }
+
     // Helper to apply color using RGB tuple from the palette
     fn colorize(&self, text: &str, color: (u8, u8, u8)) -> colored::ColoredString {
         text.truecolor(color.0, color.1, color.2)
@@ -45,238 +152,644 @@ impl Repl {
     }
 
     // --- Helper to add history entries ---
-    async fn add_history(&self, entry_type: HistoryContentType, content: String) {
-        self.state
-            .add_history_entry(HistoryEntry { entry_type, content })
-            .await;
+    async fn add_history(&self, mut entry: HistoryEntry) {
+        if self.skip_history {
+            return;
+        }
+        entry.request_id.get_or_insert_with(|| self.request_id.clone());
+        self.state.add_history_entry(entry).await;
+    }
+
+    // Shows the full-screen view; true if the user quit the REPL from there.
+    fn run_tui(&mut self) -> bool {
+        match tui::run(&self.state, &self.command_registry, &self.runtime) {
+            Ok(exit) => exit == TuiExit::Quit,
+            Err(e) => {
+                let (_skin, palette) = get_theme_resources(self.runtime.block_on(self.state.get_theme()));
+                eprintln!("{}", self.colorize(&tr_args("error-generic", &[("error", &e.to_string())]), palette.error));
+                false
+            }
+        }
+    }
+
+    // Runs `future` as the current line's request, so provider calls and history entries carry its ID.
+    fn block_on_request<F: std::future::Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(request_id::scope(self.request_id.clone(), future))
+    }
+
+    // An error message naming the request, to find its lines in the HTTP debug log.
+    fn request_error(&self, error: &ReplError) -> String {
+        format!("{} {}", error, tr_args("repl-request-id", &[("id", &self.request_id)]))
     }
     // --- End Helper ---
 
     pub fn run(&mut self) -> ReplResult<()> {
-        println!("LLM REPL - Type '/help' for commands, !<cmd> for shell, /reader for history.");
+        println!("{}", tr("repl-welcome"));
+        if let Some(workspace) = AppConfig::workspace_path() {
+            println!("{}", tr_args("repl-workspace-config", &[("path", &workspace.display().to_string())]));
+        }
+        println!("{}", tr_args("repl-session", &[("session", &self.runtime.block_on(self.state.get_session()).name)]));
         // Removed redundant mode/theme prints here, covered by /help
 
-        let mut rl = DefaultEditor::new()?;
-        if rl.load_history("history.txt").is_err() {
-            println!("INFO: No previous history found or load failed.");
+        let mut rl = ReplEditor::new()?;
+        let hint_commands = self.command_registry.list_commands().into_iter()
+            .chain(REPL_ONLY_COMMANDS.iter().copied())
+            .map(String::from)
+            .collect();
+        let usages = self.command_registry.list_usages().into_iter()
+            .map(|(name, usage)| (name.to_string(), usage.to_string()))
+            .collect();
+        rl.set_helper(Some(ReplHelper::new(hint_commands, usages)));
+        if rl.load_history(PROMPT_HISTORY_FILE).is_err() {
+            println!("{}", tr("repl-no-history"));
+        }
+        // Apply config file edits while the session runs; stops with the REPL's runtime
+        self.runtime.spawn(watch_config(self.state.clone()));
+        // Load the selected Ollama model ahead of the first prompt, if warm-up is configured
+        self.runtime.spawn(keep_warm(self.state.clone()));
+        let recall_requested = Arc::new(AtomicBool::new(false));
+        rl.bind_sequence(KeyEvent::alt('r'), EventHandler::Conditional(Box::new(RecallKeyHandler { requested: recall_requested.clone() })));
+        for (key, code_block_only) in [('y', false), ('k', true)] {
+            let handler = PasteResponseKeyHandler { state: self.state.clone(), runtime: self.runtime.handle().clone(), code_block_only };
+            rl.bind_sequence(KeyEvent::alt(key), EventHandler::Conditional(Box::new(handler)));
         }
 
+        // A panic mid-command still saves the prompt history; the panic hook has restored the terminal
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| self.read_loop(&mut rl, &recall_requested)));
+
+        // Saving writes back every loaded prompt, so apply the retention limits again afterwards
+        let retention = self.runtime.block_on(self.state.get_config()).history;
+        let saved = rl.save_history(PROMPT_HISTORY_FILE).map_err(ReplError::from)
+            .and_then(|_| prune_prompt_file(Path::new(PROMPT_HISTORY_FILE), &retention));
+        if let Err(e) = saved {
+            let (_skin_exit, palette_exit) = get_theme_resources(RenderTheme::Default);
+            eprintln!("{}", self.colorize(&tr_args("warn-history-save", &[("error", &e.to_string())]), palette_exit.error));
+        }
+        // [on_exit] actions run after a normal exit only; a panic is reported as it is
+        if outcome.is_ok() {
+            let (_skin_exit, palette_exit) = get_theme_resources(self.runtime.block_on(self.state.get_theme()));
+            // The session is kept under its name, so /sessions can return to it next time
+            let session = self.runtime.block_on(self.state.save_session());
+            if !session.is_empty() {
+                let name = self.runtime.block_on(self.state.get_session()).name;
+                match sessions::save(&name, &session) {
+                    Ok(_) => println!("{}", self.colorize(&tr_args("repl-session-saved", &[("session", &name)]), palette_exit.info)),
+                    Err(e) => eprintln!("{}", self.colorize(&tr_args("warn-session-save", &[("session", &name), ("error", &e.to_string())]), palette_exit.error)),
+                }
+            }
+            for action in self.runtime.block_on(exit_hooks::run(&self.state)) {
+                match action {
+                    Ok(message) => println!("{}", self.colorize(&message, palette_exit.info)),
+                    Err(e) => eprintln!("{}", self.colorize(&tr_args("error-generic", &[("error", &e.to_string())]), palette_exit.error)),
+                }
+            }
+        }
+        match outcome {
+            Ok(result) => result,
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    } // --- End run() ---
+
+    // Reads and dispatches input lines until the user exits.
+    fn read_loop(&mut self, rl: &mut ReplEditor, recall_requested: &AtomicBool) -> ReplResult<()> {
+        if self.start_in_tui && self.run_tui() {
+            return Ok(());
+        }
         loop {
             // --- Get State for Prompt ---
             let current_provider = self.runtime.block_on(self.state.get_provider_name());
             let current_model = self.runtime.block_on(self.state.get_model());
             let current_theme = self.runtime.block_on(self.state.get_theme());
+            let config = self.runtime.block_on(self.state.get_config());
+            let accessible = config.ui.accessible;
             let (_skin, palette) = get_theme_resources(current_theme); // Get palette
+            if let Some(helper) = rl.helper_mut() { helper.configure(!accessible, palette); }
 
             // --- Build Colored Prompt ---
             let prompt = format!(
-                "{}{}{}{}{}{}",
+                "{}{}{}{}{}{}{}",
                 self.colorize("[", palette.prompt_bracket),
+                self.colorize(&icons::provider_for(&current_provider, &config), palette.prompt_provider),
                 self.colorize(&current_provider, palette.prompt_provider),
                 self.colorize(":", palette.prompt_separator),
                 self.colorize(&current_model, palette.prompt_model),
                 self.colorize("]", palette.prompt_bracket),
-                self.colorize(">> ", palette.prompt_arrow)
+                self.colorize(icons::prompt_arrow(), palette.prompt_arrow)
             );
 
-            // --- Read Line ---
-            let readline = rl.readline(&prompt);
+            // --- Read Line (queued prompts are dispatched first) ---
+            let mut prompt_only = false;
+            let readline = match self.prompt_queue.pop() {
+                Some(queued_line) => {
+                    println!("{}{}", prompt, queued_line.text);
+                    prompt_only = queued_line.prompt_only;
+                    Ok(queued_line.text)
+                }
+                None => {
+                    let partial = self.prompt_queue.take_partial();
+                    rl.readline_with_initial(&prompt, (&partial, ""))
+                }
+            };
             match readline {
+                Ok(line) if recall_requested.swap(false, Ordering::SeqCst) => {
+                    self.run_recall(rl, line.trim(), palette.error);
+                }
                 Ok(line) => {
-                    if let Err(e) = rl.add_history_entry(line.as_str()) {
-                        eprintln!(
-                            "{}",
-                            self.colorize(
-                                &format!("WARN: Failed to add rustyline history entry: {}", e),
-                                palette.error
-                            )
-                        );
+                    // Ignored lines (leading space, `ignore_patterns`) stay out of both histories
+                    let history_config = self.runtime.block_on(self.state.get_config()).history;
+                    self.skip_history = is_ignored(&line, &history_config);
+                    let _ = rl.set_history_ignore_dups(history_config.ignore_dups);
+                    if !self.skip_history {
+                        if let Err(e) = rl.add_history_entry(line.as_str()) {
+                            eprintln!(
+                                "{}",
+                                self.colorize(
+                                    &tr_args("warn-history-add", &[("error", &e.to_string())]),
+                                    palette.error
+                                )
+                            );
+                        }
                     }
 
                     let trimmed_line = line.trim();
                     if trimmed_line.is_empty() { continue; }
+                    tee::prompt(&format!("{}:{}", current_provider, current_model), &line);
+                    self.request_id = request_id::next();
 
                     // --- Command Handling ---
-                    if line.starts_with('/') {
+                    if line.starts_with('/') && !prompt_only {
                         let parts: Vec<&str> = line[1..].splitn(2, ' ').collect();
                         let (cmd, args) = if parts.len() > 1 { (parts[0], parts[1]) } else { (parts[0], "") };
+                        // Offer the closest command for a typo (e.g. /modle) instead of only failing
+                        let corrected = self.correct_command(cmd);
+                        let cmd = corrected.as_str();
 
                         match cmd {
                             "exit" | "quit" => break,
-                            // --- Special Handling for /reader ---
-                            "reader" => {
-                                // Execute reader command, print its output, but DON'T store its output in history
-                                match self.runtime.block_on(self.execute_command(cmd, args)) {
-                                    Ok(msg) => println!("{}", msg), // Prints "Reader view finished..."
+                            // --- /tui switches to the full-screen view until Esc (or /exit there) ---
+                            "tui" => if self.run_tui() { break; },
+                            // --- Special Handling for history views (/reader, /replay) ---
+                            "reader" | "replay" => {
+                                // Execute the view, print its output, but DON'T store its output in history
+                                match self.block_on_request(self.execute_command(cmd, args)) {
+                                    Ok(msg) => {
+                                        println!("{}", msg); // Prints "Reader view finished..."
+                                        tee::output(&msg);
+                                    }
                                     Err(e) => {
-                                        // Still log errors executing the reader itself
-                                        let err_msg = format!("Error executing reader: {}", e);
+                                        // Still log errors executing the view itself
+                                        let err_msg = tr_args("error-command", &[("command", cmd), ("error", &self.request_error(&e))]);
                                         eprintln!("{}", self.colorize(&err_msg, palette.error));
-                                        self.runtime.block_on(self.add_history(
-                                            HistoryContentType::Error { source: "/reader".to_string() },
+                                        tee::output(&err_msg);
+                                        self.runtime.block_on(self.add_history(HistoryEntry::new(
+                                            HistoryContentType::Error { source: format!("/{}", cmd) },
                                             err_msg,
-                                        ));
+                                        )));
                                     }
                                 }
                             }
+                            // --- /recall pre-fills the next prompt instead of printing ---
+                            "recall" => self.run_recall(rl, args.trim(), palette.error),
+                            // --- /buf send dispatches the buffer as the next prompt ---
+                            "buf" if send_target(args).is_some() => self.send_buffer(args, palette.error),
+                            // --- /continue streams the rest of the last answer like a response ---
+                            "continue" => {
+                                let theme = self.runtime.block_on(self.state.get_theme());
+                                if let Err(e) = self.block_on_request(self.continue_last_answer(theme, palette.info)) {
+                                    eprintln!("{}", self.colorize(&tr_args("error-command", &[("command", "continue"), ("error", &self.request_error(&e))]), palette.error));
+                                }
+                            }
                             // --- Handle other commands ---
                             _ => {
-                                let command_result = self.runtime.block_on(self.execute_command(cmd, args));
+                                let started = Instant::now();
+                                let command_result = self.block_on_request(self.execute_command(cmd, args));
+                                let elapsed = started.elapsed();
+                                let summary = match &command_result { Ok(_) => tr("repl-command-finished"), Err(e) => tr_args("repl-command-failed", &[("error", &e.to_string())]) };
+                                self.notify_completion(&format!("/{}", cmd), &summary, started);
                                 let current_theme_for_output = self.runtime.block_on(self.state.get_theme()); // Re-fetch theme
                                 let (_skin_output, palette_output) = get_theme_resources(current_theme_for_output);
 
                                 match command_result {
                                     Ok(output_content) => {
                                         let string_to_print;
-                                        let current_mode = self.runtime.block_on(self.state.get_markdown_mode());
+                                        let current_mode = self.runtime.block_on(self.state.get_markdown_mode_for(OutputSource::Command));
+                                        let accessible = self.runtime.block_on(self.state.get_config()).ui.accessible;
 
-                                        if cmd == "llmconvo" {
+                                        if accessible {
+                                            string_to_print = render_accessible(&output_content);
+                                        } else if cmd == "llmconvo" {
                                             string_to_print = self.colorize(&output_content, palette_output.success).to_string();
-                                        } else if current_mode != MarkdownMode::Off {
+                                        } else if current_mode != MarkdownMode::Off || cmd == "help" { // /help is always a themed table
                                             string_to_print = self.render_markdown(&output_content, current_theme_for_output);
                                         } else {
                                             string_to_print = self.colorize(&output_content, palette_output.command_output_raw).to_string();
                                         }
 
-                                        // Print the processed output
+                                        // Print the processed output; the mirror gets it unrendered
                                         println!("{}", string_to_print);
+                                        tee::output(&output_content);
 
                                         // Store the original, unprocessed output string
-                                        self.runtime.block_on(self.add_history(
+                                        self.runtime.block_on(self.add_history(HistoryEntry::new(
                                             HistoryContentType::CommandResult { command: cmd.to_string() },
                                             output_content, // Store original string
-                                        ));
+                                        ).with_duration(elapsed)));
                                     }
                                     Err(e) => {
-                                        let err_msg = format!("Error: {}", e);
+                                        let err_msg = tr_args("error-generic", &[("error", &self.request_error(&e))]);
                                         eprintln!("{}", self.colorize(&err_msg, palette_output.error));
+                                        tee::output(&err_msg);
                                         // Store the error message
-                                        self.runtime.block_on(self.add_history(
+                                        self.runtime.block_on(self.add_history(HistoryEntry::new(
                                             HistoryContentType::Error { source: format!("/{}", cmd) },
                                             err_msg,
-                                        ));
+                                        ).with_duration(elapsed)));
                                     }
                                 }
                             }
                         }
                     // --- Shell Command Handling ---
-                    } else if line.starts_with('!') {
+                    } else if line.starts_with('!') && !prompt_only {
                         let command_line = line[1..].trim();
                         let current_theme_for_output = self.runtime.block_on(self.state.get_theme());
                         let (_skin_output, palette_output) = get_theme_resources(current_theme_for_output);
 
+                        let started = Instant::now();
                         match execute_shell_command(command_line) {
                             Ok(output_content) => {
-                                println!("{}", output_content.trim_end()); // Print raw
+                                let shell_mode = self.runtime.block_on(self.state.get_markdown_mode_for(OutputSource::Shell));
+                                let accessible = self.runtime.block_on(self.state.get_config()).ui.accessible;
+                                if shell_mode == MarkdownMode::Off {
+                                    println!("{}", output_content.trim_end()); // Print raw
+                                } else if accessible {
+                                    println!("{}", render_accessible(&output_content));
+                                } else {
+                                    println!("{}", self.render_markdown(&output_content, current_theme_for_output));
+                                }
+                                tee::output(&output_content);
                                 // Store raw output
-                                self.runtime.block_on(self.add_history(
+                                self.runtime.block_on(self.add_history(HistoryEntry::new(
                                     HistoryContentType::ShellOutput { command: command_line.to_string() },
                                     output_content,
-                                ));
+                                ).with_duration(started.elapsed())));
                             }
                             Err(e) => {
-                                let err_msg = format!("Shell Error: {}", e);
+                                let err_msg = tr_args("error-shell", &[("error", &e.to_string())]);
                                 eprintln!("{}", self.colorize(&err_msg, palette_output.error));
+                                tee::output(&err_msg);
                                 // Store error
-                                self.runtime.block_on(self.add_history(
+                                self.runtime.block_on(self.add_history(HistoryEntry::new(
                                     HistoryContentType::Error { source: format!("!{}", command_line) },
                                     err_msg,
-                                ));
+                                ).with_duration(started.elapsed())));
                             }
                         }
                     // --- LLM Query Handling ---
                     } else {
                         let current_theme_for_output = self.runtime.block_on(self.state.get_theme());
                         let (_skin_output, palette_output) = get_theme_resources(current_theme_for_output);
-                        let info_msg = "Querying...";
-                        println!("{}", self.colorize(info_msg, palette_output.info));
+
+                        // Check for a one-off `@provider:model` override before dispatch
+                        let (target, prompt) = match self.parse_query_target(trimmed_line) {
+                            Some((target, rest)) => (Some(target), rest),
+                            None => (None, line.as_str()),
+                        };
+                        if prompt.trim().is_empty() {
+                            eprintln!("{}", self.colorize(&tr("repl-missing-prompt"), palette_output.error));
+                            continue;
+                        }
+
+                        let (provider_name, model_name) = match &target {
+                            Some(t) => (t.provider.clone(), t.model.clone()),
+                            None => (current_provider.clone(), current_model.clone()),
+                        };
+                        // A repeat of a recent prompt can be answered from history instead of a fresh call
+                        if self.offer_cached_answer(prompt, &provider_name, &model_name, current_theme_for_output) {
+                            self.print_pending_prompts(palette_output.info);
+                            continue;
+                        }
+
+                        // Pull in `@data:<name>` datasets and the text of any `@file` references (PDF, DOCX, or text)
+                        let datasets = self.runtime.block_on(self.state.list_datasets());
+                        let input_limits = self.runtime.block_on(self.state.get_config()).input_limits;
+                        let expanded = expand_dataset_references(prompt, &datasets).and_then(|(with_data, data_context, used)| {
+                            for name in &used {
+                                println!("{}", self.colorize(&tr_args("repl-included-dataset", &[("dataset", name)]), palette_output.info));
+                            }
+                            expand_file_references(&with_data, &input_limits)
+                                .map(|(expanded, included)| (expanded + &data_context, included))
+                        });
+                        let prompt_with_files = match expanded {
+                            Ok((expanded, included)) => {
+                                for file in &included {
+                                    println!("{}", self.colorize(&tr_args("repl-included-file", &[("file", &file.describe())]), palette_output.info));
+                                }
+                                expanded
+                            }
+                            Err(e) => {
+                                eprintln!("{}", self.colorize(&tr_args("error-generic", &[("error", &e.to_string())]), palette_output.error));
+                                continue;
+                            }
+                        };
+
+                        // Wrap the prompt with the configured prefix/suffix hooks; the session context and
+                        // the conversation so far go before it
+                        let conversation = self.runtime.block_on(self.state.get_conversation());
+                        let messages = self.runtime.block_on(self.state.get_config()).chat_messages(&conversation, &prompt_with_files);
+                        // Earlier turns that would overflow the model's context window are dropped or summarized
+                        let (messages, trimmed) = self.block_on_request(context::fit(&self.state, &provider_name, &model_name, messages));
+                        if let Some(note) = trimmed {
+                            println!("{}", self.colorize(&note, palette_output.info));
+                            tee::output(&note);
+                        }
+
+                        let info_msg = match &target {
+                            Some(t) => tr_args("repl-querying-target", &[("provider", &t.provider), ("model", &t.model)]),
+                            None => tr("repl-querying"),
+                        };
+                        println!("{}", self.colorize(&info_msg, palette_output.info));
+                        tee::output(&info_msg);
                         // Optionally store info message
-                        // self.runtime.block_on(self.add_history(HistoryContentType::Info, info_msg.to_string()));
+                        // self.runtime.block_on(self.add_history(HistoryEntry::new(HistoryContentType::Info, info_msg.to_string())));
 
                         // Use the helper function to query, print, and collect
-                        let query_result = self.runtime.block_on(
-                            self.query_llm_and_collect(&line, current_theme_for_output),
+                        let started = Instant::now();
+                        let query_result = self.block_on_request(
+                            self.query_llm_and_collect(&messages, current_theme_for_output, target.as_ref(), None),
                         );
+                        let elapsed = started.elapsed();
+                        match &query_result {
+                            Ok((original_content, _)) => self.notify_completion(&tr("repl-response-ready"), original_content, started),
+                            Err(e) => self.notify_completion(&tr("repl-query-failed"), &e.to_string(), started),
+                        }
 
                         match query_result {
                             // Helper already printed the output correctly
                             Ok((original_content, _printed_content)) => {
-                                // Just store the original content
-                                let model_name = self.runtime.block_on(self.state.get_model());
-                                self.runtime.block_on(self.add_history(
-                                    HistoryContentType::LlmResponse { model: model_name },
+                                tee::output(&original_content);
+                                // Follow-up prompts are sent with this one and its answer
+                                if let Some(sent) = messages.last() {
+                                    self.runtime.block_on(self.state.add_turn(&sent.content, &original_content));
+                                }
+                                // Just store the original content, tagged with the provider/model that answered
+                                self.runtime.block_on(self.add_history(HistoryEntry::new(
+                                    HistoryContentType::LlmResponse { model: model_name.clone() },
                                     original_content, // Store original (potentially raw MD)
-                                ));
+                                ).with_model(&provider_name, &model_name).with_prompt(prompt).with_duration(elapsed)));
                             }
                             Err(e) => {
-                                let err_msg = format!("LLM Error: {}", e);
+                                let err_msg = tr_args("error-llm", &[("error", &self.request_error(&e))]);
                                 eprintln!("{}", self.colorize(&err_msg, palette_output.error));
+                                tee::output(&err_msg);
                                 // Store error
-                                self.runtime.block_on(self.add_history(
+                                self.runtime.block_on(self.add_history(HistoryEntry::new(
                                     HistoryContentType::Error { source: "LLM Query".to_string() },
                                     err_msg,
-                                ));
+                                ).with_model(&provider_name, &model_name).with_duration(elapsed)));
                             }
                         }
+                        self.print_pending_prompts(palette_output.info);
                     }
                 }
                 // --- Readline Error Handling ---
                 Err(ReadlineError::Interrupted) => {
                     let (_skin_exit, palette_exit) = get_theme_resources(RenderTheme::Default);
-                    println!("\n{}", self.colorize("CTRL-C received, exiting.", palette_exit.info));
+                    println!("\n{}", self.colorize(&tr("repl-exit-ctrl-c"), palette_exit.info));
                     break;
                 }
                 Err(ReadlineError::Eof) => {
                     let (_skin_exit, palette_exit) = get_theme_resources(RenderTheme::Default);
-                    println!("\n{}", self.colorize("CTRL-D received, exiting.", palette_exit.info));
+                    println!("\n{}", self.colorize(&tr("repl-exit-ctrl-d"), palette_exit.info));
                     break;
                 }
                 Err(err) => {
                     let (_skin_exit, palette_exit) = get_theme_resources(RenderTheme::Default);
-                    eprintln!("{}", self.colorize(&format!("Readline Error: {}", err), palette_exit.error));
+                    eprintln!("{}", self.colorize(&tr_args("error-readline", &[("error", &err.to_string())]), palette_exit.error));
                     // Maybe don't store readline errors in app history? Up to you.
                     return Err(ReplError::Readline(err.to_string()));
                 }
             }
         } // --- End Loop ---
+        Ok(())
+    }
 
-        if let Err(e) = rl.save_history("history.txt") {
-            let (_skin_exit, palette_exit) = get_theme_resources(RenderTheme::Default);
-            eprintln!("{}", self.colorize(&format!("WARN: Failed to save rustyline history: {}", e), palette_exit.error));
+
+    // Returns `cmd` if it is known; otherwise asks whether to run the closest known command and
+    // returns that if accepted. Unknown, uncorrected names fall through to the usual error.
+    fn correct_command(&self, cmd: &str) -> String {
+        let candidates = self.command_registry.list_commands().into_iter().chain(REPL_ONLY_COMMANDS.iter().copied());
+        if self.command_registry.get_command(cmd).is_some() || REPL_ONLY_COMMANDS.contains(&cmd) {
+            return cmd.to_string();
+        }
+        let Some(suggestion) = closest_command(cmd, candidates) else {
+            return cmd.to_string();
+        };
+        let accepted = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(tr_args("repl-did-you-mean", &[("command", cmd), ("suggestion", suggestion)]))
+            .default(true)
+            .interact_opt();
+        match accepted {
+            Ok(Some(true)) => suggestion.to_string(),
+            _ => cmd.to_string(),
+        }
+    }
+
+    // If the same prompt was answered by this provider/model within the configured window, asks
+    // whether to show that answer instead of querying again. Returns true if it was shown.
+    fn offer_cached_answer(&self, prompt: &str, provider: &str, model: &str, theme: RenderTheme) -> bool {
+        let ui_config = self.runtime.block_on(self.state.get_config()).ui;
+        if ui_config.cached_answer_window_mins == 0 {
+            return false;
+        }
+        let window = Duration::from_secs(ui_config.cached_answer_window_mins * 60);
+        let Some(cached) = self.runtime.block_on(self.state.cached_answer(prompt, provider, model, window)) else {
+            return false;
+        };
+        let minutes = (Utc::now() - cached.timestamp).num_minutes().max(0).to_string();
+        let target = format!("{}:{}", provider, model);
+        let choices = [tr("repl-cached-show"), tr("repl-cached-fresh")];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(tr_args("repl-cached-offer", &[("minutes", &minutes), ("target", &target)]))
+            .items(&choices)
+            .default(0)
+            .interact_opt();
+        if !matches!(selection, Ok(Some(0))) {
+            return false;
+        }
+        let current_mode = self.runtime.block_on(self.state.get_markdown_mode());
+        if ui_config.accessible {
+            println!("{}", render_accessible(&cached.content));
+        } else if current_mode != MarkdownMode::Off {
+            println!("{}", self.render_markdown(&cached.content, theme));
+        } else {
+            println!("{}", cached.content);
+        }
+        tee::output(&cached.content);
+        true
+    }
+
+    // Sends a desktop notification if the task started at `started` ran past the configured threshold.
+    fn notify_completion(&self, title: &str, body: &str, started: Instant) {
+        let config = self.runtime.block_on(self.state.get_config());
+        notify_if_slow(&config.notifications, title, body, started.elapsed());
+    }
+
+    // Queues a buffer's text as the next prompt, so it is expanded, hooked and streamed like typed input.
+    fn send_buffer(&self, args: &str, error_color: (u8, u8, u8)) {
+        let Some(name) = send_target(args) else { return };
+        match self.runtime.block_on(self.state.get_buffer(name)) {
+            Some(text) if !text.trim().is_empty() => self.prompt_queue.push_prompt_front(text),
+            Some(_) => eprintln!("{}", self.colorize(&tr_args("error-command", &[("command", "buf"), ("error", &format!("Buffer '{}' is empty.", name))]), error_color)),
+            None => eprintln!("{}", self.colorize(&tr_args("error-command", &[("command", "buf"), ("error", &format!("No buffer named '{}'.", name))]), error_color)),
+        }
+    }
+
+    // Streams the rest of the newest answer from the model that wrote it, appending it to that
+    // answer's history entry. Text the model repeats from the answer so far is not shown again.
+    async fn continue_last_answer(&self, theme: RenderTheme, info_color: (u8, u8, u8)) -> ReplResult<()> {
+        if !self.state.is_command_enabled("continue", CommandOrigin::Local).await {
+            return Err(ReplError::CommandDisabled("continue".to_string()));
+        }
+        let continuation = Continuation::of_last_answer(&self.state).await?;
+        println!("{}", self.colorize(&tr_args("repl-continuing", &[("provider", &continuation.provider), ("model", &continuation.model)]), info_color));
+        let target = QueryTarget { provider: continuation.provider.clone(), model: continuation.model.clone() };
+        let started = Instant::now();
+        let (text, _) = self.query_llm_and_collect(&[ChatMessage::user(continuation.prompt.clone())], theme, Some(&target), Some(&continuation.entry.content)).await?;
+        tee::output(&text);
+        if !self.skip_history {
+            continuation.record(&self.state, &text, started.elapsed()).await;
         }
         Ok(())
-    } // --- End run() ---
+    }
+
+    // Opens the recall finder over session history and this editor's prompts (which include
+    // earlier sessions loaded from the history file); the selection pre-fills the next prompt.
+    fn run_recall(&self, rl: &ReplEditor, query: &str, error_color: (u8, u8, u8)) {
+        let prompts: Vec<String> = rl.history().iter().cloned().collect();
+        match self.runtime.block_on(recall(&self.state, prompts, query)) {
+            Ok(Some(text)) => self.prompt_queue.set_partial(text),
+            Ok(None) => {}
+            Err(e) => eprintln!("{}", self.colorize(&tr_args("error-command", &[("command", "recall"), ("error", &e.to_string())]), error_color)),
+        }
+    }
 
+    // Shows prompts typed during the last response that are waiting to be sent.
+    fn print_pending_prompts(&self, color: (u8, u8, u8)) {
+        let pending = self.prompt_queue.pending();
+        if pending.is_empty() { return; }
+        println!("{}", self.colorize(&tr_args("repl-pending-prompts", &[("count", &pending.len().to_string())]), color));
+        for (index, queued_line) in pending.iter().enumerate() {
+            println!("{}", self.colorize(&format!("  {}. {}", index + 1, queued_line), color));
+        }
+    }
 
     async fn execute_command(&self, cmd: &str, args: &str) -> ReplResult<String> {
         if let Some(command) = self.command_registry.get_command(cmd) {
-            command.execute(args).await
+            if !self.state.is_command_enabled(cmd, CommandOrigin::Local).await {
+                return Err(ReplError::CommandDisabled(cmd.to_string()));
+            }
+            // Route through the streaming entry point so long-running commands can report progress
+            let (event_tx, event_rx) = mpsc::unbounded_channel();
+            let accessible = self.state.get_config().await.ui.accessible;
+            let renderer = tokio::spawn(render_progress(event_rx, accessible));
+            let result = command.execute_streaming(args, event_tx).await;
+            let _ = renderer.await; // Sender is dropped with the command; wait for the bar to clear
+            result
         } else {
             Err(ReplError::UnknownCommand(cmd.to_string()))
         }
     }
 
 
+    // Parses an `@provider:model <prompt>` (or `@alias <prompt>`) prefix. Only registered provider
+    // names are treated as overrides; the model is everything after the provider name (e.g. llama3:latest).
+    fn parse_query_target<'a>(&self, line: &'a str) -> Option<(QueryTarget, &'a str)> {
+        let rest = line.strip_prefix('@')?;
+        let (spec, prompt) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let is_alias = self.runtime.block_on(self.state.get_config()).aliases.contains_key(spec);
+        let (provider, model) = if is_alias {
+            self.runtime.block_on(self.state.resolve_model_spec(spec))
+        } else {
+            self.state.split_model_spec(spec)?
+        };
+        if model.is_empty() || !self.state.list_providers().contains(&provider) {
+            return None;
+        }
+        Some((QueryTarget { provider, model }, prompt.trim_start()))
+    }
+
     // --- New Helper: query_llm_and_collect ---
     // Executes LLM query, handles printing based on mode, and returns
     // both the original content string and the string that was printed.
+    // `target` overrides the session provider/model for this query only. `continues` is the answer
+    // being continued, whose repeated text is dropped from the start of the response.
     async fn query_llm_and_collect(
         &self,
-        prompt: &str,
+        messages: &[ChatMessage],
         theme: RenderTheme,
+        target: Option<&QueryTarget>,
+        continues: Option<&str>,
     ) -> ReplResult<(String, String)> { // Returns (original_content, printed_content)
-        if let Some(provider) = self.state.get_current_provider().await {
-            let model = self.state.get_model().await;
+        let (provider, model) = match target {
+            Some(t) => {
+                let provider = self.state.get_provider_by_name(&t.provider)
+                    .ok_or_else(|| ReplError::UnknownProvider(t.provider.clone()))?;
+                provider.check_readiness().await.map_err(|e| {
+                    ReplError::Provider(format!("Provider '{}' is not ready: {}", t.provider, e))
+                })?;
+                (Some(provider), t.model.clone())
+            }
+            None => (self.state.get_current_provider().await, self.state.get_model().await),
+        };
+        if let Some(provider) = provider {
             let current_mode = self.state.get_markdown_mode().await;
+            let config = self.state.get_config().await;
+            let split_view = config.markdown.split;
+            let ui_config = config.ui;
+            let accessible = ui_config.accessible;
+            let limits = self.state.get_output_limits().await;
+            let params = self.state.get_generation_params().await;
             let (skin, palette) = get_theme_resources(theme);
+            // Images from /attach are sent with this prompt (unstreamed) and dropped once it's answered
+            let images = self.state.get_attachments().await;
+            let streamed = if images.is_empty() { provider.chat_stream(&model, messages, &params).await } else { Ok(None) };
 
-            match provider.query_stream(&model, prompt).await {
+            match streamed {
                  // --- Streaming Case ---
                 Ok(Some(stream)) => {
+                    let stream = match continues {
+                        Some(answer) => skip_repeat(stream, answer),
+                        None => stream,
+                    };
+                    let stream = limit_stream(stream, limits); // Enforce stop sequences / max length
+                    let stream = match ui_config.pace_chars_per_sec {
+                        Some(chars_per_sec) => pace_stream(stream, chars_per_sec),
+                        None => stream,
+                    };
+                    // Keep accepting input while streaming; lines are queued until this response ends
+                    let _typeahead = TypeaheadCapture::start(self.prompt_queue.clone());
                     let mut full_response = String::new(); // Collects original content
                     let mut printed_output_capture = String::new(); // Captures what's printed (approx)
                     let mut term = io::stdout();
 
+                    // Accessibility mode: plain lines with announced structure, whatever the Markdown mode
+                    if accessible {
+                        let mut renderer = AccessibleRenderer::new();
+                        let mut stream_pin = stream;
+                        while let Some(chunk_result) = stream_pin.next().await {
+                            let chunk = chunk_result?;
+                            full_response.push_str(&chunk);
+                            let lines = renderer.push(&chunk);
+                            print!("{}", lines);
+                            io::stdout().flush().map_err(ReplError::Io)?;
+                            printed_output_capture.push_str(&lines);
+                        }
+                        let rest = renderer.finish();
+                        print!("{}", rest);
+                        printed_output_capture.push_str(&rest);
+                        return Ok((full_response, printed_output_capture));
+                    }
+
                     match current_mode {
                         MarkdownMode::Off => {
                             let mut stream_pin = stream;
@@ -301,6 +814,22 @@ impl Repl {
                                  full_response.push_str(&chunk);
                                  raw_stream_print.push_str(&chunk);
                              }
+                             // Split view: replace the streamed text with raw and formatted columns, if it fits
+                             let (width, height) = termimad::terminal_size();
+                             let split = if split_view { side_by_side(&full_response, &skin, width as usize) } else { None };
+                             if let Some(columns) = split {
+                                 let rows = printed_rows(&raw_stream_print, width as usize) - 1 + usize::from(raw_stream_print.ends_with('\n'));
+                                 if rows < height as usize {
+                                     let erase = if rows > 0 { format!("\r\x1B[{}A\x1B[J", rows) } else { "\r\x1B[J".to_string() };
+                                     term.write_all(erase.as_bytes()).map_err(ReplError::Io)?;
+                                     raw_stream_print.clear();
+                                 }
+                                 let separator = format!("\n{}\n", self.colorize("--- Raw | Formatted Response ---", palette.info));
+                                 print!("{}{}", separator, columns);
+                                 io::stdout().flush().map_err(ReplError::Io)?;
+                                 printed_output_capture = format!("{}{}{}", raw_stream_print, separator, columns);
+                                 return Ok((full_response, printed_output_capture));
+                             }
                              let separator = format!("\n\n{}", self.colorize("--- Formatted Response ---", palette.info));
                              let formatted = self.render_markdown(&full_response, theme);
                              println!("{}{}", separator, formatted); // Print separator + formatted
@@ -315,8 +844,8 @@ impl Repl {
                               let mut stream_pin = stream;
                               let mut last_term_width = 0;
                               let mut previous_render_height = 0;
-                              term.write_all(b"\x1B[?25l").map_err(ReplError::Io)?; // Hide cursor
-                              term.flush().map_err(ReplError::Io)?;
+                              // Shown again when dropped, even if rendering panics
+                              let cursor = HiddenCursor::hide().map_err(ReplError::Io)?;
 
                               let execution_result = async {
                                   while let Some(chunk_result) = stream_pin.next().await {
@@ -347,7 +876,8 @@ impl Repl {
                               }.await;
 
                               // Cleanup cursor etc.
-                              let _ = term.write_all(b"\x1B[?25h"); let _ = term.write_all(b"\n"); let _ = term.flush();
+                              drop(cursor);
+                              let _ = term.write_all(b"\n"); let _ = term.flush();
 
                               execution_result?; // Propagate error from streaming if any
 
@@ -361,8 +891,22 @@ impl Repl {
                  // --- Non-Streaming Case ---
                 Ok(None) | Err(_) => {
                     // Fallback to non-streaming query
-                    let response_content = provider.query(&model, prompt).await?;
-                    if current_mode != MarkdownMode::Off {
+                    let response_content = if images.is_empty() {
+                        provider.chat(&model, messages, &params).await?
+                    } else {
+                        let answer = provider.chat_with_images(&model, messages, &images, &params).await?;
+                        self.state.clear_attachments().await;
+                        answer
+                    };
+                    let response_content = match continues {
+                        Some(answer) => strip_repeat(answer, &response_content),
+                        None => response_content,
+                    };
+                    let response_content = limits.apply(&response_content);
+                    if accessible {
+                        let plain = render_accessible(&response_content);
+                        Ok((response_content, plain))
+                    } else if current_mode != MarkdownMode::Off {
                         let formatted = self.render_markdown(&response_content, theme);
                         Ok((response_content, formatted)) // Return raw and formatted
                     } else {
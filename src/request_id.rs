@@ -0,0 +1,82 @@
+// src/request_id.rs
+//! Request IDs tie one query or command to everything it produced: its history entry, the HTTP
+//! debug log lines of its provider calls, tracing output, and the server's `X-Request-Id` header
+//! and error bodies. The ID is carried in a task-local while the request's future runs.
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use lazy_static::lazy_static;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::Instrument;
+
+pub const HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    // Distinguishes IDs from different runs, whose counters both start at 1.
+    static ref RUN: u64 = SystemTime::now().duration_since(UNIX_EPOCH).map(|t| t.as_secs()).unwrap_or(0) & 0xff_ffff;
+}
+
+/// A new ID, e.g. `3fa2c1-0007`: the run, then a counter.
+pub fn next() -> String {
+    format!("{:06x}-{:04x}", *RUN, COUNTER.fetch_add(1, Ordering::Relaxed) + 1)
+}
+
+/// The ID of the request being handled, if any.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Runs `f` as the request `id`, if any; for code that can't await `scope`, such as `Drop`.
+pub fn within<T>(id: Option<String>, f: impl FnOnce() -> T) -> T {
+    match id {
+        Some(id) => REQUEST_ID.sync_scope(id, f),
+        None => f(),
+    }
+}
+
+/// Runs `future` as the request `id`, inside a `request` tracing span.
+pub async fn scope<F: Future>(id: String, future: F) -> F::Output {
+    let span = tracing::info_span!("request", id = %id);
+    REQUEST_ID.scope(id, future.instrument(span)).await
+}
+
+/// Runs `future` as the current request, if any; for tasks spawned while handling one. The ID is
+/// read now, since the spawned task doesn't see this one's task-local.
+pub fn inherit<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let id = current();
+    async move {
+        match id {
+            Some(id) => scope(id, future).await,
+            None => future.await,
+        }
+    }
+}
+
+// Caller-supplied IDs are kept if they are short and can't garble logs.
+fn accept(value: &HeaderValue) -> Option<String> {
+    let id = value.to_str().ok()?.trim();
+    let valid = !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| id.to_string())
+}
+
+/// Server middleware: handles each API call as a request, under the caller's `X-Request-Id` or a
+/// new ID, and returns the ID in the response's `X-Request-Id` header.
+pub async fn layer(request: Request, next: Next) -> Response {
+    let id = request.headers().get(HEADER).and_then(accept).unwrap_or_else(self::next);
+    let mut response = scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(HeaderName::from_static(HEADER), value);
+    }
+    response
+}
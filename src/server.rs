@@ -1,33 +1,52 @@
 // src/server.rs
 use crate::{
+    admission::{self, Admission},
+    audit::{self, ApiGuard},
+    cancel::{self, Cancellable},
+    commands::CommandEvent,
+    config::CommandOrigin,
     error::ReplError, // Only need ReplError
+    history,
+    limits::OutputLimits,
+    providers::{ChatMessage, GenerationParams, LlmProvider, ModelInfo},
+    request_id,
     state::{AppState, HistoryEntry}, // Only need AppState and HistoryEntry directly
     shell::execute_shell_command,
 };
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    response::{IntoResponse, Json as AxumJson, Response},
-    routing::{get, post},
+    middleware,
+    response::{sse::{Event, KeepAlive, Sse}, IntoResponse, Json as AxumJson, Response},
+    routing::{get, post, put},
     Router,
 };
+use futures::channel::mpsc;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::time::Instant;
+use tokio::sync::broadcast;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 // --- Request/Response Structs for API ---
-#[derive(Serialize)] struct ApiErrorResponse { error: String, details: Option<String> }
+#[derive(Serialize)] struct ApiErrorResponse { error: String, details: Option<String>, #[serde(skip_serializing_if = "Option::is_none")] request_id: Option<String> }
 #[derive(Serialize)] struct AppStatusResponse { current_provider: String, current_model: String, markdown_mode: String, theme: String }
 #[derive(Serialize)] struct ListResponse<T> { items: Vec<T> }
-#[derive(Deserialize)] struct QueryRequest { prompt: String, model: Option<String> }
-#[derive(Serialize)] struct QueryResponse { response: String }
-#[derive(Deserialize)] struct CommandRequest { command: String }
+// GET /providers/{name}/models: each model with what the provider reports about it
+#[derive(Serialize)] struct ModelsResponse { provider: String, items: Vec<ModelInfo> }
+#[derive(Deserialize)] struct QueryRequest { prompt: String, model: Option<String>, #[serde(default)] stream: bool }
+#[derive(Serialize)] struct QueryResponse { response: String, #[serde(skip_serializing_if = "std::ops::Not::not")] cancelled: bool }
+#[derive(Serialize)] struct CancelResponse { cancelled: bool }
+#[derive(Deserialize)] struct CommandRequest { command: String, #[serde(default)] stream: bool }
 #[derive(Serialize)] struct CommandResponse { output: String }
 #[derive(Deserialize)] struct ShellRequest { command: String }
 #[derive(Serialize)] struct ShellResponse { output: String }
 #[derive(Serialize)] struct HistoryResponse { history: Vec<HistoryEntry> }
+#[derive(Serialize)] struct CommandInfo { name: String, help: String, local: bool, remote: bool }
+#[derive(Deserialize)] struct CommandPolicyRequest { local: Option<bool>, remote: Option<bool> }
 
 // --- Axum Error Handling ---
 enum ApiError { Repl(ReplError), BadRequest(String), NotFound(String) }
@@ -37,18 +56,22 @@ impl IntoResponse for ApiError {
             ApiError::Repl(err) => {
                 let status_code = match &err {
                     ReplError::UnknownProvider(_) | ReplError::UnknownCommand(_) => StatusCode::NOT_FOUND,
+                    ReplError::CommandDisabled(_) => StatusCode::FORBIDDEN,
                     ReplError::Provider(msg) if msg.contains("API key is missing") => StatusCode::UNAUTHORIZED,
                     ReplError::Provider(_) | ReplError::Command(_) => StatusCode::BAD_REQUEST,
                     ReplError::Request(_) | ReplError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
                     ReplError::Json(_) => StatusCode::BAD_REQUEST,
-                    ReplError::Readline(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                    ReplError::Readline(_) | ReplError::Bridge(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                    ReplError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
                 };
                 (status_code, err.to_string(), None::<String>) // Provide type hint for None
             }
             ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg, None::<String>),
             ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg, None::<String>),
         };
-        let body = AxumJson(ApiErrorResponse { error: status.canonical_reason().unwrap_or("Error").to_string(), details: Some(error_message) });
+        // Logged inside the request's span, and the body names the request, so the two can be matched up
+        warn!("{} {}", status, error_message);
+        let body = AxumJson(ApiErrorResponse { error: status.canonical_reason().unwrap_or("Error").to_string(), details: Some(error_message), request_id: request_id::current() });
         (status, body).into_response()
     }
 }
@@ -61,24 +84,130 @@ async fn get_status(State(state): State<AppState>) -> Result<AxumJson<AppStatusR
 async fn list_providers(State(state): State<AppState>) -> Result<AxumJson<ListResponse<String>>, ApiError> {
     let providers = state.list_providers(); Ok(AxumJson(ListResponse { items: providers }))
 }
-async fn list_models( State(state): State<AppState>, Path(provider_name): Path<String>, ) -> Result<AxumJson<ListResponse<String>>, ApiError> {
+async fn list_models( State(state): State<AppState>, Path(provider_name): Path<String>, ) -> Result<AxumJson<ModelsResponse>, ApiError> {
     let provider = state.get_provider_by_name(&provider_name).ok_or_else(|| ApiError::NotFound(format!("Provider '{}' not found.", provider_name)))?;
-    provider.check_readiness().await?; let models = provider.get_models().await?; Ok(AxumJson(ListResponse { items: models }))
+    state.check_provider_ready(&provider_name, provider.as_ref()).await?; let models = state.provider_models(&provider_name, provider.as_ref()).await?; Ok(AxumJson(ModelsResponse { provider: provider_name, items: models }))
 }
-async fn post_query( State(state): State<AppState>, AxumJson(payload): AxumJson<QueryRequest>, ) -> Result<AxumJson<QueryResponse>, ApiError> {
+// A prompt ready to send to the current provider.
+struct PreparedQuery { provider: Box<dyn LlmProvider>, provider_name: String, model: String, prompt: String, messages: Vec<ChatMessage>, params: GenerationParams, limits: OutputLimits, started: Instant }
+impl PreparedQuery {
+    // The answer's history entry; a cancelled answer keeps what had arrived.
+    fn entry(&self, response: &str, cancelled: bool) -> HistoryEntry {
+        HistoryEntry::new(crate::state::HistoryContentType::LlmResponse { model: self.model.clone() }, response.to_string()).with_model(&self.provider_name, &self.model).with_prompt(&self.prompt).with_duration(self.started.elapsed()).with_cancelled(cancelled)
+    }
+}
+/// Answers a prompt, whole or (with `"stream": true`) as SSE. Either way the request can be stopped
+/// with `POST /query/{id}/cancel`, using its X-Request-Id.
+async fn post_query( State(state): State<AppState>, AxumJson(payload): AxumJson<QueryRequest>, ) -> Result<Response, ApiError> {
     let provider_name = state.get_provider_name().await; let provider = state.get_current_provider().await.ok_or_else(|| ApiError::BadRequest(format!("Current provider '{}' is not available or configured.", provider_name)))?;
-    let model_to_use = match payload.model { Some(m) => m, None => state.get_model().await, };
-    let response_text = provider.query(&model_to_use, &payload.prompt).await?;
-    state.add_history_entry(HistoryEntry { entry_type: crate::state::HistoryContentType::LlmResponse { model: model_to_use.clone() }, content: response_text.clone(), }).await;
-    Ok(AxumJson(QueryResponse { response: response_text }))
+    let model = match payload.model { Some(m) => m, None => state.get_model().await, };
+    let limits = state.get_output_limits().await;
+    let started = Instant::now();
+    let messages = state.get_config().await.prompt_messages(&payload.prompt);
+    let params = state.get_generation_params().await;
+    let query = PreparedQuery { provider, provider_name, model, prompt: payload.prompt, messages, params, limits, started };
+    if payload.stream { return Ok(stream_query(state, query).into_response()); }
+    let mut cancellable = Cancellable::register(&request_id::current().unwrap_or_default());
+    let answer = cancellable.run(query.provider.chat(&query.model, &query.messages, &query.params)).await.transpose()?;
+    let cancelled = answer.is_none();
+    let response_text = query.limits.apply(&answer.unwrap_or_default());
+    state.add_history_entry(query.entry(&response_text, cancelled)).await;
+    Ok(AxumJson(QueryResponse { response: response_text, cancelled }).into_response())
+}
+/// Streams an answer as SSE: `start` (data: the request ID), `token` events, then `done` with the
+/// whole answer, `cancelled` with the part that arrived, or `error`. A client that disconnects
+/// cancels it too.
+fn stream_query(state: AppState, query: PreparedQuery) -> Sse<mpsc::UnboundedReceiver<Result<Event, Infallible>>> {
+    let (event_tx, event_rx) = mpsc::unbounded::<Result<Event, Infallible>>();
+    tokio::spawn(request_id::inherit(async move {
+        let id = request_id::current().unwrap_or_default();
+        let mut cancellable = Cancellable::register(&id);
+        let _ = event_tx.unbounded_send(Ok(Event::default().event("start").data(id)));
+        let (mut response, mut failure, mut disconnected) = (String::new(), None, false);
+        match cancellable.run(query.provider.chat_stream(&query.model, &query.messages, &query.params)).await {
+            Some(Ok(Some(mut stream))) => {
+                while let Some(chunk) = cancellable.next(&mut stream).await {
+                    match chunk {
+                        Ok(text) => {
+                            response.push_str(&text);
+                            if event_tx.unbounded_send(Ok(Event::default().event("token").data(text))).is_err() { disconnected = true; break; }
+                        }
+                        Err(e) => { failure = Some(e); break; }
+                    }
+                }
+            } // Dropping the stream here closes the provider's response
+            Some(Ok(None)) => match cancellable.run(query.provider.chat(&query.model, &query.messages, &query.params)).await {
+                Some(Ok(text)) => response = text,
+                Some(Err(e)) => failure = Some(e),
+                None => {}
+            },
+            Some(Err(e)) => failure = Some(e),
+            None => {}
+        }
+        let cancelled = cancellable.is_cancelled() || disconnected;
+        let response = query.limits.apply(&response);
+        if !response.is_empty() || cancelled {
+            state.add_history_entry(query.entry(&response, cancelled)).await;
+        }
+        let end = match failure {
+            Some(e) if !cancelled => Event::default().event("error").data(e.to_string()),
+            _ if cancelled => Event::default().event("cancelled").data(response),
+            _ => Event::default().event("done").data(response),
+        };
+        let _ = event_tx.unbounded_send(Ok(end));
+    }));
+    Sse::new(event_rx).keep_alive(KeepAlive::default())
+}
+/// Stops a running `POST /query` or streamed `/v1/chat/completions` by its request ID.
+async fn post_cancel_query(Path(id): Path<String>) -> Result<AxumJson<CancelResponse>, ApiError> {
+    if cancel::cancel(&id) { Ok(AxumJson(CancelResponse { cancelled: true })) } else { Err(ApiError::NotFound(format!("No query with request ID '{}' is running.", id))) }
 }
-async fn post_command( State(state): State<AppState>, AxumJson(payload): AxumJson<CommandRequest>, ) -> Result<AxumJson<CommandResponse>, ApiError> {
+async fn post_command( State(state): State<AppState>, AxumJson(payload): AxumJson<CommandRequest>, ) -> Result<Response, ApiError> {
     let parts: Vec<&str> = payload.command.trim().splitn(2, ' ').collect(); let (cmd_name, args) = if parts.len() > 1 { (parts[0], parts[1]) } else { (parts[0], "") };
     let command_registry = state.command_registry(); // Get Arc<CommandRegistry>
-    let command = command_registry.get_command(cmd_name).ok_or_else(|| ApiError::NotFound(format!("Command '{}' not found.", cmd_name)))?; // Access via Arc
+    let command = command_registry.get_command(cmd_name).ok_or_else(|| match command_registry.suggest(cmd_name) {
+        Some(suggestion) => ApiError::NotFound(format!("Command '{}' not found. Did you mean '{}'?", cmd_name, suggestion)),
+        None => ApiError::NotFound(format!("Command '{}' not found.", cmd_name)),
+    })?; // Access via Arc
+    if !state.is_command_enabled(cmd_name, CommandOrigin::Remote).await { return Err(ReplError::CommandDisabled(cmd_name.to_string()).into()); }
+    if payload.stream { return Ok(stream_command(state.clone(), payload.command.clone(), cmd_name.to_string(), args.to_string()).into_response()); }
+    let started = Instant::now();
     let output_text = command.execute(args).await?;
-    state.add_history_entry(HistoryEntry { entry_type: crate::state::HistoryContentType::CommandResult { command: payload.command.clone() }, content: output_text.clone(), }).await;
-    Ok(AxumJson(CommandResponse { output: output_text }))
+    state.add_history_entry(HistoryEntry::new(crate::state::HistoryContentType::CommandResult { command: history::recorded_command(&payload.command) }, output_text.clone()).with_duration(started.elapsed())).await;
+    Ok(AxumJson(CommandResponse { output: output_text }).into_response())
+}
+/// Runs a command in the background and streams its incremental output as SSE:
+/// `output` and `progress` events while it runs, then a final `done` (full output) or `error` event.
+fn stream_command(state: AppState, full_command: String, cmd_name: String, args: String) -> Sse<mpsc::UnboundedReceiver<Result<Event, Infallible>>> {
+    let (event_tx, event_rx) = mpsc::unbounded::<Result<Event, Infallible>>();
+    tokio::spawn(request_id::inherit(async move {
+        let (output_tx, mut output_rx) = tokio::sync::mpsc::unbounded_channel::<CommandEvent>();
+        let forward_tx = event_tx.clone();
+        let forwarder = tokio::spawn(async move {
+            while let Some(command_event) = output_rx.recv().await {
+                let event = match command_event {
+                    CommandEvent::Output(chunk) => Event::default().event("output").data(chunk),
+                    CommandEvent::Progress(update) => Event::default().event("progress").data(serde_json::to_string(&update).unwrap_or_default()),
+                };
+                let _ = forward_tx.unbounded_send(Ok(event));
+            }
+        });
+        let command_registry = state.command_registry();
+        let started = Instant::now();
+        let result = match command_registry.get_command(&cmd_name) {
+            Some(command) => command.execute_streaming(&args, output_tx).await,
+            None => Err(ReplError::UnknownCommand(cmd_name.clone())),
+        };
+        let _ = forwarder.await; // Sender was dropped with the command future, so this drains and ends
+        match result {
+            Ok(output_text) => {
+                state.add_history_entry(HistoryEntry::new(crate::state::HistoryContentType::CommandResult { command: history::recorded_command(&full_command) }, output_text.clone()).with_duration(started.elapsed())).await;
+                let _ = event_tx.unbounded_send(Ok(Event::default().event("done").data(output_text)));
+            }
+            Err(e) => { let _ = event_tx.unbounded_send(Ok(Event::default().event("error").data(e.to_string()))); }
+        }
+    }));
+    Sse::new(event_rx).keep_alive(KeepAlive::default())
 }
 async fn post_shell( State(state): State<AppState>, AxumJson(payload): AxumJson<ShellRequest>, ) -> Result<AxumJson<ShellResponse>, ApiError> {
     let command_line = payload.command.trim();
@@ -87,35 +216,102 @@ async fn post_shell( State(state): State<AppState>, AxumJson(payload): AxumJson<
     let command_line_owned = command_line.to_string(); // Clone for spawn_blocking
     let command_line_for_history = command_line_owned.clone(); // Clone again for history
 
+    let started = Instant::now();
     let output_text = tokio::task::spawn_blocking(move || execute_shell_command(&command_line_owned)) // Closure takes ownership of command_line_owned
         .await
         .map_err(|e| ApiError::Repl(ReplError::Command(format!("Shell task join error: {}", e))))??; // Double '?'
 
     // Use the second clone for the history entry
-    state.add_history_entry(HistoryEntry {
-        entry_type: crate::state::HistoryContentType::ShellOutput { command: command_line_for_history }, // Use the second clone
-        content: output_text.clone(),
-    }).await;
+    state.add_history_entry(HistoryEntry::new(
+        crate::state::HistoryContentType::ShellOutput { command: command_line_for_history }, // Use the second clone
+        output_text.clone(),
+    ).with_duration(started.elapsed())).await;
 
     Ok(AxumJson(ShellResponse { output: output_text }))
 }
+async fn command_info(state: &AppState, name: &str) -> Option<CommandInfo> {
+    let command_registry = state.command_registry();
+    let command = command_registry.get_command(name)?;
+    let config = state.get_config().await;
+    Some(CommandInfo {
+        name: name.to_string(),
+        help: command.help().to_string(),
+        local: config.commands.is_enabled(name, CommandOrigin::Local),
+        remote: config.commands.is_enabled(name, CommandOrigin::Remote),
+    })
+}
+/// Lists every registered command and whether it is enabled locally and for API callers.
+async fn list_commands(State(state): State<AppState>) -> Result<AxumJson<ListResponse<CommandInfo>>, ApiError> {
+    let mut items = Vec::new();
+    for name in state.command_registry().list_commands() {
+        items.extend(command_info(&state, name).await);
+    }
+    Ok(AxumJson(ListResponse { items }))
+}
+/// Enables or disables a command for the local REPL and/or remote callers; omitted origins are left as they are.
+async fn put_command_policy(State(state): State<AppState>, Path(name): Path<String>, AxumJson(payload): AxumJson<CommandPolicyRequest>) -> Result<AxumJson<CommandInfo>, ApiError> {
+    if state.command_registry().get_command(&name).is_none() { return Err(ApiError::NotFound(format!("Command '{}' not found.", name))); }
+    if let Some(enabled) = payload.local { state.set_command_enabled(&name, CommandOrigin::Local, enabled).await; }
+    if let Some(enabled) = payload.remote { state.set_command_enabled(&name, CommandOrigin::Remote, enabled).await; }
+    info!("Command policy for '/{}' updated: local={:?} remote={:?}", name, payload.local, payload.remote);
+    command_info(&state, &name).await.map(AxumJson).ok_or_else(|| ApiError::NotFound(format!("Command '{}' not found.", name)))
+}
+/// Streams state changes as SSE; the event name is the change type and the data its JSON.
+/// Subscribers that fall behind get a `lagged` event with the number of events they missed.
+async fn get_events(State(state): State<AppState>) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let events = futures::stream::unfold(state.subscribe(), |mut receiver| async move {
+        let event = match receiver.recv().await {
+            Ok(state_event) => Event::default().event(state_event.kind()).data(serde_json::to_string(&state_event).unwrap_or_default()),
+            Err(broadcast::error::RecvError::Lagged(missed)) => Event::default().event("lagged").data(missed.to_string()),
+            Err(broadcast::error::RecvError::Closed) => return None,
+        };
+        Some((Ok(event), receiver))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
 async fn get_history(State(state): State<AppState>) -> Result<AxumJson<HistoryResponse>, ApiError> {
     let history_vec = state.get_history().await; Ok(AxumJson(HistoryResponse { history: history_vec }))
 }
 
 // --- Server Setup ---
-pub async fn run_server(state: AppState, addr: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
+/// Runs the REST API server. With `gateway` set, the OpenAI-compatible `/v1/*` routes are added too.
+pub async fn run_server(state: AppState, addr: SocketAddr, gateway: bool) -> Result<(), Box<dyn std::error::Error>> {
     let _ = tracing_subscriber::fmt().with_env_filter(tracing_subscriber::EnvFilter::from_default_env()).try_init();
     let cors = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
-    let app = Router::new()
+    let mut api = Router::new();
+    if gateway {
+        info!("Gateway mode: serving OpenAI-compatible endpoints under /v1");
+        api = api.merge(crate::gateway::router());
+    }
+    let server_config = state.get_config().await.server;
+    let api_guard = ApiGuard::from_config(&server_config)?;
+    let admission = Admission::from_config(&server_config.queue);
+    tokio::spawn(crate::webhooks::run(state.clone()));
+    let mut app = api
+        .merge(crate::dashboard::router())
         .route("/status", get(get_status))
         .route("/providers", get(list_providers))
         .route("/providers/:provider_name/models", get(list_models))
         .route("/query", post(post_query))
+        .route("/query/:id/cancel", post(post_cancel_query))
         .route("/command", post(post_command))
+        .route("/commands", get(list_commands))
+        .route("/commands/:name", put(put_command_policy))
         .route("/shell", post(post_shell))
         .route("/history", get(get_history))
-        .with_state(state)
+        .route("/events", get(get_events))
+        .with_state(state);
+    // Inside the guard, so refused callers never take or wait for a slot
+    if let Some(admission) = admission {
+        info!("Answering at most {} prompts at once", server_config.queue.max_concurrent.unwrap_or_default());
+        app = app.layer(middleware::from_fn_with_state(admission, admission::limit));
+    }
+    if let Some(api_guard) = api_guard {
+        if let Some(path) = api_guard.audit_path() { info!("Auditing API calls to {}", path.display()); }
+        app = app.layer(middleware::from_fn_with_state(api_guard, audit::guard));
+    }
+    let app = app
+        .layer(middleware::from_fn(request_id::layer))
         .layer(TraceLayer::new_for_http())
         .layer(cors);
     info!("Starting REST API server on {}", addr);
@@ -0,0 +1,172 @@
+// src/sessions.rs
+//! Named sessions. The REPL always works in one, named after its start time until `/save` names
+//! it; its conversation, history and settings are kept as one JSON file per name under
+//! `$XDG_DATA_HOME/llm-repl/sessions` (else `~/.local/share/llm-repl/sessions`), written by `/save`,
+//! when switching sessions (`/sessions`, `/load`) and on exit, so a session survives restarts.
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{
+    config::{PromptHooks, SessionConfig},
+    error::{ReplError, ReplResult},
+    limits::OutputLimits,
+    providers::{chat::Role, ChatMessage, GenerationParams},
+    state::{HistoryEntry, MarkdownMode, RenderTheme},
+};
+
+/// A session as `/save` writes it. Fields missing from older files keep their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSession {
+    pub saved: DateTime<Utc>,
+    /// Set with `/sessions title`; otherwise the first prompt stands in for it.
+    #[serde(default)]
+    pub title: Option<String>,
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub conversation: Vec<ChatMessage>,
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
+    /// System prompt, pins, context files and conversation memory.
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(default)]
+    pub hooks: PromptHooks,
+    #[serde(default)]
+    pub output_limits: OutputLimits,
+    #[serde(default)]
+    pub generation_params: GenerationParams,
+    pub markdown_mode: Option<MarkdownMode>,
+    pub theme: Option<RenderTheme>,
+}
+
+// Longest title shown in the session list.
+const MAX_TITLE_CHARS: usize = 60;
+
+impl SavedSession {
+    /// The title, else the session's first prompt.
+    pub fn display_title(&self) -> Option<String> {
+        let first_prompt = || {
+            self.conversation.iter().find(|message| message.role == Role::User).map(|message| message.content.clone())
+                .or_else(|| self.history.iter().find_map(|entry| entry.prompt.clone()))
+        };
+        let title = self.title.clone().or_else(first_prompt)?;
+        let flattened = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut shown: String = flattened.chars().take(MAX_TITLE_CHARS).collect();
+        if flattened.chars().count() > MAX_TITLE_CHARS {
+            shown.push('…');
+        }
+        Some(shown)
+    }
+
+    /// True if there is nothing in it worth writing to disk.
+    pub fn is_empty(&self) -> bool {
+        self.conversation.is_empty() && self.history.is_empty()
+    }
+}
+
+/// The session the REPL is working in.
+#[derive(Debug, Clone)]
+pub struct CurrentSession {
+    pub name: String,
+    pub title: Option<String>,
+}
+
+impl Default for CurrentSession {
+    /// A new session, named after the time it started (e.g. `20261016-142501`).
+    fn default() -> Self {
+        Self { name: Local::now().format("%Y%m%d-%H%M%S").to_string(), title: None }
+    }
+}
+
+/// A saved session as `/sessions` lists it.
+pub struct SessionSummary {
+    pub name: String,
+    pub saved: DateTime<Utc>,
+    pub title: Option<String>,
+}
+
+/// `$XDG_DATA_HOME/llm-repl/sessions`, else `~/.local/share/llm-repl/sessions`.
+pub fn sessions_dir() -> Option<PathBuf> {
+    let data_dir = env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var("HOME").ok().map(|home| PathBuf::from(home).join(".local").join("share")))?;
+    Some(data_dir.join("llm-repl").join("sessions"))
+}
+
+/// Checks that `name` can be a session name: names become file names, so they are kept to
+/// letters, digits, `-`, `_` and `.` (not leading).
+pub fn validate_name(name: &str) -> ReplResult<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(ReplError::Command(format!(
+            "Invalid session name '{}': use letters, digits, '-', '_' and '.'.", name
+        )));
+    }
+    Ok(())
+}
+
+fn session_path(name: &str) -> ReplResult<PathBuf> {
+    validate_name(name)?;
+    let dir = sessions_dir().ok_or_else(|| ReplError::Command("Cannot find a data directory: set HOME or XDG_DATA_HOME.".to_string()))?;
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// Writes `session` under `name`, replacing any session saved with that name; returns the file.
+pub fn save(name: &str, session: &SavedSession) -> ReplResult<PathBuf> {
+    let path = session_path(name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write beside the file and rename, so an interrupted save can't leave half a session
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(session)?)?;
+    fs::rename(&temp_path, &path)?;
+    Ok(path)
+}
+
+/// Reads the session saved under `name`.
+pub fn load(name: &str) -> ReplResult<SavedSession> {
+    let path = session_path(name)?;
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(ReplError::Command(format!("No saved session named '{}'. /load lists them.", name)));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&text)
+        .map_err(|e| ReplError::Command(format!("Cannot read session '{}' ({}): {}", name, path.display(), e)))
+}
+
+/// True if a session is saved under `name`.
+pub fn exists(name: &str) -> bool {
+    session_path(name).is_ok_and(|path| path.is_file())
+}
+
+/// The saved sessions, most recently saved first. Files that can't be read are left out.
+pub fn list() -> Vec<SessionSummary> {
+    let Some(entries) = sessions_dir().and_then(|dir| fs::read_dir(dir).ok()) else { return Vec::new() };
+    let mut sessions: Vec<SessionSummary> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            let session: SavedSession = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            Some(SessionSummary { name, saved: session.saved, title: session.display_title() })
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.saved.cmp(&a.saved));
+    sessions
+}
@@ -8,6 +8,12 @@ use crate::error::{ReplError, ReplResult};
 /// If the command fails to run or exits with a non-zero status,
 /// it returns an error containing stderr or status information.
 pub fn execute_shell_command(command_line: &str) -> ReplResult<String> {
+    execute_shell_command_with_env(command_line, &[])
+}
+
+/// `execute_shell_command` with extra environment variables, for passing values the command
+/// must not parse as shell syntax (such as model output) by reference.
+pub fn execute_shell_command_with_env(command_line: &str, vars: &[(String, String)]) -> ReplResult<String> {
     if command_line.trim().is_empty() {
         return Ok("".to_string()); // Nothing to execute
     }
@@ -16,12 +22,14 @@ pub fn execute_shell_command(command_line: &str) -> ReplResult<String> {
         Command::new("cmd")
             .arg("/C") // Tells cmd to execute the following string and then exit
             .arg(command_line)
+            .envs(vars.iter().map(|(name, value)| (name, value)))
             .output() // Executes the command and waits for completion
     } else {
         // Assume Unix-like shell (sh) for Linux, macOS, etc.
         Command::new("sh")
             .arg("-c") // Tells sh to execute the following string
             .arg(command_line)
+            .envs(vars.iter().map(|(name, value)| (name, value)))
             .output() // Executes the command and waits for completion
     };
 
@@ -1,15 +1,28 @@
 // src/state.rs
 use crate::{
+    cache::{CachedLookup, LookupCache},
     commands::CommandRegistry, // Only need CommandRegistry
+    config::{AppConfig, CommandOrigin, PromptHooks, ProviderSettings},
+    data::Dataset,
+    database::Database,
     error::{ReplError, ReplResult},
-    providers::{LlmProvider, ProviderRegistry},
+    history::{find_cached_answer, is_repeat, prune_entries},
+    limits::OutputLimits,
+    preferences::Preference,
+    providers::{citations::{self, Citation}, ChatMessage, GenerationParams, ImageAttachment, LlmProvider, ModelInfo, ProviderRegistry},
+    sessions::{CurrentSession, SavedSession},
+    request_id,
+    usage::{self, EntryUsage, SessionReport},
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize}; // Import Serde traits
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
 
 // --- History Structures ---
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum HistoryContentType {
     LlmResponse { model: String },
@@ -25,27 +38,170 @@ pub enum HistoryContentType {
 pub struct HistoryEntry {
     pub entry_type: HistoryContentType,
     pub content: String,
+    #[serde(default = "Utc::now")]
+    pub timestamp: DateTime<Utc>, // When the entry was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>, // How long the query/command took
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>, // Provider that answered (LLM entries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>, // Prompt as typed (LLM entries), for spotting repeated questions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>, // Sources listed in the answer's footnotes (LLM entries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>, // The query or command that produced it, as in logs and X-Request-Id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<EntryUsage>, // Tokens and cost of the answer (LLM entries), from the usage metrics
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool, // Stopped before it finished (POST /query/{id}/cancel); the content is what had arrived
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>, // Labels added with /tag; a tagged entry is a bookmark (/bookmarks)
+}
+
+impl HistoryEntry {
+    /// Creates an entry stamped with the current time and request ID, and no other metadata.
+    pub fn new(entry_type: HistoryContentType, content: String) -> Self {
+        Self {
+            entry_type, content, timestamp: Utc::now(), duration_ms: None, provider: None, model: None, prompt: None,
+            citations: Vec::new(), request_id: request_id::current(), usage: None, cancelled: false, tags: Vec::new(),
+        }
+    }
+
+    pub fn with_duration(mut self, elapsed: Duration) -> Self {
+        self.duration_ms = Some(elapsed.as_millis() as u64);
+        self
+    }
+
+    pub fn with_model(mut self, provider: &str, model: &str) -> Self {
+        self.provider = Some(provider.to_string());
+        self.model = Some(model.to_string());
+        self
+    }
+
+    pub fn with_prompt(mut self, prompt: &str) -> Self {
+        self.prompt = Some(prompt.to_string());
+        self
+    }
+
+    pub fn with_cancelled(mut self, cancelled: bool) -> Self {
+        self.cancelled = cancelled;
+        self
+    }
+}
+
+// What the calls for request `id` cost an LLM entry's model.
+fn entry_usage(entry: &HistoryEntry, id: Option<&str>, config: &AppConfig) -> Option<EntryUsage> {
+    let HistoryContentType::LlmResponse { model } = &entry.entry_type else { return None };
+    let totals = usage::request_usage(id?, model)?;
+    let price = entry.provider.as_deref().and_then(|provider| config.price_for(provider, model));
+    Some(EntryUsage::new(&totals, price))
 }
 // --- End History Structures ---
 
+// --- State Change Events ---
+// Events buffered per subscriber before slow subscribers start missing some.
+const EVENT_BUFFER: usize = 256;
+
+/// A change to the shared state, broadcast to subscribers such as `GET /events`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StateEvent {
+    ProviderChanged { provider: String },
+    ModelChanged { model: String },
+    MarkdownModeChanged { mode: MarkdownMode },
+    ThemeChanged { theme: RenderTheme },
+    HistoryEntryAdded { entry: HistoryEntry },
+    /// An entry grew in place, e.g. an answer extended by `/continue`.
+    HistoryEntryUpdated { entry: HistoryEntry },
+    RolledBack { checkpoint: String },
+    /// The REPL switched to another named session (`/sessions`, `/load`).
+    SessionLoaded { session: String },
+    ConfigReloaded { changed: Vec<String> },
+}
+
+impl StateEvent {
+    /// Short name of the event type, e.g. "modelChanged".
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StateEvent::ProviderChanged { .. } => "providerChanged",
+            StateEvent::ModelChanged { .. } => "modelChanged",
+            StateEvent::MarkdownModeChanged { .. } => "markdownModeChanged",
+            StateEvent::ThemeChanged { .. } => "themeChanged",
+            StateEvent::HistoryEntryAdded { .. } => "historyEntryAdded",
+            StateEvent::HistoryEntryUpdated { .. } => "historyEntryUpdated",
+            StateEvent::RolledBack { .. } => "rolledBack",
+            StateEvent::SessionLoaded { .. } => "sessionLoaded",
+            StateEvent::ConfigReloaded { .. } => "configReloaded",
+        }
+    }
+}
+// --- End State Change Events ---
+
+/// A named snapshot of the session state that shapes later prompts: provider, model, history,
+/// conversation, prompt hooks, output limits and generation parameters. Restored by `/rollback`.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub created: DateTime<Utc>,
+    pub provider: String,
+    pub model: String,
+    pub history: Vec<HistoryEntry>,
+    pub conversation: Vec<ChatMessage>,
+    pub hooks: PromptHooks,
+    pub output_limits: OutputLimits,
+    pub generation_params: GenerationParams,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum MarkdownMode {
+    #[serde(alias = "append")]
     AppendFormatted,
+    #[serde(alias = "live")]
     LiveStreaming,
+    #[serde(alias = "off")]
     Off,
 }
 
+impl MarkdownMode {
+    /// Parses `append`, `live` or `off` (as used by `/md_source` and the `[markdown]` config).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "append" | "appendformatted" => Some(MarkdownMode::AppendFormatted),
+            "live" | "livestreaming" => Some(MarkdownMode::LiveStreaming),
+            "off" => Some(MarkdownMode::Off),
+            _ => None,
+        }
+    }
+}
+
+/// Where a piece of REPL output comes from, for per-source Markdown rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputSource {
+    Llm,
+    Command,
+    Shell,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum RenderTheme {
+    #[serde(alias = "default")]
     Default,
+    #[serde(alias = "nord")]
     Nord,
+    #[serde(alias = "gruvbox")]
     Gruvbox,
+    #[serde(alias = "grayscale")]
     Grayscale,
+    #[serde(alias = "deuteranopia")]
+    Deuteranopia,
+    #[serde(alias = "protanopia")]
+    Protanopia,
 }
 
 // AppState holds the application's shared state.
 pub struct AppState {
-    provider_registry: ProviderRegistry,
+    provider_registry: Arc<RwLock<ProviderRegistry>>, // Rebuilt when the config file's [providers] change
     // CommandRegistry is wrapped in Arc for cheap cloning and sharing.
     command_registry: Arc<CommandRegistry>,
     current_provider: Arc<Mutex<String>>,
@@ -53,38 +209,96 @@ pub struct AppState {
     current_markdown_mode: Arc<Mutex<MarkdownMode>>,
     current_theme: Arc<Mutex<RenderTheme>>,
     output_history: Arc<Mutex<Vec<HistoryEntry>>>,
+    conversation: Arc<Mutex<Vec<ChatMessage>>>, // Earlier REPL prompts and answers, sent with the next prompt
+    current_session: Arc<Mutex<CurrentSession>>, // The named session (/sessions) the history and conversation belong to
+    output_limits: Arc<Mutex<OutputLimits>>,
+    generation_params: Arc<Mutex<GenerationParams>>, // Sampling settings from /set, sent with every query
+    config: Arc<Mutex<AppConfig>>,
+    events: Arc<broadcast::Sender<StateEvent>>, // State change notifications (GET /events)
+    checkpoints: Arc<Mutex<BTreeMap<String, Checkpoint>>>, // Named snapshots for /checkpoint and /rollback
+    datasets: Arc<Mutex<BTreeMap<String, Arc<Dataset>>>>, // Tables loaded with /data load
+    buffers: Arc<Mutex<BTreeMap<String, String>>>, // Prompts assembled with /buf
+    attachments: Arc<Mutex<Vec<ImageAttachment>>>, // Images from /attach, sent with the next prompt
+    last_comparison: Arc<Mutex<Option<Preference>>>, // Latest /diffmodels result, until /prefer judges it
+    database: Arc<Mutex<Option<Database>>>, // Connection opened with /db connect
+    lookups: Arc<LookupCache>, // Recent model lists and readiness checks, cleared when providers are rebuilt
+    file_config: Arc<Mutex<AppConfig>>, // The config files as last applied, so a reload only replaces what was edited
+    started: Instant, // When the session started, for its usage report
 }
 
 // Manual Clone implementation because CommandRegistry is not Clone by default.
 impl Clone for AppState {
     fn clone(&self) -> Self {
         Self {
-            provider_registry: self.provider_registry.clone(), // ProviderRegistry derives Clone
+            provider_registry: Arc::clone(&self.provider_registry),
             command_registry: Arc::clone(&self.command_registry), // Clone the Arc pointer
             current_provider: Arc::clone(&self.current_provider),
             current_model: Arc::clone(&self.current_model),
             current_markdown_mode: Arc::clone(&self.current_markdown_mode),
             current_theme: Arc::clone(&self.current_theme),
             output_history: Arc::clone(&self.output_history),
+            conversation: Arc::clone(&self.conversation),
+            current_session: Arc::clone(&self.current_session),
+            output_limits: Arc::clone(&self.output_limits),
+            generation_params: Arc::clone(&self.generation_params),
+            config: Arc::clone(&self.config),
+            events: Arc::clone(&self.events),
+            checkpoints: Arc::clone(&self.checkpoints),
+            datasets: Arc::clone(&self.datasets),
+            buffers: Arc::clone(&self.buffers),
+            attachments: Arc::clone(&self.attachments),
+            last_comparison: Arc::clone(&self.last_comparison),
+            database: Arc::clone(&self.database),
+            lookups: Arc::clone(&self.lookups),
+            file_config: Arc::clone(&self.file_config),
+            started: self.started,
         }
     }
 }
 
 
+// The `[session] model` setting (possibly an alias), else the Ollama default.
+fn starting_model(config: &AppConfig, registry: &ProviderRegistry) -> (String, String) {
+    let default_provider = "ollama".to_string();
+    let Some(spec) = config.session.model.as_deref().map(str::trim).filter(|spec| !spec.is_empty()) else {
+        return (default_provider, "llama3:latest".to_string()); // Ensure this is a valid default
+    };
+    let spec = config.aliases.get(spec).map(String::as_str).unwrap_or(spec);
+    registry.split_model_spec(spec).unwrap_or_else(|| (default_provider, spec.to_string()))
+}
+
 impl AppState {
     /// Creates the application state, including initializing and registering commands.
     pub fn new() -> Self {
         // Step 1: Initialize basic components and state Arcs
-        let provider_registry = ProviderRegistry::new();
-        let initial_provider = "ollama";
-        let initial_model = "llama3:latest"; // Ensure this is a valid default
-
-        let provider_registry_arc = provider_registry; // Assuming ProviderRegistry is Clone
-        let current_provider_arc = Arc::new(Mutex::new(initial_provider.to_string()));
-        let current_model_arc = Arc::new(Mutex::new(initial_model.to_string()));
-        let current_markdown_mode_arc = Arc::new(Mutex::new(MarkdownMode::AppendFormatted));
-        let current_theme_arc = Arc::new(Mutex::new(RenderTheme::Nord));
+        let config = AppConfig::load();
+        let provider_registry = ProviderRegistry::from_config(&config.providers).unwrap_or_else(|e| {
+            eprintln!("WARN: {}. Using default provider settings.", e);
+            ProviderRegistry::new()
+        });
+        let (initial_provider, initial_model) = starting_model(&config, &provider_registry);
+
+        let provider_registry_arc = Arc::new(RwLock::new(provider_registry));
+        let current_provider_arc = Arc::new(Mutex::new(initial_provider));
+        let current_model_arc = Arc::new(Mutex::new(initial_model));
+        let current_markdown_mode_arc = Arc::new(Mutex::new(config.markdown.llm.unwrap_or(MarkdownMode::AppendFormatted)));
+        let current_theme_arc = Arc::new(Mutex::new(config.ui.theme.unwrap_or(RenderTheme::Nord)));
         let output_history_arc = Arc::new(Mutex::new(Vec::new()));
+        let conversation_arc = Arc::new(Mutex::new(Vec::new()));
+        let current_session_arc = Arc::new(Mutex::new(CurrentSession::default()));
+        let output_limits_arc = Arc::new(Mutex::new(OutputLimits::default()));
+        let generation_params_arc = Arc::new(Mutex::new(GenerationParams::default()));
+        let file_config_arc = Arc::new(Mutex::new(config.clone()));
+        let config_arc = Arc::new(Mutex::new(config));
+        let events_arc = Arc::new(broadcast::channel(EVENT_BUFFER).0);
+        let checkpoints_arc = Arc::new(Mutex::new(BTreeMap::new()));
+        let datasets_arc = Arc::new(Mutex::new(BTreeMap::new()));
+        let buffers_arc = Arc::new(Mutex::new(BTreeMap::new()));
+        let attachments_arc = Arc::new(Mutex::new(Vec::new()));
+        let last_comparison_arc = Arc::new(Mutex::new(None));
+        let database_arc = Arc::new(Mutex::new(None));
+        let lookups_arc = Arc::new(LookupCache::default());
+        let started = Instant::now();
 
         // Step 2: Create a preliminary AppState instance.
         // This instance is needed to pass state to CommandRegistry::new().
@@ -97,6 +311,21 @@ impl AppState {
             current_markdown_mode: current_markdown_mode_arc.clone(),
             current_theme: current_theme_arc.clone(),
             output_history: output_history_arc.clone(),
+            conversation: conversation_arc.clone(),
+            current_session: current_session_arc.clone(),
+            output_limits: output_limits_arc.clone(),
+            generation_params: generation_params_arc.clone(),
+            config: config_arc.clone(),
+            events: events_arc.clone(),
+            checkpoints: checkpoints_arc.clone(),
+            datasets: datasets_arc.clone(),
+            buffers: buffers_arc.clone(),
+            attachments: attachments_arc.clone(),
+            last_comparison: last_comparison_arc.clone(),
+            database: database_arc.clone(),
+            lookups: lookups_arc.clone(),
+            file_config: file_config_arc.clone(),
+            started,
         };
 
         // Step 3: Create the *actual* fully populated CommandRegistry, passing the preliminary state clone.
@@ -112,41 +341,414 @@ impl AppState {
             current_markdown_mode: current_markdown_mode_arc,
             current_theme: current_theme_arc,
             output_history: output_history_arc,
+            conversation: conversation_arc,
+            current_session: current_session_arc,
+            output_limits: output_limits_arc,
+            generation_params: generation_params_arc,
+            config: config_arc,
+            events: events_arc,
+            checkpoints: checkpoints_arc,
+            datasets: datasets_arc,
+            buffers: buffers_arc,
+            attachments: attachments_arc,
+            last_comparison: last_comparison_arc,
+            database: database_arc,
+            lookups: lookups_arc,
+            file_config: file_config_arc,
+            started,
         }
     }
 
     // --- Getters and Setters ---
     pub async fn get_provider_name(&self) -> String { self.current_provider.lock().await.clone() }
-    pub async fn set_model(&self, model: &str) -> ReplResult<()> { let mut current_model = self.current_model.lock().await; *current_model = model.trim().to_string(); Ok(()) }
+    pub async fn set_model(&self, model: &str) -> ReplResult<()> { let mut current_model = self.current_model.lock().await; *current_model = model.trim().to_string(); self.publish(StateEvent::ModelChanged { model: current_model.clone() }); Ok(()) }
     pub async fn get_model(&self) -> String { self.current_model.lock().await.clone() }
-    pub async fn get_current_provider(&self) -> Option<Box<dyn LlmProvider>> { let provider_name = self.get_provider_name().await; self.provider_registry.get_provider(&provider_name).map(|p| p.clone_box()) }
-    pub fn get_provider_by_name(&self, name: &str) -> Option<Box<dyn LlmProvider>> { self.provider_registry.get_provider(name).map(|p| p.clone_box()) }
-    pub fn list_providers(&self) -> Vec<String> { self.provider_registry.list_providers().into_iter().map(String::from).collect() }
+    pub async fn get_current_provider(&self) -> Option<Box<dyn LlmProvider>> { let provider_name = self.get_provider_name().await; self.get_provider_by_name(&provider_name) }
+    pub fn get_provider_by_name(&self, name: &str) -> Option<Box<dyn LlmProvider>> { self.providers().get_provider(name).map(|p| p.clone_box()) }
+    /// Sends a one-off prompt (no hooks, no history) to the current provider and model.
+    pub async fn ask_current_model(&self, prompt: &str) -> ReplResult<String> {
+        let Some(provider) = self.get_current_provider().await else {
+            return Err(ReplError::UnknownProvider(self.get_provider_name().await));
+        };
+        provider.query(&self.get_model().await, prompt, &self.get_generation_params().await).await
+    }
+    /// Splits `provider:model` into its parts, or pairs a bare model with the current provider.
+    /// Only registered provider names count, so `llama3:latest` stays a model name. Names from
+    /// the config's `[aliases]` are expanded first.
+    pub async fn resolve_model_spec(&self, spec: &str) -> (String, String) {
+        let alias = self.config.lock().await.aliases.get(spec).cloned();
+        let spec = alias.as_deref().unwrap_or(spec);
+        if let Some(target) = self.split_model_spec(spec) {
+            return target;
+        }
+        (self.get_provider_name().await, spec.to_string())
+    }
+    /// Splits `provider:model` if it starts with a registered provider name (`group:lan:llama3` included).
+    pub fn split_model_spec(&self, spec: &str) -> Option<(String, String)> { self.providers().split_model_spec(spec) }
+    pub fn list_providers(&self) -> Vec<String> { self.providers().list_providers().into_iter().map(String::from).collect() }
     pub async fn set_provider(&self, provider_name: &str) -> ReplResult<()> {
         let provider_name_lower = provider_name.trim().to_lowercase();
-        let provider = match self.provider_registry.get_provider(&provider_name_lower) { Some(p) => p, None => return Err(ReplError::UnknownProvider(provider_name_lower)), };
-        provider.check_readiness().await.map_err(|e| { ReplError::Provider(format!("Provider '{}' is not ready: {}", provider_name_lower, e)) })?;
+        let provider = match self.get_provider_by_name(&provider_name_lower) { Some(p) => p, None => return Err(ReplError::UnknownProvider(provider_name_lower)), };
+        self.check_provider_ready(&provider_name_lower, provider.as_ref()).await.map_err(|e| { ReplError::Provider(format!("Provider '{}' is not ready: {}", provider_name_lower, e)) })?;
         let mut current_provider_guard = self.current_provider.lock().await;
         if *current_provider_guard != provider_name_lower {
             *current_provider_guard = provider_name_lower.clone(); drop(current_provider_guard); println!("Provider set to: {}", provider_name_lower);
-            match provider.get_models().await {
-                Ok(models) if !models.is_empty() => { if self.set_model(&models[0]).await.is_ok() { println!("Automatically selected model: {}", &models[0]); } else { eprintln!("WARN: Failed to update model state after provider change."); } }
+            self.publish(StateEvent::ProviderChanged { provider: provider_name_lower.clone() });
+            match self.provider_models(&provider_name_lower, provider.as_ref()).await {
+                Ok(models) if !models.is_empty() => { if self.set_model(&models[0].id).await.is_ok() { println!("Automatically selected model: {}", &models[0].id); } else { eprintln!("WARN: Failed to update model state after provider change."); } }
                 Ok(_) => { println!("WARN: Provider '{}' reported no available models. Model unchanged.", provider_name_lower); }
                 Err(e) => { eprintln!("WARN: Could not fetch models for provider '{}': {}. Model unchanged.", provider_name_lower, e); }
             }
         } else { println!("Provider already set to: {}", provider_name_lower); return Ok(()); }
         Ok(())
     }
-    pub async fn list_models(&self) -> ReplResult<Vec<String>> {
-         if let Some(provider) = self.get_current_provider().await { provider.get_models().await }
-         else { let provider_name = self.get_provider_name().await; Err(ReplError::Provider(format!("Current provider '{}' not found or unavailable.", provider_name))) }
+    pub async fn list_models(&self) -> ReplResult<Vec<ModelInfo>> {
+         let provider_name = self.get_provider_name().await;
+         if let Some(provider) = self.get_provider_by_name(&provider_name) { self.provider_models(&provider_name, provider.as_ref()).await }
+         else { Err(ReplError::Provider(format!("Current provider '{}' not found or unavailable.", provider_name))) }
+    }
+    /// Checks that a registered provider is ready, trusting a success from the last few minutes.
+    pub async fn check_provider_ready(&self, name: &str, provider: &dyn LlmProvider) -> ReplResult<()> {
+        if self.lookups.is_ready(name) { return Ok(()); }
+        provider.check_readiness().await?;
+        self.lookups.mark_ready(name);
+        Ok(())
+    }
+    /// Lists a registered provider's models, reusing a list fetched in the last few minutes.
+    pub async fn provider_models(&self, name: &str, provider: &dyn LlmProvider) -> ReplResult<Vec<ModelInfo>> {
+        if let Some(models) = self.lookups.models(name) { return Ok(models); }
+        let models = provider.get_models().await?;
+        self.lookups.store_models(name, &models);
+        Ok(models)
     }
+    /// The cached provider lookups still in use, for `/cache`.
+    pub fn cached_lookups(&self) -> Vec<CachedLookup> { self.lookups.list() }
+    /// Forgets cached model lists and readiness checks; returns how many providers had any.
+    pub fn clear_lookups(&self) -> usize { self.lookups.clear() }
     pub async fn get_markdown_mode(&self) -> MarkdownMode { *self.current_markdown_mode.lock().await }
-    pub async fn set_markdown_mode(&self, mode: MarkdownMode) { let mut current_mode_guard = self.current_markdown_mode.lock().await; *current_mode_guard = mode; }
+    /// Overrides the Markdown mode for command or shell output; None returns it to its default.
+    /// LLM responses use the session mode, so `mode` None resets it to the configured starting mode.
+    pub async fn set_markdown_mode_for(&self, source: OutputSource, mode: Option<MarkdownMode>) {
+        let mut config = self.config.lock().await;
+        match source {
+            OutputSource::Llm => {
+                let mode = mode.or(config.markdown.llm).unwrap_or(MarkdownMode::AppendFormatted);
+                drop(config);
+                self.set_markdown_mode(mode).await;
+            }
+            OutputSource::Command => config.markdown.commands = mode,
+            OutputSource::Shell => config.markdown.shell = mode,
+        }
+    }
+    /// The Markdown mode for output from `source`. LLM responses use the session mode (`/md`, `/md_off`...);
+    /// command output follows it unless overridden, and shell output stays raw unless overridden.
+    pub async fn get_markdown_mode_for(&self, source: OutputSource) -> MarkdownMode {
+        let markdown = self.config.lock().await.markdown.clone();
+        match source {
+            OutputSource::Llm => self.get_markdown_mode().await,
+            OutputSource::Command => match markdown.commands { Some(mode) => mode, None => self.get_markdown_mode().await },
+            OutputSource::Shell => markdown.shell.unwrap_or(MarkdownMode::Off),
+        }
+    }
+    pub async fn set_markdown_mode(&self, mode: MarkdownMode) { let mut current_mode_guard = self.current_markdown_mode.lock().await; *current_mode_guard = mode; self.publish(StateEvent::MarkdownModeChanged { mode }); }
     pub async fn get_theme(&self) -> RenderTheme { *self.current_theme.lock().await }
-    pub async fn set_theme(&self, theme: RenderTheme) { let mut current_theme_guard = self.current_theme.lock().await; *current_theme_guard = theme; }
-    pub async fn add_history_entry(&self, entry: HistoryEntry) { let mut history = self.output_history.lock().await; history.push(entry); }
+    pub async fn set_theme(&self, theme: RenderTheme) { let mut current_theme_guard = self.current_theme.lock().await; *current_theme_guard = theme; self.publish(StateEvent::ThemeChanged { theme }); }
+    pub async fn add_history_entry(&self, mut entry: HistoryEntry) {
+        if matches!(entry.entry_type, HistoryContentType::LlmResponse { .. }) && entry.citations.is_empty() {
+            entry.citations = citations::from_footnotes(&entry.content);
+        }
+        let config = self.config.lock().await.clone();
+        if entry.usage.is_none() {
+            let id = entry.request_id.clone().or_else(request_id::current);
+            entry.usage = entry_usage(&entry, id.as_deref(), &config);
+        }
+        let retention = config.history;
+        let mut history = self.output_history.lock().await;
+        if retention.ignore_dups && is_repeat(&history, &entry) {
+            return;
+        }
+        history.push(entry.clone());
+        prune_entries(&mut history, &retention);
+        drop(history);
+        self.publish(StateEvent::HistoryEntryAdded { entry });
+    }
+    /// The newest LLM answer in the session history, with its position.
+    pub async fn last_answer(&self) -> Option<(usize, HistoryEntry)> {
+        let history = self.output_history.lock().await;
+        let index = history.iter().rposition(|entry| matches!(entry.entry_type, HistoryContentType::LlmResponse { .. }))?;
+        Some((index, history[index].clone()))
+    }
+    /// Appends `text` to the entry at `index` if it is still the entry recorded at `timestamp`
+    /// (pruning may have moved it); returns whether it was extended.
+    pub async fn extend_history_entry(&self, index: usize, timestamp: DateTime<Utc>, text: &str, elapsed: Duration) -> bool {
+        let config = self.get_config().await;
+        let mut history = self.output_history.lock().await;
+        let Some(entry) = history.get_mut(index).filter(|entry| entry.timestamp == timestamp) else { return false };
+        // The continuation's calls ran under this request, not the entry's
+        if let Some(added) = entry_usage(entry, request_id::current().as_deref(), &config) {
+            entry.usage.get_or_insert_with(EntryUsage::default).add(&added);
+        }
+        entry.content.push_str(text);
+        entry.citations = citations::from_footnotes(&entry.content);
+        entry.duration_ms = Some(entry.duration_ms.unwrap_or(0) + elapsed.as_millis() as u64);
+        let entry = entry.clone();
+        drop(history);
+        self.publish(StateEvent::HistoryEntryUpdated { entry });
+        true
+    }
+    /// Adds `tag` to the entry at `index`, or removes it if `remove`; returns the updated entry.
+    pub async fn tag_history_entry(&self, index: usize, tag: &str, remove: bool) -> ReplResult<HistoryEntry> {
+        let mut history = self.output_history.lock().await;
+        let count = history.len();
+        let entry = history.get_mut(index).ok_or_else(|| {
+            ReplError::Command(format!("No history entry {}; the session has {} (numbered as in /reader).", index + 1, count))
+        })?;
+        if remove {
+            entry.tags.retain(|existing| existing != tag);
+        } else if !entry.tags.iter().any(|existing| existing == tag) {
+            entry.tags.push(tag.to_string());
+        }
+        let entry = entry.clone();
+        drop(history);
+        self.publish(StateEvent::HistoryEntryUpdated { entry: entry.clone() });
+        Ok(entry)
+    }
+    /// The newest answer from `provider`/`model` to a prompt matching `prompt`, if given within `window`.
+    pub async fn cached_answer(&self, prompt: &str, provider: &str, model: &str, window: Duration) -> Option<HistoryEntry> { find_cached_answer(&self.output_history.lock().await, prompt, provider, model, window).cloned() }
+    /// Applies the `[history]` retention limits to the session history; returns how many entries were removed.
+    pub async fn prune_history(&self) -> usize { let retention = self.config.lock().await.history.clone(); prune_entries(&mut *self.output_history.lock().await, &retention) }
     pub async fn get_history(&self) -> Vec<HistoryEntry> { self.output_history.lock().await.clone() }
+    /// The conversation so far, as alternating user and assistant messages.
+    pub async fn get_conversation(&self) -> Vec<ChatMessage> { self.conversation.lock().await.clone() }
+    /// Adds an answered prompt to the conversation, so follow-up prompts are sent with it.
+    pub async fn add_turn(&self, prompt: &str, answer: &str) {
+        self.conversation.lock().await.extend([ChatMessage::user(prompt), ChatMessage::assistant(answer)]);
+    }
+    /// Replaces the earlier turns up to the end of `covered` with one turn holding their summary
+    /// (`request`, answered by `summary`). Does nothing if `covered` is no longer in the conversation.
+    pub async fn compact_conversation(&self, covered: &[ChatMessage], request: &str, summary: &str) {
+        let mut conversation = self.conversation.lock().await;
+        if covered.is_empty() { return; }
+        let Some(start) = conversation.windows(covered.len()).rposition(|window| window == covered) else { return };
+        conversation.splice(..start + covered.len(), [ChatMessage::user(request), ChatMessage::assistant(summary)]);
+    }
+    /// Forgets the conversation, returning how many turns it had.
+    pub async fn clear_conversation(&self) -> usize { std::mem::take(&mut *self.conversation.lock().await).len() / 2 }
+    pub async fn get_output_limits(&self) -> OutputLimits { self.output_limits.lock().await.clone() }
+    pub async fn set_output_limits(&self, limits: OutputLimits) { let mut limits_guard = self.output_limits.lock().await; *limits_guard = limits; }
+    pub async fn get_generation_params(&self) -> GenerationParams { *self.generation_params.lock().await }
+    pub async fn set_generation_params(&self, params: GenerationParams) { *self.generation_params.lock().await = params; }
+    pub async fn get_config(&self) -> AppConfig { self.config.lock().await.clone() }
+    pub async fn set_config(&self, config: AppConfig) { let mut config_guard = self.config.lock().await; *config_guard = config; }
+    /// Replaces one `[providers]` entry and rebuilds the providers; nothing changes if the settings are invalid.
+    pub async fn set_provider_settings(&self, name: &str, settings: ProviderSettings) -> ReplResult<()> {
+        let mut config = self.config.lock().await;
+        let mut providers = config.providers.clone();
+        providers.insert(name.to_string(), settings);
+        let registry = ProviderRegistry::from_config(&providers)?;
+        *self.provider_registry.write().unwrap_or_else(|e| e.into_inner()) = registry;
+        self.lookups.clear();
+        config.providers = providers;
+        Ok(())
+    }
+    /// Applies an edited config file. Only settings that changed since the files were last applied
+    /// (both as read from the files) are replaced, so runtime changes elsewhere (e.g. `/hooks off`)
+    /// survive. Returns the changed setting names; nothing is applied if the new providers are invalid.
+    pub async fn reload_config(&self, updated: &AppConfig) -> ReplResult<Vec<String>> {
+        // Held throughout, so the file watcher and /config don't apply the same edit twice
+        let mut previous = self.file_config.lock().await;
+        let changed = previous.changed_settings(updated);
+        if changed.is_empty() {
+            return Ok(changed);
+        }
+        let touched = |section: &str| changed.iter().any(|name| name == section || name.starts_with(&format!("{}.", section)));
+        if touched("providers") {
+            let registry = ProviderRegistry::from_config(&updated.providers)?;
+            *self.provider_registry.write().unwrap_or_else(|e| e.into_inner()) = registry;
+            self.lookups.clear();
+        }
+        {
+            let mut config = self.config.lock().await;
+            let mut merged = serde_json::to_value(&*config)?;
+            let fresh = serde_json::to_value(updated)?;
+            for name in &changed {
+                match name.split_once('.') {
+                    Some((section, field)) => {
+                        let (Some(target), Some(source)) = (merged[section].as_object_mut(), fresh[section].as_object()) else { continue };
+                        match source.get(field) {
+                            Some(value) => { target.insert(field.to_string(), value.clone()); }
+                            None => { target.remove(field); }
+                        }
+                    }
+                    None => merged[name.as_str()] = fresh[name.as_str()].clone(),
+                }
+            }
+            *config = serde_json::from_value(merged)?;
+        }
+        if changed.iter().any(|name| name == "ui.theme") {
+            self.set_theme(updated.ui.theme.unwrap_or(RenderTheme::Nord)).await;
+        }
+        *previous = updated.clone();
+        self.publish(StateEvent::ConfigReloaded { changed: changed.clone() });
+        Ok(changed)
+    }
+    /// Queries, tokens and cost of this session's provider calls, priced with the `[pricing]` config.
+    pub async fn session_usage(&self) -> SessionReport { usage::session_report(&*self.config.lock().await, self.started.elapsed()) }
+    pub async fn is_command_enabled(&self, command: &str, origin: CommandOrigin) -> bool { self.config.lock().await.commands.is_enabled(command, origin) }
+    /// Enables or disables a command for one origin; holds the config lock so concurrent changes don't race.
+    pub async fn set_command_enabled(&self, command: &str, origin: CommandOrigin, enabled: bool) { self.config.lock().await.commands.set_enabled(command, origin, enabled); }
+    /// Saves the current session under `name`, replacing any checkpoint with that name.
+    pub async fn create_checkpoint(&self, name: &str) -> Checkpoint {
+        let checkpoint = Checkpoint {
+            created: Utc::now(),
+            provider: self.get_provider_name().await,
+            model: self.get_model().await,
+            history: self.get_history().await,
+            conversation: self.get_conversation().await,
+            hooks: self.get_config().await.hooks,
+            output_limits: self.get_output_limits().await,
+            generation_params: self.get_generation_params().await,
+        };
+        self.checkpoints.lock().await.insert(name.to_string(), checkpoint.clone());
+        checkpoint
+    }
+    /// Restores the session saved under `name`; the checkpoint is kept, so it can be restored again.
+    pub async fn rollback(&self, name: &str) -> ReplResult<Checkpoint> {
+        let checkpoint = self.checkpoints.lock().await.get(name).cloned()
+            .ok_or_else(|| ReplError::Command(format!("No checkpoint named '{}'.", name)))?;
+        // Everything that can fail comes first, so a failed rollback leaves the session as it was
+        if self.get_provider_by_name(&checkpoint.provider).is_none() {
+            return Err(ReplError::UnknownProvider(checkpoint.provider.clone()));
+        }
+        self.set_model(&checkpoint.model).await?;
+        *self.current_provider.lock().await = checkpoint.provider.clone();
+        self.publish(StateEvent::ProviderChanged { provider: checkpoint.provider.clone() });
+        *self.output_history.lock().await = checkpoint.history.clone();
+        *self.conversation.lock().await = checkpoint.conversation.clone();
+        *self.output_limits.lock().await = checkpoint.output_limits.clone();
+        *self.generation_params.lock().await = checkpoint.generation_params;
+        self.config.lock().await.hooks = checkpoint.hooks.clone();
+        self.publish(StateEvent::RolledBack { checkpoint: name.to_string() });
+        Ok(checkpoint)
+    }
+    /// The current session's name and title.
+    pub async fn get_session(&self) -> CurrentSession { self.current_session.lock().await.clone() }
+    /// Renames the current session (`/save <name>`).
+    pub async fn rename_session(&self, name: &str) { self.current_session.lock().await.name = name.to_string(); }
+    pub async fn set_session_title(&self, title: Option<String>) { self.current_session.lock().await.title = title; }
+    // Provider, model and settings of the session, without its history and conversation.
+    async fn session_settings(&self, title: Option<String>) -> SavedSession {
+        let config = self.get_config().await;
+        SavedSession {
+            saved: Utc::now(),
+            title,
+            provider: self.get_provider_name().await,
+            model: self.get_model().await,
+            conversation: Vec::new(),
+            history: Vec::new(),
+            session: config.session,
+            hooks: config.hooks,
+            output_limits: self.get_output_limits().await,
+            generation_params: self.get_generation_params().await,
+            markdown_mode: Some(self.get_markdown_mode().await),
+            theme: Some(self.get_theme().await),
+        }
+    }
+    /// The session as `/save` writes it: provider, model, conversation, history and settings.
+    pub async fn save_session(&self) -> SavedSession {
+        let title = self.get_session().await.title;
+        let mut saved = self.session_settings(title).await;
+        saved.history = self.get_history().await;
+        saved.conversation = self.get_conversation().await;
+        saved
+    }
+    /// Makes `name` the current session: the saved session's conversation, history and settings if
+    /// `incoming` is given, else an empty session keeping the current settings. The history and
+    /// conversation are swapped under one lock, so no prompt lands between the two sessions.
+    /// Returns the session switched away from (under its old name), for the caller to save. Fails,
+    /// changing nothing, if the incoming session's provider isn't registered.
+    pub async fn switch_session(&self, name: &str, incoming: Option<SavedSession>) -> ReplResult<(String, SavedSession)> {
+        if let Some(saved) = &incoming {
+            if !self.list_providers().contains(&saved.provider) {
+                return Err(ReplError::UnknownProvider(saved.provider.clone()));
+            }
+        }
+        let mut current = self.current_session.lock().await;
+        let mut outgoing = self.session_settings(current.title.clone()).await;
+        let mut history = self.output_history.lock().await;
+        let mut conversation = self.conversation.lock().await;
+        outgoing.history = std::mem::take(&mut *history);
+        outgoing.conversation = std::mem::take(&mut *conversation);
+        let previous = std::mem::replace(&mut *current, CurrentSession { name: name.to_string(), title: None });
+        if let Some(saved) = incoming {
+            *history = saved.history;
+            *conversation = saved.conversation;
+            current.title = saved.title;
+            drop((history, conversation, current));
+            *self.current_provider.lock().await = saved.provider.clone();
+            self.publish(StateEvent::ProviderChanged { provider: saved.provider });
+            self.set_model(&saved.model).await?;
+            *self.output_limits.lock().await = saved.output_limits;
+            *self.generation_params.lock().await = saved.generation_params;
+            {
+                let mut config = self.config.lock().await;
+                config.session = saved.session;
+                config.hooks = saved.hooks;
+            }
+            if let Some(mode) = saved.markdown_mode {
+                self.set_markdown_mode(mode).await;
+            }
+            if let Some(theme) = saved.theme {
+                self.set_theme(theme).await;
+            }
+        } else {
+            drop((history, conversation, current));
+        }
+        self.publish(StateEvent::SessionLoaded { session: name.to_string() });
+        Ok((previous.name, outgoing))
+    }
+    pub async fn list_checkpoints(&self) -> Vec<(String, Checkpoint)> { self.checkpoints.lock().await.iter().map(|(name, c)| (name.clone(), c.clone())).collect() }
+    /// Stores a loaded dataset, replacing any dataset with the same name.
+    pub async fn add_dataset(&self, dataset: Dataset) -> Arc<Dataset> {
+        let dataset = Arc::new(dataset);
+        self.datasets.lock().await.insert(dataset.name.clone(), Arc::clone(&dataset));
+        dataset
+    }
+    pub async fn get_dataset(&self, name: &str) -> Option<Arc<Dataset>> { self.datasets.lock().await.get(name).cloned() }
+    pub async fn list_datasets(&self) -> Vec<Arc<Dataset>> { self.datasets.lock().await.values().cloned().collect() }
+    pub async fn remove_dataset(&self, name: &str) -> bool { self.datasets.lock().await.remove(name).is_some() }
+    pub async fn get_buffer(&self, name: &str) -> Option<String> { self.buffers.lock().await.get(name).cloned() }
+    pub async fn set_buffer(&self, name: &str, text: String) { self.buffers.lock().await.insert(name.to_string(), text); }
+    /// Appends a line to a buffer (creating it), returning the new length in lines.
+    pub async fn append_buffer(&self, name: &str, text: &str) -> usize {
+        let mut buffers = self.buffers.lock().await;
+        let buffer = buffers.entry(name.to_string()).or_default();
+        if !buffer.is_empty() { buffer.push('\n'); }
+        buffer.push_str(text);
+        buffer.lines().count()
+    }
+    pub async fn list_buffers(&self) -> Vec<(String, String)> { self.buffers.lock().await.iter().map(|(name, text)| (name.clone(), text.clone())).collect() }
+    pub async fn remove_buffer(&self, name: &str) -> bool { self.buffers.lock().await.remove(name).is_some() }
+    /// Adds an image for the next prompt, returning how many are attached.
+    pub async fn attach_image(&self, image: ImageAttachment) -> usize {
+        let mut attachments = self.attachments.lock().await;
+        attachments.push(image);
+        attachments.len()
+    }
+    pub async fn get_attachments(&self) -> Vec<ImageAttachment> { self.attachments.lock().await.clone() }
+    pub async fn clear_attachments(&self) -> usize { std::mem::take(&mut *self.attachments.lock().await).len() }
+    /// Keeps a comparison (with no preference yet) for `/prefer`, replacing the previous one.
+    pub async fn set_last_comparison(&self, comparison: Preference) { *self.last_comparison.lock().await = Some(comparison); }
+    /// Takes the pending comparison, so each one is judged once.
+    pub async fn take_last_comparison(&self) -> Option<Preference> { self.last_comparison.lock().await.take() }
+    pub async fn get_database(&self) -> Option<Database> { self.database.lock().await.clone() }
+    /// Replaces the database connection, returning the previous one so the caller can close it.
+    pub async fn set_database(&self, database: Option<Database>) -> Option<Database> { std::mem::replace(&mut *self.database.lock().await, database) }
+    /// Subscribes to state change events.
+    pub fn subscribe(&self) -> broadcast::Receiver<StateEvent> { self.events.subscribe() }
+    // Sending only fails when nobody is subscribed, which is fine.
+    fn publish(&self, event: StateEvent) { let _ = self.events.send(event); }
+    fn providers(&self) -> std::sync::RwLockReadGuard<'_, ProviderRegistry> { self.provider_registry.read().unwrap_or_else(|e| e.into_inner()) }
 
     /// Provides read-only access to the command registry Arc.
     pub fn command_registry(&self) -> Arc<CommandRegistry> {
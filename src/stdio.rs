@@ -0,0 +1,232 @@
+// src/stdio.rs
+//! `--stdio` mode for editor plugins: JSON-RPC 2.0 over stdin/stdout, one message per line.
+//!
+//! Requests: `query` and `stream` (`{"prompt", "model"?}`), `cancel` (`{"id"}` of an earlier
+//! request) and `state`. `stream` sends `stream/chunk` notifications (`{"id", "text"}`) before its
+//! result; state changes are pushed as `state/changed` notifications.
+use crate::{
+    error::{ReplError, ReplResult},
+    request_id,
+    state::{AppState, HistoryContentType, HistoryEntry},
+};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::{broadcast, mpsc, Mutex},
+    task::AbortHandle,
+};
+
+// JSON-RPC error codes; -32800 is LSP's RequestCancelled.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const REQUEST_CANCELLED: i64 = -32800;
+
+#[derive(Deserialize)]
+struct Request {
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct QueryParams {
+    prompt: String,
+    model: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CancelParams {
+    id: Value,
+}
+
+type Outgoing = mpsc::UnboundedSender<Value>;
+// In-flight requests by id (as JSON text), so `cancel` can abort them.
+type Running = Arc<Mutex<HashMap<String, AbortHandle>>>;
+
+fn result(id: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+async fn state_snapshot(state: &AppState) -> Value {
+    json!({
+        "provider": state.get_provider_name().await,
+        "model": state.get_model().await,
+        "markdownMode": state.get_markdown_mode().await,
+        "theme": format!("{:?}", state.get_theme().await),
+    })
+}
+
+// Sends a prompt like the REPL does (hooks, output limits, history); streaming sends each chunk
+// as a notification first. Providers without streaming answer in one chunk.
+async fn run_query(state: &AppState, id: &Value, query: QueryParams, stream: bool, out: &Outgoing) -> ReplResult<Value> {
+    let provider_name = state.get_provider_name().await;
+    let provider = state.get_current_provider().await.ok_or_else(|| ReplError::UnknownProvider(provider_name.clone()))?;
+    let model = match query.model {
+        Some(model) => model,
+        None => state.get_model().await,
+    };
+    let messages = state.get_config().await.prompt_messages(&query.prompt);
+    let params = state.get_generation_params().await;
+    let started = Instant::now();
+    let mut response = String::new();
+    let chunks = if stream { provider.chat_stream(&model, &messages, &params).await? } else { None };
+    match chunks {
+        Some(mut chunks) => {
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk?;
+                let _ = out.send(notification("stream/chunk", json!({ "id": id, "text": chunk })));
+                response.push_str(&chunk);
+            }
+        }
+        None => {
+            response = provider.chat(&model, &messages, &params).await?;
+            if stream {
+                let _ = out.send(notification("stream/chunk", json!({ "id": id, "text": response })));
+            }
+        }
+    }
+    let response = state.get_output_limits().await.apply(&response);
+    state.add_history_entry(
+        HistoryEntry::new(HistoryContentType::LlmResponse { model: model.clone() }, response.clone())
+            .with_model(&provider_name, &model)
+            .with_prompt(&query.prompt)
+            .with_duration(started.elapsed()),
+    ).await;
+    Ok(json!({ "response": response, "provider": provider_name, "model": model, "requestId": request_id::current() }))
+}
+
+async fn handle(state: AppState, request: Request, out: Outgoing, running: Running) {
+    let id = request.id.clone().unwrap_or(Value::Null);
+    let reply = |outcome: Result<Value, (i64, String)>| match outcome {
+        Ok(value) => result(&id, value),
+        Err((code, message)) => error(&id, code, &message),
+    };
+    match request.method.as_str() {
+        "state" => {
+            let _ = out.send(reply(Ok(state_snapshot(&state).await)));
+        }
+        "cancel" => {
+            let outcome = match params::<CancelParams>(request.params) {
+                Ok(cancel) => match running.lock().await.remove(&cancel.id.to_string()) {
+                    Some(task) => {
+                        task.abort();
+                        let _ = out.send(error(&cancel.id, REQUEST_CANCELLED, "Request cancelled"));
+                        Ok(json!({ "cancelled": true }))
+                    }
+                    None => Ok(json!({ "cancelled": false })),
+                },
+                Err(e) => Err(e),
+            };
+            let _ = out.send(reply(outcome));
+        }
+        method @ ("query" | "stream") => {
+            let query = match params::<QueryParams>(request.params) {
+                Ok(query) => query,
+                Err(e) => {
+                    let _ = out.send(reply(Err(e)));
+                    return;
+                }
+            };
+            let stream = method == "stream";
+            let key = id.to_string();
+            // Held until the task is registered, so a quick finish can't race its own registration
+            let mut tasks = running.lock().await;
+            let task = {
+                let (state, out, running, id, key) = (state.clone(), out.clone(), Arc::clone(&running), id.clone(), key.clone());
+                tokio::spawn(async move {
+                    let request = request_id::next();
+                    let outcome = request_id::scope(request.clone(), run_query(&state, &id, query, stream, &out)).await;
+                    // A cancelled request was already answered by `cancel`
+                    if running.lock().await.remove(&key).is_none() {
+                        return;
+                    }
+                    let _ = out.send(match outcome {
+                        Ok(value) => result(&id, value),
+                        Err(e) => error(&id, INTERNAL_ERROR, &format!("{} (request {})", e, request)),
+                    });
+                })
+            };
+            tasks.insert(key, task.abort_handle());
+        }
+        other => {
+            let _ = out.send(reply(Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", other)))));
+        }
+    }
+}
+
+/// Serves JSON-RPC on stdin/stdout until stdin closes.
+pub async fn run_stdio(state: AppState) -> ReplResult<()> {
+    let (out, mut outgoing) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = outgoing.recv().await {
+            let line = format!("{}\n", message);
+            if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // Push state changes (provider, model, ...) so the editor's status line stays current
+    let mut events = state.subscribe();
+    let events_out = out.clone();
+    let forwarder = tokio::spawn(async move {
+        loop {
+            let message = match events.recv().await {
+                Ok(event) => notification("state/changed", serde_json::to_value(&event).unwrap_or_default()),
+                // Events were missed; the client can re-read the state to catch up
+                Err(broadcast::error::RecvError::Lagged(missed)) => notification("state/lagged", json!({ "missed": missed })),
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let _ = events_out.send(message);
+        }
+    });
+
+    let running: Running = Arc::new(Mutex::new(HashMap::new()));
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match serde_json::from_str::<Value>(&line) {
+            Ok(value) => serde_json::from_value::<Request>(value).map_err(|e| (INVALID_REQUEST, e.to_string())),
+            Err(e) => Err((PARSE_ERROR, e.to_string())),
+        };
+        match request {
+            Ok(request) => handle(state.clone(), request, out.clone(), Arc::clone(&running)).await,
+            Err((code, message)) => {
+                let _ = out.send(error(&Value::Null, code, &message));
+            }
+        }
+    }
+
+    // stdin closed: stop, but let already-queued messages reach the client
+    forwarder.abort();
+    for (_, task) in running.lock().await.drain() {
+        task.abort();
+    }
+    drop(out);
+    let _ = writer.await;
+    Ok(())
+}
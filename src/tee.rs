@@ -0,0 +1,69 @@
+// src/tee.rs
+//! Output mirroring (`/tee <file> on`). What the REPL prints — prompts, responses, command and
+//! shell output, errors — is appended to a file as it is printed, without colors or rendering.
+//! A `.md` file gets prompts as quotes between the Markdown of the answers; any other is plain text.
+use chrono::Local;
+use lazy_static::lazy_static;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+lazy_static! {
+    // The file output is mirrored to while /tee is on.
+    static ref TEE_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| matches!(extension.to_lowercase().as_str(), "md" | "markdown"))
+}
+
+// Mirroring must never get in the way of the output itself, so write errors are ignored.
+fn append(path: &Path, text: &str) {
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = file.write_all(text.as_bytes());
+    }
+}
+
+/// Starts mirroring to `path`, appending to what is already there, and marks where this run starts.
+pub fn enable(path: PathBuf) {
+    let started = Local::now().format("%Y-%m-%d %H:%M:%S");
+    let header = if is_markdown(&path) {
+        format!("\n## llm-repl, {}\n\n", started)
+    } else {
+        format!("\n--- llm-repl, {} ---\n", started)
+    };
+    append(&path, &header);
+    *TEE_FILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(path);
+}
+
+/// Stops mirroring; returns the file that was being written, if any.
+pub fn disable() -> Option<PathBuf> {
+    TEE_FILE.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// The file output is mirrored to, if /tee is on.
+pub fn path() -> Option<PathBuf> {
+    TEE_FILE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Mirrors a line typed at the prompt, labelled with the provider and model it went to.
+pub fn prompt(target: &str, line: &str) {
+    let Some(path) = path() else { return };
+    let text = if is_markdown(&path) {
+        let quoted = line.trim_end().lines().collect::<Vec<_>>().join("\n> ");
+        format!("> **[{}]** {}\n\n", target, quoted)
+    } else {
+        format!("[{}]> {}\n", target, line.trim_end())
+    };
+    append(&path, &text);
+}
+
+/// Mirrors printed output (a response, command output, a message or an error) as one block.
+pub fn output(text: &str) {
+    let Some(path) = path() else { return };
+    let separator = if is_markdown(&path) { "\n\n" } else { "\n" };
+    append(&path, &format!("{}{}", text.trim_end(), separator));
+}
@@ -0,0 +1,72 @@
+// src/terminal.rs
+//! Terminal state changed while output is drawn (the cursor hidden during LiveStreaming, raw mode
+//! and the alternate screen in `/tui`) and putting it back on every exit path, panics included.
+use ratatui::crossterm::{execute, terminal};
+use std::io::{self, IsTerminal, Write};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CURSOR_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Hides the cursor until dropped, also when a panic unwinds past it.
+pub struct HiddenCursor;
+
+impl HiddenCursor {
+    pub fn hide() -> io::Result<Self> {
+        let mut out = io::stdout();
+        out.write_all(b"\x1B[?25l")?;
+        out.flush()?;
+        CURSOR_HIDDEN.store(true, Ordering::SeqCst);
+        Ok(Self)
+    }
+}
+
+impl Drop for HiddenCursor {
+    fn drop(&mut self) {
+        if CURSOR_HIDDEN.swap(false, Ordering::SeqCst) {
+            let mut out = io::stdout();
+            let _ = out.write_all(b"\x1B[?25h");
+            let _ = out.flush();
+        }
+    }
+}
+
+/// Raw mode on the alternate screen, for full-screen views; the terminal is restored when dropped.
+pub struct FullScreen;
+
+impl FullScreen {
+    pub fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = Self;
+        execute!(io::stdout(), terminal::EnterAlternateScreen)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for FullScreen {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Puts the terminal back into its normal state: raw mode off, colors reset, the alternate screen
+/// left and the cursor shown. Output that isn't a terminal (e.g. the `--stdio` protocol) is left alone.
+pub fn restore() {
+    let _ = terminal::disable_raw_mode();
+    CURSOR_HIDDEN.store(false, Ordering::SeqCst);
+    let mut out = io::stdout();
+    if out.is_terminal() {
+        let _ = out.write_all(b"\x1B[0m\x1B[?1049l\x1B[?25h");
+        let _ = out.flush();
+    }
+}
+
+/// Restores the terminal before the panic message is printed, so the message is readable and the
+/// shell usable afterwards.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        default_hook(info);
+    }));
+}
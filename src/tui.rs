@@ -0,0 +1,424 @@
+// src/tui.rs
+//! Full-screen chat (`/tui`, or `--tui` at startup): the session history in a scrollable
+//! conversation pane, an input line and a status bar, drawn with ratatui on the alternate screen.
+//! Answers stream into the pane while it keeps taking keys; commands run as in the REPL, with the
+//! full screen suspended so their prompts and pickers work, and their output joins the history.
+use futures::StreamExt;
+use ratatui::{
+    backend::CrosstermBackend,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame, Terminal,
+};
+use std::io::{self, Stdout};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::{self, error::TryRecvError};
+use tokio::task::JoinHandle;
+
+use crate::{
+    commands::{reader::display_header, CommandRegistry},
+    config::CommandOrigin,
+    context,
+    data::expand_dataset_references,
+    documents::expand_file_references,
+    error::{ReplError, ReplResult},
+    i18n::{tr, tr_args},
+    render::{get_theme_resources, wrap_lines, ThemePalette},
+    request_id,
+    state::{AppState, HistoryContentType, HistoryEntry},
+    terminal::FullScreen,
+};
+
+// How long to wait for a key before checking the stream again.
+const TICK: Duration = Duration::from_millis(50);
+
+/// How the user left the full-screen view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuiExit {
+    /// Back to the readline prompt (Esc, /tui).
+    Back,
+    /// Out of the REPL (/exit, /quit, Ctrl+D, Ctrl+C while idle).
+    Quit,
+}
+
+// An answer arriving in the background while the view stays responsive.
+struct Streaming {
+    request_id: String,
+    provider: String,
+    model: String,
+    prompt: String,
+    sent: String, // The prompt as sent, with files and hooks; kept in the conversation once answered
+    text: String,
+    error: Option<ReplError>,
+    started: Instant,
+    chunks: mpsc::UnboundedReceiver<ReplResult<String>>,
+    task: JoinHandle<()>,
+}
+
+struct Tui<'a> {
+    state: &'a AppState,
+    commands: &'a CommandRegistry,
+    runtime: &'a Runtime,
+    history: Vec<HistoryEntry>,
+    input: Vec<char>,
+    cursor: usize,
+    // Lines scrolled up from the bottom of the conversation; 0 follows new output.
+    scroll_back: usize,
+    page: usize,
+    notice: Option<String>,
+    streaming: Option<Streaming>,
+}
+
+/// Runs the full-screen view until the user leaves it.
+pub fn run(state: &AppState, commands: &CommandRegistry, runtime: &Runtime) -> ReplResult<TuiExit> {
+    let mut tui = Tui {
+        state,
+        commands,
+        runtime,
+        history: runtime.block_on(state.get_history()),
+        input: Vec::new(),
+        cursor: 0,
+        scroll_back: 0,
+        page: 1,
+        notice: None,
+        streaming: None,
+    };
+    let screen = FullScreen::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let exit = tui.event_loop(&mut terminal, screen);
+    if let Some(streaming) = tui.streaming.take() {
+        streaming.task.abort();
+    }
+    exit
+}
+
+impl Tui<'_> {
+    fn event_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>, mut screen: FullScreen) -> ReplResult<TuiExit> {
+        loop {
+            self.receive();
+            let palette = get_theme_resources(self.runtime.block_on(self.state.get_theme())).1;
+            let status = self.status_line();
+            terminal.draw(|frame| self.draw(frame, &palette, &status))?;
+            if !event::poll(TICK)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.on_key(key) {
+                Some(Action::Exit(exit)) => return Ok(exit),
+                Some(Action::Command(cmd, args)) => {
+                    // Commands may print or ask questions, so they get the normal screen meanwhile
+                    drop(screen);
+                    self.run_command(&cmd, &args);
+                    screen = FullScreen::enter()?;
+                    terminal.clear()?;
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn on_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => {
+                if self.cancel() {
+                    return None;
+                }
+                return Some(Action::Exit(TuiExit::Quit));
+            }
+            KeyCode::Char('d') if ctrl && self.input.is_empty() => return Some(Action::Exit(TuiExit::Quit)),
+            KeyCode::Esc => {
+                if self.cancel() {
+                    return None;
+                }
+                return Some(Action::Exit(TuiExit::Back));
+            }
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Char(c) if !ctrl => {
+                self.input.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.input.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.input.len() => {
+                self.input.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.input.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.len(),
+            KeyCode::Up => self.scroll_back += 1,
+            KeyCode::Down => self.scroll_back = self.scroll_back.saturating_sub(1),
+            KeyCode::PageUp => self.scroll_back += self.page,
+            KeyCode::PageDown => self.scroll_back = self.scroll_back.saturating_sub(self.page),
+            _ => {}
+        }
+        None
+    }
+
+    // Stops the answer being streamed, if any; what arrived so far is kept in the history.
+    fn cancel(&mut self) -> bool {
+        let Some(streaming) = self.streaming.as_mut() else { return false };
+        streaming.task.abort();
+        streaming.error = Some(ReplError::Command(tr("tui-cancelled")));
+        self.finish();
+        true
+    }
+
+    fn submit(&mut self) -> Option<Action> {
+        let line: String = self.input.iter().collect();
+        let line = line.trim().to_string();
+        if line.is_empty() {
+            return None;
+        }
+        if self.streaming.is_some() {
+            self.notice = Some(tr("tui-busy"));
+            return None;
+        }
+        self.input.clear();
+        self.cursor = 0;
+        self.scroll_back = 0;
+        self.notice = None;
+        let Some(command_line) = line.strip_prefix('/') else {
+            self.start_query(&line);
+            return None;
+        };
+        let (cmd, args) = command_line.split_once(' ').unwrap_or((command_line, ""));
+        match cmd {
+            "exit" | "quit" => Some(Action::Exit(TuiExit::Quit)),
+            "tui" => Some(Action::Exit(TuiExit::Back)),
+            _ => Some(Action::Command(cmd.to_string(), args.to_string())),
+        }
+    }
+
+    // Runs a registered command like the REPL does and records its output or error in the history.
+    fn run_command(&mut self, cmd: &str, args: &str) {
+        let id = request_id::next();
+        let started = Instant::now();
+        let (state, commands) = (self.state, self.commands);
+        let result = self.runtime.block_on(request_id::scope(id.clone(), async {
+            let command = commands.get_command(cmd).ok_or_else(|| ReplError::UnknownCommand(cmd.to_string()))?;
+            if !state.is_command_enabled(cmd, CommandOrigin::Local).await {
+                return Err(ReplError::CommandDisabled(cmd.to_string()));
+            }
+            command.execute(args).await
+        }));
+        let entry = match result {
+            Ok(output) => HistoryEntry::new(HistoryContentType::CommandResult { command: cmd.to_string() }, output),
+            Err(e) => HistoryEntry::new(
+                HistoryContentType::Error { source: format!("/{}", cmd) },
+                tr_args("error-command", &[("command", cmd), ("error", &e.to_string())]),
+            ),
+        };
+        self.record(&id, entry.with_duration(started.elapsed()));
+    }
+
+    // Sends a prompt the way the REPL does (datasets, files, hooks, session context and the
+    // conversation so far) and streams the answer from a background task.
+    fn start_query(&mut self, prompt: &str) {
+        let state = self.state;
+        let datasets = self.runtime.block_on(state.list_datasets());
+        let input_limits = self.runtime.block_on(state.get_config()).input_limits;
+        let expanded = expand_dataset_references(prompt, &datasets).and_then(|(with_data, data_context, _)| {
+            expand_file_references(&with_data, &input_limits).map(|(expanded, included)| (expanded + &data_context, included))
+        });
+        let with_files = match expanded {
+            Ok((expanded, _)) => expanded,
+            Err(e) => {
+                self.notice = Some(tr_args("error-generic", &[("error", &e.to_string())]));
+                return;
+            }
+        };
+        let (provider_name, model, (messages, trimmed), params, images) = self.runtime.block_on(async {
+            let messages = state.get_config().await.chat_messages(&state.get_conversation().await, &with_files);
+            let (provider_name, model) = (state.get_provider_name().await, state.get_model().await);
+            let fitted = context::fit(state, &provider_name, &model, messages).await;
+            (provider_name, model, fitted, state.get_generation_params().await, state.get_attachments().await)
+        });
+        self.notice = trimmed;
+        let Some(provider) = state.get_provider_by_name(&provider_name) else {
+            self.notice = Some(ReplError::UnknownProvider(provider_name).to_string());
+            return;
+        };
+        let sent = messages.last().map(|message| message.content.clone()).unwrap_or_default();
+        let id = request_id::next();
+        let (chunks_tx, chunks) = mpsc::unbounded_channel();
+        let task_model = model.clone();
+        let task_state = state.clone();
+        let task = self.runtime.spawn(request_id::scope(id.clone(), async move {
+            // Images from /attach go with this prompt, unstreamed, and are dropped once it's answered
+            if !images.is_empty() {
+                let answer = provider.chat_with_images(&task_model, &messages, &images, &params).await;
+                if answer.is_ok() {
+                    task_state.clear_attachments().await;
+                }
+                let _ = chunks_tx.send(answer);
+                return;
+            }
+            let streamed = provider.chat_stream(&task_model, &messages, &params).await;
+            match streamed {
+                Ok(Some(mut stream)) => {
+                    while let Some(chunk) = stream.next().await {
+                        if chunks_tx.send(chunk).is_err() {
+                            break;
+                        }
+                    }
+                }
+                // Providers without streaming answer in one piece
+                Ok(None) => { let _ = chunks_tx.send(provider.chat(&task_model, &messages, &params).await); }
+                Err(e) => { let _ = chunks_tx.send(Err(e)); }
+            }
+        }));
+        self.streaming = Some(Streaming {
+            request_id: id,
+            provider: provider_name,
+            model,
+            prompt: prompt.to_string(),
+            sent,
+            text: String::new(),
+            error: None,
+            started: Instant::now(),
+            chunks,
+            task,
+        });
+    }
+
+    // Takes in the chunks that arrived since the last frame; the stream ends when its task is done.
+    fn receive(&mut self) {
+        let Some(streaming) = self.streaming.as_mut() else { return };
+        loop {
+            match streaming.chunks.try_recv() {
+                Ok(Ok(chunk)) => streaming.text.push_str(&chunk),
+                Ok(Err(e)) => streaming.error = Some(e),
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        let Some(streaming) = self.streaming.take() else { return };
+        let limits = self.runtime.block_on(self.state.get_output_limits());
+        let elapsed = streaming.started.elapsed();
+        let mut entries = Vec::new();
+        // Only complete answers carry the conversation on; cancelled or failed ones are left out
+        if !streaming.text.is_empty() && streaming.error.is_none() {
+            self.runtime.block_on(self.state.add_turn(&streaming.sent, &streaming.text));
+        }
+        if !streaming.text.is_empty() {
+            entries.push(HistoryEntry::new(HistoryContentType::LlmResponse { model: streaming.model.clone() }, limits.apply(&streaming.text))
+                .with_model(&streaming.provider, &streaming.model)
+                .with_prompt(&streaming.prompt)
+                .with_duration(elapsed));
+        }
+        if let Some(e) = streaming.error {
+            let message = tr_args("error-llm", &[("error", &e.to_string())]);
+            self.notice = Some(message.clone());
+            entries.push(HistoryEntry::new(HistoryContentType::Error { source: "LLM Query".to_string() }, message)
+                .with_model(&streaming.provider, &streaming.model)
+                .with_duration(elapsed));
+        }
+        for entry in entries {
+            self.record(&streaming.request_id, entry);
+        }
+    }
+
+    fn record(&mut self, id: &str, entry: HistoryEntry) {
+        self.runtime.block_on(request_id::scope(id.to_string(), self.state.add_history_entry(entry)));
+        self.history = self.runtime.block_on(self.state.get_history());
+    }
+
+    fn status_line(&self) -> String {
+        let (provider, model) = self.runtime.block_on(async { (self.state.get_provider_name().await, self.state.get_model().await) });
+        let activity = match (&self.streaming, &self.notice) {
+            (Some(streaming), _) => tr_args("tui-streaming", &[("seconds", &streaming.started.elapsed().as_secs().to_string())]),
+            (None, Some(notice)) => notice.clone(),
+            (None, None) => tr("tui-keys"),
+        };
+        format!(" {}:{} | {}", provider, model, activity)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, palette: &ThemePalette, status: &str) {
+        let [conversation, input, status_bar] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+        self.draw_conversation(frame, conversation, palette);
+        self.draw_input(frame, input, palette);
+        let style = Style::default().fg(rgb(palette.prompt_model)).add_modifier(Modifier::REVERSED);
+        frame.render_widget(Paragraph::new(Line::from(Span::styled(status.to_string(), style))).style(style), status_bar);
+    }
+
+    fn draw_conversation(&mut self, frame: &mut Frame, area: Rect, palette: &ThemePalette) {
+        let width = area.width.saturating_sub(2).max(1) as usize;
+        let height = area.height.saturating_sub(2) as usize;
+        let mut lines = Vec::new();
+        for (index, entry) in self.history.iter().enumerate() {
+            let style = match entry.entry_type {
+                HistoryContentType::Error { .. } => Style::default().fg(rgb(palette.error)),
+                HistoryContentType::CommandResult { .. } | HistoryContentType::ShellOutput { .. } => Style::default().fg(rgb(palette.command_output_raw)),
+                _ => Style::default(),
+            };
+            push_entry(&mut lines, &display_header(entry, index), entry.prompt.as_deref(), &entry.content, style, palette, width);
+        }
+        if let Some(streaming) = &self.streaming {
+            let header = format!("LLM Response ({}) ...", streaming.model);
+            push_entry(&mut lines, &header, Some(&streaming.prompt), &streaming.text, Style::default(), palette, width);
+        }
+
+        self.page = height.max(1);
+        let max_back = lines.len().saturating_sub(height);
+        self.scroll_back = self.scroll_back.min(max_back);
+        let top = max_back - self.scroll_back;
+        let title = if self.scroll_back > 0 {
+            tr_args("tui-conversation-scrolled", &[("lines", &self.scroll_back.to_string())])
+        } else {
+            tr("tui-conversation")
+        };
+        let visible: Vec<Line> = lines.into_iter().skip(top).take(height).collect();
+        let block = Block::bordered().title(format!(" {} ", title)).border_style(Style::default().fg(rgb(palette.prompt_bracket)));
+        frame.render_widget(Paragraph::new(visible).block(block), area);
+    }
+
+    fn draw_input(&self, frame: &mut Frame, area: Rect, palette: &ThemePalette) {
+        let width = area.width.saturating_sub(2).max(1) as usize;
+        // Keep the cursor in view on lines longer than the box
+        let start = (self.cursor + 1).saturating_sub(width);
+        let shown: String = self.input.iter().skip(start).take(width).collect();
+        let block = Block::bordered().title(format!(" {} ", tr("tui-input"))).border_style(Style::default().fg(rgb(palette.prompt_arrow)));
+        frame.render_widget(Paragraph::new(shown).block(block), area);
+        frame.set_cursor_position((area.x + 1 + (self.cursor - start) as u16, area.y + 1));
+    }
+}
+
+enum Action {
+    Exit(TuiExit),
+    Command(String, String),
+}
+
+fn rgb(color: (u8, u8, u8)) -> Color {
+    Color::Rgb(color.0, color.1, color.2)
+}
+
+// One history entry in the pane: its header, the prompt that produced it, and its text, wrapped to `width`.
+fn push_entry(lines: &mut Vec<Line<'static>>, header: &str, prompt: Option<&str>, content: &str, style: Style, palette: &ThemePalette, width: usize) {
+    let header_style = Style::default().fg(rgb(palette.prompt_provider)).add_modifier(Modifier::BOLD);
+    lines.extend(wrap_lines(header, width).into_iter().map(|line| Line::styled(line, header_style)));
+    if let Some(prompt) = prompt {
+        let prompt_style = Style::default().fg(rgb(palette.info));
+        lines.extend(wrap_lines(&format!("> {}", prompt), width).into_iter().map(|line| Line::styled(line, prompt_style)));
+    }
+    lines.extend(wrap_lines(content, width).into_iter().map(|line| Line::styled(line, style)));
+    lines.push(Line::default());
+}
@@ -0,0 +1,127 @@
+// src/typeahead.rs
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use termimad::crossterm::event::{self, Event, KeyCode};
+
+#[derive(Default)]
+struct QueueInner {
+    lines: VecDeque<QueuedLine>,
+    partial: String, // Text typed without pressing Enter yet
+}
+
+/// A queued input line.
+pub struct QueuedLine {
+    pub text: String,
+    /// Sent as a prompt even if it starts with `/` or `!` (e.g. a buffer from `/buf send`).
+    pub prompt_only: bool,
+}
+
+/// Input lines typed while a response was streaming, dispatched in order once it finishes.
+#[derive(Clone, Default)]
+pub struct PromptQueue {
+    inner: Arc<Mutex<QueueInner>>,
+}
+
+impl PromptQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, line: String) {
+        self.inner.lock().unwrap().lines.push_back(QueuedLine { text: line, prompt_only: false });
+    }
+
+    /// Queues text to send as a prompt ahead of the other lines, so it is dispatched next and
+    /// never run as a command or shell line.
+    pub fn push_prompt_front(&self, text: String) {
+        self.inner.lock().unwrap().lines.push_front(QueuedLine { text, prompt_only: true });
+    }
+
+    /// Takes the next queued line, if any.
+    pub fn pop(&self) -> Option<QueuedLine> {
+        self.inner.lock().unwrap().lines.pop_front()
+    }
+
+    /// Returns the queued lines without removing them.
+    pub fn pending(&self) -> Vec<String> {
+        self.inner.lock().unwrap().lines.iter().map(|line| line.text.clone()).collect()
+    }
+
+    /// Takes the partially typed line so it can be pre-filled at the next prompt.
+    pub fn take_partial(&self) -> String {
+        std::mem::take(&mut self.inner.lock().unwrap().partial)
+    }
+
+    /// Sets the text pre-filled at the next prompt.
+    pub fn set_partial(&self, partial: String) {
+        self.inner.lock().unwrap().partial = partial;
+    }
+}
+
+/// Captures lines typed on the terminal while a response is streaming.
+/// Capturing stops when the guard is dropped, so it never competes with rustyline for input.
+pub struct TypeaheadCapture {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<String>>,
+    queue: PromptQueue,
+}
+
+impl TypeaheadCapture {
+    pub fn start(queue: PromptQueue) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        // Nothing to capture when input is piped in
+        let handle = if std::io::stdin().is_terminal() {
+            let stop_flag = Arc::clone(&stop);
+            let line_queue = queue.clone();
+            Some(std::thread::spawn(move || capture_lines(stop_flag, line_queue)))
+        } else {
+            None
+        };
+        Self { stop, handle, queue }
+    }
+}
+
+impl Drop for TypeaheadCapture {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            if let Ok(partial) = handle.join() {
+                self.queue.set_partial(partial);
+            }
+        }
+    }
+}
+
+// Polls terminal key events until `stop` is set, pushing each completed line onto the queue.
+// Returns whatever was typed after the last Enter.
+fn capture_lines(stop: Arc<AtomicBool>, queue: PromptQueue) -> String {
+    let mut line = queue.take_partial();
+    while !stop.load(Ordering::SeqCst) {
+        match event::poll(Duration::from_millis(50)) {
+            Ok(true) => match event::read() {
+                Ok(Event::Key(key)) => match key.code {
+                    KeyCode::Enter => {
+                        let completed = std::mem::take(&mut line);
+                        if !completed.trim().is_empty() {
+                            queue.push(completed);
+                        }
+                    }
+                    KeyCode::Char(c) => line.push(c),
+                    KeyCode::Backspace => {
+                        line.pop();
+                    }
+                    _ => {}
+                },
+                Ok(_) => {}
+                Err(_) => break,
+            },
+            Ok(false) => {}
+            Err(_) => break,
+        }
+    }
+    line
+}
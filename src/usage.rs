@@ -0,0 +1,390 @@
+// src/usage.rs
+//! Usage metrics: every provider call is counted per day, provider and model (queries, errors,
+//! tokens, latency) in a small JSON store, which backs `GET /usage` and `/dashboard`, and for the
+//! running session, which backs `/usage`. Tokens are the provider's counts where its response
+//! includes them (`report_tokens`), estimates otherwise.
+use crate::config::{AppConfig, ModelPrice};
+use crate::request_id;
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use futures::{Stream, StreamExt};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+pub const USAGE_STATS_FILE: &str = "usage-stats.json";
+
+// Calls remembered with their request ID, for the history entries they produce.
+const RECENT_CALLS: usize = 1000;
+
+lazy_static! {
+    static ref USAGE: Mutex<UsageStore> = Mutex::new(UsageStore::load(Path::new(USAGE_STATS_FILE)));
+}
+
+tokio::task_local! {
+    // Where `report_tokens` puts the counts for the call being metered.
+    static REPORTED: Arc<Mutex<Option<ReportedTokens>>>;
+}
+
+/// Token counts from a provider's response (e.g. Groq's `usage`, Ollama's `eval_count`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportedTokens {
+    pub prompt: u64,
+    pub completion: u64,
+}
+
+/// Called by providers whose responses say how many tokens a call used; the usage of that call is
+/// recorded with these counts instead of estimates. Outside a metered call it does nothing.
+pub fn report_tokens(prompt: u64, completion: u64) {
+    let _ = REPORTED.try_with(|slot| {
+        *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(ReportedTokens { prompt, completion });
+    });
+}
+
+/// Collects what providers report with `report_tokens` during one call.
+#[derive(Debug, Clone, Default)]
+pub struct TokenReport(Arc<Mutex<Option<ReportedTokens>>>);
+
+impl TokenReport {
+    /// Runs `future` with its reports going here.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        REPORTED.scope(self.0.clone(), future).await
+    }
+
+    /// Wraps a stream so reports made while it is polled go here (usage usually comes in the last chunk).
+    pub fn stream<S: Stream + Send + 'static>(&self, stream: S) -> Pin<Box<dyn Stream<Item = S::Item> + Send>> {
+        Box::pin(ReportingStream { inner: Box::pin(stream), slot: self.0.clone() })
+    }
+
+    pub fn take(&self) -> Option<ReportedTokens> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+struct ReportingStream<S> {
+    inner: Pin<Box<S>>,
+    slot: Arc<Mutex<Option<ReportedTokens>>>,
+}
+
+impl<S: Stream> Stream for ReportingStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let slot = self.slot.clone();
+        REPORTED.sync_scope(slot, || self.inner.poll_next_unpin(cx))
+    }
+}
+
+/// Token counts are estimated from text length (about four characters per token), since not
+/// every provider reports usage.
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageTotals {
+    pub queries: u64,
+    pub errors: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub latency_ms: u64,
+    /// Queries whose tokens were counted by the provider rather than estimated.
+    pub reported: u64,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, other: &UsageTotals) {
+        self.queries += other.queries;
+        self.errors += other.errors;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.latency_ms += other.latency_ms;
+        self.reported += other.reported;
+    }
+
+    pub fn average_latency_ms(&self) -> u64 {
+        self.latency_ms.checked_div(self.queries).unwrap_or(0)
+    }
+
+    /// Cost at the given per-million-token prices.
+    pub fn cost(&self, price: &ModelPrice) -> f64 {
+        (self.prompt_tokens as f64 * price.input_per_million + self.completion_tokens as f64 * price.output_per_million) / 1_000_000.0
+    }
+}
+
+/// Usage of one model on one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRow {
+    pub day: NaiveDate,
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+// Totals per day, provider and model.
+type DailyTotals = BTreeMap<(NaiveDate, String, String), UsageTotals>;
+
+struct UsageStore {
+    path: PathBuf,
+    rows: DailyTotals,
+    // Calls recorded since the last write to the file.
+    unsaved: DailyTotals,
+    // This process's calls, per provider and model; not saved.
+    session: BTreeMap<(String, String), UsageTotals>,
+    // The newest calls made while handling a request: (request ID, model, usage).
+    recent: VecDeque<(String, String, UsageTotals)>,
+}
+
+// Held while the file is read, merged and replaced, so writes from this process don't interleave.
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+// The rows in the file at `path`: empty if there is none, an error if it can't be read or parsed.
+fn read_rows(path: &Path) -> Result<DailyTotals, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let rows = serde_json::from_str::<Vec<UsageRow>>(&text).map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(|row| ((row.day, row.provider, row.model), row.totals)).collect())
+}
+
+// Adds `calls` to the file's rows. The file is re-read first, so calls saved by another process in
+// the meantime are kept, and replaced through a temporary file, so it is never left half written.
+// A file that can't be parsed is left alone rather than replaced.
+fn write_calls(path: &Path, calls: &DailyTotals) -> Result<(), String> {
+    let _lock = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut rows = read_rows(path).map_err(|e| format!("the file can't be read ({}), so it is left unchanged", e))?;
+    for (key, call) in calls {
+        rows.entry(key.clone()).or_default().add(call);
+    }
+    let rows: Vec<UsageRow> = rows.into_iter()
+        .map(|((day, provider, model), totals)| UsageRow { day, provider, model, totals })
+        .collect();
+    let json = serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).and_then(|_| fs::rename(&temp_path, path)).map_err(|e| e.to_string())
+}
+
+impl UsageStore {
+    // A missing file starts an empty store. One that can't be read is reported and left alone:
+    // new calls aren't written to it until it is fixed or removed.
+    fn load(path: &Path) -> Self {
+        let rows = read_rows(path).unwrap_or_else(|e| {
+            eprintln!("WARN: Usage stats in '{}' can't be read ({}); they are left unchanged and new usage isn't saved there.", path.display(), e);
+            BTreeMap::new()
+        });
+        Self { path: path.to_path_buf(), rows, unsaved: BTreeMap::new(), session: BTreeMap::new(), recent: VecDeque::new() }
+    }
+
+    fn rows(&self) -> Vec<UsageRow> {
+        self.rows.iter()
+            .map(|((day, provider, model), totals)| UsageRow { day: *day, provider: provider.clone(), model: model.clone(), totals: *totals })
+            .collect()
+    }
+}
+
+// Writes `calls` off the async runtime (when there is one). Calls that couldn't be written go
+// back to the store for the next attempt. Call it without holding the store's lock.
+fn save_calls(path: PathBuf, calls: DailyTotals) {
+    let write = move || {
+        if let Err(e) = write_calls(&path, &calls) {
+            tracing::warn!("Could not save usage stats to '{}': {}", path.display(), e);
+            let mut store = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+            for (key, call) in calls {
+                store.unsaved.entry(key).or_default().add(&call);
+            }
+        }
+    };
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => drop(runtime.spawn_blocking(write)),
+        Err(_) => write(),
+    }
+}
+
+/// Records one provider call. `completion` is whatever text arrived, even if the call failed;
+/// both are estimated unless the provider `reported` its counts.
+pub fn record(provider: &str, model: &str, prompt: &str, completion: &str, latency: Duration, failed: bool, reported: Option<ReportedTokens>) {
+    let call = UsageTotals {
+        queries: 1,
+        errors: u64::from(failed),
+        prompt_tokens: reported.map_or_else(|| estimate_tokens(prompt), |tokens| tokens.prompt),
+        completion_tokens: reported.map_or_else(|| estimate_tokens(completion), |tokens| tokens.completion),
+        latency_ms: latency.as_millis() as u64,
+        reported: u64::from(reported.is_some()),
+    };
+    let mut store = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+    let key = (Utc::now().date_naive(), provider.to_string(), model.to_string());
+    store.rows.entry(key.clone()).or_default().add(&call);
+    store.unsaved.entry(key).or_default().add(&call);
+    store.session.entry((provider.to_string(), model.to_string())).or_default().add(&call);
+    if let Some(id) = request_id::current() {
+        if store.recent.len() == RECENT_CALLS {
+            store.recent.pop_front();
+        }
+        store.recent.push_back((id, model.to_string(), call));
+    }
+    let (path, calls) = (store.path.clone(), std::mem::take(&mut store.unsaved));
+    drop(store);
+    save_calls(path, calls);
+}
+
+/// Usage of the calls to `model` made for request `id` (several if a stream was resumed), if any
+/// were recorded.
+pub fn request_usage(id: &str, model: &str) -> Option<UsageTotals> {
+    let store = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut calls = store.recent.iter().filter(|(call_id, call_model, _)| call_id == id && call_model == model).peekable();
+    calls.peek()?;
+    let mut totals = UsageTotals::default();
+    calls.for_each(|(_, _, call)| totals.add(call));
+    Some(totals)
+}
+
+/// What one history entry's answer used. `cost` is None without a price for its model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// False if any of the counts were estimated.
+    pub reported: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+}
+
+impl EntryUsage {
+    pub fn new(totals: &UsageTotals, price: Option<&ModelPrice>) -> Self {
+        Self {
+            prompt_tokens: totals.prompt_tokens,
+            completion_tokens: totals.completion_tokens,
+            reported: totals.reported == totals.queries,
+            cost: price.map(|price| totals.cost(price)),
+        }
+    }
+
+    /// Adds the usage of a later call for the same entry, e.g. by `/continue`.
+    pub fn add(&mut self, other: &EntryUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.reported &= other.reported;
+        self.cost = match (self.cost, other.cost) {
+            (Some(a), Some(b)) => Some(a + b),
+            (cost, None) | (None, cost) => cost,
+        };
+    }
+
+    /// e.g. "1200 + 350 tokens, cost 0.0012", with "~" before estimated counts.
+    pub fn describe(&self) -> String {
+        let about = if self.reported { "" } else { "~" };
+        let mut text = format!("{}{} + {} tokens", about, self.prompt_tokens, self.completion_tokens);
+        if let Some(cost) = self.cost {
+            text.push_str(&format!(", cost {:.4}", cost));
+        }
+        text
+    }
+}
+
+/// All recorded usage, oldest day first.
+pub fn snapshot() -> Vec<UsageRow> {
+    USAGE.lock().unwrap_or_else(|e| e.into_inner()).rows()
+}
+
+/// Usage of the running session, per provider and model.
+pub fn session() -> BTreeMap<(String, String), UsageTotals> {
+    USAGE.lock().unwrap_or_else(|e| e.into_inner()).session.clone()
+}
+
+/// Usage of one model over a report's period; `cost` is None without a configured price.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelUsage {
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+    pub cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayUsage {
+    pub day: NaiveDate,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+    pub cost: f64,
+}
+
+/// Usage of the running session: overall and per model (busiest first). `cost` is None if no
+/// model used has a price.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionReport {
+    pub duration_secs: u64,
+    pub totals: UsageTotals,
+    pub cost: Option<f64>,
+    pub models: Vec<ModelUsage>,
+}
+
+pub fn session_report(config: &AppConfig, duration: Duration) -> SessionReport {
+    let mut totals = UsageTotals::default();
+    let mut cost = None;
+    let mut models = session().into_iter().map(|((provider, model), used)| {
+        totals.add(&used);
+        let model_cost = config.price_for(&provider, &model).map(|price| used.cost(price));
+        if let Some(model_cost) = model_cost {
+            *cost.get_or_insert(0.0) += model_cost;
+        }
+        ModelUsage { provider, model, totals: used, cost: model_cost }
+    }).collect::<Vec<_>>();
+    models.sort_by_key(|model| std::cmp::Reverse(model.totals.queries));
+    SessionReport { duration_secs: duration.as_secs(), totals, cost, models }
+}
+
+/// Usage over the last `days` days: overall, per model (busiest first) and per day.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub days: i64,
+    pub totals: UsageTotals,
+    pub cost: f64,
+    pub models: Vec<ModelUsage>,
+    pub daily: Vec<DayUsage>,
+}
+
+// Cost of a row, if the model or its provider has a price.
+fn row_cost(config: &AppConfig, row: &UsageRow) -> Option<f64> {
+    config.price_for(&row.provider, &row.model).map(|price| row.totals.cost(price))
+}
+
+/// Totals over the last `days` days (today included), per model and per day.
+pub fn build_report(rows: &[UsageRow], config: &AppConfig, days: i64) -> UsageReport {
+    let days = days.max(1);
+    let first_day = Utc::now().date_naive() - ChronoDuration::days(days - 1);
+    let mut totals = UsageTotals::default();
+    let mut cost = 0.0;
+    let mut models: BTreeMap<(String, String), ModelUsage> = BTreeMap::new();
+    let mut daily: BTreeMap<NaiveDate, DayUsage> = BTreeMap::new();
+    for row in rows.iter().filter(|row| row.day >= first_day) {
+        let row_cost = row_cost(config, row);
+        totals.add(&row.totals);
+        cost += row_cost.unwrap_or(0.0);
+        let model = models.entry((row.provider.clone(), row.model.clone())).or_insert_with(|| ModelUsage {
+            provider: row.provider.clone(), model: row.model.clone(), totals: UsageTotals::default(), cost: None,
+        });
+        model.totals.add(&row.totals);
+        if let Some(row_cost) = row_cost {
+            *model.cost.get_or_insert(0.0) += row_cost;
+        }
+        let day = daily.entry(row.day).or_insert_with(|| DayUsage { day: row.day, totals: UsageTotals::default(), cost: 0.0 });
+        day.totals.add(&row.totals);
+        day.cost += row_cost.unwrap_or(0.0);
+    }
+    let mut models = models.into_values().collect::<Vec<_>>();
+    models.sort_by_key(|model| std::cmp::Reverse(model.totals.queries));
+    UsageReport { days, totals, cost, models, daily: daily.into_values().collect() }
+}
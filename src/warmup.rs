@@ -0,0 +1,82 @@
+// src/warmup.rs
+//! Warm-up for local models: with `warm_up = true` in an Ollama provider's settings, the selected
+//! model is loaded as soon as the session starts or switches to it, and reloaded periodically so
+//! Ollama doesn't unload it between prompts. The first prompt then skips the model's load time.
+use crate::{
+    providers::ollama::OllamaProvider,
+    state::{AppState, StateEvent},
+};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+// Reload interval when `warm_up_interval_secs` is unset, inside Ollama's default 5-minute keep_alive.
+const DEFAULT_INTERVAL_SECS: u64 = 240;
+// A provider switch changes the provider and then the model; warm up once both are set.
+const SETTLE: Duration = Duration::from_millis(500);
+
+// The provider/model to keep loaded, the provider to load it with, and how often to reload it;
+// None unless the current provider is an Ollama with warm-up on.
+async fn warm_up_target(state: &AppState) -> Option<((String, String), OllamaProvider, Duration)> {
+    let provider = state.get_provider_name().await;
+    let config = state.get_config().await;
+    let settings = config.providers.get(&provider).cloned().unwrap_or_default();
+    if !settings.warm_up || !config.is_ollama(&provider) {
+        return None;
+    }
+    let ollama = OllamaProvider::with_settings(&settings).ok()?;
+    let interval = Duration::from_secs(settings.warm_up_interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS).max(1));
+    Some(((provider, state.get_model().await), ollama, interval))
+}
+
+// Waits for `timeout` (forever if None) or a change of provider, model or config; true if the
+// time ran out, None once the state is gone.
+async fn wait_for_change(events: &mut broadcast::Receiver<StateEvent>, timeout: Option<Duration>) -> Option<bool> {
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => return Some(true),
+            event = events.recv() => match event {
+                Ok(StateEvent::ProviderChanged { .. } | StateEvent::ModelChanged { .. } | StateEvent::ConfigReloaded { .. } | StateEvent::RolledBack { .. } | StateEvent::SessionLoaded { .. })
+                | Err(RecvError::Lagged(_)) => {
+                    tokio::time::sleep(SETTLE).await;
+                    return Some(false);
+                }
+                Ok(_) => {}
+                Err(RecvError::Closed) => return None,
+            },
+        }
+    }
+}
+
+/// Keeps the selected Ollama model loaded while warm-up is on, until the task is dropped. Runs
+/// quietly: a failed load is retried at the next interval, and the next prompt reports any real problem.
+pub async fn keep_warm(state: AppState) {
+    let mut events = state.subscribe();
+    let mut warmed: Option<(String, String)> = None;
+    let mut refresh = true;
+    loop {
+        let timeout = match warm_up_target(&state).await {
+            Some((target, ollama, interval)) => {
+                // A change event that leaves the target as it is doesn't reload it
+                if refresh || warmed.as_ref() != Some(&target) {
+                    warmed = ollama.load_model(&target.1).await.ok().map(|_| target);
+                }
+                Some(interval)
+            }
+            None => {
+                warmed = None;
+                None
+            }
+        };
+        match wait_for_change(&mut events, timeout).await {
+            Some(timed_out) => refresh = timed_out,
+            None => return,
+        }
+    }
+}
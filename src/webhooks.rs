@@ -0,0 +1,129 @@
+// src/webhooks.rs
+//! Server-mode webhooks: each completed query, command or shell call (every history entry the
+//! server records) is POSTed as JSON to the URLs under `[[server.webhooks]]`.
+use crate::{
+    config::Webhook,
+    state::{AppState, HistoryContentType, HistoryEntry, StateEvent},
+};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+// Delivery attempts per webhook; waits double from RETRY_DELAY between them.
+const ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+// Characters of output quoted in the chat-friendly `text` summary.
+const SUMMARY_CHARS: usize = 500;
+pub const SIGNATURE_HEADER: &str = "X-LLM-REPL-Signature";
+
+// The webhook event for a history entry, or None for entries that aren't completions.
+fn event_name(entry: &HistoryEntry) -> Option<&'static str> {
+    match &entry.entry_type {
+        HistoryContentType::LlmResponse { .. } => Some("query"),
+        HistoryContentType::CommandResult { .. } => Some("command"),
+        HistoryContentType::ShellOutput { .. } => Some("shell"),
+        HistoryContentType::Error { .. } | HistoryContentType::UserQuery | HistoryContentType::Info => None,
+    }
+}
+
+// One line for chat tools (Slack reads `text`, Discord `content`).
+fn summary(event: &str, entry: &HistoryEntry) -> String {
+    let subject = match &entry.entry_type {
+        HistoryContentType::CommandResult { command } => format!("/{}", command),
+        HistoryContentType::ShellOutput { command } => format!("!{}", command),
+        _ => match (&entry.provider, &entry.model) {
+            (Some(provider), Some(model)) => format!("{}:{}", provider, model),
+            _ => "query".to_string(),
+        },
+    };
+    let took = entry.duration_ms.map(|ms| format!(" in {:.1}s", ms as f64 / 1000.0)).unwrap_or_default();
+    let mut output = entry.content.chars().take(SUMMARY_CHARS).collect::<String>();
+    if entry.content.chars().nth(SUMMARY_CHARS).is_some() {
+        output.push_str("...");
+    }
+    format!("llm-repl {} completed ({}{}):\n{}", event, subject, took, output)
+}
+
+fn payload(event: &str, entry: &HistoryEntry) -> Value {
+    let text = summary(event, entry);
+    json!({
+        "event": format!("{}.completed", event),
+        "timestamp": Utc::now(),
+        "text": text,
+        "content": text,
+        "entry": entry,
+    })
+}
+
+/// HMAC-SHA256 of `message`, hex-encoded.
+fn sign(secret: &str, message: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(message);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+async fn deliver(client: Client, webhook: Webhook, body: String) {
+    let mut delay = RETRY_DELAY;
+    for attempt in 1..=ATTEMPTS {
+        let mut request = client.post(&webhook.url).header("Content-Type", "application/json").body(body.clone());
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, body.as_bytes())));
+        }
+        let failure = match request.send().await {
+            Ok(response) if response.status().is_success() => return,
+            // Client errors won't succeed on retry
+            Ok(response) if response.status().is_client_error() => {
+                warn!("Webhook {} rejected the payload: {}", webhook.url, response.status());
+                return;
+            }
+            Ok(response) => response.status().to_string(),
+            Err(e) => e.to_string(),
+        };
+        if attempt == ATTEMPTS {
+            warn!("Webhook {} failed after {} attempts: {}", webhook.url, ATTEMPTS, failure);
+            return;
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+}
+
+/// Sends webhooks for completions until the state's event channel closes. Webhooks are read from
+/// the config for every event, so reloaded settings apply at once.
+pub async fn run(state: AppState) {
+    let client = match Client::builder().timeout(TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Webhooks disabled: {}", e);
+            return;
+        }
+    };
+    let mut events = state.subscribe();
+    loop {
+        let entry = match events.recv().await {
+            Ok(StateEvent::HistoryEntryAdded { entry }) => entry,
+            Ok(_) => continue,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!("Webhooks skipped {} events", missed);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let Some(event) = event_name(&entry) else { continue };
+        let webhooks = state.get_config().await.server.webhooks;
+        let targets = webhooks.into_iter().filter(|webhook| webhook.wants(event)).collect::<Vec<_>>();
+        if targets.is_empty() {
+            continue;
+        }
+        let body = payload(event, &entry).to_string();
+        for webhook in targets {
+            tokio::spawn(deliver(client.clone(), webhook, body.clone()));
+        }
+    }
+}